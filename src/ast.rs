@@ -1,19 +1,21 @@
 //! Module defining the AST (abstract syntax tree).
 
 use crate::calc_fn_hash;
-use crate::dynamic::Union;
+use crate::dynamic::{AccessMode, Union};
+use crate::fn_hash::get_hasher;
 use crate::fn_native::shared_make_mut;
 use crate::module::NamespaceRef;
 use crate::token::Token;
 use crate::{
-    Dynamic, FnNamespace, Identifier, ImmutableString, Module, Position, Shared, StaticVec, INT,
+    Dynamic, ExclusiveRange, FnNamespace, Identifier, ImmutableString, InclusiveRange, Module,
+    Position, Shared, StaticVec, INT,
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
     collections::BTreeMap,
     fmt,
-    hash::Hash,
+    hash::{Hash, Hasher},
     mem,
     num::{NonZeroU8, NonZeroUsize},
     ops::{Add, AddAssign, Deref, DerefMut},
@@ -22,6 +24,9 @@ use std::{
 #[cfg(not(feature = "no_float"))]
 use std::str::FromStr;
 
+#[cfg(feature = "bigint")]
+use std::convert::TryFrom;
+
 #[cfg(not(feature = "no_float"))]
 use crate::FLOAT;
 
@@ -63,6 +68,21 @@ pub struct ScriptFnDef {
     pub access: FnAccess,
     /// Names of function parameters.
     pub params: StaticVec<Identifier>,
+    /// Type annotations of function parameters, if any.
+    ///
+    /// Empty if no parameter is annotated.
+    pub param_types: StaticVec<Option<Identifier>>,
+    /// Type annotation of the return value, if any.
+    pub return_type: Option<Identifier>,
+    /// Default values of the trailing function parameters (before any rest parameter), if any.
+    pub defaults: StaticVec<Dynamic>,
+    /// Is the last parameter a rest parameter that collects all extra arguments into an array?
+    pub variadic: bool,
+    /// Is this a generator function, i.e. one that contains `yield`?
+    ///
+    /// Calling a generator function returns a [`Generator`][crate::Generator] that runs the
+    /// function body on demand.
+    pub is_generator: bool,
     /// Access to external variables.
     ///
     /// Not available under `no_closure`.
@@ -90,13 +110,154 @@ impl fmt::Display for ScriptFnDef {
             self.name,
             self.params
                 .iter()
-                .map(|s| s.as_str())
+                .enumerate()
+                .map(|(i, s)| match self.param_types.get(i) {
+                    _ if self.variadic && i == self.params.len() - 1 => format!("...{}", s),
+                    Some(Some(typ)) => format!("{}: {}", s, typ),
+                    _ => s.to_string(),
+                })
                 .collect::<StaticVec<_>>()
                 .join(", ")
-        )
+        )?;
+
+        if let Some(ref typ) = self.return_type {
+            write!(f, " -> {}", typ)?;
+        }
+
+        Ok(())
     }
 }
 
+#[cfg(not(feature = "no_function"))]
+impl ScriptFnDef {
+    /// Number of parameters that take a single argument each, i.e. excluding any rest parameter.
+    #[inline(always)]
+    #[must_use]
+    pub fn num_fixed_params(&self) -> usize {
+        if self.variadic {
+            self.params.len() - 1
+        } else {
+            self.params.len()
+        }
+    }
+    /// Number of parameters that must be passed, i.e. those without default values.
+    #[inline(always)]
+    #[must_use]
+    pub fn num_required_params(&self) -> usize {
+        self.num_fixed_params() - self.defaults.len()
+    }
+    /// Iterate through the positions and type annotations of all annotated parameters.
+    #[inline]
+    pub fn typed_params(&self) -> impl Iterator<Item = (usize, &Identifier)> {
+        self.param_types
+            .iter()
+            .take(self.num_fixed_params())
+            .enumerate()
+            .filter_map(|(i, typ)| typ.as_ref().map(|typ| (i, typ)))
+    }
+    /// Can this function be called with a particular number of arguments?
+    #[inline(always)]
+    #[must_use]
+    pub fn accepts_num_args(&self, num_args: usize) -> bool {
+        self.num_required_params() <= num_args && (self.variadic || num_args <= self.params.len())
+    }
+    /// Map a call with `num_positional` positional arguments, followed by arguments named
+    /// `names`, to this function's parameters.
+    ///
+    /// Returns the parameter index of each named argument, or `None` if a name is not a
+    /// parameter, names a parameter already passed positionally or the rest parameter, or if
+    /// a parameter without a default value is not passed.
+    #[must_use]
+    pub(crate) fn map_named_args(
+        &self,
+        num_positional: usize,
+        names: &[&str],
+    ) -> Option<StaticVec<usize>> {
+        let num_fixed = self.num_fixed_params();
+
+        // Extra positional arguments all go into the rest parameter
+        if num_positional > num_fixed {
+            return if self.variadic && names.is_empty() {
+                Some(StaticVec::new())
+            } else {
+                None
+            };
+        }
+        if num_positional + names.len() > num_fixed {
+            return None;
+        }
+
+        let mut passed: StaticVec<_> = (0..num_fixed).map(|i| i < num_positional).collect();
+
+        let indices = names
+            .iter()
+            .map(|&name| match self.params.iter().position(|p| p == name) {
+                Some(index) if index < num_fixed && !passed[index] => {
+                    passed[index] = true;
+                    Some(index)
+                }
+                _ => None,
+            })
+            .collect::<Option<StaticVec<_>>>()?;
+
+        if passed[..self.num_required_params()].iter().all(|&p| p) {
+            Some(indices)
+        } else {
+            None
+        }
+    }
+    /// Arrange argument values, the last `names.len()` of which are named, into parameter
+    /// order, filling in default values for parameters that are not passed.
+    ///
+    /// Extra positional arguments for any rest parameter are kept at the end.
+    ///
+    /// Returns `None` if the arguments do not fit this function's parameters.
+    #[must_use]
+    pub(crate) fn bind_named_args(
+        &self,
+        values: StaticVec<Dynamic>,
+        names: &[&str],
+    ) -> Option<StaticVec<Dynamic>> {
+        let num_positional = values.len() - names.len();
+        let indices = self.map_named_args(num_positional, names)?;
+        let num_required = self.num_required_params();
+
+        let mut values = values.into_iter();
+        let mut args: StaticVec<_> = values.by_ref().take(num_positional).collect();
+
+        args.extend((num_positional..self.num_fixed_params()).map(|i| {
+            if i >= num_required {
+                self.defaults[i - num_required].clone()
+            } else {
+                Dynamic::UNIT
+            }
+        }));
+
+        values
+            .zip(indices)
+            .for_each(|(value, index)| args[index] = value);
+
+        Some(args)
+    }
+}
+
+/// _(INTERNALS)_ An `enum` declared in a script.
+/// Exported under the `internals` feature only.
+///
+/// Not available under `no_function`.
+///
+/// # Volatile Data Structure
+///
+/// This type is volatile and may change.
+#[cfg(not(feature = "no_function"))]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct EnumDef {
+    /// Name of the `enum`.
+    pub name: Identifier,
+    /// Variants in declaration order, each with the names of its fields.
+    pub variants: StaticVec<(Identifier, StaticVec<Identifier>)>,
+}
+
 /// A type containing the metadata of a script-defined function.
 ///
 /// Not available under `no_function`.
@@ -842,6 +1003,115 @@ impl fmt::Debug for Ident {
     }
 }
 
+/// _(INTERNALS)_ A destructuring pattern for `let`/`const` statements, `for` loop variables and
+/// function parameters.
+/// Exported under the `internals` feature only.
+///
+/// # Volatile Data Structure
+///
+/// This type is volatile and may change.
+#[derive(Debug, Clone, Hash)]
+pub enum Pattern {
+    /// `_` - value is discarded.
+    Ignore(Position),
+    /// A variable.
+    Var(Ident),
+    /// `[` pattern `,` ... `,` `..` rest `]`
+    #[cfg(not(feature = "no_index"))]
+    Array(Box<(StaticVec<Pattern>, Option<Pattern>)>, Position),
+    /// `#{` property `:` pattern `,` ... `,` `..` rest `}`
+    #[cfg(not(feature = "no_object"))]
+    Map(
+        Box<(StaticVec<(Ident, Pattern)>, Option<Pattern>)>,
+        Position,
+    ),
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_rest(
+            f: &mut fmt::Formatter<'_>,
+            rest: &Option<Pattern>,
+            first: bool,
+        ) -> fmt::Result {
+            match rest {
+                Some(rest) if first => write!(f, "..{}", rest),
+                Some(rest) => write!(f, ", ..{}", rest),
+                None => Ok(()),
+            }
+        }
+
+        match self {
+            Self::Ignore(_) => f.write_str("_"),
+            Self::Var(x) => f.write_str(&x.name),
+            #[cfg(not(feature = "no_index"))]
+            Self::Array(x, _) => {
+                f.write_str("[")?;
+                for (i, item) in x.0.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write_rest(f, &x.1, x.0.is_empty())?;
+                f.write_str("]")
+            }
+            #[cfg(not(feature = "no_object"))]
+            Self::Map(x, _) => {
+                f.write_str("#{")?;
+                for (i, (prop, item)) in x.0.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    match item {
+                        Self::Var(v) if v.name == prop.name => write!(f, "{}", item)?,
+                        _ => write!(f, "{}: {}", prop.name, item)?,
+                    }
+                }
+                write_rest(f, &x.1, x.0.is_empty())?;
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl Pattern {
+    /// Get the [position][Position] of this pattern.
+    #[must_use]
+    pub const fn position(&self) -> Position {
+        match self {
+            Self::Ignore(pos) => *pos,
+            Self::Var(x) => x.pos,
+            #[cfg(not(feature = "no_index"))]
+            Self::Array(_, pos) => *pos,
+            #[cfg(not(feature = "no_object"))]
+            Self::Map(_, pos) => *pos,
+        }
+    }
+    /// Call a function on each variable bound by this pattern, in the order in which they are
+    /// declared.
+    pub fn for_each_var<'a>(&'a self, f: &mut impl FnMut(&'a Ident)) {
+        match self {
+            Self::Ignore(_) => (),
+            Self::Var(x) => f(x),
+            #[cfg(not(feature = "no_index"))]
+            Self::Array(x, _) => {
+                x.0.iter().for_each(|p| p.for_each_var(f));
+                if let Some(ref rest) = x.1 {
+                    rest.for_each_var(f);
+                }
+            }
+            #[cfg(not(feature = "no_object"))]
+            Self::Map(x, _) => {
+                x.0.iter().for_each(|(_, p)| p.for_each_var(f));
+                if let Some(ref rest) = x.1 {
+                    rest.for_each_var(f);
+                }
+            }
+        }
+    }
+}
+
 /// _(INTERNALS)_ A type encapsulating the mode of a `return`/`throw` statement.
 /// Exported under the `internals` feature only.
 ///
@@ -955,6 +1225,193 @@ impl From<StmtBlock> for Stmt {
     }
 }
 
+/// _(INTERNALS)_ The cases of a `switch` statement.
+/// Exported under the `internals` feature only.
+///
+/// Each case block is stored once in `blocks`, in source order, together with its optional
+/// condition.  Literal values, integer ranges and pure conditions refer to blocks by index,
+/// so a case with multiple values (e.g. `1 | 2 | 3 => ...`) shares a single block.
+///
+/// # Volatile Data Structure
+///
+/// This type is volatile and may change.
+#[derive(Debug, Clone, Hash, Default)]
+pub struct SwitchCases {
+    /// Case blocks (with optional conditions) in source order.
+    pub blocks: StaticVec<(Option<Expr>, StmtBlock)>,
+    /// Hashed literal values, mapping to indices into `blocks`.
+    pub cases: BTreeMap<u64, StaticVec<usize>>,
+    /// Integer ranges (start, end, inclusive?), mapping to indices into `blocks`.
+    pub ranges: StaticVec<(INT, INT, bool, usize)>,
+    /// Indices into `blocks` of pure-condition cases (`if` condition `=>` ...).
+    pub conditions: StaticVec<usize>,
+    /// Patterns matching variants of script-defined `enum`'s, mapping to indices into `blocks`.
+    pub variants: StaticVec<VariantPattern>,
+    /// Default case.
+    pub def_case: StmtBlock,
+}
+
+impl SwitchCases {
+    /// Get the value of an integer, or of a big integer that fits into an [`INT`].
+    #[inline]
+    #[must_use]
+    fn int_value(value: &Dynamic) -> Option<INT> {
+        let n = value.as_int().ok();
+
+        #[cfg(feature = "bigint")]
+        let n = n.or_else(|| {
+            value
+                .read_lock::<num_bigint::BigInt>()
+                .and_then(|n| INT::try_from(&*n).ok())
+        });
+
+        n
+    }
+    /// Hash a value for lookup in `cases`.
+    ///
+    /// A big integer that fits into an [`INT`] hashes the same as that [`INT`], so it matches
+    /// the same integer literal cases.
+    #[must_use]
+    pub fn hash_value(value: &Dynamic) -> u64 {
+        let hasher = &mut get_hasher();
+
+        match Self::int_value(value) {
+            Some(n) => Dynamic::from(n).hash(hasher),
+            None => value.hash(hasher),
+        }
+
+        hasher.finish()
+    }
+    /// Get the indices of all case blocks that may match a value, in source order.
+    ///
+    /// The conditions of these blocks, if any, must still be checked in order.
+    #[must_use]
+    pub fn candidates(&self, value: &Dynamic) -> StaticVec<usize> {
+        let mut list = StaticVec::new();
+
+        // Non-hashable values never match any specific literal or range
+        if value.is_hashable() {
+            if let Some(indices) = self.cases.get(&Self::hash_value(value)) {
+                list.extend(indices.iter().cloned());
+            }
+
+            if !self.ranges.is_empty() {
+                if let Some(value) = Self::int_value(value) {
+                    list.extend(self.ranges.iter().filter_map(
+                        |&(start, end, inclusive, index)| {
+                            let in_range = if inclusive {
+                                (start..=end).contains(&value)
+                            } else {
+                                (start..end).contains(&value)
+                            };
+                            if in_range {
+                                Some(index)
+                            } else {
+                                None
+                            }
+                        },
+                    ));
+                }
+            }
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        if !self.variants.is_empty() {
+            if let Some(value) = value.read_lock::<crate::EnumValue>() {
+                list.extend(
+                    self.variants
+                        .iter()
+                        .filter(|p| p.matches(&value))
+                        .map(|p| p.index),
+                );
+            }
+        }
+
+        list.extend(self.conditions.iter().cloned());
+
+        if list.len() > 1 {
+            list.sort_unstable();
+            list.dedup();
+        }
+
+        list
+    }
+}
+
+/// _(INTERNALS)_ A `switch` case matching a variant of a script-defined `enum`,
+/// e.g. `Shape::Rect(w, _)` or `Empty`.
+/// Exported under the `internals` feature only.
+///
+/// # Volatile Data Structure
+///
+/// This type is volatile and may change.
+#[derive(Debug, Clone, Hash)]
+pub struct VariantPattern {
+    /// Name of the `enum` declaring the variant.
+    pub enum_name: Identifier,
+    /// Name of the variant.
+    pub name: Identifier,
+    /// Variables bound to the fields of the variant, in order (`None` for `_`).
+    pub fields: StaticVec<Option<Identifier>>,
+    /// Index into the blocks of the `switch` statement.
+    pub index: usize,
+    /// [Position] of the pattern.
+    pub pos: Position,
+}
+
+impl VariantPattern {
+    /// Get the variables bound to the fields of the variant, in order.
+    #[inline(always)]
+    pub fn bindings(&self) -> impl Iterator<Item = &Identifier> {
+        self.fields.iter().flatten()
+    }
+    /// Does a value match the variant?
+    ///
+    /// Variants of other `enum`'s never match, even when they have the same name.
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    #[must_use]
+    pub fn matches(&self, value: &crate::EnumValue) -> bool {
+        self.name == value.variant_name()
+            && self.enum_name == value.enum_name()
+            && self.fields.len() == value.fields().len()
+    }
+}
+
+/// _(INTERNALS)_ A `catch` clause of a `try` statement.
+/// Exported under the `internals` feature only.
+///
+/// # Volatile Data Structure
+///
+/// This type is volatile and may change.
+#[derive(Debug, Clone, Hash)]
+pub struct CatchClause {
+    /// Variable holding the error value, if any.
+    pub var: Option<Ident>,
+    /// Condition on the error value, if any.
+    ///
+    /// The clause is skipped when the condition evaluates to `false`.
+    pub condition: Option<Expr>,
+    /// Body of the clause.
+    pub body: StmtBlock,
+}
+
+/// _(INTERNALS)_ The blocks of a `try` statement.
+/// Exported under the `internals` feature only.
+///
+/// # Volatile Data Structure
+///
+/// This type is volatile and may change.
+#[derive(Debug, Clone, Hash)]
+pub struct TryCatchBlock {
+    /// The `try` block.
+    pub try_block: StmtBlock,
+    /// `catch` clauses, tried in order.
+    pub catch_clauses: StaticVec<CatchClause>,
+    /// The `finally` block, which is empty if there is none.
+    pub finally_block: StmtBlock,
+}
+
 /// _(INTERNALS)_ A statement.
 /// Exported under the `internals` feature only.
 ///
@@ -967,22 +1424,20 @@ pub enum Stmt {
     Noop(Position),
     /// `if` expr `{` stmt `}` `else` `{` stmt `}`
     If(Expr, Box<(StmtBlock, StmtBlock)>, Position),
-    /// `switch` expr `if` condition `{` literal or _ `=>` stmt `,` ... `}`
-    Switch(
-        Expr,
-        Box<(BTreeMap<u64, Box<(Option<Expr>, StmtBlock)>>, StmtBlock)>,
-        Position,
-    ),
+    /// `switch` expr `{` literal `|` range `|` ... `if` condition `=>` stmt `,` ... `}`
+    Switch(Expr, Box<SwitchCases>, Position),
     /// `while` expr `{` stmt `}`
     While(Expr, Box<StmtBlock>, Position),
     /// `do` `{` stmt `}` `while`|`until` expr
     Do(Box<StmtBlock>, Expr, bool, Position),
     /// `for` `(` id `,` counter `)` `in` expr `{` stmt `}`
     For(Expr, Box<(Ident, Option<Ident>, StmtBlock)>, Position),
-    /// \[`export`\] `let` id `=` expr
-    Let(Expr, Box<Ident>, bool, Position),
-    /// \[`export`\] `const` id `=` expr
-    Const(Expr, Box<Ident>, bool, Position),
+    /// \[`export`\] `let` id \[`:` type\] `=` expr
+    Let(Expr, Box<(Ident, Option<Identifier>)>, bool, Position),
+    /// \[`export`\] `const` id \[`:` type\] `=` expr
+    Const(Expr, Box<(Ident, Option<Identifier>)>, bool, Position),
+    /// \[`export`\] `let`|`const` pattern `=` expr
+    Destructure(Expr, Box<Pattern>, AccessMode, bool, Position),
     /// expr op`=` expr
     Assignment(Box<(Expr, Option<OpAssignment<'static>>, Expr)>, Position),
     /// func `(` expr `,` ... `)`
@@ -992,8 +1447,9 @@ pub enum Stmt {
     FnCall(Box<FnCallExpr>, Position),
     /// `{` stmt`;` ... `}`
     Block(Box<[Stmt]>, Position),
-    /// `try` `{` stmt; ... `}` `catch` `(` var `)` `{` stmt; ... `}`
-    TryCatch(Box<(StmtBlock, Option<Ident>, StmtBlock)>, Position),
+    /// `try` `{` stmt; ... `}` `catch` `(` var `)` \[`if` condition\] `{` stmt; ... `}` ...
+    /// \[`finally` `{` stmt; ... `}`\]
+    TryCatch(Box<TryCatchBlock>, Position),
     /// [expression][Expr]
     Expr(Expr),
     /// `continue`
@@ -1002,6 +1458,11 @@ pub enum Stmt {
     Break(Position),
     /// `return`/`throw`
     Return(ReturnType, Option<Expr>, Position),
+    /// `yield`
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    Yield(Option<Expr>, Position),
     /// `import` expr `as` var
     ///
     /// Not available under `no_module`.
@@ -1068,10 +1529,14 @@ impl Stmt {
             | Self::Return(_, _, pos)
             | Self::Let(_, _, _, pos)
             | Self::Const(_, _, _, pos)
+            | Self::Destructure(_, _, _, _, pos)
             | Self::TryCatch(_, pos) => *pos,
 
             Self::Expr(x) => x.position(),
 
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(_, pos) => *pos,
+
             #[cfg(not(feature = "no_module"))]
             Self::Import(_, _, pos) => *pos,
             #[cfg(not(feature = "no_module"))]
@@ -1098,12 +1563,16 @@ impl Stmt {
             | Self::Return(_, _, pos)
             | Self::Let(_, _, _, pos)
             | Self::Const(_, _, _, pos)
+            | Self::Destructure(_, _, _, _, pos)
             | Self::TryCatch(_, pos) => *pos = new_pos,
 
             Self::Expr(x) => {
                 x.set_position(new_pos);
             }
 
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(_, pos) => *pos = new_pos,
+
             #[cfg(not(feature = "no_module"))]
             Self::Import(_, _, pos) => *pos = new_pos,
             #[cfg(not(feature = "no_module"))]
@@ -1133,11 +1602,15 @@ impl Stmt {
 
             Self::Let(_, _, _, _)
             | Self::Const(_, _, _, _)
+            | Self::Destructure(_, _, _, _, _)
             | Self::Assignment(_, _)
             | Self::Continue(_)
             | Self::Break(_)
             | Self::Return(_, _, _) => false,
 
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(_, _) => false,
+
             #[cfg(not(feature = "no_module"))]
             Self::Import(_, _, _) | Self::Export(_, _) => false,
 
@@ -1161,6 +1634,7 @@ impl Stmt {
 
             Self::Let(_, _, _, _)
             | Self::Const(_, _, _, _)
+            | Self::Destructure(_, _, _, _, _)
             | Self::Assignment(_, _)
             | Self::FnCall(_, _)
             | Self::Expr(_)
@@ -1169,6 +1643,9 @@ impl Stmt {
             | Self::Break(_)
             | Self::Return(_, _, _) => false,
 
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(_, _) => false,
+
             #[cfg(not(feature = "no_module"))]
             Self::Import(_, _, _) | Self::Export(_, _) => false,
 
@@ -1191,11 +1668,11 @@ impl Stmt {
             }
             Self::Switch(expr, x, _) => {
                 expr.is_pure()
-                    && x.0.values().all(|block| {
-                        block.0.as_ref().map(Expr::is_pure).unwrap_or(true)
-                            && (block.1).0.iter().all(Stmt::is_pure)
+                    && x.blocks.iter().all(|(condition, block)| {
+                        condition.as_ref().map(Expr::is_pure).unwrap_or(true)
+                            && block.0.iter().all(Stmt::is_pure)
                     })
-                    && (x.1).0.iter().all(Stmt::is_pure)
+                    && x.def_case.0.iter().all(Stmt::is_pure)
             }
             Self::While(condition, block, _) | Self::Do(block, condition, _, _) => {
                 condition.is_pure() && block.0.iter().all(Stmt::is_pure)
@@ -1203,12 +1680,20 @@ impl Stmt {
             Self::For(iterable, x, _) => iterable.is_pure() && (x.2).0.iter().all(Stmt::is_pure),
             Self::Let(_, _, _, _)
             | Self::Const(_, _, _, _)
+            | Self::Destructure(_, _, _, _, _)
             | Self::Assignment(_, _)
             | Self::FnCall(_, _) => false,
             Self::Block(block, _) => block.iter().all(|stmt| stmt.is_pure()),
             Self::Continue(_) | Self::Break(_) | Self::Return(_, _, _) => false,
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(_, _) => false,
             Self::TryCatch(x, _) => {
-                (x.0).0.iter().all(Stmt::is_pure) && (x.2).0.iter().all(Stmt::is_pure)
+                x.try_block.iter().all(Stmt::is_pure)
+                    && x.catch_clauses.iter().all(|c| {
+                        c.condition.as_ref().map_or(true, Expr::is_pure)
+                            && c.body.iter().all(Stmt::is_pure)
+                    })
+                    && x.finally_block.iter().all(Stmt::is_pure)
             }
 
             #[cfg(not(feature = "no_module"))]
@@ -1240,6 +1725,23 @@ impl Stmt {
             _ => self.is_pure(),
         }
     }
+    /// Does this statement declare new variables in the current scope?
+    ///
+    /// Currently this is only true for `let`, `const` and `import` statements.
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_variable_declaration(&self) -> bool {
+        match self {
+            Self::Let(_, _, _, _) | Self::Const(_, _, _, _) | Self::Destructure(_, _, _, _, _) => {
+                true
+            }
+
+            #[cfg(not(feature = "no_module"))]
+            Self::Import(_, _, _) => true,
+
+            _ => false,
+        }
+    }
     /// Does this statement break the current control flow through the containing block?
     ///
     /// Currently this is only true for `return`, `throw`, `break` and `continue`.
@@ -1253,6 +1755,31 @@ impl Stmt {
             _ => false,
         }
     }
+    /// Does this statement contain a `yield` statement, not counting those inside closures?
+    ///
+    /// `yield` can only appear in statement blocks, so expressions are not searched.
+    #[cfg(not(feature = "no_function"))]
+    #[must_use]
+    pub fn contains_yield(&self) -> bool {
+        match self {
+            Self::Yield(_, _) => true,
+            Self::Block(x, _) => x.iter().any(Stmt::contains_yield),
+            Self::If(_, x, _) => (x.0)
+                .0
+                .iter()
+                .chain((x.1).0.iter())
+                .any(Stmt::contains_yield),
+            Self::While(_, x, _) | Self::Do(x, _, _, _) => x.0.iter().any(Stmt::contains_yield),
+            Self::For(_, x, _) => (x.2).0.iter().any(Stmt::contains_yield),
+            Self::Switch(_, x, _) => x
+                .blocks
+                .iter()
+                .flat_map(|(_, block)| block.0.iter())
+                .chain(x.def_case.0.iter())
+                .any(Stmt::contains_yield),
+            _ => false,
+        }
+    }
     /// Recursively walk this statement.
     /// Return `false` from the callback to terminate the walk.
     pub fn walk<'a>(
@@ -1268,7 +1795,7 @@ impl Stmt {
         }
 
         match self {
-            Self::Let(e, _, _, _) | Self::Const(e, _, _, _) => {
+            Self::Let(e, _, _, _) | Self::Const(e, _, _, _) | Self::Destructure(e, _, _, _, _) => {
                 if !e.walk(path, on_node) {
                     return false;
                 }
@@ -1292,17 +1819,21 @@ impl Stmt {
                 if !e.walk(path, on_node) {
                     return false;
                 }
-                for b in x.0.values() {
-                    if !b.0.as_ref().map(|e| e.walk(path, on_node)).unwrap_or(true) {
+                for (condition, block) in x.blocks.iter() {
+                    if !condition
+                        .as_ref()
+                        .map(|e| e.walk(path, on_node))
+                        .unwrap_or(true)
+                    {
                         return false;
                     }
-                    for s in &(b.1).0 {
+                    for s in block.iter() {
                         if !s.walk(path, on_node) {
                             return false;
                         }
                     }
                 }
-                for s in &(x.1).0 {
+                for s in x.def_case.iter() {
                     if !s.walk(path, on_node) {
                         return false;
                     }
@@ -1351,12 +1882,24 @@ impl Stmt {
                 }
             }
             Self::TryCatch(x, _) => {
-                for s in &(x.0).0 {
+                for s in x.try_block.iter() {
                     if !s.walk(path, on_node) {
                         return false;
                     }
                 }
-                for s in &(x.2).0 {
+                for c in x.catch_clauses.iter() {
+                    if let Some(ref e) = c.condition {
+                        if !e.walk(path, on_node) {
+                            return false;
+                        }
+                    }
+                    for s in c.body.iter() {
+                        if !s.walk(path, on_node) {
+                            return false;
+                        }
+                    }
+                }
+                for s in x.finally_block.iter() {
                     if !s.walk(path, on_node) {
                         return false;
                     }
@@ -1367,6 +1910,8 @@ impl Stmt {
                     return false;
                 }
             }
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(Some(e), _) if !e.walk(path, on_node) => return false,
             #[cfg(not(feature = "no_module"))]
             Self::Import(e, _, _) => {
                 if !e.walk(path, on_node) {
@@ -1556,8 +2101,13 @@ pub struct FnCallExpr {
     pub constants: smallvec::SmallVec<[Dynamic; 2]>,
     /// Function name.
     pub name: Identifier,
+    /// Names of named arguments, which are always the last `named_args.len()` items in `args`.
+    pub named_args: StaticVec<Ident>,
     /// Does this function call capture the parent scope?
     pub capture: bool,
+    /// Is this function call in tail position of a script-defined function
+    /// (i.e. returned by `return` or making up the last expression of the function body)?
+    pub is_tail_call: bool,
 }
 
 impl FnCallExpr {
@@ -1729,6 +2279,11 @@ pub enum Expr {
         Box<(StaticVec<(Ident, Expr)>, BTreeMap<Identifier, Dynamic>)>,
         Position,
     ),
+    /// `...` expr
+    ///
+    /// Only valid as a function call argument, an item in an array literal, or a property
+    /// (with an empty name) in an object map literal.
+    Spread(Box<Expr>, Position),
     /// ()
     Unit(Position),
     /// Variable access - optional short index, position, (optional index, optional (hash, modules), variable name)
@@ -1759,14 +2314,25 @@ pub enum Expr {
     Stmt(Box<StmtBlock>),
     /// func `(` expr `,` ... `)`
     FnCall(Box<FnCallExpr>, Position),
-    /// lhs `.` rhs
-    Dot(Box<BinaryExpr>, Position),
-    /// expr `[` expr `]`
-    Index(Box<BinaryExpr>, Position),
+    /// Variant of a script-defined `enum` - (declaration, variant index, field values)
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    EnumVariant(Box<(Shared<EnumDef>, usize, StaticVec<Expr>)>, Position),
+    /// lhs `.` rhs | lhs `?.` rhs
+    ///
+    /// The boolean flag is `true` for optional chaining (`?.`).
+    Dot(Box<BinaryExpr>, bool, Position),
+    /// expr `[` expr `]` | expr `?[` expr `]`
+    ///
+    /// The boolean flag is `true` for optional chaining (`?[`).
+    Index(Box<BinaryExpr>, bool, Position),
     /// lhs `&&` rhs
     And(Box<BinaryExpr>, Position),
     /// lhs `||` rhs
     Or(Box<BinaryExpr>, Position),
+    /// lhs `??` rhs
+    Coalesce(Box<BinaryExpr>, Position),
     /// Custom syntax
     Custom(Box<CustomExpr>, Position),
 }
@@ -1806,6 +2372,7 @@ impl fmt::Debug for Expr {
                     .entries(x.0.iter().map(|(k, v)| (k, v)))
                     .finish()
             }
+            Self::Spread(x, _) => f.debug_tuple("Spread").field(x).finish(),
             Self::Variable(i, _, x) => {
                 f.write_str("Variable(")?;
                 match x.1 {
@@ -1834,17 +2401,32 @@ impl fmt::Debug for Expr {
                 if !x.constants.is_empty() {
                     ff.field("constants", &x.constants);
                 }
+                if !x.named_args.is_empty() {
+                    ff.field("named_args", &x.named_args);
+                }
                 if x.capture {
                     ff.field("capture", &x.capture);
                 }
                 ff.finish()
             }
-            Self::Dot(x, pos) | Self::Index(x, pos) | Self::And(x, pos) | Self::Or(x, pos) => {
+            #[cfg(not(feature = "no_function"))]
+            Self::EnumVariant(x, _) => {
+                write!(f, "EnumVariant({}::{})", x.0.name, x.0.variants[x.1].0)?;
+                f.debug_list().entries(x.2.iter()).finish()
+            }
+            Self::Dot(x, _, pos)
+            | Self::Index(x, _, pos)
+            | Self::And(x, pos)
+            | Self::Or(x, pos)
+            | Self::Coalesce(x, pos) => {
                 let op_name = match self {
-                    Self::Dot(_, _) => "Dot",
-                    Self::Index(_, _) => "Index",
+                    Self::Dot(_, false, _) => "Dot",
+                    Self::Dot(_, true, _) => "OptionalDot",
+                    Self::Index(_, false, _) => "Index",
+                    Self::Index(_, true, _) => "OptionalIndex",
                     Self::And(_, _) => "And",
                     Self::Or(_, _) => "Or",
+                    Self::Coalesce(_, _) => "Coalesce",
                     _ => unreachable!(),
                 };
 
@@ -1890,6 +2472,7 @@ impl Expr {
             }
 
             #[cfg(not(feature = "no_object"))]
+            #[cfg(not(feature = "preserve_order"))]
             Self::Map(x, _) if self.is_constant() => {
                 let mut map = x.1.clone();
                 x.0.iter().for_each(|(k, v)| {
@@ -1900,10 +2483,53 @@ impl Expr {
                 });
                 Dynamic::from_map(map)
             }
+            #[cfg(not(feature = "no_object"))]
+            #[cfg(feature = "preserve_order")]
+            Self::Map(x, _) if self.is_constant() => Dynamic::from_map(
+                x.0.iter()
+                    .map(|(k, v)| {
+                        let value = v.get_literal_value().expect(
+                            "never fails because a constant map always has a constant value",
+                        );
+                        (k.name.clone(), value)
+                    })
+                    .collect(),
+            ),
 
             _ => return None,
         })
     }
+    /// Get the bounds of a literal integer range expression, i.e. `start..end` or `start..=end`
+    /// with integer literals as bounds, as `(start, end, inclusive)`.
+    ///
+    /// Returns [`None`] if the expression is not a literal integer range.
+    #[must_use]
+    pub fn get_literal_range(&self) -> Option<(INT, INT, bool)> {
+        match self {
+            Self::DynamicConstant(x, _) => x
+                .read_lock::<ExclusiveRange>()
+                .map(|range| (range.start, range.end, false))
+                .or_else(|| {
+                    x.read_lock::<InclusiveRange>()
+                        .map(|range| (*range.start(), *range.end(), true))
+                }),
+            Self::FnCall(x, _) if !x.is_qualified() && x.args.len() == 2 => {
+                let inclusive = match x.name.as_str() {
+                    ".." => false,
+                    "..=" => true,
+                    _ => return None,
+                };
+
+                match (&x.args[0], &x.args[1]) {
+                    (Self::IntegerConstant(start, _), Self::IntegerConstant(end, _)) => {
+                        Some((*start, *end, inclusive))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
     /// Create an [`Expr`] from a [`Dynamic`] value.
     #[inline]
     #[must_use]
@@ -1955,18 +2581,24 @@ impl Expr {
             | Self::StringConstant(_, pos)
             | Self::Array(_, pos)
             | Self::Map(_, pos)
+            | Self::Spread(_, pos)
             | Self::Variable(_, pos, _)
             | Self::Stack(_, pos)
             | Self::FnCall(_, pos)
             | Self::Custom(_, pos)
             | Self::InterpolatedString(_, pos) => *pos,
 
+            #[cfg(not(feature = "no_function"))]
+            Self::EnumVariant(_, pos) => *pos,
+
             Self::Property(x) => (x.2).1,
             Self::Stmt(x) => x.1,
 
-            Self::And(x, _) | Self::Or(x, _) | Self::Dot(x, _) | Self::Index(x, _) => {
-                x.lhs.position()
-            }
+            Self::And(x, _)
+            | Self::Or(x, _)
+            | Self::Coalesce(x, _)
+            | Self::Dot(x, _, _)
+            | Self::Index(x, _, _) => x.lhs.position(),
         }
     }
     /// Override the [position][Position] of the expression.
@@ -1984,16 +2616,21 @@ impl Expr {
             | Self::StringConstant(_, pos)
             | Self::Array(_, pos)
             | Self::Map(_, pos)
+            | Self::Spread(_, pos)
             | Self::And(_, pos)
             | Self::Or(_, pos)
-            | Self::Dot(_, pos)
-            | Self::Index(_, pos)
+            | Self::Coalesce(_, pos)
+            | Self::Dot(_, _, pos)
+            | Self::Index(_, _, pos)
             | Self::Variable(_, pos, _)
             | Self::Stack(_, pos)
             | Self::FnCall(_, pos)
             | Self::Custom(_, pos)
             | Self::InterpolatedString(_, pos) => *pos = new_pos,
 
+            #[cfg(not(feature = "no_function"))]
+            Self::EnumVariant(_, pos) => *pos = new_pos,
+
             Self::Property(x) => (x.2).1 = new_pos,
             Self::Stmt(x) => x.1 = new_pos,
         }
@@ -2011,7 +2648,15 @@ impl Expr {
 
             Self::Map(x, _) => x.0.iter().map(|(_, v)| v).all(Self::is_pure),
 
-            Self::And(x, _) | Self::Or(x, _) => x.lhs.is_pure() && x.rhs.is_pure(),
+            #[cfg(not(feature = "no_function"))]
+            Self::EnumVariant(x, _) => x.2.iter().all(Self::is_pure),
+
+            // Not pure because the number of items it expands into is unknown
+            Self::Spread(_, _) => false,
+
+            Self::And(x, _) | Self::Or(x, _) | Self::Coalesce(x, _) => {
+                x.lhs.is_pure() && x.rhs.is_pure()
+            }
 
             Self::Stmt(x) => x.0.iter().all(Stmt::is_pure),
 
@@ -2058,7 +2703,7 @@ impl Expr {
     pub const fn is_valid_postfix(&self, token: &Token) -> bool {
         match token {
             #[cfg(not(feature = "no_object"))]
-            Token::Period => return true,
+            Token::Period | Token::Elvis => return true,
             _ => (),
         }
 
@@ -2071,6 +2716,8 @@ impl Expr {
             | Self::CharConstant(_, _)
             | Self::And(_, _)
             | Self::Or(_, _)
+            | Self::Coalesce(_, _)
+            | Self::Spread(_, _)
             | Self::Unit(_) => false,
 
             Self::IntegerConstant(_, _)
@@ -2078,18 +2725,21 @@ impl Expr {
             | Self::InterpolatedString(_, _)
             | Self::FnCall(_, _)
             | Self::Stmt(_)
-            | Self::Dot(_, _)
-            | Self::Index(_, _)
+            | Self::Dot(_, _, _)
+            | Self::Index(_, _, _)
             | Self::Array(_, _)
             | Self::Map(_, _) => match token {
                 #[cfg(not(feature = "no_index"))]
-                Token::LeftBracket => true,
+                Token::LeftBracket | Token::QuestionBracket => true,
                 _ => false,
             },
 
+            #[cfg(not(feature = "no_function"))]
+            Self::EnumVariant(_, _) => false,
+
             Self::Variable(_, _, _) => match token {
                 #[cfg(not(feature = "no_index"))]
-                Token::LeftBracket => true,
+                Token::LeftBracket | Token::QuestionBracket => true,
                 Token::LeftParen => true,
                 Token::Bang => true,
                 Token::DoubleColon => true,
@@ -2098,7 +2748,7 @@ impl Expr {
 
             Self::Property(_) => match token {
                 #[cfg(not(feature = "no_index"))]
-                Token::LeftBracket => true,
+                Token::LeftBracket | Token::QuestionBracket => true,
                 Token::LeftParen => true,
                 _ => false,
             },
@@ -2137,6 +2787,14 @@ impl Expr {
                     }
                 }
             }
+            #[cfg(not(feature = "no_function"))]
+            Self::EnumVariant(x, _) => {
+                for e in &x.2 {
+                    if !e.walk(path, on_node) {
+                        return false;
+                    }
+                }
+            }
             Self::Map(x, _) => {
                 for (_, e) in &x.0 {
                     if !e.walk(path, on_node) {
@@ -2144,7 +2802,12 @@ impl Expr {
                     }
                 }
             }
-            Self::Index(x, _) | Self::Dot(x, _) | Expr::And(x, _) | Expr::Or(x, _) => {
+            Self::Spread(x, _) if !x.walk(path, on_node) => return false,
+            Self::Index(x, _, _)
+            | Self::Dot(x, _, _)
+            | Expr::And(x, _)
+            | Expr::Or(x, _)
+            | Expr::Coalesce(x, _) => {
                 if !x.lhs.walk(path, on_node) {
                     return false;
                 }
@@ -21,6 +21,7 @@ fn eprint_error(input: &str, mut err: EvalAltResult) {
     }
 
     let lines: Vec<_> = input.split('\n').collect();
+    let call_stack = err.call_stack();
 
     // Print error
     let pos = err.take_position();
@@ -32,6 +33,15 @@ fn eprint_error(input: &str, mut err: EvalAltResult) {
         // Specific position
         eprint_line(&lines, pos, &err.to_string())
     }
+
+    // Print call stack
+    if !call_stack.is_empty() {
+        eprintln!("Call stack:");
+        call_stack
+            .iter()
+            .for_each(|frame| eprintln!("    at {}", frame));
+        eprintln!();
+    }
 }
 
 fn main() {
@@ -0,0 +1,1137 @@
+//! Module implementing the [`DateTime`] and [`Duration`] types.
+#![cfg(not(feature = "no_std"))]
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Neg,
+    str::FromStr,
+};
+
+/// Number of nanoseconds in a second.
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+/// Number of seconds in a day.
+const SECS_PER_DAY: i64 = 86_400;
+/// Number of nanoseconds in a day.
+const NANOS_PER_DAY: i128 = SECS_PER_DAY as i128 * NANOS_PER_SEC;
+/// Earliest year supported by [`DateTime`].
+const MIN_YEAR: i64 = -9999;
+/// Latest year supported by [`DateTime`].
+const MAX_YEAR: i64 = 9999;
+
+/// Full names of the months.
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+/// Full names of the days of the week, starting from Monday.
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Is a year a leap year?
+#[inline]
+#[must_use]
+const fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Get the number of days in a month.
+#[inline]
+#[must_use]
+const fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Calculate the number of days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+#[must_use]
+const fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let yoe = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 } as i64;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Calculate the date in the proleptic Gregorian calendar from the number of days since 1970-01-01.
+#[must_use]
+const fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Write a fraction of a second, using 3, 6 or 9 digits as needed, or nothing if it is zero.
+fn write_fraction(f: &mut impl fmt::Write, nanos: u32) -> fmt::Result {
+    if nanos == 0 {
+        Ok(())
+    } else if nanos % 1_000_000 == 0 {
+        write!(f, ".{:03}", nanos / 1_000_000)
+    } else if nanos % 1_000 == 0 {
+        write!(f, ".{:06}", nanos / 1_000)
+    } else {
+        write!(f, ".{:09}", nanos)
+    }
+}
+
+/// Write a fixed offset from UTC in the form `+hhmm`, or `+hh:mm` if `colon` is `true`.
+fn write_offset(f: &mut impl fmt::Write, offset: i32, colon: bool) -> fmt::Result {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.abs() / 60;
+    let sep = if colon { ":" } else { "" };
+    write!(f, "{}{:02}{}{:02}", sign, minutes / 60, sep, minutes % 60)
+}
+
+/// A signed span of time with nanosecond precision.
+/// Not available under `no_std`.
+///
+/// Named `duration` in scripts, where it is created via `days`, `hours`, `minutes`, `seconds`
+/// and `milliseconds`, or by subtracting one [`DateTime`] from another.
+///
+/// Formatted as an ISO-8601 duration, e.g. `P1DT2H30M`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Duration(i128);
+
+impl Duration {
+    /// A [`Duration`] of zero length.
+    pub const ZERO: Self = Self(0);
+
+    /// Create a [`Duration`] from a number of nanoseconds.
+    #[inline(always)]
+    #[must_use]
+    pub const fn from_nanos(nanos: i128) -> Self {
+        Self(nanos)
+    }
+    /// Create a [`Duration`] from a number of milliseconds.
+    #[inline(always)]
+    #[must_use]
+    pub const fn from_millis(millis: i64) -> Self {
+        Self(millis as i128 * 1_000_000)
+    }
+    /// Create a [`Duration`] from a number of seconds.
+    #[inline(always)]
+    #[must_use]
+    pub const fn from_secs(secs: i64) -> Self {
+        Self(secs as i128 * NANOS_PER_SEC)
+    }
+    /// Create a [`Duration`] from a number of minutes.
+    #[inline(always)]
+    #[must_use]
+    pub const fn from_mins(mins: i64) -> Self {
+        Self(mins as i128 * 60 * NANOS_PER_SEC)
+    }
+    /// Create a [`Duration`] from a number of hours.
+    #[inline(always)]
+    #[must_use]
+    pub const fn from_hours(hours: i64) -> Self {
+        Self(hours as i128 * 3600 * NANOS_PER_SEC)
+    }
+    /// Create a [`Duration`] from a number of days.
+    #[inline(always)]
+    #[must_use]
+    pub const fn from_days(days: i64) -> Self {
+        Self(days as i128 * NANOS_PER_DAY)
+    }
+    /// Create a [`Duration`] from a fractional number of seconds.
+    ///
+    /// Returns [`None`] if the number is not finite or too large.
+    #[inline]
+    #[must_use]
+    pub fn from_secs_f64(secs: f64) -> Option<Self> {
+        let nanos = (secs * NANOS_PER_SEC as f64).round();
+
+        if nanos.is_finite() && nanos.abs() < i128::MAX as f64 {
+            Some(Self(nanos as i128))
+        } else {
+            None
+        }
+    }
+    /// Get the total number of nanoseconds.
+    #[inline(always)]
+    #[must_use]
+    pub const fn as_nanos(&self) -> i128 {
+        self.0
+    }
+    /// Get the total number of whole milliseconds, rounded towards zero.
+    #[inline(always)]
+    #[must_use]
+    pub const fn as_millis(&self) -> i128 {
+        self.0 / 1_000_000
+    }
+    /// Get the total number of whole seconds, rounded towards zero.
+    #[inline(always)]
+    #[must_use]
+    pub const fn as_secs(&self) -> i128 {
+        self.0 / NANOS_PER_SEC
+    }
+    /// Get the total number of seconds as a floating-point number.
+    #[inline(always)]
+    #[must_use]
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0 as f64 / NANOS_PER_SEC as f64
+    }
+    /// Is the [`Duration`] negative?
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+    /// Get the absolute value of the [`Duration`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn abs(&self) -> Self {
+        Self(self.0.abs())
+    }
+    /// Add another [`Duration`], returning [`None`] on overflow.
+    #[inline(always)]
+    #[must_use]
+    pub const fn checked_add(self, other: Self) -> Option<Self> {
+        match self.0.checked_add(other.0) {
+            Some(n) => Some(Self(n)),
+            None => None,
+        }
+    }
+    /// Subtract another [`Duration`], returning [`None`] on overflow.
+    #[inline(always)]
+    #[must_use]
+    pub const fn checked_sub(self, other: Self) -> Option<Self> {
+        match self.0.checked_sub(other.0) {
+            Some(n) => Some(Self(n)),
+            None => None,
+        }
+    }
+    /// Multiply by a number, returning [`None`] on overflow.
+    #[inline(always)]
+    #[must_use]
+    pub const fn checked_mul(self, factor: i128) -> Option<Self> {
+        match self.0.checked_mul(factor) {
+            Some(n) => Some(Self(n)),
+            None => None,
+        }
+    }
+    /// Divide by a number, returning [`None`] if the divisor is zero.
+    #[inline(always)]
+    #[must_use]
+    pub const fn checked_div(self, divisor: i128) -> Option<Self> {
+        match self.0.checked_div(divisor) {
+            Some(n) => Some(Self(n)),
+            None => None,
+        }
+    }
+}
+
+impl Neg for Duration {
+    type Output = Self;
+
+    #[inline(always)]
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    #[inline(always)]
+    fn from(value: std::time::Duration) -> Self {
+        Self(value.as_nanos() as i128)
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < 0 {
+            f.write_str("-")?;
+        }
+        f.write_str("P")?;
+
+        let nanos = self.0.unsigned_abs();
+        let days = nanos / NANOS_PER_DAY as u128;
+        let secs = (nanos % NANOS_PER_DAY as u128 / NANOS_PER_SEC as u128) as u32;
+        let nanos = (nanos % NANOS_PER_SEC as u128) as u32;
+
+        if days > 0 {
+            write!(f, "{}D", days)?;
+        }
+        if secs == 0 && nanos == 0 {
+            return if days > 0 { Ok(()) } else { f.write_str("T0S") };
+        }
+
+        f.write_str("T")?;
+
+        if secs >= 3600 {
+            write!(f, "{}H", secs / 3600)?;
+        }
+        if secs % 3600 >= 60 {
+            write!(f, "{}M", secs % 3600 / 60)?;
+        }
+        if secs % 60 != 0 || nanos != 0 {
+            write!(f, "{}", secs % 60)?;
+            if nanos != 0 {
+                let fraction = format!("{:09}", nanos);
+                write!(f, ".{}", fraction.trim_end_matches('0'))?;
+            }
+            f.write_str("S")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Components of a [`DateTime`] in its own offset.
+struct Parts {
+    /// Number of days since 1970-01-01.
+    days: i64,
+    year: i64,
+    month: u32,
+    day: u32,
+    /// Number of seconds since midnight.
+    secs: u32,
+    nanos: u32,
+}
+
+/// A point in time, with nanosecond precision, together with a fixed offset from UTC.
+/// Not available under `no_std`.
+///
+/// Named `datetime` in scripts.
+///
+/// Dates follow the proleptic Gregorian calendar, between the years -9999 and 9999.
+/// Date and time components are reported in the fixed offset, but comparisons only consider the
+/// point in time, so the same instant in two different offsets compares equal.
+///
+/// Formatted as an ISO-8601 date/time, e.g. `2021-08-05T14:30:00Z` or `2021-08-05T16:30:00+02:00`.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{DateTime, Engine};
+///
+/// let engine = Engine::new();
+///
+/// let dt: DateTime = engine.eval(
+///     r#"
+///         let due = parse_datetime("2021-01-31T12:00:00Z");
+///         due.add_months(1) + days(2)
+///     "#,
+/// )?;
+///
+/// assert_eq!(dt.to_string(), "2021-03-02T12:00:00Z");
+/// assert_eq!(dt.format("%a %e %B %Y").unwrap(), "Tue  2 March 2021");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct DateTime {
+    /// Number of nanoseconds since 1970-01-01T00:00:00Z.
+    nanos: i128,
+    /// Offset from UTC, in seconds.
+    offset: i32,
+}
+
+impl PartialEq for DateTime {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.nanos == other.nanos
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.nanos.cmp(&other.nanos)
+    }
+}
+
+impl Hash for DateTime {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.nanos.hash(state);
+    }
+}
+
+impl fmt::Debug for DateTime {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DateTime({})", self)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = self.parts();
+
+        self.write_year(f, parts.year)?;
+        write!(
+            f,
+            "-{:02}-{:02}T{:02}:{:02}:{:02}",
+            parts.month,
+            parts.day,
+            parts.secs / 3600,
+            parts.secs % 3600 / 60,
+            parts.secs % 60
+        )?;
+        write_fraction(f, parts.nanos)?;
+
+        if self.offset == 0 {
+            f.write_str("Z")
+        } else {
+            write_offset(f, self.offset, true)
+        }
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = String;
+
+    #[inline(always)]
+    fn from_str(s: &str) -> Result<Self, String> {
+        Self::parse(s)
+    }
+}
+
+impl DateTime {
+    /// The UNIX epoch, i.e. 1970-01-01T00:00:00Z.
+    pub const UNIX_EPOCH: Self = Self {
+        nanos: 0,
+        offset: 0,
+    };
+
+    /// Get the current date/time in UTC.
+    #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
+    #[must_use]
+    pub fn now() -> Self {
+        let nanos = match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(d) => d.as_nanos() as i128,
+            Err(err) => -(err.duration().as_nanos() as i128),
+        };
+        Self { nanos, offset: 0 }
+    }
+    /// Create a [`DateTime`] in UTC from the number of nanoseconds since the UNIX epoch.
+    ///
+    /// Returns [`None`] if the date/time is out of range.
+    #[inline]
+    #[must_use]
+    pub fn from_unix_nanos(nanos: i128) -> Option<Self> {
+        let min = days_from_civil(MIN_YEAR, 1, 1) as i128 * NANOS_PER_DAY;
+        let max = days_from_civil(MAX_YEAR + 1, 1, 1) as i128 * NANOS_PER_DAY;
+
+        if nanos >= min && nanos < max {
+            Some(Self { nanos, offset: 0 })
+        } else {
+            None
+        }
+    }
+    /// Create a [`DateTime`] in UTC from the number of seconds since the UNIX epoch.
+    ///
+    /// Returns [`None`] if the date/time is out of range.
+    #[inline(always)]
+    #[must_use]
+    pub fn from_unix_timestamp(secs: i64) -> Option<Self> {
+        Self::from_unix_nanos(secs as i128 * NANOS_PER_SEC)
+    }
+    /// Create a [`DateTime`] in UTC at midnight of a date.
+    ///
+    /// Returns [`None`] if the date is invalid or out of range.
+    #[inline(always)]
+    #[must_use]
+    pub fn from_ymd(year: i64, month: u32, day: u32) -> Option<Self> {
+        Self::from_ymd_hms(year, month, day, 0, 0, 0)
+    }
+    /// Create a [`DateTime`] in UTC from a date and a time.
+    ///
+    /// Returns [`None`] if the date or time is invalid or out of range.
+    #[must_use]
+    pub fn from_ymd_hms(
+        year: i64,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+    ) -> Option<Self> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year)
+            || !(1..=12).contains(&month)
+            || day < 1
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return None;
+        }
+
+        let secs = hour * 3600 + minute * 60 + second;
+        Self::from_local(days_from_civil(year, month, day), secs, 0, 0)
+    }
+    /// Create a [`DateTime`] from the components in a particular offset.
+    #[inline]
+    #[must_use]
+    fn from_local(days: i64, secs: u32, nanos: u32, offset: i32) -> Option<Self> {
+        let nanos = (days as i128 * SECS_PER_DAY as i128 + secs as i128 - offset as i128)
+            * NANOS_PER_SEC
+            + nanos as i128;
+
+        Self::from_unix_nanos(nanos).map(|dt| Self { offset, ..dt })
+    }
+    /// Get the components in the offset of this [`DateTime`].
+    #[must_use]
+    fn parts(&self) -> Parts {
+        let local = self.nanos + self.offset as i128 * NANOS_PER_SEC;
+        let days = local.div_euclid(NANOS_PER_DAY) as i64;
+        let rem = local.rem_euclid(NANOS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+
+        Parts {
+            days,
+            year,
+            month,
+            day,
+            secs: (rem / NANOS_PER_SEC) as u32,
+            nanos: (rem % NANOS_PER_SEC) as u32,
+        }
+    }
+    /// Write a year in at least four digits.
+    fn write_year(&self, f: &mut impl fmt::Write, year: i64) -> fmt::Result {
+        if year < 0 {
+            write!(f, "-{:04}", -year)
+        } else {
+            write!(f, "{:04}", year)
+        }
+    }
+    /// Get the same point in time in another fixed offset from UTC, in seconds.
+    ///
+    /// Returns [`None`] if the offset is a day or more.
+    #[inline]
+    #[must_use]
+    pub fn with_offset(self, offset: i32) -> Option<Self> {
+        if offset.abs() < SECS_PER_DAY as i32 {
+            Some(Self { offset, ..self })
+        } else {
+            None
+        }
+    }
+    /// Get the same point in time in UTC.
+    #[inline(always)]
+    #[must_use]
+    pub const fn to_utc(self) -> Self {
+        Self { offset: 0, ..self }
+    }
+    /// Get the offset from UTC, in seconds.
+    #[inline(always)]
+    #[must_use]
+    pub const fn offset(&self) -> i32 {
+        self.offset
+    }
+    /// Get the number of nanoseconds since the UNIX epoch.
+    #[inline(always)]
+    #[must_use]
+    pub const fn unix_nanos(&self) -> i128 {
+        self.nanos
+    }
+    /// Get the number of whole seconds since the UNIX epoch, rounded down.
+    #[inline(always)]
+    #[must_use]
+    pub const fn unix_timestamp(&self) -> i64 {
+        self.nanos.div_euclid(NANOS_PER_SEC) as i64
+    }
+    /// Get the year.
+    #[inline(always)]
+    #[must_use]
+    pub fn year(&self) -> i64 {
+        self.parts().year
+    }
+    /// Get the month, starting from 1.
+    #[inline(always)]
+    #[must_use]
+    pub fn month(&self) -> u32 {
+        self.parts().month
+    }
+    /// Get the day of the month, starting from 1.
+    #[inline(always)]
+    #[must_use]
+    pub fn day(&self) -> u32 {
+        self.parts().day
+    }
+    /// Get the hour.
+    #[inline(always)]
+    #[must_use]
+    pub fn hour(&self) -> u32 {
+        self.parts().secs / 3600
+    }
+    /// Get the minute.
+    #[inline(always)]
+    #[must_use]
+    pub fn minute(&self) -> u32 {
+        self.parts().secs % 3600 / 60
+    }
+    /// Get the second.
+    #[inline(always)]
+    #[must_use]
+    pub fn second(&self) -> u32 {
+        self.parts().secs % 60
+    }
+    /// Get the fraction of the second, in nanoseconds.
+    #[inline(always)]
+    #[must_use]
+    pub fn nanosecond(&self) -> u32 {
+        self.parts().nanos
+    }
+    /// Get the ISO-8601 day of the week, from 1 (Monday) to 7 (Sunday).
+    #[inline(always)]
+    #[must_use]
+    pub fn weekday(&self) -> u32 {
+        (self.parts().days + 3).rem_euclid(7) as u32 + 1
+    }
+    /// Get the day of the year, starting from 1.
+    #[inline]
+    #[must_use]
+    pub fn day_of_year(&self) -> u32 {
+        let parts = self.parts();
+        (parts.days - days_from_civil(parts.year, 1, 1)) as u32 + 1
+    }
+    /// Add a [`Duration`], returning [`None`] if the result is out of range.
+    #[inline]
+    #[must_use]
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        let nanos = self.nanos.checked_add(duration.0)?;
+        Self::from_unix_nanos(nanos).map(|dt| Self {
+            offset: self.offset,
+            ..dt
+        })
+    }
+    /// Subtract a [`Duration`], returning [`None`] if the result is out of range.
+    #[inline]
+    #[must_use]
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        let nanos = self.nanos.checked_sub(duration.0)?;
+        Self::from_unix_nanos(nanos).map(|dt| Self {
+            offset: self.offset,
+            ..dt
+        })
+    }
+    /// Get the [`Duration`] from another [`DateTime`] to this one, which is negative if the other
+    /// [`DateTime`] is later.
+    #[inline(always)]
+    #[must_use]
+    pub const fn duration_since(&self, other: &Self) -> Duration {
+        Duration(self.nanos - other.nanos)
+    }
+    /// Add a number of calendar months, keeping the time of day.
+    ///
+    /// The day is clamped to the last day of the resulting month, so adding one month to
+    /// January 31 results in the last day of February.
+    ///
+    /// Returns [`None`] if the result is out of range.
+    #[must_use]
+    pub fn checked_add_months(self, months: i64) -> Option<Self> {
+        let parts = self.parts();
+        let total = (parts.year * 12 + parts.month as i64 - 1).checked_add(months)?;
+        let year = total.div_euclid(12);
+        let month = total.rem_euclid(12) as u32 + 1;
+
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return None;
+        }
+
+        let day = parts.day.min(days_in_month(year, month));
+        let days = days_from_civil(year, month, day);
+        Self::from_local(days, parts.secs, parts.nanos, self.offset)
+    }
+    /// Format the [`DateTime`] according to a `strftime`-like format string.
+    ///
+    /// The following specifiers are supported:
+    ///
+    /// | Specifier | Meaning                                              | Example                     |
+    /// |-----------|------------------------------------------------------|-----------------------------|
+    /// | `%Y`      | year, at least four digits                           | `2021`                      |
+    /// | `%y`      | year, last two digits                                | `21`                        |
+    /// | `%m`      | month, two digits                                    | `08`                        |
+    /// | `%b`/`%h` | abbreviated month name                               | `Aug`                       |
+    /// | `%B`      | full month name                                      | `August`                    |
+    /// | `%d`      | day of the month, two digits                         | `05`                        |
+    /// | `%e`      | day of the month, padded with a space                | ` 5`                        |
+    /// | `%j`      | day of the year, three digits                        | `217`                       |
+    /// | `%a`      | abbreviated weekday name                             | `Thu`                       |
+    /// | `%A`      | full weekday name                                    | `Thursday`                  |
+    /// | `%u`      | day of the week, from 1 (Monday) to 7 (Sunday)       | `4`                         |
+    /// | `%w`      | day of the week, from 0 (Sunday) to 6 (Saturday)     | `4`                         |
+    /// | `%H`      | hour, 24-hour clock, two digits                      | `14`                        |
+    /// | `%I`      | hour, 12-hour clock, two digits                      | `02`                        |
+    /// | `%p`      | `AM` or `PM`                                         | `PM`                        |
+    /// | `%M`      | minute, two digits                                   | `30`                        |
+    /// | `%S`      | second, two digits                                   | `00`                        |
+    /// | `%f`      | nanoseconds, nine digits                             | `026490000`                 |
+    /// | `%3f`     | milliseconds, three digits (also `%6f`, `%9f`)       | `026`                       |
+    /// | `%z`      | offset from UTC                                      | `+0200`                     |
+    /// | `%:z`     | offset from UTC, with a colon                        | `+02:00`                    |
+    /// | `%s`      | number of seconds since the UNIX epoch               | `1628166600`                |
+    /// | `%F`      | same as `%Y-%m-%d`                                   | `2021-08-05`                |
+    /// | `%T`      | same as `%H:%M:%S`                                   | `14:30:00`                  |
+    /// | `%D`      | same as `%m/%d/%y`                                   | `08/05/21`                  |
+    /// | `%R`      | same as `%H:%M`                                      | `14:30`                     |
+    /// | `%+`      | ISO-8601 date/time                                   | `2021-08-05T14:30:00+02:00` |
+    /// | `%n`      | new-line                                             |                             |
+    /// | `%t`      | tab                                                  |                             |
+    /// | `%%`      | `%`                                                  |                             |
+    ///
+    /// Returns an error message if the format string contains an invalid specifier.
+    pub fn format(&self, format: &str) -> Result<String, String> {
+        let mut output = String::with_capacity(format.len() + 16);
+        self.format_into(&mut output, format, &self.parts())?;
+        Ok(output)
+    }
+    /// Format the [`DateTime`] into a [`String`].
+    fn format_into(&self, output: &mut String, format: &str, parts: &Parts) -> Result<(), String> {
+        use std::fmt::Write;
+
+        let mut chars = format.chars();
+        let hour = parts.secs / 3600;
+        let weekday = (parts.days + 3).rem_euclid(7) as usize;
+
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                output.push(ch);
+                continue;
+            }
+
+            let spec = match chars.next() {
+                Some(':') => match chars.next() {
+                    Some('z') => ":z".to_string(),
+                    Some(c) => return Err(format!("invalid format specifier '%:{}'", c)),
+                    None => return Err("incomplete format specifier '%:'".into()),
+                },
+                Some(c @ '3') | Some(c @ '6') | Some(c @ '9') => match chars.next() {
+                    Some('f') => format!("{}f", c),
+                    Some(c2) => return Err(format!("invalid format specifier '%{}{}'", c, c2)),
+                    None => return Err(format!("incomplete format specifier '%{}'", c)),
+                },
+                Some(c) => c.to_string(),
+                None => return Err("incomplete format specifier '%'".into()),
+            };
+
+            let _ = match spec.as_str() {
+                "Y" => self.write_year(output, parts.year),
+                "y" => write!(output, "{:02}", parts.year.rem_euclid(100)),
+                "m" => write!(output, "{:02}", parts.month),
+                "b" | "h" => write!(output, "{}", &MONTH_NAMES[parts.month as usize - 1][..3]),
+                "B" => write!(output, "{}", MONTH_NAMES[parts.month as usize - 1]),
+                "d" => write!(output, "{:02}", parts.day),
+                "e" => write!(output, "{:>2}", parts.day),
+                "j" => write!(output, "{:03}", self.day_of_year()),
+                "a" => write!(output, "{}", &WEEKDAY_NAMES[weekday][..3]),
+                "A" => write!(output, "{}", WEEKDAY_NAMES[weekday]),
+                "u" => write!(output, "{}", weekday + 1),
+                "w" => write!(output, "{}", (weekday + 1) % 7),
+                "H" => write!(output, "{:02}", hour),
+                "I" => write!(output, "{:02}", if hour % 12 == 0 { 12 } else { hour % 12 }),
+                "p" => write!(output, "{}", if hour < 12 { "AM" } else { "PM" }),
+                "M" => write!(output, "{:02}", parts.secs % 3600 / 60),
+                "S" => write!(output, "{:02}", parts.secs % 60),
+                "f" | "9f" => write!(output, "{:09}", parts.nanos),
+                "6f" => write!(output, "{:06}", parts.nanos / 1_000),
+                "3f" => write!(output, "{:03}", parts.nanos / 1_000_000),
+                "z" => write_offset(output, self.offset, false),
+                ":z" => write_offset(output, self.offset, true),
+                "s" => write!(output, "{}", self.unix_timestamp()),
+                "F" => {
+                    self.format_into(output, "%Y-%m-%d", parts)?;
+                    Ok(())
+                }
+                "T" => {
+                    self.format_into(output, "%H:%M:%S", parts)?;
+                    Ok(())
+                }
+                "D" => {
+                    self.format_into(output, "%m/%d/%y", parts)?;
+                    Ok(())
+                }
+                "R" => {
+                    self.format_into(output, "%H:%M", parts)?;
+                    Ok(())
+                }
+                "+" => write!(output, "{}", self),
+                "n" => output.write_char('\n'),
+                "t" => output.write_char('\t'),
+                "%" => output.write_char('%'),
+                _ => return Err(format!("invalid format specifier '%{}'", spec)),
+            };
+        }
+
+        Ok(())
+    }
+    /// Parse an ISO-8601 date/time.
+    ///
+    /// The date must be in the form `YYYY-MM-DD` and can be followed by a time, separated by `T`
+    /// or a space, in the form `hh:mm`, `hh:mm:ss` or `hh:mm:ss.fff` (with up to nine fractional
+    /// digits). The time can be followed by `Z` or by an offset from UTC in the form `+hh:mm`,
+    /// `+hhmm` or `+hh`.
+    ///
+    /// Date/times without an offset are in UTC, and dates without a time are at midnight.
+    ///
+    /// Returns an error message if the text is not a valid date/time.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut input = Input(text.trim());
+
+        let year = input.number(4, 5, true)?;
+        input.expect('-')?;
+        let month = input.number(2, 2, false)? as u32;
+        input.expect('-')?;
+        let day = input.number(2, 2, false)? as u32;
+
+        let mut fields = Fields {
+            year: Some(year),
+            month: Some(month),
+            day: Some(day),
+            ..Default::default()
+        };
+
+        if input.eat(|c| c == 'T' || c == 't' || c == ' ') {
+            fields.hour = Some(input.number(2, 2, false)? as u32);
+            input.expect(':')?;
+            fields.minute = Some(input.number(2, 2, false)? as u32);
+
+            if input.eat(|c| c == ':') {
+                fields.second = Some(input.number(2, 2, false)? as u32);
+
+                if input.eat(|c| c == '.' || c == ',') {
+                    fields.nanos = Some(input.fraction(1, 9)?);
+                }
+            }
+
+            if !input.0.is_empty() {
+                fields.offset = Some(input.offset()?);
+            }
+        }
+
+        input.end()?;
+        fields.build()
+    }
+    /// Parse a date/time according to a `strftime`-like format string.
+    ///
+    /// All the specifiers supported by [`format`][DateTime::format] can be used.
+    /// Names of months and weekdays are matched case-insensitively, and a white-space character
+    /// in the format string matches any amount of white-space.
+    ///
+    /// Missing components default to the start of 1970-01-01 in UTC.
+    ///
+    /// Returns an error message if the text does not match the format string.
+    pub fn parse_with_format(text: &str, format: &str) -> Result<Self, String> {
+        let mut input = Input(text);
+        let mut fields = Fields::default();
+
+        parse_into(&mut input, format, &mut fields)?;
+
+        input.end()?;
+        fields.build()
+    }
+}
+
+/// Date/time components collected while parsing.
+#[derive(Default)]
+struct Fields {
+    year: Option<i64>,
+    month: Option<u32>,
+    day: Option<u32>,
+    day_of_year: Option<u32>,
+    hour: Option<u32>,
+    hour12: Option<u32>,
+    pm: Option<bool>,
+    minute: Option<u32>,
+    second: Option<u32>,
+    nanos: Option<u32>,
+    offset: Option<i32>,
+    timestamp: Option<i64>,
+}
+
+impl Fields {
+    /// Build a [`DateTime`] from the components.
+    fn build(self) -> Result<DateTime, String> {
+        let offset = self.offset.unwrap_or(0);
+        let nanos = self.nanos.unwrap_or(0);
+
+        if let Some(secs) = self.timestamp {
+            let dt = DateTime::from_unix_nanos(secs as i128 * NANOS_PER_SEC + nanos as i128)
+                .ok_or("date/time out of range")?;
+            return Ok(DateTime { offset, ..dt });
+        }
+
+        let year = self.year.unwrap_or(1970);
+
+        let (month, day) = match (self.month, self.day, self.day_of_year) {
+            (None, None, Some(n)) if n >= 1 && n <= if is_leap_year(year) { 366 } else { 365 } => {
+                let (_, month, day) = civil_from_days(days_from_civil(year, 1, 1) + n as i64 - 1);
+                (month, day)
+            }
+            (None, None, Some(n)) => return Err(format!("invalid day of the year: {}", n)),
+            (month, day, _) => (month.unwrap_or(1), day.unwrap_or(1)),
+        };
+
+        let hour = match (self.hour12, self.pm) {
+            (Some(h), _) if !(1..=12).contains(&h) => return Err(format!("invalid hour: {}", h)),
+            (Some(h), pm) => h % 12 + if pm == Some(true) { 12 } else { 0 },
+            (None, _) => self.hour.unwrap_or(0),
+        };
+        let minute = self.minute.unwrap_or(0);
+        let second = self.second.unwrap_or(0);
+
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return Err(format!("year out of range: {}", year));
+        }
+        if !(1..=12).contains(&month) {
+            return Err(format!("invalid month: {}", month));
+        }
+        if day < 1 || day > days_in_month(year, month) {
+            return Err(format!("invalid day of the month: {}", day));
+        }
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(format!(
+                "invalid time: {:02}:{:02}:{:02}",
+                hour, minute, second
+            ));
+        }
+
+        let secs = hour * 3600 + minute * 60 + second;
+        DateTime::from_local(days_from_civil(year, month, day), secs, nanos, offset)
+            .ok_or_else(|| "date/time out of range".into())
+    }
+}
+
+/// Parse text according to a format string, collecting the components.
+fn parse_into(input: &mut Input, format: &str, fields: &mut Fields) -> Result<(), String> {
+    let mut chars = format.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch.is_whitespace() {
+            input.skip_whitespace();
+            continue;
+        }
+        if ch != '%' {
+            input.expect(ch)?;
+            continue;
+        }
+
+        match chars.next() {
+            Some('Y') => fields.year = Some(input.number(1, 5, true)?),
+            Some('y') => {
+                let year = input.number(2, 2, false)?;
+                fields.year = Some(if year < 69 { 2000 + year } else { 1900 + year });
+            }
+            Some('m') => fields.month = Some(input.number(1, 2, false)? as u32),
+            Some('b') | Some('h') | Some('B') => {
+                fields.month = Some(input.name(&MONTH_NAMES)? as u32 + 1)
+            }
+            Some('d') => fields.day = Some(input.number(1, 2, false)? as u32),
+            Some('e') => {
+                input.skip_whitespace();
+                fields.day = Some(input.number(1, 2, false)? as u32);
+            }
+            Some('j') => fields.day_of_year = Some(input.number(1, 3, false)? as u32),
+            Some('a') | Some('A') => {
+                input.name(&WEEKDAY_NAMES)?;
+            }
+            Some('u') | Some('w') => {
+                input.number(1, 1, false)?;
+            }
+            Some('H') => fields.hour = Some(input.number(1, 2, false)? as u32),
+            Some('I') => fields.hour12 = Some(input.number(1, 2, false)? as u32),
+            Some('p') => fields.pm = Some(input.name(&["AM", "PM"])? == 1),
+            Some('M') => fields.minute = Some(input.number(1, 2, false)? as u32),
+            Some('S') => fields.second = Some(input.number(1, 2, false)? as u32),
+            Some('f') => fields.nanos = Some(input.fraction(1, 9)?),
+            Some(c @ '3') | Some(c @ '6') | Some(c @ '9') => match chars.next() {
+                Some('f') => {
+                    let digits = c.to_digit(10).expect("`c` is a digit") as usize;
+                    fields.nanos = Some(input.fraction(digits, digits)?);
+                }
+                Some(c2) => return Err(format!("invalid format specifier '%{}{}'", c, c2)),
+                None => return Err(format!("incomplete format specifier '%{}'", c)),
+            },
+            Some('z') => fields.offset = Some(input.offset()?),
+            Some(':') => match chars.next() {
+                Some('z') => fields.offset = Some(input.offset()?),
+                Some(c) => return Err(format!("invalid format specifier '%:{}'", c)),
+                None => return Err("incomplete format specifier '%:'".into()),
+            },
+            Some('s') => fields.timestamp = Some(input.number(1, 19, true)?),
+            Some('F') => parse_into(input, "%Y-%m-%d", fields)?,
+            Some('T') => parse_into(input, "%H:%M:%S", fields)?,
+            Some('D') => parse_into(input, "%m/%d/%y", fields)?,
+            Some('R') => parse_into(input, "%H:%M", fields)?,
+            Some('+') => {
+                let dt = DateTime::parse(input.0)?;
+                input.0 = "";
+                fields.timestamp = Some(dt.unix_timestamp());
+                fields.nanos = Some(dt.nanosecond());
+                fields.offset = Some(dt.offset);
+            }
+            Some('n') | Some('t') => input.skip_whitespace(),
+            Some('%') => input.expect('%')?,
+            Some(c) => return Err(format!("invalid format specifier '%{}'", c)),
+            None => return Err("incomplete format specifier '%'".into()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Text remaining to be parsed.
+struct Input<'a>(&'a str);
+
+impl Input<'_> {
+    /// Consume the next character if it satisfies a predicate.
+    fn eat(&mut self, predicate: impl Fn(char) -> bool) -> bool {
+        match self.0.chars().next() {
+            Some(c) if predicate(c) => {
+                self.0 = &self.0[c.len_utf8()..];
+                true
+            }
+            _ => false,
+        }
+    }
+    /// Consume a particular character.
+    fn expect(&mut self, ch: char) -> Result<(), String> {
+        if self.eat(|c| c == ch) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", ch)))
+        }
+    }
+    /// Skip any white-space.
+    fn skip_whitespace(&mut self) {
+        self.0 = self.0.trim_start();
+    }
+    /// Make sure all the text has been consumed.
+    fn end(&self) -> Result<(), String> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("unexpected trailing text '{}'", self.0))
+        }
+    }
+    /// Make an error message for unexpected text.
+    fn unexpected(&self, expected: &str) -> String {
+        match self.0.chars().next() {
+            Some(c) => format!("expecting {} but found '{}'", expected, c),
+            None => format!("expecting {} but reached the end of the text", expected),
+        }
+    }
+    /// Consume a number with a minimum and maximum number of digits, optionally with a sign.
+    fn number(&mut self, min: usize, max: usize, signed: bool) -> Result<i64, String> {
+        let negative = signed && !self.eat(|c| c == '+') && self.eat(|c| c == '-');
+        let len = self
+            .0
+            .bytes()
+            .take(max)
+            .take_while(u8::is_ascii_digit)
+            .count();
+
+        if len < min {
+            return Err(self.unexpected(&format!("{} digit(s)", min)));
+        }
+
+        let value: i64 = self.0[..len]
+            .parse()
+            .map_err(|_| "number too large".to_string())?;
+        self.0 = &self.0[len..];
+        Ok(if negative { -value } else { value })
+    }
+    /// Consume the digits of a fraction of a second, returning the number of nanoseconds.
+    fn fraction(&mut self, min: usize, max: usize) -> Result<u32, String> {
+        let start = self.0;
+        let value = self.number(min, max, false)? as u32;
+        let len = start.len() - self.0.len();
+        Ok(value * 10_u32.pow(9 - len as u32))
+    }
+    /// Consume a name (or its first three letters), ignoring case, returning its index.
+    fn name(&mut self, names: &[&str]) -> Result<usize, String> {
+        let matches = |s: &str| {
+            self.0.len() >= s.len()
+                && self.0.is_char_boundary(s.len())
+                && self.0[..s.len()].eq_ignore_ascii_case(s)
+        };
+
+        if let Some(i) = names.iter().position(|name| matches(name)) {
+            self.0 = &self.0[names[i].len()..];
+            Ok(i)
+        } else if let Some(i) = names
+            .iter()
+            .position(|name| name.len() > 3 && matches(&name[..3]))
+        {
+            self.0 = &self.0[3..];
+            Ok(i)
+        } else {
+            Err(self.unexpected(&format!("one of {}", names.join(", "))))
+        }
+    }
+    /// Consume an offset from UTC (`Z`, `+hh:mm`, `+hhmm` or `+hh`), returning it in seconds.
+    fn offset(&mut self) -> Result<i32, String> {
+        if self.eat(|c| c == 'Z' || c == 'z') {
+            return Ok(0);
+        }
+
+        let negative = if self.eat(|c| c == '+') {
+            false
+        } else if self.eat(|c| c == '-') {
+            true
+        } else {
+            return Err(self.unexpected("an offset from UTC"));
+        };
+
+        let hours = self.number(2, 2, false)? as i32;
+        let has_colon = self.eat(|c| c == ':');
+        let minutes = if has_colon || self.0.starts_with(|c: char| c.is_ascii_digit()) {
+            self.number(2, 2, false)? as i32
+        } else {
+            0
+        };
+
+        if hours > 23 || minutes > 59 {
+            return Err(format!(
+                "invalid offset from UTC: {:02}:{:02}",
+                hours, minutes
+            ));
+        }
+
+        let offset = hours * 3600 + minutes * 60;
+        Ok(if negative { -offset } else { offset })
+    }
+}
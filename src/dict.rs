@@ -0,0 +1,385 @@
+//! Module implementing the [`Dict`] dictionary type.
+#![cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+
+use crate::dynamic::Union;
+use crate::fn_hash::get_hasher;
+use crate::{Dynamic, ExclusiveRange, InclusiveRange};
+use smallvec::SmallVec;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    collections::{btree_map, BTreeMap},
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+};
+
+/// Entries with keys having the same hash.
+type Bucket = SmallVec<[(HashableKey, Dynamic); 1]>;
+
+/// A [`Dynamic`] key of a [`Dict`] (or value of a [`Set`][crate::Set]).
+///
+/// Keys are compared by type and value, consistently with their hashes: integers and
+/// floating-point numbers are never equal to each other, floating-point numbers are compared
+/// bit-wise, and arrays, BLOBs, object maps, dictionaries and sets are compared item by item.
+/// Values that cannot be hashed are never equal to anything.
+#[derive(Debug, Clone)]
+pub(crate) struct HashableKey(pub Dynamic);
+
+impl Hash for HashableKey {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl PartialEq for HashableKey {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        keys_equal(&self.0, &other.0)
+    }
+}
+
+impl PartialEq<Dynamic> for HashableKey {
+    #[inline(always)]
+    fn eq(&self, other: &Dynamic) -> bool {
+        keys_equal(&self.0, other)
+    }
+}
+
+impl Eq for HashableKey {}
+
+/// Compare two [`Dynamic`] values as keys.
+#[must_use]
+fn keys_equal(x: &Dynamic, y: &Dynamic) -> bool {
+    #[cfg(not(feature = "no_closure"))]
+    const CHECKED: &str = "never fails because the value is shared";
+
+    match (&x.0, &y.0) {
+        #[cfg(not(feature = "no_closure"))]
+        (Union::Shared(_, _, _), _) => keys_equal(&x.read_lock::<Dynamic>().expect(CHECKED), y),
+        #[cfg(not(feature = "no_closure"))]
+        (_, Union::Shared(_, _, _)) => keys_equal(x, &y.read_lock::<Dynamic>().expect(CHECKED)),
+
+        (Union::Unit(_, _, _), Union::Unit(_, _, _)) => true,
+        (Union::Bool(a, _, _), Union::Bool(b, _, _)) => a == b,
+        (Union::Str(a, _, _), Union::Str(b, _, _)) => a == b,
+        (Union::Char(a, _, _), Union::Char(b, _, _)) => a == b,
+        (Union::Int(a, _, _), Union::Int(b, _, _)) => a == b,
+        #[cfg(not(feature = "no_float"))]
+        (Union::Float(a, _, _), Union::Float(b, _, _)) => a.to_ne_bytes() == b.to_ne_bytes(),
+        #[cfg(feature = "decimal")]
+        (Union::Decimal(a, _, _), Union::Decimal(b, _, _)) => a == b,
+        #[cfg(feature = "bigint")]
+        (Union::BigInt(a, _, _), Union::BigInt(b, _, _)) => a == b,
+        #[cfg(not(feature = "no_index"))]
+        (Union::Array(a, _, _), Union::Array(b, _, _)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| keys_equal(a, b))
+        }
+        #[cfg(not(feature = "no_index"))]
+        (Union::Blob(a, _, _), Union::Blob(b, _, _)) => a == b,
+        #[cfg(not(feature = "no_index"))]
+        (Union::Set(a, _, _), Union::Set(b, _, _)) => a == b,
+        #[cfg(not(feature = "no_object"))]
+        (Union::Map(a, _, _), Union::Map(b, _, _)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(k, v)| b.get(k).map_or(false, |w| keys_equal(v, w)))
+        }
+        #[cfg(not(feature = "no_object"))]
+        (Union::Dict(a, _, _), Union::Dict(b, _, _)) => a == b,
+
+        (Union::Variant(a, _, _), Union::Variant(b, _, _)) => {
+            let (a, b) = ((***a).as_any(), (***b).as_any());
+
+            if let (Some(x), Some(y)) = (
+                a.downcast_ref::<ExclusiveRange>(),
+                b.downcast_ref::<ExclusiveRange>(),
+            ) {
+                return x == y;
+            } else if let (Some(x), Some(y)) = (
+                a.downcast_ref::<InclusiveRange>(),
+                b.downcast_ref::<InclusiveRange>(),
+            ) {
+                return x == y;
+            }
+
+            #[cfg(not(feature = "no_std"))]
+            if let (Some(x), Some(y)) = (
+                a.downcast_ref::<crate::DateTime>(),
+                b.downcast_ref::<crate::DateTime>(),
+            ) {
+                return x == y;
+            } else if let (Some(x), Some(y)) = (
+                a.downcast_ref::<crate::Duration>(),
+                b.downcast_ref::<crate::Duration>(),
+            ) {
+                return x == y;
+            }
+
+            false
+        }
+
+        _ => false,
+    }
+}
+
+/// Dictionary of [`Dynamic`] values keyed by [`Dynamic`] values.
+/// Not available under `no_object`.
+///
+/// Only values that can be hashed (i.e. `()`, `bool`, characters, strings, numbers, ranges, and
+/// arrays, BLOBs, object maps, dictionaries and sets containing them) can be used as keys.
+/// Keys are compared by value and type, so the integer `1` and the string `"1"` are different keys.
+///
+/// Iteration order is unspecified.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{Dict, Dynamic, Engine, INT};
+///
+/// let engine = Engine::new();
+///
+/// let dict: Dict = engine.eval(r#"let d = dict(); d[1] = "one"; d['x'] = 42; d"#)?;
+///
+/// assert_eq!(dict.len(), 2);
+/// assert_eq!(dict.get(&(1 as INT).into()).unwrap().to_string(), "one");
+/// assert!(dict.get(&"1".into()).is_none());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct Dict {
+    /// Entries, keyed by the hashes of their keys.
+    buckets: BTreeMap<u64, Bucket>,
+    /// Number of entries.
+    len: usize,
+}
+
+impl fmt::Debug for Dict {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl Hash for Dict {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        self.buckets.iter().for_each(|(h, bucket)| {
+            h.hash(state);
+            // Values that cannot be hashed hash the same
+            bucket
+                .iter()
+                .for_each(|(_, v)| calc_key_hash(v).hash(state));
+        });
+    }
+}
+
+impl PartialEq for Dict {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len
+            && self
+                .iter()
+                .all(|(k, v)| other.get(k).map_or(false, |w| keys_equal(v, w)))
+    }
+}
+
+impl Eq for Dict {}
+
+/// Calculate the hash key of a value, or [`None`] if the value cannot be hashed.
+#[inline]
+#[must_use]
+pub(crate) fn calc_key_hash(key: &Dynamic) -> Option<u64> {
+    if key.is_hashable() {
+        let hasher = &mut get_hasher();
+        key.hash(hasher);
+        Some(hasher.finish())
+    } else {
+        None
+    }
+}
+
+impl Dict {
+    /// Create a new empty [`Dict`].
+    #[inline(always)]
+    #[must_use]
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Get the number of entries in the [`Dict`].
+    #[inline(always)]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Is the [`Dict`] empty?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Does the [`Dict`] contain a key?
+    #[inline(always)]
+    #[must_use]
+    pub fn contains_key(&self, key: &Dynamic) -> bool {
+        self.get(key).is_some()
+    }
+    /// Get a reference to the value of a key.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, key: &Dynamic) -> Option<&Dynamic> {
+        let hash = calc_key_hash(key)?;
+
+        self.buckets
+            .get(&hash)?
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+    /// Get a mutable reference to the value of a key.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self, key: &Dynamic) -> Option<&mut Dynamic> {
+        let hash = calc_key_hash(key)?;
+
+        self.buckets
+            .get_mut(&hash)?
+            .iter_mut()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+    /// Set the value of a key, returning the previous value, if any.
+    ///
+    /// Returns the name of the actual type if the key cannot be hashed.
+    #[inline]
+    pub fn insert(
+        &mut self,
+        key: Dynamic,
+        value: Dynamic,
+    ) -> Result<Option<Dynamic>, &'static str> {
+        let key = key.flatten();
+        let hash = calc_key_hash(&key).ok_or_else(|| key.type_name())?;
+        let bucket = self.buckets.entry(hash).or_default();
+
+        match bucket.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => Ok(Some(std::mem::replace(v, value))),
+            None => {
+                bucket.push((HashableKey(key), value));
+                self.len += 1;
+                Ok(None)
+            }
+        }
+    }
+    /// Remove a key, returning the key and its value if it was in the [`Dict`].
+    #[inline]
+    pub fn remove_entry(&mut self, key: &Dynamic) -> Option<(Dynamic, Dynamic)> {
+        let hash = calc_key_hash(key)?;
+
+        match self.buckets.entry(hash) {
+            btree_map::Entry::Occupied(mut entry) => {
+                let index = entry.get().iter().position(|(k, _)| k == key)?;
+                let (HashableKey(key), value) = entry.get_mut().remove(index);
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+                self.len -= 1;
+                Some((key, value))
+            }
+            btree_map::Entry::Vacant(_) => None,
+        }
+    }
+    /// Remove a key, returning its value if it was in the [`Dict`].
+    #[inline(always)]
+    pub fn remove(&mut self, key: &Dynamic) -> Option<Dynamic> {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+    /// Remove all entries from the [`Dict`].
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.len = 0;
+    }
+    /// Keep only the entries specified by a predicate.
+    #[inline]
+    pub fn retain(&mut self, mut f: impl FnMut(&Dynamic, &mut Dynamic) -> bool) {
+        let mut len = 0;
+
+        self.buckets.retain(|_, bucket| {
+            bucket.retain(|(k, v)| f(&k.0, v));
+            len += bucket.len();
+            !bucket.is_empty()
+        });
+
+        self.len = len;
+    }
+    /// Iterate through all the entries in the [`Dict`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = (&Dynamic, &Dynamic)> {
+        self.buckets
+            .values()
+            .flat_map(|bucket| bucket.iter().map(|(k, v)| (&k.0, v)))
+    }
+    /// Iterate through all the keys in the [`Dict`].
+    #[inline(always)]
+    pub fn keys(&self) -> impl Iterator<Item = &Dynamic> {
+        self.iter().map(|(k, _)| k)
+    }
+    /// Iterate through all the values in the [`Dict`].
+    #[inline(always)]
+    pub fn values(&self) -> impl Iterator<Item = &Dynamic> {
+        self.iter().map(|(_, v)| v)
+    }
+    /// Iterate through mutable references to all the values in the [`Dict`].
+    #[inline(always)]
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Dynamic> {
+        self.buckets
+            .values_mut()
+            .flat_map(|bucket| bucket.iter_mut().map(|(_, v)| v))
+    }
+}
+
+/// An iterator that moves out of a [`Dict`].
+pub struct IntoIter(std::iter::Flatten<btree_map::IntoValues<u64, Bucket>>);
+
+impl Iterator for IntoIter {
+    type Item = (Dynamic, Dynamic);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(HashableKey(k), v)| (k, v))
+    }
+}
+
+impl IntoIterator for Dict {
+    type Item = (Dynamic, Dynamic);
+    type IntoIter = IntoIter;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.buckets.into_values().flatten())
+    }
+}
+
+impl Extend<(Dynamic, Dynamic)> for Dict {
+    /// Add entries into the [`Dict`], skipping those with keys that cannot be hashed.
+    #[inline]
+    fn extend<T: IntoIterator<Item = (Dynamic, Dynamic)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|(k, v)| {
+            let _ = self.insert(k, v);
+        });
+    }
+}
+
+impl FromIterator<(Dynamic, Dynamic)> for Dict {
+    /// Collect entries into a [`Dict`], skipping those with keys that cannot be hashed.
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (Dynamic, Dynamic)>>(iter: T) -> Self {
+        let mut dict = Self::new();
+        dict.extend(iter);
+        dict
+    }
+}
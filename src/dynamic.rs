@@ -2,7 +2,7 @@
 
 use crate::fn_native::SendSync;
 use crate::r#unsafe::{unsafe_cast_box, unsafe_try_cast};
-use crate::{FnPtr, ImmutableString, INT};
+use crate::{ExclusiveRange, FnPtr, ImmutableString, InclusiveRange, INT};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
@@ -15,14 +15,16 @@ use std::{
 #[cfg(not(feature = "no_float"))]
 use crate::{ast::FloatWrapper, FLOAT};
 
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
 #[cfg(feature = "decimal")]
 use rust_decimal::Decimal;
 
 #[cfg(not(feature = "no_index"))]
-use crate::Array;
+use crate::{Array, Blob, Set};
 
 #[cfg(not(feature = "no_object"))]
-use crate::Map;
+use crate::{Dict, Map};
 
 #[cfg(not(feature = "no_std"))]
 #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
@@ -193,16 +195,35 @@ pub enum Union {
     /// Exported under the `decimal` feature only.
     #[cfg(feature = "decimal")]
     Decimal(Box<Decimal>, Tag, AccessMode),
+    /// _(BIGINT)_ An arbitrary-precision integer value.
+    /// Exported under the `bigint` feature only.
+    #[cfg(feature = "bigint")]
+    BigInt(Box<BigInt>, Tag, AccessMode),
     /// An array value.
     ///
     /// Not available under `no_index`.
     #[cfg(not(feature = "no_index"))]
     Array(Box<Array>, Tag, AccessMode),
+    /// A byte buffer.
+    ///
+    /// Not available under `no_index`.
+    #[cfg(not(feature = "no_index"))]
+    Blob(Box<Blob>, Tag, AccessMode),
+    /// A set value.
+    ///
+    /// Not available under `no_index`.
+    #[cfg(not(feature = "no_index"))]
+    Set(Box<Set>, Tag, AccessMode),
     /// An object map value.
     ///
     /// Not available under `no_object`.
     #[cfg(not(feature = "no_object"))]
     Map(Box<Map>, Tag, AccessMode),
+    /// A dictionary value.
+    ///
+    /// Not available under `no_object`.
+    #[cfg(not(feature = "no_object"))]
+    Dict(Box<Dict>, Tag, AccessMode),
     /// A function pointer.
     FnPtr(Box<FnPtr>, Tag, AccessMode),
     /// A timestamp value.
@@ -337,8 +358,16 @@ impl Dynamic {
             Union::Float(_, tag, _) => tag,
             #[cfg(feature = "decimal")]
             Union::Decimal(_, tag, _) => tag,
+            #[cfg(feature = "bigint")]
+            Union::BigInt(_, tag, _) => tag,
             #[cfg(not(feature = "no_index"))]
             Union::Array(_, tag, _) => tag,
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(_, tag, _) => tag,
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(_, tag, _) => tag,
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(_, tag, _) => tag,
             #[cfg(not(feature = "no_object"))]
             Union::Map(_, tag, _) => tag,
             #[cfg(not(feature = "no_std"))]
@@ -362,8 +391,16 @@ impl Dynamic {
             Union::Float(_, ref mut tag, _) => *tag = value,
             #[cfg(feature = "decimal")]
             Union::Decimal(_, ref mut tag, _) => *tag = value,
+            #[cfg(feature = "bigint")]
+            Union::BigInt(_, ref mut tag, _) => *tag = value,
             #[cfg(not(feature = "no_index"))]
             Union::Array(_, ref mut tag, _) => *tag = value,
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(_, ref mut tag, _) => *tag = value,
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(_, ref mut tag, _) => *tag = value,
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(_, ref mut tag, _) => *tag = value,
             #[cfg(not(feature = "no_object"))]
             Union::Map(_, ref mut tag, _) => *tag = value,
             #[cfg(not(feature = "no_std"))]
@@ -431,8 +468,16 @@ impl Dynamic {
             Union::Float(_, _, _) => TypeId::of::<FLOAT>(),
             #[cfg(feature = "decimal")]
             Union::Decimal(_, _, _) => TypeId::of::<Decimal>(),
+            #[cfg(feature = "bigint")]
+            Union::BigInt(_, _, _) => TypeId::of::<BigInt>(),
             #[cfg(not(feature = "no_index"))]
             Union::Array(_, _, _) => TypeId::of::<Array>(),
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(_, _, _) => TypeId::of::<Blob>(),
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(_, _, _) => TypeId::of::<Set>(),
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(_, _, _) => TypeId::of::<Dict>(),
             #[cfg(not(feature = "no_object"))]
             Union::Map(_, _, _) => TypeId::of::<Map>(),
             Union::FnPtr(_, _, _) => TypeId::of::<FnPtr>(),
@@ -468,8 +513,16 @@ impl Dynamic {
             Union::Float(_, _, _) => type_name::<FLOAT>(),
             #[cfg(feature = "decimal")]
             Union::Decimal(_, _, _) => "decimal",
+            #[cfg(feature = "bigint")]
+            Union::BigInt(_, _, _) => "bigint",
             #[cfg(not(feature = "no_index"))]
             Union::Array(_, _, _) => "array",
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(_, _, _) => "blob",
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(_, _, _) => "set",
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(_, _, _) => "dict",
             #[cfg(not(feature = "no_object"))]
             Union::Map(_, _, _) => "map",
             Union::FnPtr(_, _, _) => "Fn",
@@ -494,9 +547,8 @@ impl Dynamic {
 impl Hash for Dynamic {
     /// Hash the [`Dynamic`] value.
     ///
-    /// # Panics
-    ///
-    /// Panics if the [`Dynamic`] value contains an unrecognized trait object.
+    /// Values that cannot be compared by value (such as timestamps and most custom types) are
+    /// hashed by type only.
     fn hash<H: Hasher>(&self, state: &mut H) {
         std::mem::discriminant(&self.0).hash(state);
 
@@ -510,10 +562,27 @@ impl Hash for Dynamic {
             Union::Float(ref f, _, _) => f.hash(state),
             #[cfg(feature = "decimal")]
             Union::Decimal(ref d, _, _) => d.hash(state),
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref d, _, _) => d.hash(state),
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref a, _, _) => a.as_ref().hash(state),
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(ref a, _, _) => a.as_ref().hash(state),
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(ref a, _, _) => a.as_ref().hash(state),
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(ref a, _, _) => a.as_ref().hash(state),
             #[cfg(not(feature = "no_object"))]
+            #[cfg(not(feature = "preserve_order"))]
             Union::Map(ref m, _, _) => m.as_ref().hash(state),
+            #[cfg(not(feature = "no_object"))]
+            #[cfg(feature = "preserve_order")]
+            Union::Map(ref m, _, _) => {
+                // Hash the properties in sorted order, so maps that compare equal hash the same
+                let mut props: Vec<_> = m.iter().collect();
+                props.sort_unstable_by_key(|(k, _)| *k);
+                props.hash(state);
+            }
             Union::FnPtr(ref f, _, _) => f.hash(state),
 
             #[cfg(not(feature = "no_closure"))]
@@ -524,54 +593,60 @@ impl Hash for Dynamic {
             #[cfg(feature = "sync")]
             Union::Shared(ref cell, _, _) => (*cell.read().unwrap()).hash(state),
 
-            Union::Variant(ref _value, _, _) => {
+            Union::Variant(ref value, _, _) => {
+                let value_any = (***value).as_any();
+                let type_id = value_any.type_id();
+
+                type_id.hash(state);
+
+                if let Some(range) = value_any.downcast_ref::<ExclusiveRange>() {
+                    return range.hash(state);
+                } else if let Some(range) = value_any.downcast_ref::<InclusiveRange>() {
+                    return range.hash(state);
+                }
+
+                #[cfg(not(feature = "no_std"))]
+                if let Some(dt) = value_any.downcast_ref::<crate::DateTime>() {
+                    return dt.hash(state);
+                } else if let Some(duration) = value_any.downcast_ref::<crate::Duration>() {
+                    return duration.hash(state);
+                }
+
                 #[cfg(not(feature = "only_i32"))]
                 #[cfg(not(feature = "only_i64"))]
                 {
-                    let value_any = (***_value).as_any();
-                    let type_id = value_any.type_id();
-
                     if type_id == TypeId::of::<u8>() {
-                        TypeId::of::<u8>().hash(state);
-                        value_any.downcast_ref::<u8>().expect(CHECKED).hash(state);
+                        return value_any.downcast_ref::<u8>().expect(CHECKED).hash(state);
                     } else if type_id == TypeId::of::<u16>() {
-                        TypeId::of::<u16>().hash(state);
-                        value_any.downcast_ref::<u16>().expect(CHECKED).hash(state);
+                        return value_any.downcast_ref::<u16>().expect(CHECKED).hash(state);
                     } else if type_id == TypeId::of::<u32>() {
-                        TypeId::of::<u32>().hash(state);
-                        value_any.downcast_ref::<u32>().expect(CHECKED).hash(state);
+                        return value_any.downcast_ref::<u32>().expect(CHECKED).hash(state);
                     } else if type_id == TypeId::of::<u64>() {
-                        TypeId::of::<u64>().hash(state);
-                        value_any.downcast_ref::<u64>().expect(CHECKED).hash(state);
+                        return value_any.downcast_ref::<u64>().expect(CHECKED).hash(state);
                     } else if type_id == TypeId::of::<i8>() {
-                        TypeId::of::<i8>().hash(state);
-                        value_any.downcast_ref::<i8>().expect(CHECKED).hash(state);
+                        return value_any.downcast_ref::<i8>().expect(CHECKED).hash(state);
                     } else if type_id == TypeId::of::<i16>() {
-                        TypeId::of::<i16>().hash(state);
-                        value_any.downcast_ref::<i16>().expect(CHECKED).hash(state);
+                        return value_any.downcast_ref::<i16>().expect(CHECKED).hash(state);
                     } else if type_id == TypeId::of::<i32>() {
-                        TypeId::of::<i32>().hash(state);
-                        value_any.downcast_ref::<i32>().expect(CHECKED).hash(state);
+                        return value_any.downcast_ref::<i32>().expect(CHECKED).hash(state);
                     } else if type_id == TypeId::of::<i64>() {
-                        TypeId::of::<i64>().hash(state);
-                        value_any.downcast_ref::<i64>().expect(CHECKED).hash(state);
+                        return value_any.downcast_ref::<i64>().expect(CHECKED).hash(state);
                     }
 
                     #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
                     if type_id == TypeId::of::<u128>() {
-                        TypeId::of::<u128>().hash(state);
                         value_any.downcast_ref::<u128>().expect(CHECKED).hash(state);
                     } else if type_id == TypeId::of::<i128>() {
-                        TypeId::of::<i128>().hash(state);
                         value_any.downcast_ref::<i128>().expect(CHECKED).hash(state);
                     }
                 }
 
-                unimplemented!("a custom type cannot be hashed")
+                // Other custom types are only hashed by type
             }
 
+            // Timestamps are only hashed by type
             #[cfg(not(feature = "no_std"))]
-            Union::TimeStamp(_, _, _) => unimplemented!("{} cannot be hashed", self.type_name()),
+            Union::TimeStamp(_, _, _) => (),
         }
     }
 }
@@ -592,26 +667,77 @@ pub(crate) fn map_std_type_name(name: &str) -> &str {
     if name == type_name::<FnPtr>() {
         return "Fn";
     }
+    #[cfg(not(feature = "no_function"))]
+    if name == type_name::<crate::Generator>() {
+        return "generator";
+    }
+    #[cfg(not(feature = "no_function"))]
+    if name == type_name::<crate::EnumValue>() {
+        return "enum";
+    }
     #[cfg(feature = "decimal")]
     if name == type_name::<Decimal>() {
         return "decimal";
     }
+    #[cfg(feature = "bigint")]
+    if name == type_name::<BigInt>() {
+        return "bigint";
+    }
     #[cfg(not(feature = "no_index"))]
     if name == type_name::<Array>() {
         return "array";
     }
+    #[cfg(not(feature = "no_index"))]
+    if name == type_name::<Blob>() {
+        return "blob";
+    }
+    #[cfg(not(feature = "no_index"))]
+    if name == type_name::<Set>() {
+        return "set";
+    }
     #[cfg(not(feature = "no_object"))]
     if name == type_name::<Map>() {
         return "map";
     }
+    #[cfg(not(feature = "no_object"))]
+    if name == type_name::<Dict>() {
+        return "dict";
+    }
     #[cfg(not(feature = "no_std"))]
     if name == type_name::<Instant>() {
         return "timestamp";
     }
+    #[cfg(not(feature = "no_std"))]
+    if name == type_name::<crate::DateTime>() {
+        return "datetime";
+    }
+    #[cfg(not(feature = "no_std"))]
+    if name == type_name::<crate::Duration>() {
+        return "duration";
+    }
+    if name == type_name::<ExclusiveRange>() {
+        return "range";
+    }
+    if name == type_name::<InclusiveRange>() {
+        return "range=";
+    }
 
     name
 }
 
+/// Format a [`Blob`] as a list of hex bytes.
+#[cfg(not(feature = "no_index"))]
+fn fmt_blob(blob: &Blob, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("[")?;
+    for (i, b) in blob.iter().enumerate() {
+        if i > 0 {
+            f.write_str(" ")?;
+        }
+        write!(f, "{:02x}", b)?;
+    }
+    f.write_str("]")
+}
+
 impl fmt::Display for Dynamic {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.0 {
@@ -624,8 +750,16 @@ impl fmt::Display for Dynamic {
             Union::Float(ref value, _, _) => fmt::Display::fmt(value, f),
             #[cfg(feature = "decimal")]
             Union::Decimal(ref value, _, _) => fmt::Display::fmt(value, f),
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref value, _, _) => fmt::Display::fmt(value, f),
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref value, _, _) => fmt::Debug::fmt(value, f),
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(ref value, _, _) => fmt_blob(value, f),
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(ref value, _, _) => fmt::Debug::fmt(value, f),
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(ref value, _, _) => fmt::Debug::fmt(value, f),
             #[cfg(not(feature = "no_object"))]
             Union::Map(ref value, _, _) => {
                 f.write_str("#")?;
@@ -639,6 +773,27 @@ impl fmt::Display for Dynamic {
                 let _value_any = (***value).as_any();
                 let _type_id = _value_any.type_id();
 
+                if let Some(range) = _value_any.downcast_ref::<ExclusiveRange>() {
+                    return write!(f, "{}..{}", range.start, range.end);
+                } else if let Some(range) = _value_any.downcast_ref::<InclusiveRange>() {
+                    return write!(f, "{}..={}", range.start(), range.end());
+                }
+
+                #[cfg(not(feature = "no_function"))]
+                if _value_any.is::<crate::Generator>() {
+                    return f.write_str("<generator>");
+                }
+                #[cfg(not(feature = "no_function"))]
+                if let Some(value) = _value_any.downcast_ref::<crate::EnumValue>() {
+                    return fmt::Display::fmt(value, f);
+                }
+                #[cfg(not(feature = "no_std"))]
+                if let Some(value) = _value_any.downcast_ref::<crate::DateTime>() {
+                    return fmt::Display::fmt(value, f);
+                } else if let Some(value) = _value_any.downcast_ref::<crate::Duration>() {
+                    return fmt::Display::fmt(value, f);
+                }
+
                 #[cfg(not(feature = "only_i32"))]
                 #[cfg(not(feature = "only_i64"))]
                 if _type_id == TypeId::of::<u8>() {
@@ -704,8 +859,16 @@ impl fmt::Debug for Dynamic {
             Union::Float(ref value, _, _) => fmt::Debug::fmt(value, f),
             #[cfg(feature = "decimal")]
             Union::Decimal(ref value, _, _) => fmt::Debug::fmt(value, f),
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref value, _, _) => fmt::Debug::fmt(value, f),
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref value, _, _) => fmt::Debug::fmt(value, f),
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(ref value, _, _) => fmt_blob(value, f),
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(ref value, _, _) => fmt::Debug::fmt(value, f),
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(ref value, _, _) => fmt::Debug::fmt(value, f),
             #[cfg(not(feature = "no_object"))]
             Union::Map(ref value, _, _) => {
                 f.write_str("#")?;
@@ -719,6 +882,27 @@ impl fmt::Debug for Dynamic {
                 let _value_any = (***value).as_any();
                 let _type_id = _value_any.type_id();
 
+                if let Some(range) = _value_any.downcast_ref::<ExclusiveRange>() {
+                    return write!(f, "{}..{}", range.start, range.end);
+                } else if let Some(range) = _value_any.downcast_ref::<InclusiveRange>() {
+                    return write!(f, "{}..={}", range.start(), range.end());
+                }
+
+                #[cfg(not(feature = "no_function"))]
+                if _value_any.is::<crate::Generator>() {
+                    return f.write_str("<generator>");
+                }
+                #[cfg(not(feature = "no_function"))]
+                if let Some(value) = _value_any.downcast_ref::<crate::EnumValue>() {
+                    return fmt::Debug::fmt(value, f);
+                }
+                #[cfg(not(feature = "no_std"))]
+                if let Some(value) = _value_any.downcast_ref::<crate::DateTime>() {
+                    return fmt::Debug::fmt(value, f);
+                } else if let Some(value) = _value_any.downcast_ref::<crate::Duration>() {
+                    return fmt::Debug::fmt(value, f);
+                }
+
                 #[cfg(not(feature = "only_i32"))]
                 #[cfg(not(feature = "only_i64"))]
                 if _type_id == TypeId::of::<u8>() {
@@ -793,8 +977,16 @@ impl Clone for Dynamic {
             Union::Decimal(ref value, tag, _) => {
                 Self(Union::Decimal(value.clone(), tag, ReadWrite))
             }
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref value, tag, _) => Self(Union::BigInt(value.clone(), tag, ReadWrite)),
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref value, tag, _) => Self(Union::Array(value.clone(), tag, ReadWrite)),
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(ref value, tag, _) => Self(Union::Blob(value.clone(), tag, ReadWrite)),
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(ref value, tag, _) => Self(Union::Set(value.clone(), tag, ReadWrite)),
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(ref value, tag, _) => Self(Union::Dict(value.clone(), tag, ReadWrite)),
             #[cfg(not(feature = "no_object"))]
             Union::Map(ref value, tag, _) => Self(Union::Map(value.clone(), tag, ReadWrite)),
             Union::FnPtr(ref value, tag, _) => Self(Union::FnPtr(value.clone(), tag, ReadWrite)),
@@ -902,8 +1094,16 @@ impl Dynamic {
             Union::Float(_, _, access) => access,
             #[cfg(feature = "decimal")]
             Union::Decimal(_, _, access) => access,
+            #[cfg(feature = "bigint")]
+            Union::BigInt(_, _, access) => access,
             #[cfg(not(feature = "no_index"))]
             Union::Array(_, _, access) => access,
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(_, _, access) => access,
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(_, _, access) => access,
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(_, _, access) => access,
             #[cfg(not(feature = "no_object"))]
             Union::Map(_, _, access) => access,
             #[cfg(not(feature = "no_std"))]
@@ -927,6 +1127,8 @@ impl Dynamic {
             Union::Float(_, _, ref mut access) => *access = typ,
             #[cfg(feature = "decimal")]
             Union::Decimal(_, _, ref mut access) => *access = typ,
+            #[cfg(feature = "bigint")]
+            Union::BigInt(_, _, ref mut access) => *access = typ,
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref mut a, _, ref mut access) => {
                 *access = typ;
@@ -934,6 +1136,10 @@ impl Dynamic {
                     v.set_access_mode(typ);
                 });
             }
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(_, _, ref mut access) => *access = typ,
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(_, _, ref mut access) => *access = typ,
             #[cfg(not(feature = "no_object"))]
             Union::Map(ref mut m, _, ref mut access) => {
                 *access = typ;
@@ -941,6 +1147,13 @@ impl Dynamic {
                     v.set_access_mode(typ);
                 });
             }
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(ref mut m, _, ref mut access) => {
+                *access = typ;
+                m.values_mut().for_each(|v| {
+                    v.set_access_mode(typ);
+                });
+            }
             #[cfg(not(feature = "no_std"))]
             Union::TimeStamp(_, _, ref mut access) => *access = typ,
             #[cfg(not(feature = "no_closure"))]
@@ -996,10 +1209,29 @@ impl Dynamic {
 
             #[cfg(not(feature = "no_float"))]
             Union::Float(_, _, _) => true,
+            #[cfg(feature = "decimal")]
+            Union::Decimal(_, _, _) => true,
+            #[cfg(feature = "bigint")]
+            Union::BigInt(_, _, _) => true,
+            #[cfg(not(feature = "no_index"))]
+            Union::Array(ref a, _, _) => a.iter().all(Self::is_hashable),
             #[cfg(not(feature = "no_index"))]
-            Union::Array(_, _, _) => true,
+            Union::Blob(_, _, _) | Union::Set(_, _, _) => true,
+            #[cfg(not(feature = "no_object"))]
+            Union::Map(ref m, _, _) => m.values().all(Self::is_hashable),
             #[cfg(not(feature = "no_object"))]
-            Union::Map(_, _, _) => true,
+            Union::Dict(ref m, _, _) => m.values().all(Self::is_hashable),
+
+            Union::Variant(ref value, _, _) => {
+                let value_any = (***value).as_any();
+
+                #[cfg(not(feature = "no_std"))]
+                if value_any.is::<crate::DateTime>() || value_any.is::<crate::Duration>() {
+                    return true;
+                }
+
+                value_any.is::<ExclusiveRange>() || value_any.is::<InclusiveRange>()
+            }
 
             #[cfg(not(feature = "no_closure"))]
             #[cfg(not(feature = "sync"))]
@@ -1066,6 +1298,10 @@ impl Dynamic {
         if TypeId::of::<T>() == TypeId::of::<Decimal>() {
             return val.downcast_ref::<Decimal>().expect(CHECKED).clone().into();
         }
+        #[cfg(feature = "bigint")]
+        if TypeId::of::<T>() == TypeId::of::<BigInt>() {
+            return val.downcast_ref::<BigInt>().expect(CHECKED).clone().into();
+        }
         if TypeId::of::<T>() == TypeId::of::<bool>() {
             return val.downcast_ref::<bool>().expect(CHECKED).clone().into();
         }
@@ -1097,6 +1333,20 @@ impl Dynamic {
                 Err(value) => value,
             };
         }
+        #[cfg(not(feature = "no_index"))]
+        {
+            value = match unsafe_try_cast::<_, Blob>(value) {
+                Ok(blob) => return Self::from_blob(blob),
+                Err(value) => value,
+            };
+        }
+        #[cfg(not(feature = "no_index"))]
+        {
+            value = match unsafe_try_cast::<_, Set>(value) {
+                Ok(set) => return set.into(),
+                Err(value) => value,
+            };
+        }
 
         #[cfg(not(feature = "no_object"))]
         {
@@ -1105,6 +1355,13 @@ impl Dynamic {
                 Err(value) => value,
             };
         }
+        #[cfg(not(feature = "no_object"))]
+        {
+            value = match unsafe_try_cast::<_, Dict>(value) {
+                Ok(dict) => return dict.into(),
+                Err(value) => value,
+            };
+        }
 
         value = match unsafe_try_cast::<_, FnPtr>(value) {
             Ok(fn_ptr) => return fn_ptr.into(),
@@ -1221,6 +1478,13 @@ impl Dynamic {
                 _ => None,
             };
         }
+        #[cfg(feature = "bigint")]
+        if TypeId::of::<T>() == TypeId::of::<BigInt>() {
+            return match self.0 {
+                Union::BigInt(value, _, _) => unsafe_try_cast(*value).ok(),
+                _ => None,
+            };
+        }
 
         if TypeId::of::<T>() == TypeId::of::<bool>() {
             return match self.0 {
@@ -1258,6 +1522,22 @@ impl Dynamic {
             };
         }
 
+        #[cfg(not(feature = "no_index"))]
+        if TypeId::of::<T>() == TypeId::of::<Blob>() {
+            return match self.0 {
+                Union::Blob(value, _, _) => unsafe_cast_box::<_, T>(value).ok().map(|v| *v),
+                _ => None,
+            };
+        }
+
+        #[cfg(not(feature = "no_index"))]
+        if TypeId::of::<T>() == TypeId::of::<Set>() {
+            return match self.0 {
+                Union::Set(value, _, _) => unsafe_cast_box::<_, T>(value).ok().map(|v| *v),
+                _ => None,
+            };
+        }
+
         #[cfg(not(feature = "no_object"))]
         if TypeId::of::<T>() == TypeId::of::<Map>() {
             return match self.0 {
@@ -1266,6 +1546,14 @@ impl Dynamic {
             };
         }
 
+        #[cfg(not(feature = "no_object"))]
+        if TypeId::of::<T>() == TypeId::of::<Dict>() {
+            return match self.0 {
+                Union::Dict(value, _, _) => unsafe_cast_box::<_, T>(value).ok().map(|v| *v),
+                _ => None,
+            };
+        }
+
         if TypeId::of::<T>() == TypeId::of::<FnPtr>() {
             return match self.0 {
                 Union::FnPtr(value, _, _) => unsafe_cast_box::<_, T>(value).ok().map(|v| *v),
@@ -1569,6 +1857,13 @@ impl Dynamic {
                 _ => None,
             };
         }
+        #[cfg(feature = "bigint")]
+        if TypeId::of::<T>() == TypeId::of::<BigInt>() {
+            return match self.0 {
+                Union::BigInt(ref value, _, _) => value.as_ref().as_any().downcast_ref::<T>(),
+                _ => None,
+            };
+        }
         if TypeId::of::<T>() == TypeId::of::<bool>() {
             return match self.0 {
                 Union::Bool(ref value, _, _) => value.as_any().downcast_ref::<T>(),
@@ -1594,6 +1889,20 @@ impl Dynamic {
                 _ => None,
             };
         }
+        #[cfg(not(feature = "no_index"))]
+        if TypeId::of::<T>() == TypeId::of::<Blob>() {
+            return match self.0 {
+                Union::Blob(ref value, _, _) => value.as_ref().as_any().downcast_ref::<T>(),
+                _ => None,
+            };
+        }
+        #[cfg(not(feature = "no_index"))]
+        if TypeId::of::<T>() == TypeId::of::<Set>() {
+            return match self.0 {
+                Union::Set(ref value, _, _) => value.as_ref().as_any().downcast_ref::<T>(),
+                _ => None,
+            };
+        }
         #[cfg(not(feature = "no_object"))]
         if TypeId::of::<T>() == TypeId::of::<Map>() {
             return match self.0 {
@@ -1601,6 +1910,13 @@ impl Dynamic {
                 _ => None,
             };
         }
+        #[cfg(not(feature = "no_object"))]
+        if TypeId::of::<T>() == TypeId::of::<Dict>() {
+            return match self.0 {
+                Union::Dict(ref value, _, _) => value.as_ref().as_any().downcast_ref::<T>(),
+                _ => None,
+            };
+        }
         if TypeId::of::<T>() == TypeId::of::<FnPtr>() {
             return match self.0 {
                 Union::FnPtr(ref value, _, _) => value.as_ref().as_any().downcast_ref::<T>(),
@@ -1664,6 +1980,15 @@ impl Dynamic {
                 _ => None,
             };
         }
+        #[cfg(feature = "bigint")]
+        if TypeId::of::<T>() == TypeId::of::<BigInt>() {
+            return match self.0 {
+                Union::BigInt(ref mut value, _, _) => {
+                    value.as_mut().as_mut_any().downcast_mut::<T>()
+                }
+                _ => None,
+            };
+        }
         if TypeId::of::<T>() == TypeId::of::<bool>() {
             return match self.0 {
                 Union::Bool(ref mut value, _, _) => value.as_mut_any().downcast_mut::<T>(),
@@ -1691,6 +2016,20 @@ impl Dynamic {
                 _ => None,
             };
         }
+        #[cfg(not(feature = "no_index"))]
+        if TypeId::of::<T>() == TypeId::of::<Blob>() {
+            return match self.0 {
+                Union::Blob(ref mut value, _, _) => value.as_mut().as_mut_any().downcast_mut::<T>(),
+                _ => None,
+            };
+        }
+        #[cfg(not(feature = "no_index"))]
+        if TypeId::of::<T>() == TypeId::of::<Set>() {
+            return match self.0 {
+                Union::Set(ref mut value, _, _) => value.as_mut().as_mut_any().downcast_mut::<T>(),
+                _ => None,
+            };
+        }
         #[cfg(not(feature = "no_object"))]
         if TypeId::of::<T>() == TypeId::of::<Map>() {
             return match self.0 {
@@ -1698,6 +2037,13 @@ impl Dynamic {
                 _ => None,
             };
         }
+        #[cfg(not(feature = "no_object"))]
+        if TypeId::of::<T>() == TypeId::of::<Dict>() {
+            return match self.0 {
+                Union::Dict(ref mut value, _, _) => value.as_mut().as_mut_any().downcast_mut::<T>(),
+                _ => None,
+            };
+        }
         if TypeId::of::<T>() == TypeId::of::<FnPtr>() {
             return match self.0 {
                 Union::FnPtr(ref mut value, _, _) => {
@@ -1786,6 +2132,24 @@ impl Dynamic {
             _ => Err(self.type_name()),
         }
     }
+    /// _(BIGINT)_ Cast the [`Dynamic`] as a [`BigInt`] and return a clone of it.
+    /// Returns the name of the actual type if the cast fails.
+    ///
+    /// Exported under the `bigint` feature only.
+    #[cfg(feature = "bigint")]
+    #[inline(always)]
+    #[must_use]
+    pub fn as_bigint(&self) -> Result<BigInt, &'static str> {
+        match self.0 {
+            Union::BigInt(ref n, _, _) => Ok(n.as_ref().clone()),
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(_, _, _) => self
+                .read_lock::<BigInt>()
+                .map(|v| v.clone())
+                .ok_or_else(|| self.type_name()),
+            _ => Err(self.type_name()),
+        }
+    }
     /// Cast the [`Dynamic`] as a [`bool`] and return it.
     /// Returns the name of the actual type if the cast fails.
     #[inline(always)]
@@ -1897,6 +2261,13 @@ impl From<Decimal> for Dynamic {
         Self(Union::Decimal(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
     }
 }
+#[cfg(feature = "bigint")]
+impl From<BigInt> for Dynamic {
+    #[inline(always)]
+    fn from(value: BigInt) -> Self {
+        Self(Union::BigInt(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
+}
 impl From<char> for Dynamic {
     #[inline(always)]
     fn from(value: char) -> Self {
@@ -1922,6 +2293,22 @@ impl Dynamic {
     pub(crate) fn from_array(array: Array) -> Self {
         Self(Union::Array(array.into(), DEFAULT_TAG_VALUE, ReadWrite))
     }
+    /// Create a [`Dynamic`] from a [`Blob`].
+    ///
+    /// A [`Vec<u8>`][Vec] converted via [`From`] becomes an [`Array`] of `u8` values,
+    /// so use this method (or [`Dynamic::from`]) to create a byte buffer.
+    #[inline(always)]
+    #[must_use]
+    pub fn from_blob(blob: Blob) -> Self {
+        Self(Union::Blob(blob.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
+}
+#[cfg(not(feature = "no_index"))]
+impl From<Set> for Dynamic {
+    #[inline(always)]
+    fn from(value: Set) -> Self {
+        Self(Union::Set(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
 }
 #[cfg(not(feature = "no_index"))]
 impl<T: Variant + Clone> From<Vec<T>> for Dynamic {
@@ -1957,6 +2344,13 @@ impl<T: Variant + Clone> std::iter::FromIterator<T> for Dynamic {
     }
 }
 #[cfg(not(feature = "no_object"))]
+impl From<Dict> for Dynamic {
+    #[inline(always)]
+    fn from(value: Dict) -> Self {
+        Self(Union::Dict(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
+}
+#[cfg(not(feature = "no_object"))]
 impl Dynamic {
     /// Create a [`Dynamic`] from a [`Map`].
     #[inline(always)]
@@ -2019,6 +2413,23 @@ impl<K: Into<crate::Identifier>, T: Variant + Clone> From<std::collections::BTre
     }
 }
 #[cfg(not(feature = "no_object"))]
+#[cfg(feature = "preserve_order")]
+impl<K: Into<crate::Identifier>, T: Variant + Clone> From<indexmap::IndexMap<K, T>> for Dynamic {
+    #[inline(always)]
+    fn from(value: indexmap::IndexMap<K, T>) -> Self {
+        Self(Union::Map(
+            Box::new(
+                value
+                    .into_iter()
+                    .map(|(k, v)| (k.into(), Dynamic::from(v)))
+                    .collect(),
+            ),
+            DEFAULT_TAG_VALUE,
+            ReadWrite,
+        ))
+    }
+}
+#[cfg(not(feature = "no_object"))]
 impl<K: Into<crate::Identifier>> From<std::collections::BTreeSet<K>> for Dynamic {
     #[inline(always)]
     fn from(value: std::collections::BTreeSet<K>) -> Self {
@@ -1,9 +1,8 @@
 //! Main module defining the script evaluation [`Engine`].
 
-use crate::ast::{Expr, FnCallExpr, Ident, OpAssignment, ReturnType, Stmt};
+use crate::ast::{Expr, FnCallExpr, Ident, OpAssignment, Pattern, ReturnType, Stmt, TryCatchBlock};
 use crate::custom_syntax::CustomSyntax;
 use crate::dynamic::{map_std_type_name, AccessMode, Union, Variant};
-use crate::fn_hash::get_hasher;
 use crate::fn_native::{
     CallableFunction, IteratorFn, OnDebugCallback, OnPrintCallback, OnVarCallback,
 };
@@ -23,7 +22,7 @@ use std::{
     borrow::Cow,
     collections::{BTreeMap, BTreeSet},
     fmt,
-    hash::{Hash, Hasher},
+    hash::Hash,
     iter::{FromIterator, Rev, Zip},
     num::{NonZeroU8, NonZeroUsize},
     ops::{Deref, DerefMut},
@@ -60,6 +59,8 @@ pub type Precedence = NonZeroU8;
 pub struct Imports {
     keys: StaticVec<Identifier>,
     modules: StaticVec<Shared<Module>>,
+    /// Random number generator seeded for this evaluation only, overriding that of the [`Engine`].
+    rng: Option<Shared<crate::Locked<crate::packages::random_basic::Rng>>>,
 }
 
 impl Imports {
@@ -116,6 +117,18 @@ impl Imports {
         self.keys.truncate(size);
         self.modules.truncate(size);
     }
+    /// Seed a random number generator for this evaluation only.
+    #[inline(always)]
+    pub(crate) fn set_random_seed(&mut self, seed: u64) {
+        let rng = crate::packages::random_basic::Rng::from_seed(seed);
+        self.rng = Some(Shared::new(crate::Locked::new(rng)));
+    }
+    /// Get the random number generator seeded for this evaluation, if any.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn rng(&self) -> Option<&Shared<crate::Locked<crate::packages::random_basic::Rng>>> {
+        self.rng.as_ref()
+    }
     /// Get an iterator to this stack of imported [modules][Module] in reverse order.
     #[allow(dead_code)]
     #[inline(always)]
@@ -371,6 +384,10 @@ pub enum Target<'a> {
     /// This is necessary because directly pointing to a char inside a String is impossible.
     #[cfg(not(feature = "no_index"))]
     StringChar(&'a mut Dynamic, usize, Dynamic),
+    /// The target is a byte inside a [`Blob`][crate::Blob].
+    /// This is necessary because directly pointing to a byte (in [`u8`]) inside a BLOB is impossible.
+    #[cfg(not(feature = "no_index"))]
+    BlobByte(&'a mut Dynamic, usize, Dynamic),
 }
 
 impl<'a> Target<'a> {
@@ -388,6 +405,8 @@ impl<'a> Target<'a> {
             Self::BitField(_, _, _) => false,
             #[cfg(not(feature = "no_index"))]
             Self::StringChar(_, _, _) => false,
+            #[cfg(not(feature = "no_index"))]
+            Self::BlobByte(_, _, _) => false,
         }
     }
     /// Is the `Target` a temp value?
@@ -403,6 +422,8 @@ impl<'a> Target<'a> {
             Self::BitField(_, _, _) => false,
             #[cfg(not(feature = "no_index"))]
             Self::StringChar(_, _, _) => false,
+            #[cfg(not(feature = "no_index"))]
+            Self::BlobByte(_, _, _) => false,
         }
     }
     /// Is the `Target` a shared value?
@@ -419,6 +440,8 @@ impl<'a> Target<'a> {
             Self::BitField(_, _, _) => false,
             #[cfg(not(feature = "no_index"))]
             Self::StringChar(_, _, _) => false,
+            #[cfg(not(feature = "no_index"))]
+            Self::BlobByte(_, _, _) => false,
         }
     }
     /// Is the `Target` a specific type?
@@ -435,6 +458,8 @@ impl<'a> Target<'a> {
             Self::BitField(_, _, _) => TypeId::of::<T>() == TypeId::of::<bool>(),
             #[cfg(not(feature = "no_index"))]
             Self::StringChar(_, _, _) => TypeId::of::<T>() == TypeId::of::<char>(),
+            #[cfg(not(feature = "no_index"))]
+            Self::BlobByte(_, _, _) => TypeId::of::<T>() == TypeId::of::<crate::INT>(),
         }
     }
     /// Get the value of the `Target` as a `Dynamic`, cloning a referenced value if necessary.
@@ -450,6 +475,8 @@ impl<'a> Target<'a> {
             Self::BitField(_, _, value) => value, // Boolean is taken
             #[cfg(not(feature = "no_index"))]
             Self::StringChar(_, _, ch) => ch, // Character is taken
+            #[cfg(not(feature = "no_index"))]
+            Self::BlobByte(_, _, value) => value, // Byte is taken
         }
     }
     /// Take a `&mut Dynamic` reference from the `Target`.
@@ -527,6 +554,23 @@ impl<'a> Target<'a> {
                     .map(|(i, ch)| if i == index { new_ch } else { ch })
                     .collect();
             }
+            #[cfg(not(feature = "no_index"))]
+            Self::BlobByte(blob, index, new_val) => {
+                // Replace the byte at the specified index position
+                let new_byte = new_val.as_int().map_err(|err| {
+                    Box::new(EvalAltResult::ErrorMismatchDataType(
+                        "int".to_string(),
+                        err.to_string(),
+                        Position::NONE,
+                    ))
+                })?;
+
+                let blob = &mut *blob
+                    .write_lock::<crate::Blob>()
+                    .expect("never fails because `BlobByte` always holds a `Blob`");
+
+                blob[*index] = (new_byte & 0x00ff) as u8;
+            }
         }
 
         Ok(())
@@ -566,6 +610,8 @@ impl Deref for Target<'_> {
             Self::BitField(_, _, ref r) => r,
             #[cfg(not(feature = "no_index"))]
             Self::StringChar(_, _, ref r) => r,
+            #[cfg(not(feature = "no_index"))]
+            Self::BlobByte(_, _, ref r) => r,
         }
     }
 }
@@ -589,6 +635,8 @@ impl DerefMut for Target<'_> {
             Self::BitField(_, _, ref mut r) => r,
             #[cfg(not(feature = "no_index"))]
             Self::StringChar(_, _, ref mut r) => r,
+            #[cfg(not(feature = "no_index"))]
+            Self::BlobByte(_, _, ref mut r) => r,
         }
     }
 }
@@ -656,6 +704,9 @@ pub struct EvalState {
     pub resolver: Option<Shared<crate::module::resolvers::StaticModuleResolver>>,
     /// Stack of function resolution caches.
     fn_resolution_caches: Vec<FnResolutionCache>,
+    /// Pending call in tail position of a script-defined function, if any.
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) tail_call: Option<Box<crate::fn_call::TailCall>>,
 }
 
 impl EvalState {
@@ -672,6 +723,8 @@ impl EvalState {
             #[cfg(not(feature = "no_module"))]
             resolver: None,
             fn_resolution_caches: Vec::new(),
+            #[cfg(not(feature = "no_function"))]
+            tail_call: None,
         }
     }
     /// Is the state currently at global (root) level?
@@ -778,6 +831,26 @@ impl Default for Limits {
     }
 }
 
+/// How integer arithmetic handles overflows.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum OverflowMode {
+    /// Raise an arithmetic error on overflow. This is the default.
+    ///
+    /// Under `unchecked`, overflows are not detected.
+    Checked,
+    /// Wrap around at the boundary of the integer type.
+    Wrapping,
+    /// Clamp to the minimum or maximum value of the integer type.
+    Saturating,
+}
+
+impl Default for OverflowMode {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::Checked
+    }
+}
+
 /// Context of a script evaluation process.
 #[derive(Debug)]
 pub struct EvalContext<'a, 'x, 'px, 'm, 's, 'b, 't, 'pt> {
@@ -925,6 +998,12 @@ pub struct Engine {
 
     /// Optimize the AST after compilation.
     pub(crate) optimization_level: OptimizationLevel,
+    /// How integer arithmetic handles overflows.
+    pub(crate) overflow_mode: OverflowMode,
+    /// Random number generator, seeded upon first use if not seeded explicitly.
+    pub(crate) rng: crate::Locked<Option<crate::packages::random_basic::Rng>>,
+    /// Cache of compiled regular expressions.
+    pub(crate) regex_cache: crate::Locked<BTreeMap<Identifier, Shared<crate::regex::Regex>>>,
 
     /// Max limits.
     #[cfg(not(feature = "unchecked"))]
@@ -969,6 +1048,49 @@ pub fn is_anonymous_fn(fn_name: &str) -> bool {
     fn_name.starts_with(FN_ANONYMOUS)
 }
 
+/// Is this index value a range?
+#[cfg(not(feature = "no_index"))]
+#[inline(always)]
+#[must_use]
+fn is_range_index(idx: &Dynamic) -> bool {
+    idx.is::<crate::ExclusiveRange>() || idx.is::<crate::InclusiveRange>()
+}
+
+/// Calculate the offsets covered by a range index into a collection of `len` items.
+///
+/// Negative bounds count from the end.  Out-of-bounds offsets are clamped.
+///
+/// # Panics
+///
+/// Panics if `idx` is not a range.
+#[cfg(not(feature = "no_index"))]
+#[must_use]
+fn calc_index_range(len: usize, idx: &Dynamic) -> std::ops::Range<usize> {
+    let calc_offset = |n: INT| {
+        if n >= 0 {
+            (n as usize).min(len)
+        } else {
+            len - (n.unsigned_abs() as usize).min(len)
+        }
+    };
+
+    let (start, end) = if let Some(range) = idx.read_lock::<crate::ExclusiveRange>() {
+        (calc_offset(range.start), calc_offset(range.end))
+    } else if let Some(range) = idx.read_lock::<crate::InclusiveRange>() {
+        let end = *range.end();
+        let end = if end < 0 && end.unsigned_abs() as usize > len {
+            0
+        } else {
+            (calc_offset(end) + 1).min(len)
+        };
+        (calc_offset(*range.start()), end)
+    } else {
+        unreachable!("range expected, but gets {}", idx.type_name())
+    };
+
+    start..end.max(start)
+}
+
 /// Print to `stdout`
 #[inline(always)]
 #[allow(unused_variables)]
@@ -1047,6 +1169,9 @@ impl Engine {
             progress: None,
 
             optimization_level: Default::default(),
+            overflow_mode: Default::default(),
+            rng: Default::default(),
+            regex_cache: Default::default(),
 
             #[cfg(not(feature = "unchecked"))]
             limits: Default::default(),
@@ -1214,8 +1339,34 @@ impl Engine {
         Ok((val.into(), var_pos))
     }
 
+    /// Search for the index of a variable within the [`Scope`], if it is there.
+    ///
+    /// Variables resolved by the variable resolver, `this` and qualified variables are never
+    /// in the [`Scope`].
+    #[must_use]
+    fn search_scope_index(&self, scope: &Scope, state: &EvalState, expr: &Expr) -> Option<usize> {
+        let index = match expr {
+            Expr::Variable(None, _, v) if v.1.is_some() || v.2 == KEYWORD_THIS => return None,
+            _ if state.always_search_scope => 0,
+            Expr::Variable(Some(i), _, _) => i.get() as usize,
+            Expr::Variable(None, _, v) => v.0.map(NonZeroUsize::get).unwrap_or(0),
+            _ => return None,
+        };
+
+        if index > 0 {
+            Some(scope.len() - index)
+        } else {
+            scope
+                .get_index(expr.get_variable_name(true)?)
+                .map(|(index, _)| index)
+        }
+    }
+
     /// Chain-evaluate a dot/index chain.
     /// [`Position`] in [`EvalAltResult`] is [`NONE`][Position::NONE] and must be set afterwards.
+    ///
+    /// If `optional` is `true` (i.e. `?.` or `?[`) and `target` is `()`, the rest of the chain
+    /// is skipped and `()` is returned.
     #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
     #[must_use]
     fn eval_dot_index_chain_helper(
@@ -1229,19 +1380,25 @@ impl Engine {
         rhs: &Expr,
         idx_values: &mut StaticVec<ChainArgument>,
         chain_type: ChainType,
+        optional: bool,
         level: usize,
         new_val: Option<((Dynamic, Position), (Option<OpAssignment>, Position))>,
     ) -> Result<(Dynamic, bool), Box<EvalAltResult>> {
-        fn match_chain_type(expr: &Expr) -> ChainType {
+        fn match_chain_type(expr: &Expr) -> (ChainType, bool) {
             match expr {
                 #[cfg(not(feature = "no_index"))]
-                Expr::Index(_, _) => ChainType::Index,
+                Expr::Index(_, optional, _) => (ChainType::Index, *optional),
                 #[cfg(not(feature = "no_object"))]
-                Expr::Dot(_, _) => ChainType::Dot,
+                Expr::Dot(_, optional, _) => (ChainType::Dot, *optional),
                 _ => unreachable!("`expr` should only be `Index` or `Dot`, but got {:?}", expr),
             }
         }
 
+        // Short-circuit optional chaining on ()
+        if optional && target.as_ref().is::<()>() {
+            return Ok((Dynamic::UNIT, false));
+        }
+
         let is_ref_mut = target.is_ref();
 
         // Pop the last index value
@@ -1259,16 +1416,28 @@ impl Engine {
 
                 match rhs {
                     // xxx[idx].expr... | xxx[idx][expr]...
-                    Expr::Dot(x, x_pos) | Expr::Index(x, x_pos) => {
+                    Expr::Dot(x, _, x_pos) | Expr::Index(x, _, x_pos) => {
                         let idx_pos = x.lhs.position();
+                        // Writing through a slice would only change a copy
+                        let create = new_val.is_some() && is_range_index(&idx_val);
                         let obj_ptr = &mut self.get_indexed_mut(
-                            mods, state, lib, target, idx_val, idx_pos, false, true, level,
+                            mods, state, lib, target, idx_val, idx_pos, create, true, level,
                         )?;
-                        let rhs_chain = match_chain_type(rhs);
+                        let (rhs_chain, rhs_optional) = match_chain_type(rhs);
 
                         self.eval_dot_index_chain_helper(
-                            mods, state, lib, this_ptr, obj_ptr, root, &x.rhs, idx_values,
-                            rhs_chain, level, new_val,
+                            mods,
+                            state,
+                            lib,
+                            this_ptr,
+                            obj_ptr,
+                            root,
+                            &x.rhs,
+                            idx_values,
+                            rhs_chain,
+                            rhs_optional,
+                            level,
+                            new_val,
                         )
                         .map_err(|err| err.fill_position(*x_pos))
                     }
@@ -1285,6 +1454,7 @@ impl Engine {
                             Ok(ref mut obj_ptr) => {
                                 self.eval_op_assignment(
                                     mods, state, lib, op_info, op_pos, obj_ptr, root, new_val,
+                                    level,
                                 )
                                 .map_err(|err| err.fill_position(new_pos))?;
                                 None
@@ -1355,7 +1525,7 @@ impl Engine {
                                 mods, state, lib, target, index, *pos, true, false, level,
                             )?;
                             self.eval_op_assignment(
-                                mods, state, lib, op_info, op_pos, val_target, root, new_val,
+                                mods, state, lib, op_info, op_pos, val_target, root, new_val, level,
                             )
                             .map_err(|err| err.fill_position(new_pos))?;
                         }
@@ -1414,6 +1584,7 @@ impl Engine {
                                 &mut (&mut orig_val).into(),
                                 root,
                                 new_val,
+                                level,
                             )
                             .map_err(|err| err.fill_position(new_pos))?;
 
@@ -1482,7 +1653,7 @@ impl Engine {
                         )
                     }
                     // {xxx:map}.sub_lhs[expr] | {xxx:map}.sub_lhs.expr
-                    Expr::Index(x, x_pos) | Expr::Dot(x, x_pos) if target.is::<Map>() => {
+                    Expr::Index(x, _, x_pos) | Expr::Dot(x, _, x_pos) if target.is::<Map>() => {
                         let val_target = &mut match x.lhs {
                             Expr::Property(ref p) => {
                                 let (name, pos) = &p.2;
@@ -1509,22 +1680,32 @@ impl Engine {
                             // Others - syntax error
                             ref expr => unreachable!("invalid dot expression: {:?}", expr),
                         };
-                        let rhs_chain = match_chain_type(rhs);
+                        let (rhs_chain, rhs_optional) = match_chain_type(rhs);
 
                         self.eval_dot_index_chain_helper(
-                            mods, state, lib, this_ptr, val_target, root, &x.rhs, idx_values,
-                            rhs_chain, level, new_val,
+                            mods,
+                            state,
+                            lib,
+                            this_ptr,
+                            val_target,
+                            root,
+                            &x.rhs,
+                            idx_values,
+                            rhs_chain,
+                            rhs_optional,
+                            level,
+                            new_val,
                         )
                         .map_err(|err| err.fill_position(*x_pos))
                     }
                     // xxx.sub_lhs[expr] | xxx.sub_lhs.expr
-                    Expr::Index(x, x_pos) | Expr::Dot(x, x_pos) => {
+                    Expr::Index(x, _, x_pos) | Expr::Dot(x, _, x_pos) => {
                         match x.lhs {
                             // xxx.prop[expr] | xxx.prop.expr
                             Expr::Property(ref p) => {
                                 let ((getter, hash_get), (setter, hash_set), (name, pos)) =
                                     p.as_ref();
-                                let rhs_chain = match_chain_type(rhs);
+                                let (rhs_chain, rhs_optional) = match_chain_type(rhs);
                                 let hash_get = FnCallHashes::from_native(*hash_get);
                                 let hash_set = FnCallHashes::from_native(*hash_set);
                                 let mut arg_values = [target.as_mut(), &mut Default::default()];
@@ -1566,6 +1747,7 @@ impl Engine {
                                         &x.rhs,
                                         idx_values,
                                         rhs_chain,
+                                        rhs_optional,
                                         level,
                                         new_val,
                                     )
@@ -1614,7 +1796,7 @@ impl Engine {
                             // xxx.fn_name(arg_expr_list)[expr] | xxx.fn_name(arg_expr_list).expr
                             Expr::FnCall(ref f, pos) if !f.is_qualified() => {
                                 let FnCallExpr { name, hashes, .. } = f.as_ref();
-                                let rhs_chain = match_chain_type(rhs);
+                                let (rhs_chain, rhs_optional) = match_chain_type(rhs);
                                 let args = &mut idx_val
                                     .as_fn_call_args()
                                     .expect("never fails because `chain_type` is `ChainType::Dot` with `Expr::FnCallExpr`");
@@ -1625,8 +1807,18 @@ impl Engine {
                                 let target = &mut val.into();
 
                                 self.eval_dot_index_chain_helper(
-                                    mods, state, lib, this_ptr, target, root, &x.rhs, idx_values,
-                                    rhs_chain, level, new_val,
+                                    mods,
+                                    state,
+                                    lib,
+                                    this_ptr,
+                                    target,
+                                    root,
+                                    &x.rhs,
+                                    idx_values,
+                                    rhs_chain,
+                                    rhs_optional,
+                                    level,
+                                    new_val,
                                 )
                                 .map_err(|err| err.fill_position(pos))
                             }
@@ -1649,7 +1841,7 @@ impl Engine {
     #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
     #[must_use]
     #[must_use]
-    fn eval_dot_index_chain(
+    pub(crate) fn eval_dot_index_chain(
         &self,
         scope: &mut Scope,
         mods: &mut Imports,
@@ -1660,11 +1852,11 @@ impl Engine {
         level: usize,
         new_val: Option<((Dynamic, Position), (Option<OpAssignment>, Position))>,
     ) -> RhaiResult {
-        let (crate::ast::BinaryExpr { lhs, rhs }, chain_type, op_pos) = match expr {
+        let (crate::ast::BinaryExpr { lhs, rhs }, chain_type, optional, op_pos) = match expr {
             #[cfg(not(feature = "no_index"))]
-            Expr::Index(x, pos) => (x.as_ref(), ChainType::Index, *pos),
+            Expr::Index(x, optional, pos) => (x.as_ref(), ChainType::Index, *optional, *pos),
             #[cfg(not(feature = "no_object"))]
-            Expr::Dot(x, pos) => (x.as_ref(), ChainType::Dot, *pos),
+            Expr::Dot(x, optional, pos) => (x.as_ref(), ChainType::Dot, *optional, *pos),
             _ => unreachable!("index or dot chain expected, but gets {:?}", expr),
         };
 
@@ -1685,8 +1877,8 @@ impl Engine {
                 let obj_ptr = &mut target.into();
                 let root = (x.2.as_str(), *var_pos);
                 self.eval_dot_index_chain_helper(
-                    mods, state, lib, &mut None, obj_ptr, root, rhs, idx_values, chain_type, level,
-                    new_val,
+                    mods, state, lib, &mut None, obj_ptr, root, rhs, idx_values, chain_type,
+                    optional, level, new_val,
                 )
                 .map(|(v, _)| v)
                 .map_err(|err| err.fill_position(op_pos))
@@ -1699,8 +1891,8 @@ impl Engine {
                 let obj_ptr = &mut value.into();
                 let root = ("", expr.position());
                 self.eval_dot_index_chain_helper(
-                    mods, state, lib, this_ptr, obj_ptr, root, rhs, idx_values, chain_type, level,
-                    new_val,
+                    mods, state, lib, this_ptr, obj_ptr, root, rhs, idx_values, chain_type,
+                    optional, level, new_val,
                 )
                 .map(|(v, _)| v)
                 .map_err(|err| err.fill_position(op_pos))
@@ -1761,7 +1953,7 @@ impl Engine {
             }
             Expr::Property(_) => unreachable!("unexpected Expr::Property for indexing"),
 
-            Expr::Index(x, _) | Expr::Dot(x, _) => {
+            Expr::Index(x, _, _) | Expr::Dot(x, _, _) => {
                 let crate::ast::BinaryExpr { lhs, rhs, .. } = x.as_ref();
 
                 // Evaluate in left-to-right order
@@ -1812,9 +2004,9 @@ impl Engine {
                 // Push in reverse order
                 let chain_type = match expr {
                     #[cfg(not(feature = "no_index"))]
-                    Expr::Index(_, _) => ChainType::Index,
+                    Expr::Index(_, _, _) => ChainType::Index,
                     #[cfg(not(feature = "no_object"))]
-                    Expr::Dot(_, _) => ChainType::Dot,
+                    Expr::Dot(_, _, _) => ChainType::Dot,
                     _ => unreachable!("index or dot chain expected, but gets {:?}", expr),
                 };
                 self.eval_indexed_chain(
@@ -1859,6 +2051,32 @@ impl Engine {
         self.inc_operations(state, Position::NONE)?;
 
         match target {
+            #[cfg(not(feature = "no_index"))]
+            Dynamic(Union::Array(_, _, _))
+            | Dynamic(Union::Blob(_, _, _))
+            | Dynamic(Union::Str(_, _, _))
+                if _create && is_range_index(&idx) =>
+            {
+                // Slices are copies, so they cannot be written to - try an index setter instead
+                EvalAltResult::ErrorIndexingType(
+                    format!(
+                        "{} [{}]",
+                        self.map_type_name(target.type_name()),
+                        self.map_type_name(idx.type_name())
+                    ),
+                    Position::NONE,
+                )
+                .into()
+            }
+
+            #[cfg(not(feature = "no_index"))]
+            Dynamic(Union::Array(arr, _, _)) if is_range_index(&idx) => {
+                // val_array[range]
+                let range = calc_index_range(arr.len(), &idx);
+                let slice: Array = arr[range].to_vec();
+                Ok(Target::from(Dynamic::from(slice)))
+            }
+
             #[cfg(not(feature = "no_index"))]
             Dynamic(Union::Array(arr, _, _)) => {
                 // val_array[idx]
@@ -1901,6 +2119,41 @@ impl Engine {
                     .ok_or_else(|| EvalAltResult::ErrorArrayBounds(arr_len, index, idx_pos).into())
             }
 
+            #[cfg(not(feature = "no_index"))]
+            Dynamic(Union::Blob(blob, _, _)) if is_range_index(&idx) => {
+                // val_blob[range]
+                let range = calc_index_range(blob.len(), &idx);
+                let slice: crate::Blob = blob[range].to_vec();
+                Ok(Target::from(Dynamic::from_blob(slice)))
+            }
+
+            #[cfg(not(feature = "no_index"))]
+            Dynamic(Union::Blob(blob, _, _)) => {
+                // val_blob[idx]
+                let index = idx
+                    .as_int()
+                    .map_err(|typ| self.make_type_mismatch_err::<crate::INT>(typ, idx_pos))?;
+
+                let len = blob.len();
+
+                let offset = if index >= 0 {
+                    index as usize
+                } else {
+                    // Count from end if negative
+                    match index.checked_abs() {
+                        Some(n) if n as usize <= len => len - n as usize,
+                        _ => return EvalAltResult::ErrorArrayBounds(len, index, idx_pos).into(),
+                    }
+                };
+
+                let value = match blob.get(offset) {
+                    Some(&b) => (b as crate::INT).into(),
+                    None => return EvalAltResult::ErrorArrayBounds(len, index, idx_pos).into(),
+                };
+
+                Ok(Target::BlobByte(target, offset, value))
+            }
+
             #[cfg(not(feature = "no_object"))]
             Dynamic(Union::Map(map, _, _)) => {
                 // val_map[idx]
@@ -1918,6 +2171,29 @@ impl Engine {
                     .unwrap_or_else(|| Target::from(Dynamic::UNIT)))
             }
 
+            #[cfg(not(feature = "no_object"))]
+            Dynamic(Union::Dict(dict, _, _)) => {
+                // val_dict[idx]
+                if !idx.is_hashable() {
+                    return EvalAltResult::ErrorMismatchDataType(
+                        "a hashable value".into(),
+                        idx.type_name().into(),
+                        idx_pos,
+                    )
+                    .into();
+                }
+
+                if _create && !dict.contains_key(&idx) {
+                    dict.insert(idx.clone(), Default::default())
+                        .expect("`idx` is hashable");
+                }
+
+                Ok(dict
+                    .get_mut(&idx)
+                    .map(Target::from)
+                    .unwrap_or_else(|| Target::from(Dynamic::UNIT)))
+            }
+
             #[cfg(not(feature = "no_index"))]
             Dynamic(Union::Int(value, _, _)) => {
                 // val_int[idx]
@@ -1955,6 +2231,19 @@ impl Engine {
                 Ok(Target::BitField(target, offset, bit_value.into()))
             }
 
+            #[cfg(not(feature = "no_index"))]
+            Dynamic(Union::Str(s, _, _)) if is_range_index(&idx) => {
+                // val_string[range]
+                let range = calc_index_range(s.chars().count(), &idx);
+                let sub_string: ImmutableString = s
+                    .chars()
+                    .skip(range.start)
+                    .take(range.end - range.start)
+                    .collect::<String>()
+                    .into();
+                Ok(Target::from(Dynamic::from(sub_string)))
+            }
+
             #[cfg(not(feature = "no_index"))]
             Dynamic(Union::Str(s, _, _)) => {
                 // val_string[idx]
@@ -2051,13 +2340,20 @@ impl Engine {
 
             // lhs[idx_expr]
             #[cfg(not(feature = "no_index"))]
-            Expr::Index(_, _) => {
+            Expr::Index(_, _, _) => {
                 self.eval_dot_index_chain(scope, mods, state, lib, this_ptr, expr, level, None)
             }
 
+            // Method call in tail position
+            #[cfg(not(feature = "no_function"))]
+            #[cfg(not(feature = "no_object"))]
+            Expr::Dot(x, _, _) if matches!(x.rhs, Expr::FnCall(ref f, _) if f.is_tail_call) => {
+                self.make_tail_method_call(scope, mods, state, lib, this_ptr, expr, level)
+            }
+
             // lhs.dot_rhs
             #[cfg(not(feature = "no_object"))]
-            Expr::Dot(_, _) => {
+            Expr::Dot(_, _, _) => {
                 self.eval_dot_index_chain(scope, mods, state, lib, this_ptr, expr, level, None)
             }
 
@@ -2078,6 +2374,7 @@ impl Engine {
                         &mut (&mut result).into(),
                         ("", Position::NONE),
                         item,
+                        level,
                     )
                     .map_err(|err| err.fill_position(expr.position()))?;
 
@@ -2099,28 +2396,114 @@ impl Engine {
             Expr::Array(x, _) => {
                 let mut arr = Array::with_capacity(x.len());
                 for item in x.as_ref() {
-                    arr.push(
-                        self.eval_expr(scope, mods, state, lib, this_ptr, item, level)?
-                            .flatten(),
-                    );
+                    match item {
+                        // ...expr
+                        Expr::Spread(expr, _) => {
+                            let value = self
+                                .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
+                                .flatten();
+                            let typ = value.type_name();
+                            arr.extend(value.try_cast::<Array>().ok_or_else(|| {
+                                self.make_type_mismatch_err::<Array>(typ, expr.position())
+                            })?);
+                        }
+                        _ => arr.push(
+                            self.eval_expr(scope, mods, state, lib, this_ptr, item, level)?
+                                .flatten(),
+                        ),
+                    }
                 }
                 Ok(arr.into())
             }
 
             #[cfg(not(feature = "no_object"))]
             Expr::Map(x, _) => {
+                #[cfg(not(feature = "preserve_order"))]
                 let mut map = x.1.clone();
+                // Insert the properties in order instead of filling in the sorted template
+                #[cfg(feature = "preserve_order")]
+                let mut map = Map::with_capacity(x.0.len());
+
                 for (Ident { name: key, .. }, expr) in &x.0 {
-                    let value_ref = map
-                        .get_mut(key.as_str())
-                        .expect("never fails because the template should contain all the keys");
-                    *value_ref = self
+                    // ...expr
+                    if let Expr::Spread(expr, _) = expr {
+                        let value = self
+                            .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
+                            .flatten();
+                        let typ = value.type_name();
+                        map.extend(value.try_cast::<Map>().ok_or_else(|| {
+                            self.make_type_mismatch_err::<Map>(typ, expr.position())
+                        })?);
+                        continue;
+                    }
+
+                    let value = self
                         .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
                         .flatten();
+
+                    #[cfg(not(feature = "preserve_order"))]
+                    {
+                        *map.get_mut(key.as_str()).expect(
+                            "never fails because the template should contain all the keys",
+                        ) = value;
+                    }
+                    #[cfg(feature = "preserve_order")]
+                    map.insert(key.clone(), value);
                 }
                 Ok(map.into())
             }
 
+            // Variant of a script-defined enum
+            #[cfg(not(feature = "no_function"))]
+            Expr::EnumVariant(x, _) => {
+                let (def, index, args) = x.as_ref();
+                let fields = args
+                    .iter()
+                    .map(|expr| {
+                        self.eval_expr(scope, mods, state, lib, this_ptr, expr, level)
+                            .map(Dynamic::flatten)
+                    })
+                    .collect::<Result<StaticVec<_>, _>>()?;
+                Ok(Dynamic::from(crate::EnumValue::new(
+                    def.clone(),
+                    *index,
+                    fields,
+                )))
+            }
+
+            // Function call with named arguments
+            #[cfg(not(feature = "no_function"))]
+            Expr::FnCall(x, pos) if !x.named_args.is_empty() => {
+                let FnCallExpr {
+                    name,
+                    namespace,
+                    args,
+                    constants,
+                    named_args,
+                    ..
+                } = x.as_ref();
+                self.make_named_function_call(
+                    scope,
+                    mods,
+                    state,
+                    lib,
+                    this_ptr,
+                    namespace.as_ref(),
+                    name,
+                    args,
+                    constants,
+                    named_args,
+                    *pos,
+                    level,
+                )
+            }
+
+            // Function call with spread arguments
+            #[cfg(not(feature = "no_index"))]
+            Expr::FnCall(x, pos) if x.args.iter().any(|a| matches!(a, Expr::Spread(_, _))) => {
+                self.make_spread_function_call(scope, mods, state, lib, this_ptr, x, *pos, level)
+            }
+
             // Namespace-qualified function call
             Expr::FnCall(x, pos) if x.is_qualified() => {
                 let FnCallExpr {
@@ -2141,6 +2524,12 @@ impl Engine {
                 )
             }
 
+            // Function call in tail position
+            #[cfg(not(feature = "no_function"))]
+            Expr::FnCall(x, pos) if x.is_tail_call => {
+                self.make_tail_call(scope, mods, state, lib, this_ptr, x, *pos, level)
+            }
+
             // Normal function call
             Expr::FnCall(x, pos) => {
                 let FnCallExpr {
@@ -2183,6 +2572,17 @@ impl Engine {
                 .into())
             }
 
+            Expr::Coalesce(x, _) => {
+                let value = self.eval_expr(scope, mods, state, lib, this_ptr, &x.lhs, level)?;
+
+                if value.is::<()>() {
+                    // Short-circuit unless the value is ()
+                    self.eval_expr(scope, mods, state, lib, this_ptr, &x.rhs, level)
+                } else {
+                    Ok(value)
+                }
+            }
+
             Expr::BoolConstant(x, _) => Ok((*x).into()),
             Expr::Unit(_) => Ok(Dynamic::UNIT),
 
@@ -2303,6 +2703,7 @@ impl Engine {
         target: &mut Target,
         root: (&str, Position),
         mut new_val: Dynamic,
+        _level: usize,
     ) -> Result<(), Box<EvalAltResult>> {
         if target.is_read_only() {
             // Assignment to constant variable
@@ -2336,20 +2737,44 @@ impl Engine {
                 let hash = hash_op_assign;
                 let args = &mut [lhs_ptr_inner, &mut new_val];
 
-                match self.call_native_fn(mods, state, lib, op, hash, args, true, true, op_pos) {
-                    Err(err) if matches!(err.as_ref(), EvalAltResult::ErrorFunctionNotFound(f, _) if f.starts_with(op)) =>
+                // Script-defined overload of the operator - expand to `var = var op rhs`
+                #[cfg(not(feature = "no_function"))]
+                let is_overloaded = match self.call_operator_overload(
+                    mods,
+                    state,
+                    lib,
+                    (op, hash),
+                    (&op[..op.len() - 1], hash_op),
+                    args,
+                    op_pos,
+                    _level,
+                )? {
+                    Some(value) => {
+                        *args[0] = value.flatten();
+                        true
+                    }
+                    None => false,
+                };
+                #[cfg(feature = "no_function")]
+                let is_overloaded = false;
+
+                if !is_overloaded {
+                    match self.call_native_fn(mods, state, lib, op, hash, args, true, true, op_pos)
                     {
-                        // Expand to `var = var op rhs`
-                        let op = &op[..op.len() - 1]; // extract operator without =
+                        Err(err) if matches!(err.as_ref(), EvalAltResult::ErrorFunctionNotFound(f, _) if f.starts_with(op)) =>
+                        {
+                            // Expand to `var = var op rhs`
+                            let op = &op[..op.len() - 1]; // extract operator without =
 
-                        // Run function
-                        let (value, _) = self.call_native_fn(
-                            mods, state, lib, op, hash_op, args, true, false, op_pos,
-                        )?;
+                            // Run function
+                            let (value, _) = self.call_native_fn(
+                                mods, state, lib, op, hash_op, args, true, false, op_pos,
+                            )?;
 
-                        *args[0] = value.flatten();
+                            *args[0] = value.flatten();
+                        }
+                        err => return err.map(|_| ()),
                     }
-                    err => return err.map(|_| ()),
                 }
             }
         } else {
@@ -2360,6 +2785,224 @@ impl Engine {
         target.propagate_changed_value()
     }
 
+    /// Find the case of a `switch` statement that matches a value.
+    ///
+    /// Returns the index of the case into `blocks`, or [`None`] for the default case.  The fields
+    /// of a matched `enum` variant are bound as new variables in the [`Scope`].
+    #[must_use]
+    pub(crate) fn eval_switch_case(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        this_ptr: &mut Option<&mut Dynamic>,
+        x: &crate::ast::SwitchCases,
+        value: &Dynamic,
+        level: usize,
+    ) -> Result<Option<usize>, Box<EvalAltResult>> {
+        let orig_scope_len = scope.len();
+
+        // Check all cases that can match the value, in order
+        for index in x.candidates(value) {
+            let condition = &x.blocks[index].0;
+
+            // Bind the fields of a matched enum variant
+            #[cfg(not(feature = "no_function"))]
+            if let Some(variant) = value.read_lock::<crate::EnumValue>() {
+                if let Some(pattern) = x
+                    .variants
+                    .iter()
+                    .find(|p| p.index == index && p.matches(&variant))
+                {
+                    pattern
+                        .fields
+                        .iter()
+                        .zip(variant.fields())
+                        .filter_map(|(name, value)| name.as_ref().map(|name| (name, value)))
+                        .for_each(|(name, value)| {
+                            scope.push(unsafe_cast_var_name_to_lifetime(name), value.clone());
+                        });
+                }
+            }
+
+            let matched = match condition {
+                Some(condition) => self
+                    .eval_expr(scope, mods, state, lib, this_ptr, condition, level)?
+                    .as_bool()
+                    .map_err(|typ| {
+                        self.make_type_mismatch_err::<bool>(typ, condition.position())
+                    })?,
+                None => true,
+            };
+
+            if matched {
+                return Ok(Some(index));
+            }
+
+            scope.rewind(orig_scope_len);
+        }
+
+        Ok(None)
+    }
+
+    /// Push a variable declared by a `let` or `const` statement into the [`Scope`].
+    fn push_variable(
+        &self,
+        scope: &mut Scope,
+        _mods: &mut Imports,
+        state: &EvalState,
+        _lib: &[&Module],
+        name: &Identifier,
+        entry_type: AccessMode,
+        export: bool,
+        value: Dynamic,
+    ) {
+        let (var_name, _alias): (Cow<'_, str>, _) = if state.is_global() {
+            #[cfg(not(feature = "no_function"))]
+            if entry_type == AccessMode::ReadOnly && _lib.iter().any(|&m| !m.is_empty()) {
+                let global = if let Some(index) = _mods.find(KEYWORD_GLOBAL) {
+                    match _mods
+                        .get_mut(index)
+                        .expect("never fails because the index came from `find`")
+                    {
+                        m if m.internal => Some(m),
+                        _ => None,
+                    }
+                } else {
+                    // Create automatic global module
+                    let mut global = Module::new();
+                    global.internal = true;
+                    _mods.push(KEYWORD_GLOBAL, global);
+                    Some(
+                        _mods
+                            .get_mut(_mods.len() - 1)
+                            .expect("never fails because the global module was just added"),
+                    )
+                };
+
+                if let Some(global) = global {
+                    Shared::get_mut(global)
+                        .expect("never fails because the global module is never shared")
+                        .set_var(name.clone(), value.clone());
+                }
+            }
+
+            (
+                name.to_string().into(),
+                if export { Some(name.clone()) } else { None },
+            )
+        } else if export {
+            unreachable!("exported variable not on global level");
+        } else {
+            (unsafe_cast_var_name_to_lifetime(name).into(), None)
+        };
+
+        scope.push_dynamic_value(var_name, entry_type, value);
+
+        #[cfg(not(feature = "no_module"))]
+        _alias.map(|alias| scope.add_entry_alias(scope.len() - 1, alias));
+    }
+    /// Destructure a value according to a [`Pattern`], pushing all the variables it binds into
+    /// the [`Scope`].
+    fn destructure(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &EvalState,
+        lib: &[&Module],
+        pattern: &Pattern,
+        entry_type: AccessMode,
+        export: bool,
+        value: Dynamic,
+    ) -> Result<(), Box<EvalAltResult>> {
+        match pattern {
+            Pattern::Ignore(_) => (),
+
+            Pattern::Var(x) => {
+                self.push_variable(scope, mods, state, lib, &x.name, entry_type, export, value)
+            }
+
+            #[cfg(not(feature = "no_index"))]
+            Pattern::Array(x, pos) => {
+                let (items, rest) = x.as_ref();
+                let typ = value.type_name();
+                let mut arr = value
+                    .try_cast::<Array>()
+                    .ok_or_else(|| self.make_type_mismatch_err::<Array>(typ, *pos))?;
+
+                match rest {
+                    None if arr.len() != items.len() => {
+                        return EvalAltResult::ErrorDestructure(
+                            format!(
+                                "expecting {} item(s), but the array has {}",
+                                items.len(),
+                                arr.len()
+                            ),
+                            *pos,
+                        )
+                        .into()
+                    }
+                    Some(_) if arr.len() < items.len() => {
+                        return EvalAltResult::ErrorDestructure(
+                            format!(
+                                "expecting at least {} item(s), but the array has {}",
+                                items.len(),
+                                arr.len()
+                            ),
+                            *pos,
+                        )
+                        .into()
+                    }
+                    _ => (),
+                }
+
+                let remainder: Array = arr.drain(items.len()..).collect();
+
+                for (pattern, value) in items.iter().zip(arr) {
+                    let value = value.flatten();
+                    self.destructure(scope, mods, state, lib, pattern, entry_type, export, value)?;
+                }
+
+                if let Some(pattern) = rest {
+                    let value = remainder.into();
+                    self.destructure(scope, mods, state, lib, pattern, entry_type, export, value)?;
+                }
+            }
+
+            #[cfg(not(feature = "no_object"))]
+            Pattern::Map(x, pos) => {
+                let (props, rest) = x.as_ref();
+                let typ = value.type_name();
+                let mut map = value
+                    .try_cast::<Map>()
+                    .ok_or_else(|| self.make_type_mismatch_err::<Map>(typ, *pos))?;
+
+                for (prop, pattern) in props.iter() {
+                    #[cfg(not(feature = "preserve_order"))]
+                    let value = map.remove(prop.name.as_str());
+                    #[cfg(feature = "preserve_order")]
+                    let value = map.shift_remove(prop.name.as_str());
+
+                    let value = value.ok_or_else(|| {
+                        EvalAltResult::ErrorDestructure(
+                            format!("property '{}' not found in the object map", prop.name),
+                            prop.pos,
+                        )
+                    })?;
+                    let value = value.flatten();
+                    self.destructure(scope, mods, state, lib, pattern, entry_type, export, value)?;
+                }
+
+                if let Some(pattern) = rest {
+                    let value = map.into();
+                    self.destructure(scope, mods, state, lib, pattern, entry_type, export, value)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
     /// Evaluate a statement.
     ///
     /// # Safety
@@ -2395,6 +3038,13 @@ impl Engine {
                 let rhs_val = self
                     .eval_expr(scope, mods, state, lib, this_ptr, rhs_expr, level)?
                     .flatten();
+
+                // A variable with a declared type must keep it
+                let declared_type = self
+                    .search_scope_index(scope, state, lhs_expr)
+                    .and_then(|index| scope.get_entry_type(index))
+                    .map(Identifier::from);
+
                 let (mut lhs_ptr, pos) =
                     self.search_namespace(scope, mods, state, lib, this_ptr, lhs_expr)?;
 
@@ -2410,6 +3060,26 @@ impl Engine {
                 #[cfg(not(feature = "unchecked"))]
                 self.inc_operations(state, pos)?;
 
+                let check_type = |value: &Dynamic| {
+                    self.check_type_annotation(
+                        value,
+                        declared_type.as_ref().map(|t| t.as_str()),
+                        || format!("for variable '{}'", var_name),
+                        pos,
+                    )
+                };
+
+                // Check the new value before a plain assignment, but keep the original value
+                // of a compound assignment in case its result has another type
+                let orig_val = match declared_type {
+                    None => None,
+                    Some(_) if op_info.is_none() => {
+                        check_type(&rhs_val)?;
+                        None
+                    }
+                    Some(_) => Some(lhs_ptr.as_ref().flatten_clone()),
+                };
+
                 self.eval_op_assignment(
                     mods,
                     state,
@@ -2419,9 +3089,19 @@ impl Engine {
                     &mut lhs_ptr,
                     (var_name, pos),
                     rhs_val,
+                    level,
                 )
                 .map_err(|err| err.fill_position(rhs_expr.position()))?;
 
+                if let Some(orig_val) = orig_val {
+                    if let Err(err) = check_type(lhs_ptr.as_ref()) {
+                        *lhs_ptr
+                            .write_lock::<Dynamic>()
+                            .expect("never fails when casting to `Dynamic`") = orig_val;
+                        return Err(err);
+                    }
+                }
+
                 if op_info.is_some() {
                     self.check_data_size(lhs_ptr.as_ref())
                         .map_err(|err| err.fill_position(lhs_expr.position()))?;
@@ -2446,7 +3126,7 @@ impl Engine {
                     }
                     // idx_lhs[idx_expr] op= rhs
                     #[cfg(not(feature = "no_index"))]
-                    Expr::Index(_, _) => {
+                    Expr::Index(_, _, _) => {
                         self.eval_dot_index_chain(
                             scope, mods, state, lib, this_ptr, lhs_expr, level, _new_val,
                         )?;
@@ -2454,7 +3134,7 @@ impl Engine {
                     }
                     // dot_lhs.dot_rhs op= rhs
                     #[cfg(not(feature = "no_object"))]
-                    Expr::Dot(_, _) => {
+                    Expr::Dot(_, _, _) => {
                         self.eval_dot_index_chain(
                             scope, mods, state, lib, this_ptr, lhs_expr, level, _new_val,
                         )?;
@@ -2494,57 +3174,27 @@ impl Engine {
 
             // Switch statement
             Stmt::Switch(match_expr, x, _) => {
-                let (table, def_stmt) = x.as_ref();
-
                 let value = self.eval_expr(scope, mods, state, lib, this_ptr, match_expr, level)?;
 
-                if value.is_hashable() {
-                    let hasher = &mut get_hasher();
-                    value.hash(hasher);
-                    let hash = hasher.finish();
-
-                    table.get(&hash).and_then(|t| {
-                        if let Some(condition) = &t.0 {
-                            match self
-                                .eval_expr(scope, mods, state, lib, this_ptr, &condition, level)
-                                .and_then(|v| {
-                                    v.as_bool().map_err(|typ| {
-                                        self.make_type_mismatch_err::<bool>(
-                                            typ,
-                                            condition.position(),
-                                        )
-                                    })
-                                }) {
-                                Ok(true) => (),
-                                Ok(false) => return None,
-                                Err(err) => return Some(Err(err)),
-                            }
-                        }
+                let orig_scope_len = scope.len();
 
-                        let statements = &t.1;
+                let statements = match self
+                    .eval_switch_case(scope, mods, state, lib, this_ptr, x, &value, level)?
+                {
+                    Some(index) => &x.blocks[index].1,
+                    // Default match clause
+                    None => &x.def_case,
+                };
 
-                        Some(if !statements.is_empty() {
-                            self.eval_stmt_block(
-                                scope, mods, state, lib, this_ptr, statements, true, level,
-                            )
-                        } else {
-                            Ok(Dynamic::UNIT)
-                        })
-                    })
+                let result = if !statements.is_empty() {
+                    self.eval_stmt_block(scope, mods, state, lib, this_ptr, statements, true, level)
                 } else {
-                    // Non-hashable values never match any specific clause
-                    None
-                }
-                .unwrap_or_else(|| {
-                    // Default match clause
-                    if !def_stmt.is_empty() {
-                        self.eval_stmt_block(
-                            scope, mods, state, lib, this_ptr, def_stmt, true, level,
-                        )
-                    } else {
-                        Ok(Dynamic::UNIT)
-                    }
-                })
+                    Ok(Dynamic::UNIT)
+                };
+
+                scope.rewind(orig_scope_len);
+
+                result
             }
 
             // While loop
@@ -2603,31 +3253,25 @@ impl Engine {
                 let iter_obj = self
                     .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
                     .flatten();
-                let iter_type = iter_obj.type_id();
-
-                // lib should only contain scripts, so technically they cannot have iterators
-
-                // Search order:
-                // 1) Global namespace - functions registered via Engine::register_XXX
-                // 2) Global modules - packages
-                // 3) Imported modules - functions marked with global namespace
-                // 4) Global sub-modules - functions marked with global namespace
-                let func = self
-                    .global_namespace
-                    .get_iter(iter_type)
-                    .or_else(|| {
-                        self.global_modules
-                            .iter()
-                            .find_map(|m| m.get_iter(iter_type))
-                    })
-                    .or_else(|| mods.get_iter(iter_type))
-                    .or_else(|| {
-                        self.global_sub_modules
-                            .values()
-                            .find_map(|m| m.get_qualified_iter(iter_type))
-                    });
 
-                if let Some(func) = func {
+                // Generators are resumed directly, as they need the evaluation context
+                #[cfg(not(feature = "no_function"))]
+                let (iter_obj, mut generator) = if iter_obj.is::<crate::Generator>() {
+                    (Dynamic::UNIT, Some(iter_obj.cast::<crate::Generator>()))
+                } else {
+                    (iter_obj, None)
+                };
+                #[allow(unused_mut)]
+                let mut iter = self
+                    .get_type_iterator(mods, iter_obj.type_id())
+                    .map(|func| func(iter_obj));
+
+                #[cfg(not(feature = "no_function"))]
+                if generator.is_some() {
+                    iter = Some(Box::new(std::iter::empty()));
+                }
+
+                if let Some(mut iter) = iter {
                     // Add the loop variables
                     let orig_scope_len = scope.len();
                     let counter_index = if let Some(Ident { name, .. }) = counter {
@@ -2640,7 +3284,26 @@ impl Engine {
                     let index = scope.len() - 1;
                     state.scope_level += 1;
 
-                    for (x, iter_value) in func(iter_obj).enumerate() {
+                    for x in 0.. {
+                        #[cfg(not(feature = "no_function"))]
+                        let iter_value = match generator {
+                            Some(ref mut generator) => self.resume_generator(
+                                state,
+                                lib,
+                                generator,
+                                expr.position(),
+                                level + 1,
+                            )?,
+                            None => iter.next(),
+                        };
+                        #[cfg(feature = "no_function")]
+                        let iter_value = iter.next();
+
+                        let iter_value = match iter_value {
+                            Some(value) => value,
+                            None => break,
+                        };
+
                         // Increment counter
                         if let Some(c) = counter_index {
                             #[cfg(not(feature = "unchecked"))]
@@ -2708,12 +3371,49 @@ impl Engine {
                 }
             }
 
+            // Yield statement
+            #[cfg(not(feature = "no_function"))]
+            Stmt::Yield(_, _) => unreachable!("`yield` statements are handled by the generator"),
+
             // Continue statement
             Stmt::Continue(pos) => EvalAltResult::LoopBreak(false, *pos).into(),
 
             // Break statement
             Stmt::Break(pos) => EvalAltResult::LoopBreak(true, *pos).into(),
 
+            // Function call with named arguments
+            #[cfg(not(feature = "no_function"))]
+            Stmt::FnCall(x, pos) if !x.named_args.is_empty() => {
+                let FnCallExpr {
+                    name,
+                    namespace,
+                    args,
+                    constants,
+                    named_args,
+                    ..
+                } = x.as_ref();
+                self.make_named_function_call(
+                    scope,
+                    mods,
+                    state,
+                    lib,
+                    this_ptr,
+                    namespace.as_ref(),
+                    name,
+                    args,
+                    constants,
+                    named_args,
+                    *pos,
+                    level,
+                )
+            }
+
+            // Function call with spread arguments
+            #[cfg(not(feature = "no_index"))]
+            Stmt::FnCall(x, pos) if x.args.iter().any(|a| matches!(a, Expr::Spread(_, _))) => {
+                self.make_spread_function_call(scope, mods, state, lib, this_ptr, x, *pos, level)
+            }
+
             // Namespace-qualified function call
             Stmt::FnCall(x, pos) if x.is_qualified() => {
                 let FnCallExpr {
@@ -2734,6 +3434,12 @@ impl Engine {
                 )
             }
 
+            // Function call in tail position
+            #[cfg(not(feature = "no_function"))]
+            Stmt::FnCall(x, pos) if x.is_tail_call => {
+                self.make_tail_call(scope, mods, state, lib, this_ptr, x, *pos, level)
+            }
+
             // Normal function call
             Stmt::FnCall(x, pos) => {
                 let FnCallExpr {
@@ -2752,17 +3458,24 @@ impl Engine {
 
             // Try/Catch statement
             Stmt::TryCatch(x, _) => {
-                let (try_stmt, err_var, catch_stmt) = x.as_ref();
+                let TryCatchBlock {
+                    try_block,
+                    catch_clauses,
+                    finally_block,
+                } = x.as_ref();
 
                 let result = self
-                    .eval_stmt_block(scope, mods, state, lib, this_ptr, try_stmt, true, level)
+                    .eval_stmt_block(scope, mods, state, lib, this_ptr, try_block, true, level)
                     .map(|_| Dynamic::UNIT);
 
-                match result {
+                let result = match result {
                     Ok(_) => result,
                     Err(err) if err.is_pseudo_error() => Err(err),
                     Err(err) if !err.is_catchable() => Err(err),
+                    Err(err) if catch_clauses.is_empty() => Err(err),
                     Err(mut err) => {
+                        let err_pos = err.position();
+
                         let err_value = match *err {
                             EvalAltResult::ErrorRuntime(ref x, _) => x.clone(),
 
@@ -2774,6 +3487,32 @@ impl Engine {
                             #[cfg(not(feature = "no_object"))]
                             _ => {
                                 let mut err_map: Map = Default::default();
+
+                                #[cfg(not(feature = "no_index"))]
+                                err_map.insert(
+                                    "stack".into(),
+                                    err.call_stack()
+                                        .into_iter()
+                                        .map(|frame| {
+                                            let mut frame_map: Map = Default::default();
+                                            frame_map
+                                                .insert("function".into(), frame.fn_name.into());
+                                            frame_map.insert("source".into(), frame.source.into());
+                                            if let Some(line) = frame.position.line() {
+                                                frame_map
+                                                    .insert("line".into(), (line as INT).into());
+                                                frame_map.insert(
+                                                    "position".into(),
+                                                    (frame.position.position().unwrap_or(0) as INT)
+                                                        .into(),
+                                                );
+                                            }
+                                            frame_map.into()
+                                        })
+                                        .collect::<Array>()
+                                        .into(),
+                                );
+
                                 let err_pos = err.take_position();
 
                                 err_map.insert("message".into(), err.to_string().into());
@@ -2800,23 +3539,63 @@ impl Engine {
                             }
                         };
 
-                        let orig_scope_len = scope.len();
-                        state.scope_level += 1;
+                        err.set_position(err_pos);
 
-                        err_var.as_ref().map(|Ident { name, .. }| {
-                            scope.push(unsafe_cast_var_name_to_lifetime(name), err_value)
-                        });
+                        // Find the first catch clause that matches
+                        let mut caught = None;
 
-                        let result = self.eval_stmt_block(
-                            scope, mods, state, lib, this_ptr, catch_stmt, true, level,
-                        );
+                        for clause in catch_clauses.iter() {
+                            let orig_scope_len = scope.len();
+                            state.scope_level += 1;
 
-                        state.scope_level -= 1;
-                        scope.rewind(orig_scope_len);
+                            clause.var.as_ref().map(|Ident { name, .. }| {
+                                scope
+                                    .push(unsafe_cast_var_name_to_lifetime(name), err_value.clone())
+                            });
 
-                        match result {
-                            Ok(_) => Ok(Dynamic::UNIT),
-                            Err(result_err) => match *result_err {
+                            let matched = match clause.condition {
+                                Some(ref condition) => self
+                                    .eval_expr(scope, mods, state, lib, this_ptr, condition, level)
+                                    .and_then(|v| {
+                                        v.as_bool().map_err(|typ| {
+                                            self.make_type_mismatch_err::<bool>(
+                                                typ,
+                                                condition.position(),
+                                            )
+                                        })
+                                    }),
+                                None => Ok(true),
+                            };
+
+                            let result = match matched {
+                                Ok(true) => Some(self.eval_stmt_block(
+                                    scope,
+                                    mods,
+                                    state,
+                                    lib,
+                                    this_ptr,
+                                    &clause.body,
+                                    true,
+                                    level,
+                                )),
+                                Ok(false) => None,
+                                Err(err) => Some(Err(err)),
+                            };
+
+                            state.scope_level -= 1;
+                            scope.rewind(orig_scope_len);
+
+                            if result.is_some() {
+                                caught = result;
+                                break;
+                            }
+                        }
+
+                        match caught {
+                            // No catch clause matches - propagate the exception
+                            None => Err(err),
+                            Some(Ok(_)) => Ok(Dynamic::UNIT),
+                            Some(Err(result_err)) => match *result_err {
                                 // Re-throw exception
                                 EvalAltResult::ErrorRuntime(Dynamic(Union::Unit(_, _, _)), pos) => {
                                     err.set_position(pos);
@@ -2826,6 +3605,25 @@ impl Engine {
                             },
                         }
                     }
+                };
+
+                // The finally block always runs, unless the script is aborted by a system exception
+                match result {
+                    Err(ref err) if err.is_system_exception() => result,
+                    _ if finally_block.is_empty() => result,
+                    _ => {
+                        self.eval_stmt_block(
+                            scope,
+                            mods,
+                            state,
+                            lib,
+                            this_ptr,
+                            finally_block,
+                            true,
+                            level,
+                        )?;
+                        result
+                    }
                 }
             }
 
@@ -2857,60 +3655,59 @@ impl Engine {
 
             // Let/const statement
             Stmt::Let(expr, x, export, _) | Stmt::Const(expr, x, export, _) => {
-                let name = &x.name;
                 let entry_type = match stmt {
                     Stmt::Let(_, _, _, _) => AccessMode::ReadWrite,
                     Stmt::Const(_, _, _, _) => AccessMode::ReadOnly,
                     _ => unreachable!("should be Stmt::Let or Stmt::Const, but gets {:?}", stmt),
                 };
 
+                let (var_def, typ) = x.as_ref();
+
                 let value = self
                     .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
                     .flatten();
 
-                let (var_name, _alias): (Cow<'_, str>, _) = if state.is_global() {
-                    #[cfg(not(feature = "no_function"))]
-                    if entry_type == AccessMode::ReadOnly && lib.iter().any(|&m| !m.is_empty()) {
-                        let global = if let Some(index) = mods.find(KEYWORD_GLOBAL) {
-                            match mods
-                                .get_mut(index)
-                                .expect("never fails because the index came from `find`")
-                            {
-                                m if m.internal => Some(m),
-                                _ => None,
-                            }
-                        } else {
-                            // Create automatic global module
-                            let mut global = Module::new();
-                            global.internal = true;
-                            mods.push(KEYWORD_GLOBAL, global);
-                            Some(
-                                mods.get_mut(mods.len() - 1)
-                                    .expect("never fails because the global module was just added"),
-                            )
-                        };
+                self.check_type_annotation(
+                    &value,
+                    typ.as_ref().map(|t| t.as_str()),
+                    || format!("for variable '{}'", var_def.name),
+                    var_def.pos,
+                )?;
 
-                        if let Some(global) = global {
-                            Shared::get_mut(global)
-                                .expect("never fails because the global module is never shared")
-                                .set_var(name.clone(), value.clone());
-                        }
-                    }
+                self.push_variable(
+                    scope,
+                    mods,
+                    state,
+                    lib,
+                    &var_def.name,
+                    entry_type,
+                    *export,
+                    value,
+                );
 
-                    (
-                        name.to_string().into(),
-                        if *export { Some(name.clone()) } else { None },
-                    )
-                } else if *export {
-                    unreachable!("exported variable not on global level");
-                } else {
-                    (unsafe_cast_var_name_to_lifetime(name).into(), None)
-                };
+                if let Some(typ) = typ {
+                    scope.set_entry_type(scope.len() - 1, typ.clone());
+                }
+
+                Ok(Dynamic::UNIT)
+            }
 
-                scope.push_dynamic_value(var_name, entry_type, value);
+            // Destructuring let/const statement
+            Stmt::Destructure(expr, pattern, entry_type, export, _) => {
+                let value = self
+                    .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
+                    .flatten();
 
-                #[cfg(not(feature = "no_module"))]
-                _alias.map(|alias| scope.add_entry_alias(scope.len() - 1, alias));
+                self.destructure(
+                    scope,
+                    mods,
+                    state,
+                    lib,
+                    pattern,
+                    *entry_type,
+                    *export,
+                    value,
+                )?;
 
                 Ok(Dynamic::UNIT)
             }
@@ -3050,6 +3847,10 @@ impl Engine {
                                 let (a, m, s) = calc_size(value);
                                 (arrays + a + 1, maps + m, strings + s)
                             }
+                            Union::Blob(ref b, _, _) => (arrays + b.len() + 1, maps, strings),
+                            Union::Set(ref x, _, _) => (arrays + x.len() + 1, maps, strings),
+                            #[cfg(not(feature = "no_object"))]
+                            Union::Dict(ref x, _, _) => (arrays + 1, maps + x.len(), strings),
                             Union::Str(ref s, _, _) => (arrays + 1, maps, strings + s.len()),
                             _ => (arrays + 1, maps, strings),
                         })
@@ -3067,10 +3868,21 @@ impl Engine {
                                 let (a, m, s) = calc_size(value);
                                 (arrays + a, maps + m + 1, strings + s)
                             }
+                            #[cfg(not(feature = "no_index"))]
+                            Union::Blob(ref b, _, _) => (arrays + b.len(), maps + 1, strings),
+                            #[cfg(not(feature = "no_index"))]
+                            Union::Set(ref x, _, _) => (arrays + x.len(), maps + 1, strings),
+                            Union::Dict(ref x, _, _) => (arrays, maps + x.len() + 1, strings),
                             Union::Str(ref s, _, _) => (arrays, maps + 1, strings + s.len()),
                             _ => (arrays, maps + 1, strings),
                         })
                 }
+                #[cfg(not(feature = "no_index"))]
+                Union::Blob(ref b, _, _) => (b.len(), 0, 0),
+                #[cfg(not(feature = "no_index"))]
+                Union::Set(ref x, _, _) => (x.len(), 0, 0),
+                #[cfg(not(feature = "no_object"))]
+                Union::Dict(ref x, _, _) => (0, x.len(), 0),
                 Union::Str(ref s, _, _) => (0, 0, s.len()),
                 _ => (0, 0, 0),
             }
@@ -3133,6 +3945,34 @@ impl Engine {
         Ok(())
     }
 
+    /// Get the iterator function registered for a type, if any.
+    #[must_use]
+    pub(crate) fn get_type_iterator(
+        &self,
+        mods: &Imports,
+        iter_type: TypeId,
+    ) -> Option<IteratorFn> {
+        // lib should only contain scripts, so technically they cannot have iterators
+
+        // Search order:
+        // 1) Global namespace - functions registered via Engine::register_XXX
+        // 2) Global modules - packages
+        // 3) Imported modules - functions marked with global namespace
+        // 4) Global sub-modules - functions marked with global namespace
+        self.global_namespace
+            .get_iter(iter_type)
+            .or_else(|| {
+                self.global_modules
+                    .iter()
+                    .find_map(|m| m.get_iter(iter_type))
+            })
+            .or_else(|| mods.get_iter(iter_type))
+            .or_else(|| {
+                self.global_sub_modules
+                    .values()
+                    .find_map(|m| m.get_qualified_iter(iter_type))
+            })
+    }
     /// Check if the number of operations stay within limit.
     #[cfg(not(feature = "unchecked"))]
     #[must_use]
@@ -3172,6 +4012,90 @@ impl Engine {
             .unwrap_or_else(|| map_std_type_name(name))
     }
 
+    /// Is a name valid in a type annotation?
+    ///
+    /// Besides the standard type names (e.g. `int`, `string`, `array`), names provided to
+    /// [`register_type_with_name`][Engine::register_type_with_name] are also recognized.
+    #[must_use]
+    pub(crate) fn is_type_name(&self, name: &str) -> bool {
+        match name {
+            "()" | "bool" | "char" | "string" | "int" | "Fn" | "range" => return true,
+
+            #[cfg(not(feature = "no_float"))]
+            "float" => return true,
+            #[cfg(feature = "decimal")]
+            "decimal" => return true,
+            #[cfg(feature = "bigint")]
+            "bigint" => return true,
+            #[cfg(not(feature = "no_index"))]
+            "array" => return true,
+            #[cfg(not(feature = "no_index"))]
+            "blob" | "set" => return true,
+            #[cfg(not(feature = "no_object"))]
+            "map" | "dict" => return true,
+            #[cfg(not(feature = "no_std"))]
+            "timestamp" | "datetime" | "duration" => return true,
+            #[cfg(not(feature = "no_function"))]
+            "generator" | "enum" => return true,
+
+            #[cfg(not(feature = "only_i32"))]
+            #[cfg(not(feature = "only_i64"))]
+            "i8" | "u8" | "i16" | "u16" | "i32" | "u32" | "i64" | "u64" => return true,
+            #[cfg(not(feature = "only_i32"))]
+            #[cfg(not(feature = "only_i64"))]
+            #[cfg(not(target_arch = "wasm32"))]
+            "i128" | "u128" => return true,
+
+            _ => (),
+        }
+
+        if name == type_name::<INT>() {
+            return true;
+        }
+        #[cfg(not(feature = "no_float"))]
+        if name == type_name::<crate::FLOAT>() {
+            return true;
+        }
+
+        self.type_names.values().any(|n| n.as_str() == name)
+    }
+
+    /// Does a value match a type annotation?
+    #[must_use]
+    pub(crate) fn matches_type_name(&self, value: &Dynamic, name: &str) -> bool {
+        let value_type = value.type_name();
+
+        match name {
+            "int" => value_type == type_name::<INT>(),
+            #[cfg(not(feature = "no_float"))]
+            "float" => value_type == type_name::<crate::FLOAT>(),
+            _ => self.map_type_name(value_type) == name,
+        }
+    }
+
+    /// Check that a value matches a type annotation, if any.
+    ///
+    /// `target` describes what is annotated, e.g. "for parameter 'x'".
+    pub(crate) fn check_type_annotation(
+        &self,
+        value: &Dynamic,
+        typ: Option<&str>,
+        target: impl FnOnce() -> String,
+        pos: Position,
+    ) -> Result<(), Box<EvalAltResult>> {
+        match typ {
+            Some(typ) if !self.matches_type_name(value, typ) => {
+                EvalAltResult::ErrorMismatchDataType(
+                    format!("{} {}", typ, target()),
+                    self.map_type_name(value.type_name()).into(),
+                    pos,
+                )
+                .into()
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Make a `Box<`[`EvalAltResult<ErrorMismatchDataType>`][EvalAltResult::ErrorMismatchDataType]`>`.
     #[inline(always)]
     #[must_use]
@@ -143,6 +143,165 @@ impl Engine {
         );
         self
     }
+    /// Register a custom asynchronous function with the [`Engine`].
+    ///
+    /// The function returns a [`Future`][std::future::Future], which is awaited each time the
+    /// function is called from a script.
+    ///
+    /// Under [`eval_async`][Engine::eval_async], [`eval_ast_async`][Engine::eval_ast_async] and
+    /// [`call_fn_async`][Engine::call_fn_async], the future is driven by the task awaiting the
+    /// evaluation, which yields to the executor while the future is pending.
+    /// Under all other evaluation methods, calling the function fails with
+    /// [`EvalAltResult::ErrorRuntime`] instead of blocking the current thread.
+    ///
+    /// Only available under the `async` feature.  Not available under `no_std`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # use std::future::Future;
+    /// # use std::sync::Arc;
+    /// # use std::task::{Context, Poll, Wake, Waker};
+    /// # struct Unpark(std::thread::Thread);
+    /// # impl Wake for Unpark { fn wake(self: Arc<Self>) { self.0.unpark() } }
+    /// # fn block_on<F: Future>(future: F) -> F::Output {
+    /// #     let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    /// #     let mut future = Box::pin(future);
+    /// #     loop {
+    /// #         match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+    /// #             Poll::Ready(r) => return r,
+    /// #             Poll::Pending => std::thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    /// use rhai::{Engine, Shared};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_async_fn("add", |x: i64, y: i64| async move { x + y });
+    ///
+    /// let engine = Shared::new(engine);
+    ///
+    /// assert_eq!(block_on(engine.eval_async::<i64>("add(40, 2)"))?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg(not(feature = "no_std"))]
+    #[inline]
+    pub fn register_async_fn<N, A, F, R>(&mut self, name: N, func: F) -> &mut Self
+    where
+        N: AsRef<str> + Into<Identifier>,
+        F: RegisterNativeFunction<A, crate::fn_async::Async<R>>,
+    {
+        let param_types = F::param_types();
+
+        #[cfg(feature = "metadata")]
+        let mut param_type_names: crate::StaticVec<_> = F::param_names()
+            .iter()
+            .map(|ty| format!("_: {}", self.map_type_name(ty)))
+            .collect();
+
+        #[cfg(feature = "metadata")]
+        if F::return_type() != TypeId::of::<()>() {
+            param_type_names.push(self.map_type_name(F::return_type_name()).into());
+        }
+
+        #[cfg(feature = "metadata")]
+        let param_type_names: Option<crate::StaticVec<_>> =
+            Some(param_type_names.iter().map(|ty| ty.as_str()).collect());
+
+        #[cfg(not(feature = "metadata"))]
+        let param_type_names: Option<[&str; 0]> = None;
+
+        self.global_namespace.set_fn(
+            name,
+            FnNamespace::Global,
+            FnAccess::Public,
+            param_type_names.as_ref().map(|v| v.as_ref()),
+            &param_types,
+            func.into_callable_function(),
+        );
+        self
+    }
+    /// Register a custom fallible asynchronous function with the [`Engine`].
+    ///
+    /// See [`register_async_fn`][Engine::register_async_fn] for details.
+    ///
+    /// Only available under the `async` feature.  Not available under `no_std`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::future::Future;
+    /// # use std::sync::Arc;
+    /// # use std::task::{Context, Poll, Wake, Waker};
+    /// # struct Unpark(std::thread::Thread);
+    /// # impl Wake for Unpark { fn wake(self: Arc<Self>) { self.0.unpark() } }
+    /// # fn block_on<F: Future>(future: F) -> F::Output {
+    /// #     let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    /// #     let mut future = Box::pin(future);
+    /// #     loop {
+    /// #         match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+    /// #             Poll::Ready(r) => return r,
+    /// #             Poll::Pending => std::thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    /// use rhai::{Engine, EvalAltResult, Shared};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_async_result_fn("div", |x: i64, y: i64| async move {
+    ///     if y == 0 {
+    ///         Err("division by zero!".into())
+    ///     } else {
+    ///         Ok(x / y)
+    ///     }
+    /// });
+    ///
+    /// let engine = Shared::new(engine);
+    ///
+    /// block_on(engine.eval_async::<i64>("div(42, 0)"))
+    ///     .expect_err("expecting division by zero error!");
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg(not(feature = "no_std"))]
+    #[inline]
+    pub fn register_async_result_fn<N, A, F, R>(&mut self, name: N, func: F) -> &mut Self
+    where
+        N: AsRef<str> + Into<Identifier>,
+        F: RegisterNativeFunction<A, crate::fn_async::AsyncResult<R>>,
+    {
+        let param_types = F::param_types();
+
+        #[cfg(feature = "metadata")]
+        let param_type_names: crate::StaticVec<_> = F::param_names()
+            .iter()
+            .map(|ty| format!("_: {}", self.map_type_name(ty)))
+            .chain(std::iter::once(
+                self.map_type_name(F::return_type_name()).into(),
+            ))
+            .collect();
+
+        #[cfg(feature = "metadata")]
+        let param_type_names: Option<crate::StaticVec<_>> =
+            Some(param_type_names.iter().map(|ty| ty.as_str()).collect());
+
+        #[cfg(not(feature = "metadata"))]
+        let param_type_names: Option<[&str; 0]> = None;
+
+        self.global_namespace.set_fn(
+            name,
+            FnNamespace::Global,
+            FnAccess::Public,
+            param_type_names.as_ref().map(|v| v.as_ref()),
+            &param_types,
+            func.into_callable_function(),
+        );
+        self
+    }
     /// Register a function of the [`Engine`].
     ///
     /// # WARNING - Low Level API
@@ -1739,6 +1898,54 @@ impl Engine {
             .into()
         });
     }
+    /// Evaluate an [`AST`] with own scope, seeding the random number generator used by
+    /// [`RandomPackage`][crate::packages::RandomPackage] for this evaluation only.
+    ///
+    /// Unlike [`set_random_seed`][Engine::set_random_seed], this does not need mutable access to
+    /// the [`Engine`], so a shared [`Engine`] can run many reproducible evaluations at once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Scope};
+    /// use rhai::packages::{Package, RandomPackage};
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.register_global_module(RandomPackage::new().as_shared_module());
+    ///
+    /// let ast = engine.compile("[rand(), rand(), rand()]")?;
+    ///
+    /// let first: rhai::Array = engine.eval_ast_with_scope_and_seed(&mut Scope::new(), &ast, 42)?;
+    /// let second: rhai::Array = engine.eval_ast_with_scope_and_seed(&mut Scope::new(), &ast, 42)?;
+    ///
+    /// assert_eq!(format!("{:?}", first), format!("{:?}", second));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn eval_ast_with_scope_and_seed<T: Variant + Clone>(
+        &self,
+        scope: &mut Scope,
+        ast: &AST,
+        seed: u64,
+    ) -> Result<T, Box<EvalAltResult>> {
+        let mods = &mut Imports::new();
+        mods.set_random_seed(seed);
+
+        let result = self.eval_ast_with_scope_raw(scope, mods, ast, 0)?;
+
+        let typ = self.map_type_name(result.type_name());
+
+        return result.try_cast::<T>().ok_or_else(|| {
+            EvalAltResult::ErrorMismatchOutputType(
+                self.map_type_name(type_name::<T>()).into(),
+                typ.into(),
+                Position::NONE,
+            )
+            .into()
+        });
+    }
     /// Evaluate an [`AST`] with own scope.
     #[inline(always)]
     #[must_use]
@@ -1765,6 +1972,72 @@ impl Engine {
         let lib = &[ast.lib()];
         self.eval_global_statements(scope, mods, &mut state, statements, lib, level)
     }
+    /// Evaluate a string asynchronously.
+    ///
+    /// The script runs on a thread taken from a pool, so the [`Engine`] must be
+    /// [shared][Shared].
+    /// While an asynchronous function registered via
+    /// [`register_async_fn`][Engine::register_async_fn] is pending, the returned
+    /// [`EvalFuture`][crate::EvalFuture] yields to the executor.
+    ///
+    /// Only available under the `async` feature.  Not available under `no_std`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # use std::future::Future;
+    /// # use std::sync::Arc;
+    /// # use std::task::{Context, Poll, Wake, Waker};
+    /// # struct Unpark(std::thread::Thread);
+    /// # impl Wake for Unpark { fn wake(self: Arc<Self>) { self.0.unpark() } }
+    /// # fn block_on<F: Future>(future: F) -> F::Output {
+    /// #     let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    /// #     let mut future = Box::pin(future);
+    /// #     loop {
+    /// #         match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+    /// #             Poll::Ready(r) => return r,
+    /// #             Poll::Pending => std::thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    /// use rhai::{Engine, Shared};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_async_fn("fetch", |x: i64| async move { x * 2 });
+    ///
+    /// let engine = Shared::new(engine);
+    ///
+    /// assert_eq!(block_on(engine.eval_async::<i64>("fetch(20) + 2"))?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg(not(feature = "no_std"))]
+    #[inline(always)]
+    pub fn eval_async<T: Variant + Clone>(
+        self: &Shared<Self>,
+        script: &str,
+    ) -> crate::EvalFuture<T> {
+        let script = script.to_string();
+        crate::EvalFuture::spawn(self.clone(), move |engine| engine.eval(&script))
+    }
+    /// Evaluate an [`AST`] asynchronously.
+    ///
+    /// See [`eval_async`][Engine::eval_async] for details.
+    ///
+    /// Only available under the `async` feature.  Not available under `no_std`.
+    #[cfg(feature = "async")]
+    #[cfg(not(feature = "no_std"))]
+    #[inline(always)]
+    pub fn eval_ast_async<T: Variant + Clone>(
+        self: &Shared<Self>,
+        ast: &AST,
+    ) -> crate::EvalFuture<T> {
+        let ast = ast.clone();
+        crate::EvalFuture::spawn(self.clone(), move |engine| engine.eval_ast(&ast))
+    }
     /// Evaluate a file, but throw away the result and only return error (if any).
     /// Useful for when you don't need the result, but still need to keep track of possible errors.
     ///
@@ -1850,6 +2123,8 @@ impl Engine {
     ///
     /// Not available under `no_function`.
     ///
+    /// Trailing parameters with default values may be omitted.
+    ///
     /// The [`AST`] is evaluated before calling the function.
     /// This allows a script to load the necessary modules.
     /// This is usually desired. If not, a specialized [`AST`] can be prepared that contains only
@@ -1915,11 +2190,175 @@ impl Engine {
             .into()
         })
     }
+    /// Call a script function defined in an [`AST`] asynchronously, with a new [`Scope`].
+    ///
+    /// See [`call_fn`][Engine::call_fn] and [`eval_async`][Engine::eval_async] for details.
+    ///
+    /// Only available under the `async` feature.  Not available under `no_std` or `no_function`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # use std::future::Future;
+    /// # use std::sync::Arc;
+    /// # use std::task::{Context, Poll, Wake, Waker};
+    /// # struct Unpark(std::thread::Thread);
+    /// # impl Wake for Unpark { fn wake(self: Arc<Self>) { self.0.unpark() } }
+    /// # fn block_on<F: Future>(future: F) -> F::Output {
+    /// #     let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    /// #     let mut future = Box::pin(future);
+    /// #     loop {
+    /// #         match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+    /// #             Poll::Ready(r) => return r,
+    /// #             Poll::Pending => std::thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    /// use rhai::{Engine, Shared};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_async_fn("fetch", |x: i64| async move { x * 2 });
+    ///
+    /// let ast = engine.compile("fn add(x, y) { fetch(x) + y }")?;
+    ///
+    /// let engine = Shared::new(engine);
+    ///
+    /// let result: i64 = block_on(engine.call_fn_async(&ast, "add", (20_i64, 2_i64)))?;
+    /// assert_eq!(result, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg(not(feature = "no_std"))]
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    pub fn call_fn_async<T: Variant + Clone>(
+        self: &Shared<Self>,
+        ast: &AST,
+        name: impl AsRef<str>,
+        args: impl crate::FuncArgs,
+    ) -> crate::EvalFuture<T> {
+        let ast = ast.clone();
+        let name = name.as_ref().to_string();
+        let mut arg_values: Vec<Dynamic> = Default::default();
+        args.parse(&mut arg_values);
+
+        crate::EvalFuture::spawn(self.clone(), move |engine| {
+            engine.call_fn(&mut Scope::new(), &ast, name, arg_values)
+        })
+    }
+    /// Call a script function defined in an [`AST`] with positional arguments followed by
+    /// named arguments.
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// Parameters that are not passed take their default values.
+    ///
+    /// The [`AST`] is evaluated before calling the function.
+    /// This allows a script to load the necessary modules.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_function"))]
+    /// # {
+    /// use rhai::{Engine, Scope};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile(r#"
+    ///     fn greet(name, greeting = "Hello", punctuation = "!") {
+    ///         `${greeting}, ${name}${punctuation}`
+    ///     }
+    /// "#)?;
+    ///
+    /// let mut scope = Scope::new();
+    ///
+    /// let result: String = engine.call_fn_with_named_args(&mut scope, &ast, "greet",
+    ///                                 ( "world", ), [ ("punctuation", "?".into()) ])?;
+    /// assert_eq!(result, "Hello, world?");
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_function"))]
+    #[must_use]
+    pub fn call_fn_with_named_args<T: Variant + Clone, S: AsRef<str>>(
+        &self,
+        scope: &mut Scope,
+        ast: &AST,
+        name: impl AsRef<str>,
+        args: impl crate::FuncArgs,
+        named_args: impl IntoIterator<Item = (S, Dynamic)>,
+    ) -> Result<T, Box<EvalAltResult>> {
+        let mut arg_values: crate::StaticVec<_> = Default::default();
+        args.parse(&mut arg_values);
+        let num_positional = arg_values.len();
+
+        let mut names: crate::StaticVec<_> = Default::default();
+        named_args.into_iter().for_each(|(name, value)| {
+            names.push(name);
+            arg_values.push(value);
+        });
+        let names: crate::StaticVec<_> = names.iter().map(|n| n.as_ref()).collect();
+
+        let state = &mut EvalState::new();
+        let mods = &mut Default::default();
+        let lib = &[ast.lib()];
+        let statements = ast.statements();
+        let name = name.as_ref();
+
+        if !statements.is_empty() {
+            self.eval_global_statements(scope, mods, state, statements, lib, 0)?;
+        }
+
+        let fn_def = ast
+            .lib()
+            .get_script_fn_with_named_args(name, num_positional, &names)
+            .ok_or_else(|| EvalAltResult::ErrorFunctionNotFound(name.into(), Position::NONE))?;
+
+        let mut arg_values = fn_def
+            .bind_named_args(arg_values, &names)
+            .expect("never fails because the function accepts the named arguments");
+        let mut args: crate::StaticVec<_> = arg_values.iter_mut().collect();
+
+        // Check for data race.
+        #[cfg(not(feature = "no_closure"))]
+        crate::fn_call::ensure_no_data_race(name, &args, false)?;
+
+        let result = self.call_script_fn(
+            scope,
+            mods,
+            state,
+            lib,
+            &mut None,
+            fn_def,
+            &mut args,
+            Position::NONE,
+            0,
+        )?;
+
+        let typ = self.map_type_name(result.type_name());
+
+        result.try_cast().ok_or_else(|| {
+            EvalAltResult::ErrorMismatchOutputType(
+                self.map_type_name(type_name::<T>()).into(),
+                typ.into(),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
     /// Call a script function defined in an [`AST`] with multiple [`Dynamic`] arguments
     /// and optionally a value for binding to the `this` pointer.
     ///
     /// Not available under `no_function`.
     ///
+    /// Trailing parameters with default values may be omitted.
+    ///
     /// There is an option to evaluate the [`AST`] to load necessary modules before calling the function.
     ///
     /// # WARNING
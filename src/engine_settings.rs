@@ -32,6 +32,31 @@ impl Engine {
     pub const fn optimization_level(&self) -> crate::OptimizationLevel {
         self.optimization_level
     }
+    /// Control how integer arithmetic handles overflows.
+    ///
+    /// The default is [`OverflowMode::Checked`][crate::OverflowMode::Checked].
+    #[inline(always)]
+    pub fn set_overflow_mode(&mut self, mode: crate::OverflowMode) -> &mut Self {
+        self.overflow_mode = mode;
+        self
+    }
+    /// How integer arithmetic handles overflows.
+    #[inline(always)]
+    #[must_use]
+    pub const fn overflow_mode(&self) -> crate::OverflowMode {
+        self.overflow_mode
+    }
+    /// Seed the random number generator used by [`RandomPackage`][crate::packages::RandomPackage].
+    ///
+    /// The same seed always produces the same sequence of random numbers.
+    /// Setting the seed again before an evaluation makes that evaluation reproducible.
+    ///
+    /// Without a seed, the random number generator is seeded randomly upon first use.
+    #[inline(always)]
+    pub fn set_random_seed(&mut self, seed: u64) -> &mut Self {
+        self.rng = crate::Locked::new(Some(crate::packages::random_basic::Rng::from_seed(seed)));
+        self
+    }
     /// Set the maximum levels of function calls allowed for a script in order to avoid
     /// infinite recursion and stack overflows.
     ///
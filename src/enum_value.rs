@@ -0,0 +1,146 @@
+//! Module implementing values of `enum`'s declared in scripts.
+#![cfg(not(feature = "no_function"))]
+
+use crate::ast::EnumDef;
+use crate::fn_native::Shared;
+use crate::{Dynamic, StaticVec};
+use std::fmt;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// A value of an `enum` declared in a script, i.e. one of its variants together with the values
+/// of the variant's fields.
+///
+/// In scripts, values are created by naming a variant qualified with its `enum`, followed by the
+/// values of its fields (e.g. `Shape::Rect(3, 4)` or `Shape::Empty`), and are matched by `switch`
+/// cases.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{Engine, EnumValue, INT};
+///
+/// let engine = Engine::new();
+///
+/// let value: EnumValue = engine.eval(
+///     "
+///         enum Shape { Circle(r), Rect(w, h) }
+///         Shape::Rect(3, 4)
+///     ",
+/// )?;
+///
+/// assert_eq!(value.enum_name(), "Shape");
+/// assert_eq!(value.variant_name(), "Rect");
+/// assert_eq!(value.field("h").unwrap().as_int().unwrap(), 4 as INT);
+/// assert_eq!(value.to_string(), "Rect(3, 4)");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct EnumValue {
+    /// The `enum` declaration.
+    def: Shared<EnumDef>,
+    /// Index of the variant in the declaration.
+    index: usize,
+    /// Values of the fields, in declaration order.
+    fields: StaticVec<Dynamic>,
+}
+
+impl fmt::Debug for EnumValue {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.enum_name(), self.variant_name())?;
+
+        if !self.fields.is_empty() {
+            let mut t = f.debug_tuple("");
+            self.fields.iter().for_each(|v| {
+                t.field(v);
+            });
+            t.finish()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for EnumValue {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.variant_name())?;
+
+        if !self.fields.is_empty() {
+            f.write_str("(")?;
+            for (i, v) in self.fields.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                fmt::Display::fmt(v, f)?;
+            }
+            f.write_str(")")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl EnumValue {
+    /// Create a new [`EnumValue`] of a variant of an `enum` declaration.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn new(def: Shared<EnumDef>, index: usize, fields: StaticVec<Dynamic>) -> Self {
+        Self { def, index, fields }
+    }
+    /// Get the name of the `enum`.
+    #[inline(always)]
+    #[must_use]
+    pub fn enum_name(&self) -> &str {
+        &self.def.name
+    }
+    /// Get the name of the variant.
+    #[inline(always)]
+    #[must_use]
+    pub fn variant_name(&self) -> &str {
+        &self.def.variants[self.index].0
+    }
+    /// Get the names of the fields of the variant, in declaration order.
+    #[inline(always)]
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.def.variants[self.index].1.iter().map(|s| s.as_str())
+    }
+    /// Get the values of the fields of the variant, in declaration order.
+    #[inline(always)]
+    #[must_use]
+    pub fn fields(&self) -> &[Dynamic] {
+        &self.fields
+    }
+    /// Get mutable references to the values of the fields of the variant, in declaration order.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn fields_mut(&mut self) -> &mut [Dynamic] {
+        &mut self.fields
+    }
+    /// Is this the same variant, of the same `enum` declaration, as another [`EnumValue`]?
+    ///
+    /// The values of the fields are not compared.
+    #[inline]
+    #[must_use]
+    pub(crate) fn is_same_variant(&self, other: &Self) -> bool {
+        self.index == other.index
+            && (Shared::ptr_eq(&self.def, &other.def) || *self.def == *other.def)
+    }
+    /// Get the value of a field of the variant by name.
+    #[inline]
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&Dynamic> {
+        self.field_names()
+            .position(|n| n == name)
+            .map(|i| &self.fields[i])
+    }
+    /// Consume the [`EnumValue`], returning the values of the fields of the variant.
+    #[inline(always)]
+    #[must_use]
+    pub fn into_fields(self) -> Vec<Dynamic> {
+        self.fields.into_vec()
+    }
+}
@@ -69,6 +69,9 @@ pub enum EvalAltResult {
     ErrorAssignmentToConstant(String, Position),
     /// Inappropriate property access. Wrapped value is the property name.
     ErrorDotExpr(String, Position),
+    /// A value does not match the shape of a destructuring pattern.
+    /// Wrapped value is the error message.
+    ErrorDestructure(String, Position),
     /// Arithmetic error encountered. Wrapped value is the error message.
     ErrorArithmetic(String, Position),
     /// Number of operations over maximum limit.
@@ -93,6 +96,39 @@ pub enum EvalAltResult {
     Return(Dynamic, Position),
 }
 
+/// A frame in the call stack of an [`EvalAltResult`], created by each function call that an
+/// error propagates through.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CallFrame {
+    /// Name of the function.
+    pub fn_name: String,
+    /// Source of the function, if any.
+    pub source: String,
+    /// [Position][Position] of the function call.
+    pub position: Position,
+}
+
+impl fmt::Display for CallFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(not(feature = "no_function"))]
+        if crate::engine::is_anonymous_fn(&self.fn_name) {
+            f.write_str("closure")?;
+        } else {
+            f.write_str(&self.fn_name)?;
+        }
+        #[cfg(feature = "no_function")]
+        f.write_str(&self.fn_name)?;
+
+        if !self.source.is_empty() {
+            write!(f, " @ '{}'", self.source)?;
+        }
+        if !self.position.is_none() {
+            write!(f, " ({})", self.position)?;
+        }
+        Ok(())
+    }
+}
+
 impl Error for EvalAltResult {}
 
 impl fmt::Display for EvalAltResult {
@@ -204,6 +240,7 @@ impl fmt::Display for EvalAltResult {
                 index, max
             )?,
             Self::ErrorDataTooLarge(typ, _) => write!(f, "{} exceeds maximum limit", typ)?,
+            Self::ErrorDestructure(s, _) => write!(f, "Cannot destructure value: {}", s)?,
         }
 
         // Do not write any position if None
@@ -266,6 +303,7 @@ impl EvalAltResult {
             | Self::ErrorAssignmentToConstant(_, _)
             | Self::ErrorMismatchOutputType(_, _, _)
             | Self::ErrorDotExpr(_, _)
+            | Self::ErrorDestructure(_, _)
             | Self::ErrorArithmetic(_, _)
             | Self::ErrorRuntime(_, _) => true,
 
@@ -314,6 +352,7 @@ impl EvalAltResult {
             | Self::ErrorParsing(_, _)
             | Self::ErrorUnboundThis(_)
             | Self::ErrorFor(_)
+            | Self::ErrorDestructure(_, _)
             | Self::ErrorArithmetic(_, _)
             | Self::ErrorTooManyOperations(_)
             | Self::ErrorTooManyModules(_)
@@ -386,6 +425,7 @@ impl EvalAltResult {
             | Self::ErrorAssignmentToConstant(_, pos)
             | Self::ErrorMismatchOutputType(_, _, pos)
             | Self::ErrorDotExpr(_, pos)
+            | Self::ErrorDestructure(_, pos)
             | Self::ErrorArithmetic(_, pos)
             | Self::ErrorTooManyOperations(pos)
             | Self::ErrorTooManyModules(pos)
@@ -397,6 +437,39 @@ impl EvalAltResult {
             | Self::Return(_, pos) => *pos,
         }
     }
+    /// Get the call stack of this error, innermost function call first.
+    ///
+    /// Each call to a script-defined function that the error propagated through adds one
+    /// [frame][CallFrame], as does each built-in function that calls back into scripts and wraps
+    /// their errors in [`ErrorInFunctionCall`][EvalAltResult::ErrorInFunctionCall] (e.g. `map`,
+    /// `filter` or `eval`).  Calls to other native Rust functions do not appear, and neither do
+    /// script-defined functions that made tail calls, as they are replaced by the functions they
+    /// called.
+    /// The call stack is empty if the error did not occur inside a function call.
+    #[must_use]
+    pub fn call_stack(&self) -> Vec<CallFrame> {
+        match self {
+            Self::ErrorInFunctionCall(fn_name, source, err, pos) => {
+                let mut stack = err.call_stack();
+                stack.push(CallFrame {
+                    fn_name: fn_name.clone(),
+                    source: source.clone(),
+                    position: *pos,
+                });
+                stack
+            }
+            _ => Vec::new(),
+        }
+    }
+    /// Get the innermost error, i.e. the error itself with all
+    /// [`ErrorInFunctionCall`][EvalAltResult::ErrorInFunctionCall] wrappers removed.
+    #[must_use]
+    pub fn root_error(&self) -> &Self {
+        match self {
+            Self::ErrorInFunctionCall(_, _, err, _) => err.root_error(),
+            _ => self,
+        }
+    }
     /// Remove the [position][Position] information from this error.
     ///
     /// The [position][Position] of this error is set to [`NONE`][Position::NONE] afterwards.
@@ -433,6 +506,7 @@ impl EvalAltResult {
             | Self::ErrorAssignmentToConstant(_, pos)
             | Self::ErrorMismatchOutputType(_, _, pos)
             | Self::ErrorDotExpr(_, pos)
+            | Self::ErrorDestructure(_, pos)
             | Self::ErrorArithmetic(_, pos)
             | Self::ErrorTooManyOperations(pos)
             | Self::ErrorTooManyModules(pos)
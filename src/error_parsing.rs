@@ -32,6 +32,8 @@ pub enum LexError {
     MalformedChar(String),
     /// An identifier is in an invalid format.
     MalformedIdentifier(String),
+    /// A format specifier in an interpolated string is in an invalid format.
+    MalformedFormatSpec(String),
     /// Bad symbol encountered when tokenizing the script text.
     ImproperSymbol(String, String),
 }
@@ -47,6 +49,7 @@ impl fmt::Display for LexError {
             Self::MalformedNumber(s) => write!(f, "Invalid number: '{}'", s),
             Self::MalformedChar(s) => write!(f, "Invalid character: '{}'", s),
             Self::MalformedIdentifier(s) => write!(f, "Variable name is not proper: '{}'", s),
+            Self::MalformedFormatSpec(s) => write!(f, "Invalid format specifier: '{}'", s),
             Self::UnterminatedString => f.write_str("Open string is not terminated"),
             Self::StringTooLong(max) => write!(
                 f,
@@ -128,6 +131,8 @@ pub enum ParseErrorType {
     /// An expression is of the wrong type.
     /// Wrapped values are the type requested and type of the actual result.
     MismatchedType(String, String),
+    /// A type annotation names an unknown type. Wrapped value is the type name.
+    UnknownType(String),
     /// Missing an expression. Wrapped value is the expression type.
     ExprExpected(String),
     /// Defining a doc-comment in an appropriate place (e.g. not at global level).
@@ -160,6 +165,42 @@ pub enum ParseErrorType {
     ///
     /// Never appears under the `no_function` feature.
     FnMissingBody(String),
+    /// An operator overload names an operator that cannot be overloaded, or does not take exactly
+    /// two parameters (one parameter for a unary operator).  Wrapped value is the operator.
+    ///
+    /// Never appears under the `no_function` feature.
+    FnInvalidOperator(String),
+    /// An `enum` declaration not at global level.
+    ///
+    /// Never appears under the `no_function` feature.
+    WrongEnumDefinition,
+    /// An `enum` is declared more than once. Wrapped value is the `enum` name.
+    ///
+    /// Never appears under the `no_function` feature.
+    EnumDuplicatedDefinition(String),
+    /// An `enum` declares the same variant more than once.
+    /// Wrapped values are the `enum` name and the variant name.
+    ///
+    /// Never appears under the `no_function` feature.
+    EnumDuplicatedVariant(String, String),
+    /// A variant that no `enum` declared so far has. Wrapped value is the variant name.
+    ///
+    /// Never appears under the `no_function` feature.
+    UnknownVariant(String),
+    /// A `switch` case names a variant declared by more than one `enum` without qualifying it with
+    /// the `enum` name. Wrapped value is the variant name.
+    ///
+    /// Never appears under the `no_function` feature.
+    AmbiguousVariant(String),
+    /// A variant is constructed or matched with the wrong number of fields.
+    /// Wrapped values are the variant name and the number of fields it has.
+    ///
+    /// Never appears under the `no_function` feature.
+    WrongVariantFields(String, usize),
+    /// A `switch` case binding the fields of an `enum` variant also has other values.
+    ///
+    /// Never appears under the `no_function` feature.
+    WrongVariantBindings,
     /// Export statement not at global level.
     ///
     /// Never appears under the `no_module` feature.
@@ -179,6 +220,11 @@ pub enum ParseErrorType {
     LiteralTooLarge(String, usize),
     /// Break statement not inside a loop.
     LoopBreak,
+    /// Yield statement not directly inside a function body, or inside an expression or a `try`
+    /// ... `catch` block.
+    ///
+    /// Never appears under the `no_function` feature.
+    WrongYield,
 }
 
 impl ParseErrorType {
@@ -228,6 +274,7 @@ impl fmt::Display for ParseErrorType {
             },
             Self::FnMissingParams(s) => write!(f, "Expecting parameters for function '{}'", s),
             Self::FnDuplicatedParam(s, arg) => write!(f, "Duplicated parameter '{}' for function '{}'", arg, s),
+            Self::FnInvalidOperator(s) => write!(f, "Invalid overload for operator '{}': only binary operators taking two parameters, and unary '-' and '!' taking one parameter, can be overloaded", s),
 
             Self::DuplicatedProperty(s) => write!(f, "Duplicated property '{}' for object map literal", s),
             Self::DuplicatedSwitchCase => f.write_str("Duplicated switch case"),
@@ -249,6 +296,7 @@ impl fmt::Display for ParseErrorType {
 
             Self::LiteralTooLarge(typ, max) => write!(f, "{} exceeds the maximum limit ({})", typ, max),
             Self::Reserved(s) => write!(f, "'{}' is a reserved keyword", s),
+            Self::UnknownType(s) => write!(f, "Unknown type '{}'", s),
             Self::UnexpectedEOF => f.write_str("Script is incomplete"),
             Self::WrongSwitchDefaultCase => f.write_str("Default switch case is not the last"),
             Self::WrongSwitchCaseCondition => f.write_str("Default switch case cannot have condition"),
@@ -257,9 +305,21 @@ impl fmt::Display for ParseErrorType {
             Self::WrongFnDefinition => f.write_str("Function definitions must be at global level and cannot be inside a block or another function"),
             Self::FnMissingName => f.write_str("Expecting function name in function declaration"),
             Self::WrongDocComment => f.write_str("Doc-comment must be followed immediately by a function definition"),
+            Self::WrongEnumDefinition => f.write_str("Enum declarations must be at global level and cannot be inside a block or a function"),
+            Self::EnumDuplicatedDefinition(s) => write!(f, "Enum '{}' already exists", s),
+            Self::EnumDuplicatedVariant(s, v) => write!(f, "Duplicated variant '{}' for enum '{}'", v, s),
+            Self::UnknownVariant(s) => write!(f, "Unknown enum variant '{}'", s),
+            Self::AmbiguousVariant(s) => write!(f, "Variant '{}' is declared by more than one enum and must be qualified with the enum name", s),
+            Self::WrongVariantFields(s, n) => match n {
+                0 => write!(f, "Variant '{}' has no fields", s),
+                1 => write!(f, "Variant '{}' has 1 field", s),
+                _ => write!(f, "Variant '{}' has {} fields", s, n),
+            },
+            Self::WrongVariantBindings => f.write_str("A switch case binding the fields of a variant cannot have other values"),
             Self::WrongExport => f.write_str("Export statement can only appear at global level"),
             Self::ExprTooDeep => f.write_str("Expression exceeds maximum complexity"),
             Self::LoopBreak => f.write_str("Break statement should only be used inside a loop"),
+            Self::WrongYield => f.write_str("Yield statement can only be used inside a function body, and not inside an expression or a try/catch block"),
         }
     }
 }
@@ -0,0 +1,291 @@
+//! Module implementing asynchronous native functions and evaluation.
+#![cfg(feature = "async")]
+#![cfg(not(feature = "no_std"))]
+
+use crate::dynamic::Variant;
+use crate::{Engine, EvalAltResult, Position, RhaiResult, Shared};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// Marker type for native functions returning a [`Future`] that resolves to a value.
+///
+/// This type is not actually used anywhere.
+pub struct Async<T>(T);
+
+/// Marker type for native functions returning a [`Future`] that resolves to a [`Result`].
+///
+/// This type is not actually used anywhere.
+pub struct AsyncResult<T>(T);
+
+/// A boxed [`Future`] created by calling an asynchronous native function.
+type BoxedFuture = Pin<Box<dyn Future<Output = RhaiResult> + Send>>;
+
+/// Slot holding the [`Waker`] of the task awaiting an [`EvalFuture`].
+type WakerSlot = Arc<Mutex<Option<Waker>>>;
+
+/// Call to an asynchronous native function that is pending, sent by an evaluation thread to its
+/// [`EvalFuture`].  The future must be driven by the host, and its result sent back.
+type PendingCall = (BoxedFuture, Sender<RhaiResult>);
+
+/// Handle used by asynchronous native functions to hand their futures over to the host.
+#[derive(Clone)]
+struct Bridge {
+    tx: Sender<PendingCall>,
+    waker: WakerSlot,
+}
+
+thread_local! {
+    /// The [`Bridge`] of the evaluation running on this thread, if any.
+    static BRIDGE: RefCell<Option<Bridge>> = const { RefCell::new(None) };
+}
+
+/// Wake the task awaiting an [`EvalFuture`], if any.
+fn wake(waker: &WakerSlot) {
+    let waker = waker
+        .lock()
+        .expect("never fails because the lock is never held while panicking")
+        .take();
+
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+/// Wait for the result of an asynchronous native function.
+///
+/// The future is driven by the host task awaiting the evaluation started by
+/// [`Engine::eval_async`] or its siblings.  Outside of such an evaluation, the function fails
+/// instead of blocking the current thread.
+pub(crate) fn wait_for(fn_name: &str, future: BoxedFuture) -> RhaiResult {
+    let bridge = BRIDGE.with(|bridge| bridge.borrow().clone());
+
+    let bridge = match bridge {
+        Some(bridge) => bridge,
+        None => {
+            let msg = format!(
+                "asynchronous function '{}' can only be called during an asynchronous evaluation",
+                fn_name
+            );
+            return EvalAltResult::ErrorRuntime(msg.into(), Position::NONE).into();
+        }
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let sent = bridge.tx.send((future, tx)).is_ok();
+    wake(&bridge.waker);
+
+    // The host has dropped the `EvalFuture`, so the evaluation is cancelled
+    let cancelled = || EvalAltResult::ErrorTerminated(Default::default(), Position::NONE).into();
+
+    if sent {
+        rx.recv().unwrap_or_else(|_| cancelled())
+    } else {
+        cancelled()
+    }
+}
+
+/// An evaluation waiting to run on the [`Pool`].
+type Job = Box<dyn FnOnce() + Send>;
+
+/// State of the [`Pool`].
+struct PoolState {
+    /// Evaluations waiting for a thread.
+    jobs: VecDeque<Job>,
+    /// Number of threads waiting for evaluations.
+    idle: usize,
+}
+
+/// Pool of threads running asynchronous evaluations, shared by all engines.
+///
+/// Threads are started as needed and, up to a limit, are kept for later evaluations.
+///
+/// The number of threads is not bounded: an evaluation never waits for a thread to become free,
+/// as that thread may itself be waiting for it (e.g. an asynchronous native function that awaits
+/// a nested asynchronous evaluation).
+struct Pool {
+    state: Mutex<PoolState>,
+    available: Condvar,
+}
+
+static POOL: Pool = Pool {
+    state: Mutex::new(PoolState {
+        jobs: VecDeque::new(),
+        idle: 0,
+    }),
+    available: Condvar::new(),
+};
+
+impl Pool {
+    /// Maximum number of idle threads kept for later evaluations.
+    ///
+    /// This is the available parallelism, but at least 4, as evaluations mostly wait for
+    /// asynchronous functions.
+    #[must_use]
+    fn max_idle() -> usize {
+        thread::available_parallelism().map_or(4, |n| n.get().max(4))
+    }
+    /// Run an evaluation on a thread of the pool, starting a new thread if none is idle.
+    fn execute(&'static self, job: Job) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("never fails because the lock is never held while panicking");
+
+        state.jobs.push_back(job);
+
+        if state.jobs.len() <= state.idle {
+            self.available.notify_one();
+        } else {
+            thread::spawn(move || self.work());
+        }
+    }
+    /// Run evaluations as they come, until there are enough idle threads.
+    fn work(&self) {
+        loop {
+            let job = {
+                let mut state = self
+                    .state
+                    .lock()
+                    .expect("never fails because the lock is never held while panicking");
+
+                loop {
+                    if let Some(job) = state.jobs.pop_front() {
+                        break job;
+                    }
+                    if state.idle >= Self::max_idle() {
+                        return;
+                    }
+                    state.idle += 1;
+                    state = self
+                        .available
+                        .wait(state)
+                        .expect("never fails because the lock is never held while panicking");
+                    state.idle -= 1;
+                }
+            };
+
+            job();
+        }
+    }
+}
+
+/// Guard that detaches an evaluation thread from its [`EvalFuture`] when the evaluation ends,
+/// even by panicking.
+struct Detach(WakerSlot);
+
+impl Drop for Detach {
+    fn drop(&mut self) {
+        BRIDGE.with(|bridge| bridge.borrow_mut().take());
+        wake(&self.0);
+    }
+}
+
+/// A [`Future`] resolving to the result of an asynchronous evaluation.
+///
+/// Created by [`Engine::eval_async`], [`Engine::eval_ast_async`] and [`Engine::call_fn_async`].
+///
+/// The script runs on a thread taken from a pool shared by all engines (which is why the
+/// `async` feature implies `sync`).  Whenever it calls an asynchronous native function that is
+/// pending, the function's future is driven by the task awaiting the [`EvalFuture`], which
+/// yields to the executor meanwhile.  When all threads of the pool are busy, a new thread is
+/// started, so asynchronous evaluations may be nested to any depth.
+///
+/// Dropping an [`EvalFuture`] cancels the evaluation at the next call to an asynchronous native
+/// function, which then fails with [`EvalAltResult::ErrorTerminated`].
+#[must_use = "futures do nothing unless polled"]
+pub struct EvalFuture<T> {
+    calls: Receiver<PendingCall>,
+    result: Receiver<thread::Result<Result<T, Box<EvalAltResult>>>>,
+    waker: WakerSlot,
+    pending: Option<PendingCall>,
+}
+
+impl<T> Unpin for EvalFuture<T> {}
+
+impl<T: Variant + Clone> EvalFuture<T> {
+    /// Run an evaluation on a thread of the pool.
+    pub(crate) fn spawn(
+        engine: Shared<Engine>,
+        eval: impl FnOnce(&Engine) -> Result<T, Box<EvalAltResult>> + Send + 'static,
+    ) -> Self {
+        let (tx, calls) = mpsc::channel();
+        let (result_tx, result) = mpsc::channel();
+        let waker = WakerSlot::default();
+        let bridge = Bridge {
+            tx,
+            waker: waker.clone(),
+        };
+
+        POOL.execute(Box::new(move || {
+            let _detach = Detach(bridge.waker.clone());
+            BRIDGE.with(|b| *b.borrow_mut() = Some(bridge));
+
+            // A panic is passed on to the host, keeping the thread for later evaluations
+            let result = panic::catch_unwind(AssertUnwindSafe(|| eval(&engine)));
+            // The host may have dropped the `EvalFuture`
+            let _ = result_tx.send(result);
+        }));
+
+        Self {
+            calls,
+            result,
+            waker,
+            pending: None,
+        }
+    }
+}
+
+impl<T> Future for EvalFuture<T> {
+    type Output = Result<T, Box<EvalAltResult>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            // Drive the pending asynchronous native function, if any
+            if let Some((ref mut future, _)) = this.pending {
+                let result = match future.as_mut().poll(cx) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return Poll::Pending,
+                };
+                let (_, reply) = this
+                    .pending
+                    .take()
+                    .expect("never fails because there is a pending function");
+                // The evaluation thread is blocked waiting for the result
+                let _ = reply.send(result);
+            }
+
+            // Register the waker before checking for messages, so that none is missed
+            *this
+                .waker
+                .lock()
+                .expect("never fails because the lock is never held while panicking") =
+                Some(cx.waker().clone());
+
+            match this.calls.try_recv() {
+                Ok(call) => this.pending = Some(call),
+                Err(TryRecvError::Empty) => return Poll::Pending,
+                // The evaluation has ended
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+
+        match this
+            .result
+            .try_recv()
+            .expect("never fails because the result is sent before the evaluation ends")
+        {
+            Ok(result) => Poll::Ready(result),
+            // The evaluation panicked
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
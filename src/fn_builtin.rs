@@ -14,6 +14,9 @@ use crate::FLOAT;
 #[cfg(feature = "decimal")]
 use rust_decimal::Decimal;
 
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+
 /// The message: never fails because this is built-in code and the type is already checked
 const BUILTIN: &str = "never fails because this is built-in code and the type is already checked";
 
@@ -39,9 +42,46 @@ fn is_numeric(type_id: TypeId) -> bool {
     #[cfg(feature = "decimal")]
     let result = result || type_id == TypeId::of::<rust_decimal::Decimal>();
 
+    #[cfg(feature = "bigint")]
+    let result = result || type_id == TypeId::of::<BigInt>();
+
     result
 }
 
+/// Convert a [`BigInt`] or [`Decimal`] operand into a [`Decimal`].
+#[cfg(feature = "bigint")]
+#[cfg(feature = "decimal")]
+fn bigint_to_decimal(value: &Dynamic) -> Result<Decimal, Box<crate::EvalAltResult>> {
+    use num_traits::{FromPrimitive, ToPrimitive};
+
+    if let Ok(x) = value.as_decimal() {
+        return Ok(x);
+    }
+
+    let x = value.as_bigint().expect(BUILTIN);
+
+    x.to_i128().and_then(Decimal::from_i128).ok_or_else(|| {
+        crate::packages::arithmetic::make_err(format!("Cannot convert to Decimal: {}", x))
+    })
+}
+
+/// Convert a [`BigInt`] or floating-point operand into a [`FLOAT`].
+#[cfg(feature = "bigint")]
+#[cfg(not(feature = "no_float"))]
+fn bigint_to_float(value: &Dynamic) -> FLOAT {
+    use num_traits::ToPrimitive;
+
+    if let Ok(x) = value.as_float() {
+        return x;
+    }
+
+    let x = value.as_bigint().expect(BUILTIN);
+
+    // A big integer beyond the range of floating-point numbers becomes infinity
+    x.to_f64()
+        .expect("never fails because `BigInt` converts into any float") as FLOAT
+}
+
 /// Build in common binary operator implementations to avoid the cost of calling a registered function.
 #[must_use]
 pub fn get_builtin_binary_op_fn(
@@ -111,6 +151,11 @@ pub fn get_builtin_binary_op_fn(
             let y = args[1].$yy().expect(BUILTIN) as $base;
             $func(x, y).map(Into::<Dynamic>::into)
         } };
+        ($base:ty => $func:ident ( ctx, $xx:ident, $yy:ident )) => { |ctx, args| {
+            let x = args[0].$xx().expect(BUILTIN) as $base;
+            let y = args[1].$yy().expect(BUILTIN) as $base;
+            $func(ctx, x, y).map(Into::<Dynamic>::into)
+        } };
         (from $base:ty => $xx:ident $op:tt $yy:ident) => { |_, args| {
             let x = <$base>::from(args[0].$xx().expect(BUILTIN));
             let y = <$base>::from(args[1].$yy().expect(BUILTIN));
@@ -204,6 +249,110 @@ pub fn get_builtin_binary_op_fn(
     impl_decimal!(Decimal, as_decimal, INT, as_int);
     impl_decimal!(INT, as_int, Decimal, as_decimal);
 
+    macro_rules! impl_bigint {
+        ($x:ty, $xx:ident, $y:ty, $yy:ident) => {
+            #[cfg(feature = "bigint")]
+            if types_pair == (TypeId::of::<$x>(), TypeId::of::<$y>()) {
+                use crate::packages::arithmetic::bigint_functions::*;
+
+                return match op {
+                    "+" => Some(impl_op!(from BigInt => add($xx, $yy))),
+                    "-" => Some(impl_op!(from BigInt => subtract($xx, $yy))),
+                    "*" => Some(impl_op!(from BigInt => multiply($xx, $yy))),
+                    "/" => Some(impl_op!(from BigInt => divide($xx, $yy))),
+                    "%" => Some(impl_op!(from BigInt => modulo($xx, $yy))),
+                    "**" => Some(impl_op!(from BigInt => power($xx, $yy))),
+                    "<<" => Some(impl_op!(from BigInt => shift_left($xx, $yy))),
+                    ">>" => Some(impl_op!(from BigInt => shift_right($xx, $yy))),
+                    "==" => Some(impl_op!(from BigInt => $xx == $yy)),
+                    "!=" => Some(impl_op!(from BigInt => $xx != $yy)),
+                    ">" => Some(impl_op!(from BigInt => $xx > $yy)),
+                    ">=" => Some(impl_op!(from BigInt => $xx >= $yy)),
+                    "<" => Some(impl_op!(from BigInt => $xx < $yy)),
+                    "<=" => Some(impl_op!(from BigInt => $xx <= $yy)),
+                    _ =>  None
+                };
+            }
+        };
+    }
+
+    impl_bigint!(BigInt, as_bigint, BigInt, as_bigint);
+    impl_bigint!(BigInt, as_bigint, INT, as_int);
+    impl_bigint!(INT, as_int, BigInt, as_bigint);
+
+    // Mixed big integer and decimal operations are performed on decimals
+    #[cfg(feature = "bigint")]
+    #[cfg(feature = "decimal")]
+    if types_pair == (TypeId::of::<BigInt>(), TypeId::of::<Decimal>())
+        || types_pair == (TypeId::of::<Decimal>(), TypeId::of::<BigInt>())
+    {
+        use crate::packages::arithmetic::decimal_functions::*;
+
+        macro_rules! impl_mixed {
+            ($func:ident) => { |_, args| {
+                let x = bigint_to_decimal(args[0])?;
+                let y = bigint_to_decimal(args[1])?;
+                $func(x, y).map(Into::<Dynamic>::into)
+            } };
+            ($op:tt) => { |_, args| {
+                let x = bigint_to_decimal(args[0])?;
+                let y = bigint_to_decimal(args[1])?;
+                Ok((x $op y).into())
+            } };
+        }
+
+        return match op {
+            "+" => Some(impl_mixed!(add)),
+            "-" => Some(impl_mixed!(subtract)),
+            "*" => Some(impl_mixed!(multiply)),
+            "/" => Some(impl_mixed!(divide)),
+            "%" => Some(impl_mixed!(modulo)),
+            "**" => Some(impl_mixed!(power)),
+            "==" => Some(impl_mixed!(==)),
+            "!=" => Some(impl_mixed!(!=)),
+            ">" => Some(impl_mixed!(>)),
+            ">=" => Some(impl_mixed!(>=)),
+            "<" => Some(impl_mixed!(<)),
+            "<=" => Some(impl_mixed!(<=)),
+            _ => None,
+        };
+    }
+
+    // Mixed big integer and floating-point operations are performed on floating-point numbers
+    #[cfg(feature = "bigint")]
+    #[cfg(not(feature = "no_float"))]
+    if types_pair == (TypeId::of::<BigInt>(), TypeId::of::<FLOAT>())
+        || types_pair == (TypeId::of::<FLOAT>(), TypeId::of::<BigInt>())
+    {
+        macro_rules! impl_mixed {
+            ($op:tt) => { |_, args| {
+                let x = bigint_to_float(args[0]);
+                let y = bigint_to_float(args[1]);
+                Ok((x $op y).into())
+            } };
+        }
+
+        return match op {
+            "+" => Some(impl_mixed!(+)),
+            "-" => Some(impl_mixed!(-)),
+            "*" => Some(impl_mixed!(*)),
+            "/" => Some(impl_mixed!(/)),
+            "%" => Some(impl_mixed!(%)),
+            "**" => Some(|_, args| {
+                let x = bigint_to_float(args[0]);
+                let y = bigint_to_float(args[1]);
+                Ok(x.powf(y).into())
+            }),
+            "==" => Some(impl_mixed!(==)),
+            "!=" => Some(impl_mixed!(!=)),
+            ">" => Some(impl_mixed!(>)),
+            ">=" => Some(impl_mixed!(>=)),
+            "<" => Some(impl_mixed!(<)),
+            "<=" => Some(impl_mixed!(<=)),
+            _ => None,
+        };
+    }
+
     // char op string
     if types_pair == (TypeId::of::<char>(), TypeId::of::<ImmutableString>()) {
         #[inline(always)]
@@ -292,32 +441,18 @@ pub fn get_builtin_binary_op_fn(
     // Beyond here, type1 == type2
 
     if type1 == TypeId::of::<INT>() {
-        #[cfg(not(feature = "unchecked"))]
         use crate::packages::arithmetic::arith_basic::INT::functions::*;
 
-        #[cfg(not(feature = "unchecked"))]
+        // Arithmetic honors the overflow mode of the engine
         match op {
-            "+" => return Some(impl_op!(INT => add(as_int, as_int))),
-            "-" => return Some(impl_op!(INT => subtract(as_int, as_int))),
-            "*" => return Some(impl_op!(INT => multiply(as_int, as_int))),
-            "/" => return Some(impl_op!(INT => divide(as_int, as_int))),
-            "%" => return Some(impl_op!(INT => modulo(as_int, as_int))),
-            "**" => return Some(impl_op!(INT => power(as_int, as_int))),
-            ">>" => return Some(impl_op!(INT => shift_right(as_int, as_int))),
-            "<<" => return Some(impl_op!(INT => shift_left(as_int, as_int))),
-            _ => (),
-        }
-
-        #[cfg(feature = "unchecked")]
-        match op {
-            "+" => return Some(impl_op!(INT => as_int + as_int)),
-            "-" => return Some(impl_op!(INT => as_int - as_int)),
-            "*" => return Some(impl_op!(INT => as_int * as_int)),
-            "/" => return Some(impl_op!(INT => as_int / as_int)),
-            "%" => return Some(impl_op!(INT => as_int % as_int)),
-            "**" => return Some(impl_op!(INT => as_int.pow(as_int as u32))),
-            ">>" => return Some(impl_op!(INT => as_int >> as_int)),
-            "<<" => return Some(impl_op!(INT => as_int << as_int)),
+            "+" => return Some(impl_op!(INT => add(ctx, as_int, as_int))),
+            "-" => return Some(impl_op!(INT => subtract(ctx, as_int, as_int))),
+            "*" => return Some(impl_op!(INT => multiply(ctx, as_int, as_int))),
+            "/" => return Some(impl_op!(INT => divide(ctx, as_int, as_int))),
+            "%" => return Some(impl_op!(INT => modulo(ctx, as_int, as_int))),
+            "**" => return Some(impl_op!(INT => power(ctx, as_int, as_int))),
+            ">>" => return Some(impl_op!(INT => shift_right(ctx, as_int, as_int))),
+            "<<" => return Some(impl_op!(INT => shift_left(ctx, as_int, as_int))),
             _ => (),
         }
 
@@ -331,6 +466,16 @@ pub fn get_builtin_binary_op_fn(
             "&" => Some(impl_op!(INT => as_int & as_int)),
             "|" => Some(impl_op!(INT => as_int | as_int)),
             "^" => Some(impl_op!(INT => as_int ^ as_int)),
+            ".." => Some(|_, args| {
+                let x = args[0].as_int().expect(BUILTIN);
+                let y = args[1].as_int().expect(BUILTIN);
+                Ok(Dynamic::from(x..y))
+            }),
+            "..=" => Some(|_, args| {
+                let x = args[0].as_int().expect(BUILTIN);
+                let y = args[1].as_int().expect(BUILTIN);
+                Ok(Dynamic::from(x..=y))
+            }),
             _ => None,
         };
     }
@@ -437,6 +582,11 @@ pub fn get_builtin_op_assignment_fn(
             let y = args[1].$yy().expect(BUILTIN) as $x;
             Ok((*args[0].write_lock().expect(BUILTIN) = $func(x, y)?).into())
         } };
+        ($x:ty => $func:ident ( ctx, $xx:ident, $yy:ident )) => { |ctx, args| {
+            let x = args[0].$xx().expect(BUILTIN);
+            let y = args[1].$yy().expect(BUILTIN) as $x;
+            Ok((*args[0].write_lock().expect(BUILTIN) = $func(ctx, x, y)?).into())
+        } };
         (from $x:ident $op:tt $yy:ident) => { |_, args| {
             let y = <$x>::from(args[1].$yy().expect(BUILTIN));
             Ok((*args[0].write_lock::<$x>().expect(BUILTIN) $op y).into())
@@ -511,6 +661,30 @@ pub fn get_builtin_op_assignment_fn(
     impl_decimal!(Decimal, as_decimal, Decimal, as_decimal);
     impl_decimal!(Decimal, as_decimal, INT, as_int);
 
+    macro_rules! impl_bigint {
+        ($x:ident, $xx:ident, $y:ty, $yy:ident) => {
+            #[cfg(feature = "bigint")]
+            if types_pair == (TypeId::of::<$x>(), TypeId::of::<$y>()) {
+                use crate::packages::arithmetic::bigint_functions::*;
+
+                return match op {
+                    "+=" => Some(impl_op!(from $x => add($xx, $yy))),
+                    "-=" => Some(impl_op!(from $x => subtract($xx, $yy))),
+                    "*=" => Some(impl_op!(from $x => multiply($xx, $yy))),
+                    "/=" => Some(impl_op!(from $x => divide($xx, $yy))),
+                    "%=" => Some(impl_op!(from $x => modulo($xx, $yy))),
+                    "**=" => Some(impl_op!(from $x => power($xx, $yy))),
+                    "<<=" => Some(impl_op!(from $x => shift_left($xx, $yy))),
+                    ">>=" => Some(impl_op!(from $x => shift_right($xx, $yy))),
+                    _ => None,
+                };
+            }
+        };
+    }
+
+    impl_bigint!(BigInt, as_bigint, BigInt, as_bigint);
+    impl_bigint!(BigInt, as_bigint, INT, as_int);
+
     // string op= char
     if types_pair == (TypeId::of::<ImmutableString>(), TypeId::of::<char>()) {
         return match op {
@@ -545,32 +719,18 @@ pub fn get_builtin_op_assignment_fn(
 
     // Beyond here, type1 == type2
     if type1 == TypeId::of::<INT>() {
-        #[cfg(not(feature = "unchecked"))]
         use crate::packages::arithmetic::arith_basic::INT::functions::*;
 
-        #[cfg(not(feature = "unchecked"))]
-        match op {
-            "+=" => return Some(impl_op!(INT => add(as_int, as_int))),
-            "-=" => return Some(impl_op!(INT => subtract(as_int, as_int))),
-            "*=" => return Some(impl_op!(INT => multiply(as_int, as_int))),
-            "/=" => return Some(impl_op!(INT => divide(as_int, as_int))),
-            "%=" => return Some(impl_op!(INT => modulo(as_int, as_int))),
-            "**=" => return Some(impl_op!(INT => power(as_int, as_int))),
-            ">>=" => return Some(impl_op!(INT => shift_right(as_int, as_int))),
-            "<<=" => return Some(impl_op!(INT => shift_left(as_int, as_int))),
-            _ => (),
-        }
-
-        #[cfg(feature = "unchecked")]
+        // Arithmetic honors the overflow mode of the engine
         match op {
-            "+=" => return Some(impl_op!(INT += as_int)),
-            "-=" => return Some(impl_op!(INT -= as_int)),
-            "*=" => return Some(impl_op!(INT *= as_int)),
-            "/=" => return Some(impl_op!(INT /= as_int)),
-            "%=" => return Some(impl_op!(INT %= as_int)),
-            "**=" => return Some(impl_op!(INT => as_int.pow(as_int as u32))),
-            ">>=" => return Some(impl_op!(INT >>= as_int)),
-            "<<=" => return Some(impl_op!(INT <<= as_int)),
+            "+=" => return Some(impl_op!(INT => add(ctx, as_int, as_int))),
+            "-=" => return Some(impl_op!(INT => subtract(ctx, as_int, as_int))),
+            "*=" => return Some(impl_op!(INT => multiply(ctx, as_int, as_int))),
+            "/=" => return Some(impl_op!(INT => divide(ctx, as_int, as_int))),
+            "%=" => return Some(impl_op!(INT => modulo(ctx, as_int, as_int))),
+            "**=" => return Some(impl_op!(INT => power(ctx, as_int, as_int))),
+            ">>=" => return Some(impl_op!(INT => shift_right(ctx, as_int, as_int))),
+            "<<=" => return Some(impl_op!(INT => shift_left(ctx, as_int, as_int))),
             _ => (),
         }
 
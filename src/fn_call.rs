@@ -17,7 +17,8 @@ use crate::{
 };
 use crate::{
     calc_fn_hash, calc_fn_params_hash, combine_hashes, Dynamic, Engine, EvalAltResult, FnPtr,
-    Identifier, ImmutableString, Module, ParseErrorType, Position, Scope, StaticVec,
+    Identifier, ImmutableString, Module, NativeCallContext, ParseErrorType, Position, Scope,
+    StaticVec,
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -30,6 +31,9 @@ use std::{
 #[cfg(not(feature = "no_object"))]
 use crate::Map;
 
+#[cfg(not(feature = "no_function"))]
+use crate::token::{is_overloadable_operator, is_overloadable_unary_operator};
+
 /// Arguments to a function call, which is a list of [`&mut Dynamic`][Dynamic].
 pub type FnCallArgs<'a> = [&'a mut Dynamic];
 
@@ -124,6 +128,83 @@ pub fn ensure_no_data_race(
     Ok(())
 }
 
+/// A call in tail position of a script-defined function, to be made in place of that function.
+#[cfg(not(feature = "no_function"))]
+#[derive(Debug, Clone)]
+pub(crate) struct TailCall {
+    /// The script-defined function to call.
+    pub fn_def: crate::fn_native::Shared<crate::ast::ScriptFnDef>,
+    /// Arguments to the function call.
+    pub args: StaticVec<Dynamic>,
+    /// Source of the function, if any.
+    pub source: Option<Identifier>,
+    /// Is the function called on `this` (i.e. `this.func(...)`), which stays bound?
+    pub is_method_call: bool,
+    /// [Position] of the function call.
+    pub pos: Position,
+}
+
+/// Wrap an error raised inside a script-defined function.
+#[cfg(not(feature = "no_function"))]
+#[must_use]
+pub(crate) fn make_script_fn_error(
+    fn_def: &crate::ast::ScriptFnDef,
+    state: &EvalState,
+    err: Box<EvalAltResult>,
+    pos: Position,
+) -> Box<EvalAltResult> {
+    // System errors are passed straight-through
+    if err.is_system_exception() {
+        let mut err = err;
+        err.set_position(pos);
+        return err;
+    }
+
+    let source = fn_def
+        .lib
+        .as_ref()
+        .and_then(|m| m.id().map(|id| id.to_string()))
+        .or_else(|| state.source.as_ref().map(|s| s.to_string()))
+        .unwrap_or_default();
+
+    EvalAltResult::ErrorInFunctionCall(fn_def.name.to_string(), source, err, pos).into()
+}
+
+/// Get the hash of a script-defined overload of the operator `op`, if any of the operands
+/// is of a type that scripts may overload operators for (i.e. object maps and custom types).
+#[cfg(not(feature = "no_function"))]
+#[must_use]
+pub(crate) fn operator_overload_hash(op: &str, args: &FnCallArgs) -> Option<u64> {
+    fn is_overloadable(value: &Dynamic) -> bool {
+        #[cfg(not(feature = "no_closure"))]
+        if value.is_shared() {
+            return !value.is_locked()
+                && value
+                    .read_lock::<Dynamic>()
+                    .map_or(false, |v| is_overloadable(&v));
+        }
+
+        #[cfg(not(feature = "no_object"))]
+        if value.is::<Map>() {
+            return true;
+        }
+
+        value.is_variant()
+    }
+
+    let is_operator = match args.len() {
+        1 => is_overloadable_unary_operator(op),
+        2 => is_overloadable_operator(op),
+        _ => false,
+    };
+
+    if is_operator && args.iter().any(|a| is_overloadable(a)) {
+        Some(calc_fn_hash(op, args.len()))
+    } else {
+        None
+    }
+}
+
 impl Engine {
     /// Generate the signature for a function call.
     #[inline]
@@ -167,6 +248,7 @@ impl Engine {
         fn_name: &str,
         hash_script: u64,
         args: Option<&mut FnCallArgs>,
+        _num_script_args: usize,
         allow_dynamic: bool,
         is_op_assignment: bool,
     ) -> &'s Option<Box<FnResolutionCacheEntry>> {
@@ -206,6 +288,14 @@ impl Engine {
                         // Specific version found
                         Some(f) => return Some(Box::new(f)),
 
+                        // Script function with default parameter values or a rest parameter
+                        #[cfg(not(feature = "no_function"))]
+                        None if args.is_none() => {
+                            return lib.iter().find_map(|m| m.get_script_fn_by_num_args(fn_name, _num_script_args).cloned().map(|func| FnResolutionCacheEntry {
+                                func, source: m.id_raw().cloned()
+                            })).map(Box::new);
+                        }
+
                         // Stop when all permutations are exhausted
                         None if bitmask >= max_bitmask => {
                             if num_args != 2 {
@@ -280,13 +370,29 @@ impl Engine {
 
         let state_source = state.source.clone();
 
+        // Operations performed by the function itself count towards the maximum
+        #[cfg(not(feature = "unchecked"))]
+        let operations = std::cell::Cell::new(state.operations);
+
         // Check if function access already in the cache
-        let func = self.resolve_fn(mods, state, lib, name, hash, Some(args), true, is_op_assign);
+        let func = self.resolve_fn(
+            mods,
+            state,
+            lib,
+            name,
+            hash,
+            Some(args),
+            0,
+            true,
+            is_op_assign,
+        );
 
         if let Some(f) = func {
             let FnResolutionCacheEntry { func, source } = f.as_ref();
             assert!(func.is_native());
 
+            let is_method = func.is_method();
+
             // Calling pure function but the first argument is a reference?
             let mut backup: Option<ArgBackup> = None;
             if is_method_call && func.is_pure() && !args.is_empty() {
@@ -300,20 +406,29 @@ impl Engine {
                 .or_else(|| state_source.as_ref())
                 .map(|s| s.as_str());
 
+            let context: NativeCallContext = (self, name, source, mods, lib).into();
+            #[cfg(not(feature = "unchecked"))]
+            let context = context.with_operations(&operations);
+
             let result = if func.is_plugin_fn() {
                 func.get_plugin_fn()
                     .expect("never fails because the function is a plugin")
-                    .call((self, name, source, mods, lib).into(), args)
+                    .call(context, args)
             } else {
                 let func = func
                     .get_native_fn()
                     .expect("never fails because the function is native");
-                func((self, name, source, mods, lib).into(), args)
+                func(context, args)
             };
 
             // Restore the original reference
             backup.map(|bk| bk.restore_first_arg(args));
 
+            #[cfg(not(feature = "unchecked"))]
+            {
+                state.operations = operations.get();
+            }
+
             let result = result.map_err(|err| err.fill_position(pos))?;
 
             // See if the function match print/debug (which requires special processing)
@@ -347,7 +462,7 @@ impl Engine {
                         (Dynamic::UNIT, false)
                     }
                 }
-                _ => (result, func.is_method()),
+                _ => (result, is_method),
             });
         }
 
@@ -442,37 +557,17 @@ impl Engine {
         state: &mut EvalState,
         lib: &[&Module],
         this_ptr: &mut Option<&mut Dynamic>,
-        fn_def: &crate::ast::ScriptFnDef,
+        fn_def: &crate::fn_native::Shared<crate::ast::ScriptFnDef>,
         args: &mut FnCallArgs,
         pos: Position,
         level: usize,
     ) -> RhaiResult {
-        #[inline(always)]
-        fn make_error(
-            name: String,
-            fn_def: &crate::ast::ScriptFnDef,
-            state: &EvalState,
-            err: Box<EvalAltResult>,
-            pos: Position,
-        ) -> RhaiResult {
-            EvalAltResult::ErrorInFunctionCall(
-                name,
-                fn_def
-                    .lib
-                    .as_ref()
-                    .and_then(|m| m.id().map(|id| id.to_string()))
-                    .or_else(|| state.source.as_ref().map(|s| s.to_string()))
-                    .unwrap_or_default(),
-                err,
-                pos,
-            )
-            .into()
-        }
-
         #[cfg(not(feature = "unchecked"))]
         self.inc_operations(state, pos)?;
 
-        if fn_def.body.is_empty() {
+        let is_typed = !fn_def.param_types.is_empty() || fn_def.return_type.is_some();
+
+        if fn_def.body.is_empty() && !fn_def.is_generator && !is_typed {
             return Ok(Dynamic::UNIT);
         }
 
@@ -483,83 +578,477 @@ impl Engine {
             return EvalAltResult::ErrorStackOverflow(pos).into();
         }
 
+        let mut args = self.make_script_fn_args(fn_def, args, pos)?;
+
+        // Calling a generator function only creates the generator
+        if fn_def.is_generator {
+            let generator =
+                crate::Generator::new(fn_def, scope, mods, state.source.clone(), args.into_iter());
+            let generator = Dynamic::from(generator);
+            self.check_return_type(fn_def, &generator, pos)?;
+            return Ok(generator);
+        }
+
         let orig_scope_level = state.scope_level;
         state.scope_level += 1;
 
         let prev_scope_len = scope.len();
         let prev_mods_len = mods.len();
 
-        // Put arguments into scope as variables
-        // Actually consume the arguments instead of cloning them
-        scope.extend(
-            fn_def
-                .params
-                .iter()
-                .zip(args.iter_mut().map(|v| mem::take(*v)))
-                .map(|(name, value)| {
-                    let var_name: std::borrow::Cow<'_, str> =
-                        crate::r#unsafe::unsafe_cast_var_name_to_lifetime(name).into();
-                    (var_name, value)
-                }),
-        );
+        let mut fn_def = fn_def.clone();
+        let mut pos = pos;
+        let mut no_this_ptr = None;
+        let mut this_ptr = this_ptr;
+
+        // Functions that made tail calls, whose return types must also be checked
+        let mut tail_callers = StaticVec::new();
+
+        let result = loop {
+            // Put arguments into scope as variables
+            let params = fn_def.params.iter().zip(args.drain(..));
+            scope.extend(params.map(|(name, value)| {
+                let var_name: std::borrow::Cow<'_, str> =
+                    crate::r#unsafe::unsafe_cast_var_name_to_lifetime(name).into();
+                (var_name, value)
+            }));
+
+            // Typed parameters keep their declared types
+            fn_def.typed_params().for_each(|(i, typ)| {
+                scope.set_entry_type(prev_scope_len + i, typ.clone());
+            });
 
-        // Merge in encapsulated environment, if any
-        let mut lib_merged = StaticVec::with_capacity(lib.len() + 1);
+            let result = {
+                // Merge in encapsulated environment, if any
+                let mut lib_merged = StaticVec::with_capacity(lib.len() + 1);
 
-        let (unified_lib, unified) = if let Some(ref env_lib) = fn_def.lib {
-            state.push_fn_resolution_cache();
-            lib_merged.push(env_lib.as_ref());
-            lib_merged.extend(lib.iter().cloned());
-            (lib_merged.as_ref(), true)
-        } else {
-            (lib, false)
-        };
+                let (unified_lib, unified) = if let Some(ref env_lib) = fn_def.lib {
+                    state.push_fn_resolution_cache();
+                    lib_merged.push(env_lib.as_ref());
+                    lib_merged.extend(lib.iter().cloned());
+                    (lib_merged.as_ref(), true)
+                } else {
+                    (lib, false)
+                };
 
-        #[cfg(not(feature = "no_module"))]
-        if !fn_def.mods.is_empty() {
-            fn_def
-                .mods
-                .iter_raw()
-                .for_each(|(n, m)| mods.push(n.clone(), m.clone()));
-        }
+                #[cfg(not(feature = "no_module"))]
+                if !fn_def.mods.is_empty() {
+                    fn_def
+                        .mods
+                        .iter_raw()
+                        .for_each(|(n, m)| mods.push(n.clone(), m.clone()));
+                }
 
-        // Evaluate the function
-        let body = &fn_def.body;
+                // Evaluate the function
+                let body = &fn_def.body;
 
-        let result = self
-            .eval_stmt_block(scope, mods, state, unified_lib, this_ptr, body, true, level)
-            .or_else(|err| match *err {
-                // Convert return statement to return value
-                EvalAltResult::Return(x, _) => Ok(x),
-                // Error in sub function call
-                EvalAltResult::ErrorInFunctionCall(name, src, err, _) => {
-                    let fn_name = if src.is_empty() {
-                        format!("{} < {}", name, fn_def.name)
-                    } else {
-                        format!("{} @ '{}' < {}", name, src, fn_def.name)
+                let result = self
+                    .eval_stmt_block(scope, mods, state, unified_lib, this_ptr, body, true, level)
+                    .or_else(|err| match *err {
+                        // Convert return statement to return value
+                        EvalAltResult::Return(x, _) => Ok(x),
+                        _ => Err(make_script_fn_error(&fn_def, state, err, pos)),
+                    });
+
+                // Remove all local variables
+                scope.rewind(prev_scope_len);
+                mods.truncate(prev_mods_len);
+
+                if unified {
+                    state.pop_fn_resolution_cache();
+                }
+
+                result
+            };
+
+            match state.tail_call.take() {
+                // Make the tail call in place of the current function
+                Some(tail_call) if result.is_ok() => {
+                    let TailCall {
+                        fn_def: next_fn_def,
+                        args: mut next_args,
+                        source,
+                        is_method_call,
+                        pos: next_pos,
+                    } = *tail_call;
+
+                    #[cfg(not(feature = "unchecked"))]
+                    self.inc_operations(state, next_pos)?;
+
+                    let mut next_args: StaticVec<_> = next_args.iter_mut().collect();
+
+                    args = match self.make_script_fn_args(&next_fn_def, &mut next_args, next_pos) {
+                        Ok(args) => args,
+                        Err(err) => break Err(err),
                     };
 
-                    make_error(fn_name, fn_def, state, err, pos)
+                    if fn_def.return_type.is_some() {
+                        tail_callers.push(fn_def);
+                    }
+
+                    fn_def = next_fn_def;
+                    pos = next_pos;
+                    state.source = source;
+                    if !is_method_call {
+                        this_ptr = &mut no_this_ptr;
+                    }
                 }
-                // System errors are passed straight-through
-                mut err if err.is_system_exception() => {
-                    err.set_position(pos);
-                    err.into()
+                _ => {
+                    break result.and_then(|r| {
+                        self.check_return_type(&fn_def, &r, pos)?;
+                        tail_callers
+                            .iter()
+                            .rev()
+                            .try_for_each(|f| self.check_return_type(f, &r, pos))?;
+                        Ok(r)
+                    })
                 }
-                // Other errors are wrapped in `ErrorInFunctionCall`
-                _ => make_error(fn_def.name.to_string(), fn_def, state, err, pos),
-            });
+            }
+        };
 
-        // Remove all local variables
-        scope.rewind(prev_scope_len);
-        mods.truncate(prev_mods_len);
         state.scope_level = orig_scope_level;
 
-        if unified {
-            state.pop_fn_resolution_cache();
+        result
+    }
+
+    /// Prepare the arguments to a scripted function.
+    ///
+    /// The arguments are checked against the types of the parameters, and completed with the
+    /// default values of omitted parameters and the rest parameter, if any.
+    ///
+    /// # WARNING
+    ///
+    /// All function call arguments are _consumed_.
+    #[cfg(not(feature = "no_function"))]
+    fn make_script_fn_args(
+        &self,
+        fn_def: &crate::ast::ScriptFnDef,
+        args: &mut FnCallArgs,
+        pos: Position,
+    ) -> Result<StaticVec<Dynamic>, Box<EvalAltResult>> {
+        let num_args = args.len();
+        let num_fixed = fn_def.num_fixed_params();
+
+        // Check the types of arguments
+        fn_def
+            .param_types
+            .iter()
+            .zip(fn_def.params.iter())
+            .zip(args.iter())
+            .take(num_fixed)
+            .try_for_each(|((typ, name), value)| {
+                self.check_type_annotation(
+                    value,
+                    typ.as_ref().map(|t| t.as_str()),
+                    || format!("for parameter '{}'", name),
+                    pos,
+                )
+            })?;
+
+        // Parameters not passed take their default values
+        let num_required = fn_def.num_required_params();
+        let defaults =
+            (num_args.min(num_fixed)..num_fixed).map(|i| fn_def.defaults[i - num_required].clone());
+
+        // Extra arguments are collected into the rest parameter, if any
+        #[cfg(not(feature = "no_index"))]
+        let rest = if fn_def.variadic {
+            let rest: crate::Array = args
+                .iter_mut()
+                .skip(num_fixed)
+                .map(|v| mem::take(*v))
+                .collect();
+            Some(rest.into())
+        } else {
+            None
+        };
+        #[cfg(feature = "no_index")]
+        let rest: Option<Dynamic> = None;
+
+        // Actually consume the arguments instead of cloning them
+        Ok(args
+            .iter_mut()
+            .take(num_fixed)
+            .map(|v| mem::take(*v))
+            .chain(defaults)
+            .chain(rest)
+            .collect())
+    }
+
+    /// Call a function in tail position of a scripted function (e.g. `return` func `(` ... `)`).
+    ///
+    /// A call to another scripted function is not made here.  Instead, it is recorded in the
+    /// [`EvalState`] and made by [`call_script_fn`][Engine::call_script_fn] in place of the
+    /// current function, without consuming another call level.
+    #[cfg(not(feature = "no_function"))]
+    #[must_use]
+    pub(crate) fn make_tail_call(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        this_ptr: &mut Option<&mut Dynamic>,
+        x: &crate::ast::FnCallExpr,
+        pos: Position,
+        level: usize,
+    ) -> RhaiResult {
+        let crate::ast::FnCallExpr {
+            name,
+            hashes,
+            args: args_expr,
+            constants,
+            capture,
+            ..
+        } = x;
+
+        let target = if *name == KEYWORD_FN_PTR_CALL {
+            // Handle call()
+            let (arg, _) = self.get_arg_value(
+                scope, mods, state, lib, this_ptr, level, args_expr, constants, 0,
+            )?;
+
+            arg.try_cast::<FnPtr>().and_then(|fn_ptr| {
+                let (fn_name, curry) = fn_ptr.take_data();
+                self.resolve_tail_call(mods, state, lib, &fn_name, curry, args_expr.len() - 1)
+                    .map(|target| (target, 1))
+            })
+        } else {
+            hashes
+                .script
+                .and_then(|_| {
+                    self.resolve_tail_call(
+                        mods,
+                        state,
+                        lib,
+                        name,
+                        Default::default(),
+                        args_expr.len(),
+                    )
+                })
+                .map(|target| (target, 0))
+        };
+
+        match target {
+            Some((tail_call, skip)) => self.set_tail_call(
+                scope, mods, state, lib, this_ptr, tail_call, args_expr, constants, skip, pos,
+                level,
+            ),
+            None => self.make_function_call(
+                scope, mods, state, lib, this_ptr, name, args_expr, constants, *hashes, pos,
+                *capture, level,
+            ),
         }
+    }
 
-        result
+    /// Call a method in tail position of a scripted function (e.g. `return this.func(...)` or
+    /// `return fn_ptr.call(...)`).
+    ///
+    /// A call to another scripted function is not made here, but recorded in the [`EvalState`]
+    /// as by [`make_tail_call`][Engine::make_tail_call].  A method called on `this` keeps it
+    /// bound.
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    #[must_use]
+    pub(crate) fn make_tail_method_call(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        this_ptr: &mut Option<&mut Dynamic>,
+        expr: &Expr,
+        level: usize,
+    ) -> RhaiResult {
+        let (lhs, x, pos) = match expr {
+            Expr::Dot(x, _, _) => match x.rhs {
+                Expr::FnCall(ref f, pos) => (&x.lhs, f, pos),
+                _ => unreachable!("tail calls are function calls"),
+            },
+            _ => unreachable!("tail method calls are dot expressions"),
+        };
+        let args_expr = &x.args;
+        let is_this = lhs.get_variable_name(true) == Some(crate::engine::KEYWORD_THIS);
+
+        let target = if x.name == KEYWORD_FN_PTR_CALL {
+            let obj = if is_this {
+                this_ptr
+                    .as_deref()
+                    .and_then(|v| v.read_lock::<FnPtr>().map(|f| f.clone()))
+            } else {
+                self.eval_expr(scope, mods, state, lib, this_ptr, lhs, level)?
+                    .try_cast::<FnPtr>()
+            };
+
+            match obj {
+                // fn_ptr.call(...)
+                Some(fn_ptr) => {
+                    let (fn_name, curry) = fn_ptr.take_data();
+                    self.resolve_tail_call(mods, state, lib, &fn_name, curry, args_expr.len())
+                        .map(|target| (target, 0))
+                }
+                // this.call(fn_ptr, ...)
+                None if is_this
+                    && this_ptr.is_some()
+                    && args_expr
+                        .first()
+                        .map_or(false, |a| a.is_variable_access(true)) =>
+                {
+                    let (arg, _) = self.get_arg_value(
+                        scope,
+                        mods,
+                        state,
+                        lib,
+                        this_ptr,
+                        level,
+                        args_expr,
+                        &x.constants,
+                        0,
+                    )?;
+
+                    arg.try_cast::<FnPtr>().and_then(|fn_ptr| {
+                        let (fn_name, curry) = fn_ptr.take_data();
+                        self.resolve_tail_call(
+                            mods,
+                            state,
+                            lib,
+                            &fn_name,
+                            curry,
+                            args_expr.len() - 1,
+                        )
+                        .map(|mut target| {
+                            target.is_method_call = true;
+                            (target, 1)
+                        })
+                    })
+                }
+                None => None,
+            }
+        } else if is_this && this_ptr.is_some() {
+            // this.func(...)
+            self.resolve_tail_call(
+                mods,
+                state,
+                lib,
+                &x.name,
+                Default::default(),
+                args_expr.len(),
+            )
+            .map(|mut target| {
+                target.is_method_call = true;
+                (target, 0)
+            })
+        } else {
+            None
+        };
+
+        match target {
+            Some((tail_call, skip)) => self.set_tail_call(
+                scope,
+                mods,
+                state,
+                lib,
+                this_ptr,
+                tail_call,
+                args_expr,
+                &x.constants,
+                skip,
+                pos,
+                level,
+            ),
+            None => self.eval_dot_index_chain(scope, mods, state, lib, this_ptr, expr, level, None),
+        }
+    }
+
+    /// Find the scripted function to call in tail position, which can be called with a number of
+    /// arguments following any curried arguments.
+    ///
+    /// Returns [`None`] if the function is not scripted, or must be called normally because it
+    /// is a generator or captures its parent scope.
+    #[cfg(not(feature = "no_function"))]
+    fn resolve_tail_call(
+        &self,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        fn_name: &str,
+        curry: StaticVec<Dynamic>,
+        num_args: usize,
+    ) -> Option<TailCall> {
+        let num_args = curry.len() + num_args;
+        let hash = calc_fn_hash(fn_name, num_args);
+
+        let (fn_def, source) = self
+            .resolve_fn(
+                mods, state, lib, fn_name, hash, None, num_args, false, false,
+            )
+            .as_ref()
+            .and_then(|f| {
+                f.func
+                    .get_script_fn_def()
+                    .filter(|fn_def| !fn_def.is_generator)
+                    .map(|fn_def| (fn_def.clone(), f.source.clone()))
+            })?;
+
+        // Functions capturing the parent scope are called normally
+        #[cfg(not(feature = "no_closure"))]
+        if !fn_def.externals.is_empty() {
+            return None;
+        }
+
+        Some(TailCall {
+            fn_def,
+            args: curry,
+            source,
+            is_method_call: false,
+            pos: Position::NONE,
+        })
+    }
+
+    /// Evaluate the arguments of a call in tail position, skipping the first `skip` ones, and
+    /// record it in the [`EvalState`] to be made in place of the current function.
+    #[cfg(not(feature = "no_function"))]
+    fn set_tail_call(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        this_ptr: &mut Option<&mut Dynamic>,
+        mut tail_call: TailCall,
+        args_expr: &[Expr],
+        constants: &[Dynamic],
+        skip: usize,
+        pos: Position,
+        level: usize,
+    ) -> RhaiResult {
+        for index in skip..args_expr.len() {
+            let (value, _) = self.get_arg_value(
+                scope, mods, state, lib, this_ptr, level, args_expr, constants, index,
+            )?;
+            tail_call.args.push(value.flatten());
+        }
+
+        tail_call.pos = pos;
+        state.tail_call = Some(tail_call.into());
+
+        EvalAltResult::Return(Dynamic::UNIT, pos).into()
+    }
+
+    /// Check the return value of a scripted function against its return type, if any.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    fn check_return_type(
+        &self,
+        fn_def: &crate::ast::ScriptFnDef,
+        value: &Dynamic,
+        pos: Position,
+    ) -> Result<(), Box<EvalAltResult>> {
+        self.check_type_annotation(
+            value,
+            fn_def.return_type.as_ref().map(|t| t.as_str()),
+            || "for the return value".to_string(),
+            pos,
+        )
     }
 
     // Does a scripted function exist?
@@ -571,8 +1060,10 @@ impl Engine {
         mods: Option<&Imports>,
         state: &mut EvalState,
         lib: &[&Module],
-        hash_script: u64,
+        fn_name: &str,
+        num_params: usize,
     ) -> bool {
+        let hash_script = calc_fn_hash(fn_name, num_params);
         let cache = state.fn_resolution_cache_mut();
 
         if let Some(result) = cache.get(&hash_script).map(|v| v.is_some()) {
@@ -581,6 +1072,8 @@ impl Engine {
 
         // First check script-defined functions
         let result = lib.iter().any(|&m| m.contains_fn(hash_script))
+            // Then check script-defined functions with default parameter values or a rest parameter
+            || lib.iter().any(|&m| m.get_script_fn_by_num_args(fn_name, num_params).is_some())
             // Then check registered functions
             || self.global_namespace.contains_fn(hash_script)
             // Then check packages
@@ -655,8 +1148,7 @@ impl Engine {
                     if num_params < 0 {
                         Dynamic::FALSE
                     } else {
-                        let hash_script = calc_fn_hash(fn_name.as_str(), num_params as usize);
-                        self.has_script_fn(Some(mods), state, lib, hash_script)
+                        self.has_script_fn(Some(mods), state, lib, &fn_name, num_params as usize)
                             .into()
                     },
                     false,
@@ -680,14 +1172,29 @@ impl Engine {
             _ => (),
         }
 
-        // Scripted function call?
+        // Scripted function call?  Operators may be overloaded by script-defined functions,
+        // but only where no native function or built-in operator matches the arguments.
         #[cfg(not(feature = "no_function"))]
-        let hash_script = hashes.script;
+        let hash_script = match hashes.script {
+            Some(hash) => Some(hash),
+            None => operator_overload_hash(fn_name, args).filter(|_| {
+                !self.has_native_fn(mods, state, lib, fn_name, hashes.native, args, false)
+            }),
+        };
 
         #[cfg(not(feature = "no_function"))]
         if let Some(f) = hash_script.and_then(|hash| {
-            self.resolve_fn(mods, state, lib, fn_name, hash, None, false, false)
-                .clone()
+            // A method call passes the object as the first argument
+            let num_args = if _is_method_call {
+                args.len() - 1
+            } else {
+                args.len()
+            };
+
+            self.resolve_fn(
+                mods, state, lib, fn_name, hash, None, num_args, false, false,
+            )
+            .clone()
         }) {
             let FnResolutionCacheEntry { func, source } = *f;
             // Script function call
@@ -697,7 +1204,7 @@ impl Engine {
                 .get_script_fn_def()
                 .expect("never fails because the function is scripted");
 
-            if func.body.is_empty() {
+            if func.body.is_empty() && !func.is_generator {
                 return Ok((Dynamic::UNIT, false));
             }
 
@@ -1040,6 +1547,78 @@ impl Engine {
         }
     }
 
+    /// Does a native Rust function, or a built-in operator, match the arguments?
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    #[must_use]
+    fn has_native_fn(
+        &self,
+        mods: &Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        fn_name: &str,
+        hash: u64,
+        args: &mut FnCallArgs,
+        is_op_assign: bool,
+    ) -> bool {
+        self.resolve_fn(
+            mods,
+            state,
+            lib,
+            fn_name,
+            hash,
+            Some(args),
+            0,
+            true,
+            is_op_assign,
+        )
+        .is_some()
+    }
+
+    /// Call the script-defined overload of the operator `op` for an op-assignment `op=`, if one
+    /// exists for the arguments and neither `op=` nor `op` is defined natively for them.
+    ///
+    /// Returns `None` if there is no such overload.
+    #[cfg(not(feature = "no_function"))]
+    #[must_use]
+    pub(crate) fn call_operator_overload(
+        &self,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        (op_assign, hash_op_assign): (&str, u64),
+        (op, hash_op): (&str, u64),
+        args: &mut FnCallArgs,
+        pos: Position,
+        level: usize,
+    ) -> Result<Option<Dynamic>, Box<EvalAltResult>> {
+        let hash = match operator_overload_hash(op, args) {
+            Some(hash) => hash,
+            None => return Ok(None),
+        };
+
+        // Native functions and built-in operators take precedence
+        if self.has_native_fn(mods, state, lib, op_assign, hash_op_assign, args, true)
+            || self.has_native_fn(mods, state, lib, op, hash_op, args, false)
+        {
+            return Ok(None);
+        }
+
+        if self
+            .resolve_fn(mods, state, lib, op, hash, None, args.len(), false, false)
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let hashes = FnCallHashes::from_script(hash);
+
+        self.exec_fn_call(
+            mods, state, lib, op, hashes, args, false, false, pos, None, level,
+        )
+        .map(|(v, _)| Some(v))
+    }
+
     /// Call a function in normal function-call style.
     #[must_use]
     pub(crate) fn make_function_call(
@@ -1169,8 +1748,7 @@ impl Engine {
                 return Ok(if num_params < 0 {
                     Dynamic::FALSE
                 } else {
-                    let hash_script = calc_fn_hash(&fn_name, num_params as usize);
-                    self.has_script_fn(Some(mods), state, lib, hash_script)
+                    self.has_script_fn(Some(mods), state, lib, &fn_name, num_params as usize)
                         .into()
                 });
             }
@@ -1388,6 +1966,19 @@ impl Engine {
             r => r,
         };
 
+        // Then search for script-defined functions with default parameter values or a rest parameter
+        #[cfg(not(feature = "no_function"))]
+        let func = func.or_else(|| {
+            namespace
+                .iter()
+                .skip(1)
+                .try_fold(module.as_ref(), |m, ns| m.get_sub_module(&ns.name))
+                .and_then(|m| m.get_script_fn_by_num_args(fn_name, args.len()))
+                .filter(|f| {
+                    matches!(f.get_script_fn_def(), Some(fn_def) if fn_def.access == crate::FnAccess::Public)
+                })
+        });
+
         // Clone first argument if the function is not a method after-all
         if !func.map(|f| f.is_method()).unwrap_or(true) {
             first_arg_value.map(|first| {
@@ -1403,7 +1994,7 @@ impl Engine {
                     .get_script_fn_def()
                     .expect("never fails because the function is scripted");
 
-                if fn_def.body.is_empty() {
+                if fn_def.body.is_empty() && !fn_def.is_generator {
                     Ok(Dynamic::UNIT)
                 } else {
                     let new_scope = &mut Default::default();
@@ -1447,4 +2038,198 @@ impl Engine {
             .into(),
         }
     }
+
+    /// Call a script-defined function with named arguments in normal function-call style.
+    #[cfg(not(feature = "no_function"))]
+    #[must_use]
+    pub(crate) fn make_named_function_call(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        this_ptr: &mut Option<&mut Dynamic>,
+        namespace: Option<&NamespaceRef>,
+        fn_name: &str,
+        args_expr: &[Expr],
+        constants: &[Dynamic],
+        named_args: &[crate::ast::Ident],
+        pos: Position,
+        level: usize,
+    ) -> RhaiResult {
+        let mut arg_values = StaticVec::with_capacity(args_expr.len());
+
+        for index in 0..args_expr.len() {
+            let (value, _) = self.get_arg_value(
+                scope, mods, state, lib, this_ptr, level, args_expr, constants, index,
+            )?;
+            arg_values.push(value.flatten());
+        }
+
+        let names: StaticVec<_> = named_args.iter().map(|n| n.name.as_str()).collect();
+        let num_positional = arg_values.len() - names.len();
+
+        let func = if let Some(namespace) = namespace {
+            let module = self.search_imports(mods, state, namespace).ok_or_else(|| {
+                EvalAltResult::ErrorModuleNotFound(namespace[0].name.to_string(), namespace[0].pos)
+            })?;
+
+            // Only public functions can be called with a namespace
+            namespace
+                .iter()
+                .skip(1)
+                .try_fold(module.as_ref(), |m, ns| m.get_sub_module(&ns.name))
+                .and_then(|m| m.get_script_fn_with_named_args(fn_name, num_positional, &names))
+                .filter(|f| f.access == crate::FnAccess::Public)
+                .map(|f| (f.clone(), module.id_raw().cloned()))
+        } else {
+            lib.iter().find_map(|m| {
+                m.get_script_fn_with_named_args(fn_name, num_positional, &names)
+                    .map(|f| (f.clone(), m.id_raw().cloned()))
+            })
+        };
+
+        let (fn_def, mut source) = match func {
+            Some(f) => f,
+            None => {
+                let signature = format!(
+                    "{}{} ({})",
+                    namespace.map_or(String::new(), |ns| ns.to_string()),
+                    fn_name,
+                    arg_values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, a)| {
+                            let typ = if a.is::<ImmutableString>() {
+                                "&str | ImmutableString | String"
+                            } else {
+                                self.map_type_name(a.type_name())
+                            };
+                            if i < num_positional {
+                                typ.to_string()
+                            } else {
+                                format!("{}: {}", names[i - num_positional], typ)
+                            }
+                        })
+                        .collect::<StaticVec<_>>()
+                        .join(", ")
+                );
+                return EvalAltResult::ErrorFunctionNotFound(signature, pos).into();
+            }
+        };
+
+        let mut arg_values = fn_def
+            .bind_named_args(arg_values, &names)
+            .expect("never fails because the function accepts the named arguments");
+        let mut args: StaticVec<_> = arg_values.iter_mut().collect();
+
+        let new_scope = &mut Default::default();
+        mem::swap(&mut state.source, &mut source);
+
+        let level = level + 1;
+
+        let result = self.call_script_fn(
+            new_scope, mods, state, lib, &mut None, &fn_def, &mut args, pos, level,
+        );
+
+        state.source = source;
+
+        result
+    }
+
+    /// Call a function in normal function-call style with spread arguments.
+    ///
+    /// The arguments are expanded first, and the function is then resolved by the actual
+    /// number of arguments.
+    #[cfg(not(feature = "no_index"))]
+    #[must_use]
+    pub(crate) fn make_spread_function_call(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        this_ptr: &mut Option<&mut Dynamic>,
+        x: &crate::ast::FnCallExpr,
+        pos: Position,
+        level: usize,
+    ) -> RhaiResult {
+        let mut arg_values = StaticVec::with_capacity(x.args.len());
+
+        for (index, expr) in x.args.iter().enumerate() {
+            match expr {
+                // ...expr
+                Expr::Spread(expr, _) => {
+                    let value = self
+                        .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
+                        .flatten();
+                    let typ = value.type_name();
+                    arg_values.extend(value.try_cast::<crate::Array>().ok_or_else(|| {
+                        self.make_type_mismatch_err::<crate::Array>(typ, expr.position())
+                    })?);
+                }
+                _ => {
+                    let (value, _) = self.get_arg_value(
+                        scope,
+                        mods,
+                        state,
+                        lib,
+                        this_ptr,
+                        level,
+                        &x.args,
+                        &x.constants,
+                        index,
+                    )?;
+                    arg_values.push(value.flatten());
+                }
+            }
+        }
+
+        // Pass the expanded argument values as constants
+        let num_args = arg_values.len();
+        let args_expr: StaticVec<_> = (0..num_args).map(|i| Expr::Stack(i, pos)).collect();
+
+        if let Some(ref namespace) = x.namespace {
+            let hash = crate::calc_qualified_fn_hash(
+                namespace.iter().map(|m| m.name.as_str()),
+                &x.name,
+                num_args,
+            );
+            self.make_qualified_function_call(
+                scope,
+                mods,
+                state,
+                lib,
+                this_ptr,
+                namespace,
+                &x.name,
+                &args_expr,
+                &arg_values,
+                hash,
+                pos,
+                level,
+            )
+        } else {
+            let hash = calc_fn_hash(&x.name, num_args);
+            let hashes = if crate::token::is_valid_identifier(x.name.chars()) {
+                FnCallHashes::from_script(hash)
+            } else {
+                FnCallHashes::from_native(hash)
+            };
+            self.make_function_call(
+                scope,
+                mods,
+                state,
+                lib,
+                this_ptr,
+                &x.name,
+                &args_expr,
+                &arg_values,
+                hashes,
+                pos,
+                x.capture,
+                level,
+            )
+        }
+    }
 }
@@ -11,6 +11,9 @@ use std::fmt;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
+#[cfg(not(feature = "unchecked"))]
+use std::cell::Cell;
+
 /// Trait that maps to `Send + Sync` only under the `sync` feature.
 #[cfg(feature = "sync")]
 pub trait SendSync: Send + Sync {}
@@ -33,15 +36,9 @@ pub use std::rc::Rc as Shared;
 pub use std::sync::Arc as Shared;
 
 /// Synchronized shared object.
-///
-/// Not available under `no_closure`.
-#[cfg(not(feature = "no_closure"))]
 #[cfg(not(feature = "sync"))]
 pub use std::cell::RefCell as Locked;
 /// Synchronized shared object.
-///
-/// Not available under `no_closure`.
-#[cfg(not(feature = "no_closure"))]
 #[cfg(feature = "sync")]
 pub use std::sync::RwLock as Locked;
 
@@ -53,6 +50,9 @@ pub struct NativeCallContext<'a> {
     source: Option<&'a str>,
     mods: Option<&'a Imports>,
     lib: &'a [&'a Module],
+    /// Number of operations performed so far by the evaluation calling the function, if any.
+    #[cfg(not(feature = "unchecked"))]
+    operations: Option<&'a Cell<u64>>,
 }
 
 impl<'a, M: AsRef<[&'a Module]> + ?Sized>
@@ -66,6 +66,8 @@ impl<'a, M: AsRef<[&'a Module]> + ?Sized>
             source: value.2,
             mods: Some(value.3),
             lib: value.4.as_ref(),
+            #[cfg(not(feature = "unchecked"))]
+            operations: None,
         }
     }
 }
@@ -81,6 +83,8 @@ impl<'a, M: AsRef<[&'a Module]> + ?Sized> From<(&'a Engine, &'a str, &'a M)>
             source: None,
             mods: None,
             lib: value.2.as_ref(),
+            #[cfg(not(feature = "unchecked"))]
+            operations: None,
         }
     }
 }
@@ -96,6 +100,8 @@ impl<'a> NativeCallContext<'a> {
             source: None,
             mods: None,
             lib,
+            #[cfg(not(feature = "unchecked"))]
+            operations: None,
         }
     }
     /// _(INTERNALS)_ Create a new [`NativeCallContext`].
@@ -119,8 +125,18 @@ impl<'a> NativeCallContext<'a> {
             source,
             mods: Some(imports),
             lib,
+            #[cfg(not(feature = "unchecked"))]
+            operations: None,
         }
     }
+    /// Attach the running number of operations of the evaluation calling the function.
+    #[cfg(not(feature = "unchecked"))]
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn with_operations(mut self, operations: &'a Cell<u64>) -> Self {
+        self.operations = Some(operations);
+        self
+    }
     /// The current [`Engine`].
     #[inline(always)]
     #[must_use]
@@ -139,6 +155,24 @@ impl<'a> NativeCallContext<'a> {
     pub const fn source(&self) -> Option<&str> {
         self.source
     }
+    /// Number of operations performed so far by the evaluation calling the function.
+    ///
+    /// Zero when the function is not called by an evaluation.
+    #[cfg(not(feature = "unchecked"))]
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn operations(&self) -> u64 {
+        self.operations.map_or(0, Cell::get)
+    }
+    /// Count operations performed by the function (e.g. steps matching a regular expression)
+    /// towards the maximum number of operations of the evaluation calling it.
+    #[cfg(not(feature = "unchecked"))]
+    #[inline(always)]
+    pub(crate) fn add_operations(&self, num: u64) {
+        if let Some(operations) = self.operations {
+            operations.set(operations.get().saturating_add(num));
+        }
+    }
     /// Get an iterator over the current set of modules imported via `import` statements.
     ///
     /// Not available under `no_module`.
@@ -169,6 +203,12 @@ impl<'a> NativeCallContext<'a> {
     pub const fn imports(&self) -> Option<&Imports> {
         self.mods
     }
+    /// Get the random number generator seeded for the current evaluation, if any.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn rng(&self) -> Option<&Shared<crate::Locked<crate::packages::random_basic::Rng>>> {
+        self.mods.and_then(|m| m.rng())
+    }
     /// Get an iterator over the namespaces containing definitions of all script-defined functions.
     #[inline(always)]
     #[must_use]
@@ -12,6 +12,13 @@ use crate::{Dynamic, EvalAltResult, NativeCallContext};
 use std::prelude::v1::*;
 use std::{any::TypeId, mem};
 
+#[cfg(feature = "async")]
+#[cfg(not(feature = "no_std"))]
+use crate::fn_async::{wait_for, Async, AsyncResult};
+#[cfg(feature = "async")]
+#[cfg(not(feature = "no_std"))]
+use std::future::Future;
+
 // These types are used to build a unique _marker_ tuple type for each combination
 // of function parameter types in order to make each trait implementation unique.
 // That is because stable Rust currently does not allow distinguishing implementations
@@ -220,6 +227,64 @@ macro_rules! def_register {
             }
         }
 
+        #[cfg(feature = "async")]
+        #[cfg(not(feature = "no_std"))]
+        impl<
+            FN: Fn($($param),*) -> FUT + SendSync + 'static,
+            FUT: Future<Output = RET> + Send + 'static,
+            $($par: Variant + Clone,)*
+            RET: Variant + Clone
+        > RegisterNativeFunction<($($mark,)*), Async<RET>> for FN {
+            #[inline(always)] fn param_types() -> Box<[TypeId]> { vec![$(TypeId::of::<$par>()),*].into_boxed_slice() }
+            #[cfg(feature = "metadata")] #[inline(always)] fn param_names() -> Box<[&'static str]> { vec![$(std::any::type_name::<$par>()),*].into_boxed_slice() }
+            #[cfg(feature = "metadata")] #[inline(always)] fn return_type() -> TypeId { TypeId::of::<RET>() }
+            #[cfg(feature = "metadata")] #[inline(always)] fn return_type_name() -> &'static str { std::any::type_name::<RET>() }
+            #[inline(always)] fn into_callable_function(self) -> CallableFunction {
+                CallableFunction::$abi(Box::new(move |ctx: NativeCallContext, args: &mut FnCallArgs| {
+                    if args.len() == 2 && args[0].is_read_only() && is_setter(ctx.fn_name()) {
+                        return EvalAltResult::ErrorAssignmentToConstant(Default::default(), Position::NONE).into();
+                    }
+
+                    // The arguments are assumed to be of the correct number and types!
+                    let mut _drain = args.iter_mut();
+                    $($let $par = ($clone)(_drain.next().expect("never fails because arguments list is fixed")); )*
+
+                    // Call the function with each argument value and wait for the result
+                    let future = self($($arg),*);
+                    wait_for(ctx.fn_name(), Box::pin(async move { Ok(future.await.into_dynamic()) }))
+                }) as Box<FnAny>)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        #[cfg(not(feature = "no_std"))]
+        impl<
+            FN: Fn($($param),*) -> FUT + SendSync + 'static,
+            FUT: Future<Output = Result<RET, Box<EvalAltResult>>> + Send + 'static,
+            $($par: Variant + Clone,)*
+            RET: Variant + Clone
+        > RegisterNativeFunction<($($mark,)*), AsyncResult<RET>> for FN {
+            #[inline(always)] fn param_types() -> Box<[TypeId]> { vec![$(TypeId::of::<$par>()),*].into_boxed_slice() }
+            #[cfg(feature = "metadata")] #[inline(always)] fn param_names() -> Box<[&'static str]> { vec![$(std::any::type_name::<$par>()),*].into_boxed_slice() }
+            #[cfg(feature = "metadata")] #[inline(always)] fn return_type() -> TypeId { TypeId::of::<Result<RET, Box<EvalAltResult>>>() }
+            #[cfg(feature = "metadata")] #[inline(always)] fn return_type_name() -> &'static str { std::any::type_name::<Result<RET, Box<EvalAltResult>>>() }
+            #[inline(always)] fn into_callable_function(self) -> CallableFunction {
+                CallableFunction::$abi(Box::new(move |ctx: NativeCallContext, args: &mut FnCallArgs| {
+                    if args.len() == 2 && args[0].is_read_only() && is_setter(ctx.fn_name()) {
+                        return EvalAltResult::ErrorAssignmentToConstant(Default::default(), Position::NONE).into();
+                    }
+
+                    // The arguments are assumed to be of the correct number and types!
+                    let mut _drain = args.iter_mut();
+                    $($let $par = ($clone)(_drain.next().expect("never fails because arguments list is fixed")); )*
+
+                    // Call the function with each argument value and wait for the result
+                    let future = self($($arg),*);
+                    wait_for(ctx.fn_name(), Box::pin(async move { future.await.map(Dynamic::from) }))
+                }) as Box<FnAny>)
+            }
+        }
+
         //def_register!(imp_pop $($par => $mark => $param),*);
     };
     ($p0:ident $(, $p:ident)*) => {
@@ -0,0 +1,483 @@
+//! Module implementing format strings and format specifiers, used by the `format` function and
+//! by string interpolation.
+
+use crate::packages::string_basic::{print_with_func, FUNC_TO_DEBUG, FUNC_TO_STRING};
+use crate::{Dynamic, EvalAltResult, NativeCallContext, INT};
+use std::iter::Peekable;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::str::Chars;
+
+#[cfg(not(feature = "no_float"))]
+use crate::{ast::FloatWrapper, FLOAT};
+
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+
+/// Maximum width or precision in a format specifier.
+const MAX_FORMAT_WIDTH: usize = u16::MAX as usize;
+
+/// Alignment of a formatted value within its width.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum Align {
+    /// `<`
+    Left,
+    /// `^`
+    Center,
+    /// `>`
+    Right,
+}
+
+/// How a formatted value is rendered.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum Kind {
+    /// No type, i.e. `to_string`.
+    Display,
+    /// `?`, i.e. `to_debug`.
+    Debug,
+    /// `x`
+    LowerHex,
+    /// `X`
+    UpperHex,
+    /// `o`
+    Octal,
+    /// `b`
+    Binary,
+    /// `e`
+    LowerExp,
+    /// `E`
+    UpperExp,
+}
+
+/// A format specifier, i.e. the part after the `:` in `{:>8.2}`.
+///
+/// The syntax is a subset of Rust's:
+/// `[[fill]align][sign]['#']['0'][width]['.' precision][type]`
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub(crate) struct FormatSpec {
+    /// Character used to pad the value to its width.
+    fill: char,
+    /// Alignment of the value, or [`None`] for the default alignment of its type.
+    align: Option<Align>,
+    /// Always print the sign of a number?
+    sign_plus: bool,
+    /// Prefix numbers in a radix with `0x`, `0o` or `0b`?
+    alternate: bool,
+    /// Pad numbers with zeros after the sign?
+    zero_pad: bool,
+    /// Minimum width, in characters.
+    width: usize,
+    /// Number of decimal places of a floating-point number, or maximum number of characters of
+    /// any other non-integer value.
+    precision: Option<usize>,
+    /// How the value is rendered.
+    kind: Kind,
+}
+
+impl Default for FormatSpec {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            fill: ' ',
+            align: None,
+            sign_plus: false,
+            alternate: false,
+            zero_pad: false,
+            width: 0,
+            precision: None,
+            kind: Kind::Display,
+        }
+    }
+}
+
+/// Parse a decimal number, if any, from a stream of characters.
+fn parse_number(chars: &mut Peekable<Chars>) -> Option<usize> {
+    let mut number: Option<usize> = None;
+
+    while let Some(&ch) = chars.peek() {
+        match ch.to_digit(10) {
+            Some(d) => {
+                chars.next();
+                number = Some(
+                    number
+                        .unwrap_or(0)
+                        .saturating_mul(10)
+                        .saturating_add(d as usize),
+                );
+            }
+            None => break,
+        }
+    }
+
+    number
+}
+
+impl FormatSpec {
+    /// Parse a format specifier.
+    ///
+    /// Returns an error message if the format specifier is malformed.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        fn to_align(ch: char) -> Option<Align> {
+            match ch {
+                '<' => Some(Align::Left),
+                '^' => Some(Align::Center),
+                '>' => Some(Align::Right),
+                _ => None,
+            }
+        }
+
+        let mut result = Self::default();
+        let mut chars = spec.chars().peekable();
+
+        // [[fill]align]
+        let mut lookahead = spec.chars();
+        match (lookahead.next(), lookahead.next()) {
+            (Some(fill), Some(ch)) if to_align(ch).is_some() => {
+                result.fill = fill;
+                result.align = to_align(ch);
+                chars.next();
+                chars.next();
+            }
+            (Some(ch), _) if to_align(ch).is_some() => {
+                result.align = to_align(ch);
+                chars.next();
+            }
+            _ => (),
+        }
+
+        // [sign]
+        match chars.peek() {
+            Some('+') => {
+                result.sign_plus = true;
+                chars.next();
+            }
+            Some('-') => {
+                chars.next();
+            }
+            _ => (),
+        }
+
+        // ['#']
+        if chars.peek() == Some(&'#') {
+            result.alternate = true;
+            chars.next();
+        }
+
+        // ['0']
+        if chars.peek() == Some(&'0') {
+            result.zero_pad = true;
+            chars.next();
+        }
+
+        // [width]
+        result.width = parse_number(&mut chars).unwrap_or(0);
+
+        if result.width > MAX_FORMAT_WIDTH {
+            return Err(format!("width too large in '{}'", spec));
+        }
+
+        // ['.' precision]
+        if chars.peek() == Some(&'.') {
+            chars.next();
+
+            match parse_number(&mut chars) {
+                Some(precision) if precision > MAX_FORMAT_WIDTH => {
+                    return Err(format!("precision too large in '{}'", spec))
+                }
+                Some(precision) => result.precision = Some(precision),
+                None => return Err(format!("missing precision in '{}'", spec)),
+            }
+        }
+
+        // [type]
+        result.kind = match chars.next() {
+            None => Kind::Display,
+            Some('?') => Kind::Debug,
+            Some('x') => Kind::LowerHex,
+            Some('X') => Kind::UpperHex,
+            Some('o') => Kind::Octal,
+            Some('b') => Kind::Binary,
+            Some('e') => Kind::LowerExp,
+            Some('E') => Kind::UpperExp,
+            Some(ch) => return Err(format!("unknown format type '{}' in '{}'", ch, spec)),
+        };
+
+        match chars.next() {
+            None => Ok(result),
+            Some(_) => Err(format!("invalid format specifier '{}'", spec)),
+        }
+    }
+
+    /// Format a value according to this format specifier.
+    ///
+    /// Returns an error message if the format specifier does not apply to the type of the value.
+    pub fn format(&self, ctx: &NativeCallContext, value: &mut Dynamic) -> Result<String, String> {
+        // Numbers are split into sign, radix prefix and digits to support zero padding.
+        let number: Option<(bool, &str, String)> = if value.is::<INT>() {
+            let n = value
+                .as_int()
+                .expect("never fails because `value` is `INT`");
+            let abs = n.unsigned_abs();
+
+            // Like `to_hex`, radix formats show negative numbers in two's complement.
+            Some(match self.kind {
+                Kind::Display | Kind::Debug => (n < 0, "", abs.to_string()),
+                Kind::LowerHex => (false, "0x", format!("{:x}", n)),
+                Kind::UpperHex => (false, "0x", format!("{:X}", n)),
+                Kind::Octal => (false, "0o", format!("{:o}", n)),
+                Kind::Binary => (false, "0b", format!("{:b}", n)),
+                Kind::LowerExp => (n < 0, "", format!("{:e}", abs)),
+                Kind::UpperExp => (n < 0, "", format!("{:E}", abs)),
+            })
+        } else {
+            self.format_float(value)?
+        };
+
+        #[cfg(feature = "bigint")]
+        let number = match number {
+            None => self.format_bigint(value)?,
+            number => number,
+        };
+
+        let (sign, prefix, digits) = match number {
+            Some(number) => number,
+            None => return self.format_other(ctx, value),
+        };
+
+        let sign = match (sign, self.sign_plus) {
+            (true, _) => "-",
+            (false, true) => "+",
+            (false, false) => "",
+        };
+        let prefix = if self.alternate { prefix } else { "" };
+
+        if self.zero_pad {
+            let len = sign.len() + prefix.len() + digits.chars().count();
+            let zeros = "0".repeat(self.width.saturating_sub(len));
+            Ok(format!("{}{}{}{}", sign, prefix, zeros, digits))
+        } else {
+            let body = format!("{}{}{}", sign, prefix, digits);
+            Ok(self.pad(body, Align::Right))
+        }
+    }
+    /// Split a floating-point or [`Decimal`][rust_decimal::Decimal] number into its sign and
+    /// digits, or return [`None`] if the value is not such a number.
+    #[allow(unused_variables)]
+    fn format_float(
+        &self,
+        value: &Dynamic,
+    ) -> Result<Option<(bool, &'static str, String)>, String> {
+        #[cfg(not(feature = "no_float"))]
+        if value.is::<FLOAT>() {
+            let f = value
+                .as_float()
+                .expect("never fails because `value` is `FLOAT`");
+            let abs = f.abs();
+
+            let digits = match (self.kind, self.precision) {
+                (Kind::Display, None) | (Kind::Debug, None) => FloatWrapper::new(abs).to_string(),
+                (Kind::Display, Some(p)) | (Kind::Debug, Some(p)) => format!("{:.*}", p, abs),
+                (Kind::LowerExp, None) => format!("{:e}", abs),
+                (Kind::LowerExp, Some(p)) => format!("{:.*e}", p, abs),
+                (Kind::UpperExp, None) => format!("{:E}", abs),
+                (Kind::UpperExp, Some(p)) => format!("{:.*E}", p, abs),
+                _ => return Err(self.unsupported(value)),
+            };
+
+            return Ok(Some((f.is_sign_negative(), "", digits)));
+        }
+
+        #[cfg(feature = "decimal")]
+        if value.is::<Decimal>() {
+            let d = value
+                .as_decimal()
+                .expect("never fails because `value` is `Decimal`");
+            let abs = d.abs();
+
+            let digits = match (self.kind, self.precision) {
+                (Kind::Display, None) | (Kind::Debug, None) => abs.to_string(),
+                (Kind::Display, Some(p)) | (Kind::Debug, Some(p)) => format!("{:.*}", p, abs),
+                _ => return Err(self.unsupported(value)),
+            };
+
+            return Ok(Some((d.is_sign_negative(), "", digits)));
+        }
+
+        Ok(None)
+    }
+    /// Split a [`BigInt`] number into its sign, radix prefix and digits, or return [`None`] if
+    /// the value is not such a number.
+    ///
+    /// Unlike `INT`, radix formats show the sign of negative numbers.
+    #[cfg(feature = "bigint")]
+    fn format_bigint(
+        &self,
+        value: &Dynamic,
+    ) -> Result<Option<(bool, &'static str, String)>, String> {
+        use num_traits::Signed;
+
+        if !value.is::<BigInt>() {
+            return Ok(None);
+        }
+
+        let n = value
+            .as_bigint()
+            .expect("never fails because `value` is `BigInt`");
+        let abs = n.abs();
+
+        let (prefix, digits) = match self.kind {
+            Kind::Display | Kind::Debug => ("", abs.to_string()),
+            Kind::LowerHex => ("0x", format!("{:x}", abs)),
+            Kind::UpperHex => ("0x", format!("{:X}", abs)),
+            Kind::Octal => ("0o", format!("{:o}", abs)),
+            Kind::Binary => ("0b", format!("{:b}", abs)),
+            Kind::LowerExp | Kind::UpperExp => return Err(self.unsupported(value)),
+        };
+
+        Ok(Some((n.is_negative(), prefix, digits)))
+    }
+    /// Format a value that is not a number.
+    fn format_other(&self, ctx: &NativeCallContext, value: &mut Dynamic) -> Result<String, String> {
+        let func = match self.kind {
+            _ if self.sign_plus || self.alternate || self.zero_pad => {
+                return Err(self.unsupported(value))
+            }
+            Kind::Display => FUNC_TO_STRING,
+            Kind::Debug => FUNC_TO_DEBUG,
+            _ => return Err(self.unsupported(value)),
+        };
+
+        let text = print_with_func(func, ctx, value);
+
+        let text = match self.precision {
+            Some(p) => text.chars().take(p).collect(),
+            None => text.to_string(),
+        };
+
+        Ok(self.pad(text, Align::Left))
+    }
+    /// Pad a formatted value to the width.
+    fn pad(&self, text: String, default_align: Align) -> String {
+        let len = text.chars().count();
+
+        if len >= self.width {
+            return text;
+        }
+
+        let padding = self.width - len;
+        let (before, after) = match self.align.unwrap_or(default_align) {
+            Align::Left => (0, padding),
+            Align::Center => (padding / 2, padding - padding / 2),
+            Align::Right => (padding, 0),
+        };
+
+        let mut result = String::with_capacity(text.len() + padding * self.fill.len_utf8());
+        (0..before).for_each(|_| result.push(self.fill));
+        result.push_str(&text);
+        (0..after).for_each(|_| result.push(self.fill));
+        result
+    }
+    /// Error message for a format specifier that does not apply to the type of a value.
+    fn unsupported(&self, value: &Dynamic) -> String {
+        format!(
+            "format specifier is not supported for {}",
+            value.type_name()
+        )
+    }
+}
+
+/// Format a list of arguments according to a format string, e.g. `"{:>8.2} {:08x}"`.
+///
+/// Each `{}` placeholder takes the next argument, while `{n}` takes the argument at index `n`.
+/// A format specifier may follow after a colon, e.g. `{:.2}` or `{1:x}`.
+/// `{{` and `}}` stand for literal braces.
+pub(crate) fn format_args(
+    ctx: &NativeCallContext,
+    fmt: &str,
+    args: &mut [&mut Dynamic],
+) -> Result<String, Box<EvalAltResult>> {
+    let mut result = String::with_capacity(fmt.len());
+    let mut chars = fmt.chars().peekable();
+    let mut next_arg = 0;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                result.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                result.push('}');
+            }
+            '}' => return Err("unmatched '}' in format string".into()),
+            '{' => {
+                let mut placeholder = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => placeholder.push(ch),
+                        None => return Err("unterminated '{' in format string".into()),
+                    }
+                }
+
+                let (index, spec) = match placeholder.find(':') {
+                    Some(n) => (&placeholder[..n], &placeholder[n + 1..]),
+                    None => (&placeholder[..], ""),
+                };
+
+                let index = if index.is_empty() {
+                    next_arg += 1;
+                    next_arg - 1
+                } else {
+                    index.parse::<usize>().map_err(|_| {
+                        format!("invalid argument index '{}' in format string", index)
+                    })?
+                };
+
+                let spec = FormatSpec::parse(spec)?;
+
+                let num_args = args.len();
+                let value = args.get_mut(index).ok_or_else(|| {
+                    format!(
+                        "argument #{} requested but only {} argument{} given",
+                        index + 1,
+                        num_args,
+                        if num_args == 1 { "" } else { "s" }
+                    )
+                })?;
+
+                // Check if the padded value will be over max size limit before formatting it
+                #[cfg(not(feature = "unchecked"))]
+                if ctx.engine().max_string_size() > 0
+                    && result.len() + spec.width > ctx.engine().max_string_size()
+                {
+                    return Err(make_too_large_err());
+                }
+
+                result.push_str(&spec.format(ctx, value)?);
+            }
+            ch => result.push(ch),
+        }
+
+        // Check if string will be over max size limit
+        #[cfg(not(feature = "unchecked"))]
+        if ctx.engine().max_string_size() > 0 && result.len() > ctx.engine().max_string_size() {
+            return Err(make_too_large_err());
+        }
+    }
+
+    Ok(result)
+}
+
+/// Make an error for a formatted string that is over the max size limit.
+#[cfg(not(feature = "unchecked"))]
+#[inline(always)]
+#[must_use]
+fn make_too_large_err() -> Box<EvalAltResult> {
+    EvalAltResult::ErrorDataTooLarge("Length of string".to_string(), crate::Position::NONE).into()
+}
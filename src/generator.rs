@@ -0,0 +1,814 @@
+//! Module implementing generators, returned by script-defined functions containing `yield`.
+#![cfg(not(feature = "no_function"))]
+
+use crate::ast::{Expr, Ident, ScriptFnDef, Stmt};
+use crate::engine::{EvalState, Imports};
+use crate::fn_call::make_script_fn_error;
+use crate::fn_native::{IteratorFn, Shared};
+use crate::r#unsafe::unsafe_cast_var_name_to_lifetime;
+use crate::{
+    Dynamic, Engine, EvalAltResult, ExclusiveRange, Identifier, InclusiveRange, Module, Position,
+    Scope, StaticVec, AST,
+};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{fmt, mem};
+
+#[cfg(not(feature = "no_index"))]
+use crate::Array;
+
+#[cfg(not(feature = "unchecked"))]
+use crate::INT;
+
+/// Items of a `for` loop suspended inside a generator.
+#[derive(Debug, Clone)]
+enum ForItems {
+    /// Items of an array, taken out in order.
+    #[cfg(not(feature = "no_index"))]
+    Array(Array),
+    /// Remaining numbers of an exclusive range.
+    Range(ExclusiveRange),
+    /// Remaining numbers of an inclusive range.
+    InclusiveRange(InclusiveRange),
+    /// Values yielded by another generator.
+    Generator(Box<Generator>),
+    /// Items of any other value with a registered iterator.
+    Iterable(Iterable),
+}
+
+/// Iterator over the items of a value with a registered iterator, suspended inside a generator.
+///
+/// The iterator is created when the first item is taken and kept until the loop ends.
+/// Iterators cannot be cloned, so a cloned generator creates a new one from the value and skips
+/// the items already taken.
+///
+/// Under the `sync` feature, iterators cannot be kept because they are not [`Send`].  Instead,
+/// whenever the items taken ahead of time run out, a new iterator is created from the value and
+/// as many items are taken ahead as have been taken so far, so that iterating stays linear.
+struct Iterable {
+    /// Function creating the iterator.
+    func: IteratorFn,
+    /// Value to iterate over.
+    value: Dynamic,
+    /// The iterator, if already created.
+    #[cfg(not(feature = "sync"))]
+    iter: Option<Box<dyn Iterator<Item = Dynamic>>>,
+    /// Items taken ahead of time, in reverse order.
+    #[cfg(feature = "sync")]
+    ahead: Vec<Dynamic>,
+}
+
+impl Clone for Iterable {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            func: self.func,
+            value: self.value.clone(),
+            #[cfg(not(feature = "sync"))]
+            iter: None,
+            #[cfg(feature = "sync")]
+            ahead: self.ahead.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for Iterable {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Iterable").field(&self.value).finish()
+    }
+}
+
+impl Iterable {
+    /// Create an [`Iterable`] over the items of a value.
+    #[inline(always)]
+    #[must_use]
+    fn new(func: IteratorFn, value: Dynamic) -> Self {
+        Self {
+            func,
+            value,
+            #[cfg(not(feature = "sync"))]
+            iter: None,
+            #[cfg(feature = "sync")]
+            ahead: Vec::new(),
+        }
+    }
+    /// Take the next item, given the number of items already taken.
+    #[must_use]
+    fn next(&mut self, count: usize) -> Option<Dynamic> {
+        #[cfg(not(feature = "sync"))]
+        {
+            let (func, value) = (self.func, &self.value);
+            self.iter
+                .get_or_insert_with(|| Box::new(func(value.clone()).skip(count)))
+                .next()
+        }
+        #[cfg(feature = "sync")]
+        {
+            if self.ahead.is_empty() {
+                self.ahead = (self.func)(self.value.clone())
+                    .skip(count)
+                    .take(count.max(1))
+                    .collect();
+                self.ahead.reverse();
+            }
+            self.ahead.pop()
+        }
+    }
+}
+
+/// A compound statement suspended inside a generator.
+#[derive(Debug, Clone)]
+enum Frame {
+    /// Statements block, with the index of the current statement.
+    Block {
+        index: usize,
+        scope_len: usize,
+        mods_len: usize,
+    },
+    /// `if` statement, with the branch taken.
+    If(bool),
+    /// `switch` statement, with the case taken ([`None`] for the default case).
+    Switch {
+        case: Option<usize>,
+        scope_len: usize,
+    },
+    /// `while`, `loop` or `do` loop.
+    Loop,
+    /// `for` loop, with the number of items taken so far.
+    For {
+        items: ForItems,
+        count: usize,
+        scope_len: usize,
+    },
+}
+
+/// How running a part of a generator's body ends.
+enum Flow {
+    /// Ran to the end.
+    Done,
+    /// Suspended by `yield`.
+    Yield(Dynamic),
+    /// Stopped by `break` (`true`) or `continue` (`false`).
+    Break(bool),
+    /// Stopped by `return`.
+    Return,
+}
+
+/// A generator, returned by calling a script-defined function that contains `yield`.
+///
+/// The function body does not run when the function is called.  Instead, it runs each time the
+/// next value is requested, up to the following `yield` statement, where it is suspended.
+///
+/// In scripts, a generator can be iterated by a `for` loop, or advanced by calling `next`, which
+/// returns `()` once the generator has finished.
+///
+/// `yield` can be used inside blocks, `if` and `switch` statements and loops, but not inside
+/// expressions or `try` statements.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{Engine, Generator, Scope, INT};
+///
+/// let engine = Engine::new();
+///
+/// let ast = engine.compile("fn count(n) { let i = 0; while i < n { yield i; i += 1; } }")?;
+///
+/// let mut gen: Generator = engine.call_fn(&mut Scope::new(), &ast, "count", (3 as INT,))?;
+///
+/// let mut values = Vec::new();
+///
+/// for value in gen.iter(&engine, &ast) {
+///     values.push(value?.cast::<INT>());
+/// }
+///
+/// assert_eq!(values, [0, 1, 2]);
+/// assert!(gen.is_done());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Generator {
+    /// Local variables of the function body.
+    ///
+    /// Variable names may refer to the function body, so this must be dropped before `fn_def`.
+    scope: Scope<'static>,
+    /// Imported modules.
+    mods: Imports,
+    /// Suspended compound statements, outermost first.
+    frames: Vec<Frame>,
+    /// Source of the function, if any.
+    source: Option<Identifier>,
+    /// Must variables be searched by name in the function body?
+    always_search_scope: bool,
+    /// The generator function, or `None` if the generator has finished.
+    fn_def: Option<Shared<ScriptFnDef>>,
+}
+
+impl fmt::Debug for Generator {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.fn_def {
+            Some(ref fn_def) => write!(f, "Generator({})", fn_def.name),
+            None => f.write_str("Generator(<done>)"),
+        }
+    }
+}
+
+impl Generator {
+    /// Create a new [`Generator`] running the body of a generator function.
+    ///
+    /// Variables in the current [`Scope`] stay visible to the function body, just like a normal
+    /// function call.
+    #[must_use]
+    pub(crate) fn new(
+        fn_def: &Shared<ScriptFnDef>,
+        scope: &Scope,
+        mods: &Imports,
+        source: Option<Identifier>,
+        args: impl Iterator<Item = Dynamic>,
+    ) -> Self {
+        let mut gen_scope = Scope::new();
+
+        gen_scope.extend(
+            scope
+                .iter_raw()
+                .map(|(name, _, value)| (name.to_string(), value.clone())),
+        );
+        let params_start = gen_scope.len();
+
+        gen_scope.extend(
+            fn_def
+                .params
+                .iter()
+                .zip(args)
+                .map(|(name, value)| (unsafe_cast_var_name_to_lifetime(name), value)),
+        );
+        fn_def.typed_params().for_each(|(i, typ)| {
+            gen_scope.set_entry_type(params_start + i, typ.clone());
+        });
+
+        #[cfg(not(feature = "no_module"))]
+        let mods = {
+            let mut mods = mods.clone();
+            fn_def
+                .mods
+                .iter_raw()
+                .for_each(|(n, m)| mods.push(n.clone(), m.clone()));
+            mods
+        };
+        #[cfg(feature = "no_module")]
+        let mods = mods.clone();
+
+        Self {
+            scope: gen_scope,
+            mods,
+            frames: Vec::new(),
+            source,
+            always_search_scope: false,
+            fn_def: Some(fn_def.clone()),
+        }
+    }
+    /// Has the generator finished running?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.fn_def.is_none()
+    }
+    /// Run the generator up to the next `yield` statement and return the value yielded,
+    /// or [`None`] if the generator has finished.
+    ///
+    /// Functions called by the generator are searched for in the [`AST`], which should be the one
+    /// defining the generator function.
+    ///
+    /// The generator finishes when it returns an error.
+    #[inline(always)]
+    pub fn resume(
+        &mut self,
+        engine: &Engine,
+        ast: &AST,
+    ) -> Result<Option<Dynamic>, Box<EvalAltResult>> {
+        engine.resume_generator(&mut EvalState::new(), &[ast.lib()], self, Position::NONE, 0)
+    }
+    /// Get an iterator over the values yielded by the generator.
+    ///
+    /// See [`resume`][Generator::resume] for details.
+    #[inline(always)]
+    pub fn iter<'a>(
+        &'a mut self,
+        engine: &'a Engine,
+        ast: &'a AST,
+    ) -> impl Iterator<Item = Result<Dynamic, Box<EvalAltResult>>> + 'a {
+        std::iter::from_fn(move || self.resume(engine, ast).transpose())
+    }
+    /// Mark the generator as finished, dropping all its state.
+    fn finish(&mut self) {
+        // Variable names may refer to the function body, so clear them first
+        self.scope.clear();
+        self.frames.clear();
+        self.mods = Default::default();
+        self.fn_def = None;
+    }
+}
+
+impl Engine {
+    /// Run a [`Generator`] up to the next `yield` statement and return the value yielded,
+    /// or [`None`] if the generator has finished.
+    pub(crate) fn resume_generator(
+        &self,
+        state: &mut EvalState,
+        lib: &[&Module],
+        generator: &mut Generator,
+        pos: Position,
+        level: usize,
+    ) -> Result<Option<Dynamic>, Box<EvalAltResult>> {
+        let fn_def = match generator.fn_def {
+            Some(ref fn_def) => fn_def.clone(),
+            None => return Ok(None),
+        };
+
+        #[cfg(not(feature = "unchecked"))]
+        self.inc_operations(state, pos)?;
+
+        // Check for stack overflow
+        #[cfg(not(feature = "unchecked"))]
+        if level > self.max_call_levels() {
+            return EvalAltResult::ErrorStackOverflow(pos).into();
+        }
+
+        // Merge in encapsulated environment, if any
+        let mut lib_merged = StaticVec::with_capacity(lib.len() + 1);
+
+        let lib = if let Some(ref env_lib) = fn_def.lib {
+            lib_merged.push(env_lib.as_ref());
+            lib_merged.extend(lib.iter().cloned());
+            lib_merged.as_ref()
+        } else {
+            lib
+        };
+
+        let orig_source = mem::replace(&mut state.source, generator.source.clone());
+        let orig_always_search_scope = mem::replace(
+            &mut state.always_search_scope,
+            generator.always_search_scope,
+        );
+        let orig_scope_level = state.scope_level;
+        state.scope_level += 1;
+        state.push_fn_resolution_cache();
+
+        let result = self.resume_block(
+            &mut generator.scope,
+            &mut generator.mods,
+            state,
+            lib,
+            &mut generator.frames,
+            &fn_def.body,
+            0,
+            level,
+        );
+
+        state.pop_fn_resolution_cache();
+        state.scope_level = orig_scope_level;
+        generator.always_search_scope =
+            mem::replace(&mut state.always_search_scope, orig_always_search_scope);
+
+        let result = match result {
+            Ok(Flow::Yield(value)) => Ok(Some(value)),
+            Ok(_) => {
+                generator.finish();
+                Ok(None)
+            }
+            Err(err) => {
+                generator.finish();
+                Err(make_script_fn_error(&fn_def, state, err, pos))
+            }
+        };
+
+        state.source = orig_source;
+
+        result
+    }
+    /// Run, or continue running, a statements block inside a generator.
+    fn resume_block(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        frames: &mut Vec<Frame>,
+        statements: &[Stmt],
+        depth: usize,
+        level: usize,
+    ) -> Result<Flow, Box<EvalAltResult>> {
+        if frames.len() == depth {
+            frames.push(Frame::Block {
+                index: 0,
+                scope_len: scope.len(),
+                mods_len: mods.len(),
+            });
+        }
+
+        loop {
+            let index = match frames[depth] {
+                Frame::Block { index, .. } => index,
+                _ => unreachable!("expecting a block frame"),
+            };
+
+            let flow = match statements.get(index) {
+                None => Flow::Done,
+
+                // yield expr;
+                Some(Stmt::Yield(expr, _pos)) => {
+                    #[cfg(not(feature = "unchecked"))]
+                    self.inc_operations(state, *_pos)?;
+
+                    let value = match expr {
+                        Some(expr) => self
+                            .eval_expr(scope, mods, state, lib, &mut None, expr, level)?
+                            .flatten(),
+                        None => Dynamic::UNIT,
+                    };
+
+                    // Continue from the next statement when resumed
+                    if let Frame::Block { ref mut index, .. } = frames[depth] {
+                        *index += 1;
+                    }
+
+                    Flow::Yield(value)
+                }
+
+                // Statements containing `yield` must be able to be suspended
+                Some(stmt) if stmt.contains_yield() => {
+                    self.resume_stmt(scope, mods, state, lib, frames, stmt, depth + 1, level)?
+                }
+
+                // All other statements are run normally
+                Some(stmt) => {
+                    let flow = match self.eval_stmt(scope, mods, state, lib, &mut None, stmt, level)
+                    {
+                        Ok(_) => Flow::Done,
+                        Err(err) => match *err {
+                            EvalAltResult::LoopBreak(is_break, _) => Flow::Break(is_break),
+                            EvalAltResult::Return(_, _) => Flow::Return,
+                            _ => return Err(err),
+                        },
+                    };
+
+                    // Newly-imported modules may affect function resolution
+                    #[cfg(not(feature = "no_module"))]
+                    if matches!(stmt, Stmt::Import(_, _, _)) {
+                        state.fn_resolution_cache_mut().clear();
+                    }
+
+                    flow
+                }
+            };
+
+            match flow {
+                // Go on to the next statement
+                Flow::Done if index < statements.len() => {
+                    if let Frame::Block { ref mut index, .. } = frames[depth] {
+                        *index += 1;
+                    }
+                }
+                Flow::Yield(_) => return Ok(flow),
+                // Leave the block
+                _ => {
+                    if let Frame::Block {
+                        scope_len,
+                        mods_len,
+                        ..
+                    } = frames[depth]
+                    {
+                        scope.rewind(scope_len);
+                        mods.truncate(mods_len);
+                    }
+                    frames.truncate(depth);
+                    return Ok(flow);
+                }
+            }
+        }
+    }
+    /// Run, or continue running, a compound statement containing `yield` inside a generator.
+    fn resume_stmt(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        frames: &mut Vec<Frame>,
+        stmt: &Stmt,
+        depth: usize,
+        level: usize,
+    ) -> Result<Flow, Box<EvalAltResult>> {
+        let resuming = frames.len() > depth;
+
+        #[cfg(not(feature = "unchecked"))]
+        if !resuming {
+            self.inc_operations(state, stmt.position())?;
+        }
+
+        match stmt {
+            // { stmt; ... }
+            Stmt::Block(statements, _) => {
+                self.resume_block(scope, mods, state, lib, frames, statements, depth, level)
+            }
+
+            // if expr { if_block } else { else_block }
+            Stmt::If(expr, x, _) => {
+                if !resuming {
+                    let condition = self.eval_condition(scope, mods, state, lib, expr, level)?;
+                    frames.push(Frame::If(condition));
+                }
+
+                let block = match frames[depth] {
+                    Frame::If(true) => &x.0,
+                    Frame::If(false) => &x.1,
+                    _ => unreachable!("expecting an if frame"),
+                };
+
+                let flow =
+                    self.resume_block(scope, mods, state, lib, frames, block, depth + 1, level)?;
+
+                if !matches!(flow, Flow::Yield(_)) {
+                    frames.truncate(depth);
+                }
+
+                Ok(flow)
+            }
+
+            // switch expr { case => stmt, ... }
+            Stmt::Switch(match_expr, x, _) => {
+                if !resuming {
+                    let value =
+                        self.eval_expr(scope, mods, state, lib, &mut None, match_expr, level)?;
+                    let scope_len = scope.len();
+                    let case = self
+                        .eval_switch_case(scope, mods, state, lib, &mut None, x, &value, level)?;
+                    frames.push(Frame::Switch { case, scope_len });
+                }
+
+                let (block, scope_len) = match frames[depth] {
+                    Frame::Switch {
+                        case: Some(index),
+                        scope_len,
+                    } => (&x.blocks[index].1, scope_len),
+                    Frame::Switch {
+                        case: None,
+                        scope_len,
+                    } => (&x.def_case, scope_len),
+                    _ => unreachable!("expecting a switch frame"),
+                };
+
+                let flow =
+                    self.resume_block(scope, mods, state, lib, frames, block, depth + 1, level)?;
+
+                if !matches!(flow, Flow::Yield(_)) {
+                    // Remove variables bound to the fields of a matched variant
+                    scope.rewind(scope_len);
+                    frames.truncate(depth);
+                }
+
+                Ok(flow)
+            }
+
+            // while expr { block }
+            Stmt::While(expr, body, _) => {
+                if !resuming {
+                    frames.push(Frame::Loop);
+                }
+
+                loop {
+                    // Check the condition unless continuing in the middle of the body
+                    if frames.len() == depth + 1
+                        && !expr.is_unit()
+                        && !self.eval_condition(scope, mods, state, lib, expr, level)?
+                    {
+                        frames.truncate(depth);
+                        return Ok(Flow::Done);
+                    }
+
+                    match self.resume_block(
+                        scope,
+                        mods,
+                        state,
+                        lib,
+                        frames,
+                        body,
+                        depth + 1,
+                        level,
+                    )? {
+                        Flow::Done | Flow::Break(false) => (),
+                        Flow::Break(true) => {
+                            frames.truncate(depth);
+                            return Ok(Flow::Done);
+                        }
+                        flow => return Ok(flow),
+                    }
+                }
+            }
+
+            // do { block } while|until expr
+            Stmt::Do(body, expr, is_while, _) => {
+                if !resuming {
+                    frames.push(Frame::Loop);
+                }
+
+                loop {
+                    match self.resume_block(
+                        scope,
+                        mods,
+                        state,
+                        lib,
+                        frames,
+                        body,
+                        depth + 1,
+                        level,
+                    )? {
+                        Flow::Done => (),
+                        Flow::Break(false) => continue,
+                        Flow::Break(true) => {
+                            frames.truncate(depth);
+                            return Ok(Flow::Done);
+                        }
+                        flow => return Ok(flow),
+                    }
+
+                    if self.eval_condition(scope, mods, state, lib, expr, level)? ^ *is_while {
+                        frames.truncate(depth);
+                        return Ok(Flow::Done);
+                    }
+                }
+            }
+
+            // for x in expr { block }
+            Stmt::For(expr, x, _) => {
+                let (Ident { name, .. }, counter, statements) = x.as_ref();
+
+                if !resuming {
+                    let iter_obj = self
+                        .eval_expr(scope, mods, state, lib, &mut None, expr, level)?
+                        .flatten();
+                    let items = self
+                        .make_for_items(mods, iter_obj)
+                        .ok_or_else(|| EvalAltResult::ErrorFor(expr.position()))?;
+
+                    // Add the loop variables
+                    let scope_len = scope.len();
+                    if let Some(Ident { name, .. }) = counter {
+                        scope.push(unsafe_cast_var_name_to_lifetime(name), 0 as crate::INT);
+                    }
+                    scope.push(unsafe_cast_var_name_to_lifetime(name), ());
+
+                    frames.push(Frame::For {
+                        items,
+                        count: 0,
+                        scope_len,
+                    });
+                }
+
+                loop {
+                    // Get the next item unless continuing in the middle of the body
+                    if frames.len() == depth + 1 {
+                        let (item, count, scope_len) = match frames[depth] {
+                            Frame::For {
+                                ref mut items,
+                                ref mut count,
+                                scope_len,
+                            } => {
+                                let item = match items {
+                                    #[cfg(not(feature = "no_index"))]
+                                    ForItems::Array(array) => array.get_mut(*count).map(mem::take),
+                                    ForItems::Range(range) => range.next().map(Into::into),
+                                    ForItems::InclusiveRange(range) => range.next().map(Into::into),
+                                    ForItems::Generator(generator) => self.resume_generator(
+                                        state,
+                                        lib,
+                                        generator,
+                                        expr.position(),
+                                        level + 1,
+                                    )?,
+                                    ForItems::Iterable(iterable) => iterable.next(*count),
+                                };
+                                *count += 1;
+                                (item, *count - 1, scope_len)
+                            }
+                            _ => unreachable!("expecting a for frame"),
+                        };
+
+                        let item = match item {
+                            Some(item) => item.flatten(),
+                            None => {
+                                scope.rewind(scope_len);
+                                frames.truncate(depth);
+                                return Ok(Flow::Done);
+                            }
+                        };
+
+                        let mut index = scope_len;
+
+                        // Increment counter
+                        if let Some(Ident { pos, .. }) = counter {
+                            #[cfg(not(feature = "unchecked"))]
+                            if count > INT::MAX as usize {
+                                return EvalAltResult::ErrorArithmetic(
+                                    format!("for-loop counter overflow: {}", count),
+                                    *pos,
+                                )
+                                .into();
+                            }
+                            #[cfg(feature = "unchecked")]
+                            let _ = pos;
+
+                            set_loop_var(
+                                scope.get_mut_by_index(index),
+                                (count as crate::INT).into(),
+                            );
+                            index += 1;
+                        }
+
+                        set_loop_var(scope.get_mut_by_index(index), item);
+
+                        #[cfg(not(feature = "unchecked"))]
+                        self.inc_operations(state, statements.position())?;
+                    }
+
+                    match self.resume_block(
+                        scope,
+                        mods,
+                        state,
+                        lib,
+                        frames,
+                        statements,
+                        depth + 1,
+                        level,
+                    )? {
+                        Flow::Done | Flow::Break(false) => (),
+                        Flow::Break(true) => {
+                            if let Frame::For { scope_len, .. } = frames[depth] {
+                                scope.rewind(scope_len);
+                            }
+                            frames.truncate(depth);
+                            return Ok(Flow::Done);
+                        }
+                        flow => return Ok(flow),
+                    }
+                }
+            }
+
+            _ => unreachable!("statement cannot contain `yield`: {:?}", stmt),
+        }
+    }
+    /// Evaluate the condition of an `if` statement or a loop.
+    fn eval_condition(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut EvalState,
+        lib: &[&Module],
+        expr: &Expr,
+        level: usize,
+    ) -> Result<bool, Box<EvalAltResult>> {
+        self.eval_expr(scope, mods, state, lib, &mut None, expr, level)?
+            .as_bool()
+            .map_err(|typ| self.make_type_mismatch_err::<bool>(typ, expr.position()))
+    }
+    /// Get the items of a `for` loop suspended inside a generator.
+    fn make_for_items(&self, mods: &Imports, iter_obj: Dynamic) -> Option<ForItems> {
+        #[cfg(not(feature = "no_index"))]
+        if iter_obj.is::<Array>() {
+            return Some(ForItems::Array(iter_obj.cast()));
+        }
+
+        if iter_obj.is::<Generator>() {
+            return Some(ForItems::Generator(Box::new(iter_obj.cast())));
+        }
+
+        let func = self.get_type_iterator(mods, iter_obj.type_id())?;
+
+        // Ranges are resumed from their remaining numbers
+        if iter_obj.is::<ExclusiveRange>() {
+            return Some(ForItems::Range(iter_obj.cast()));
+        }
+        if iter_obj.is::<InclusiveRange>() {
+            return Some(ForItems::InclusiveRange(iter_obj.cast()));
+        }
+
+        Some(ForItems::Iterable(Iterable::new(func, iter_obj)))
+    }
+}
+
+/// Set the value of a loop variable, which may be shared.
+fn set_loop_var(loop_var: &mut Dynamic, value: Dynamic) {
+    #[cfg(not(feature = "no_closure"))]
+    if loop_var.is_shared() {
+        *loop_var
+            .write_lock::<Dynamic>()
+            .expect("never fails when casting to `Dynamic`") = value;
+        return;
+    }
+
+    *loop_var = value;
+}
@@ -71,13 +71,17 @@ use std::prelude::v1::*;
 
 mod ast;
 mod custom_syntax;
+mod datetime;
+mod dict;
 mod dynamic;
 mod engine;
 mod engine_api;
 mod engine_settings;
+mod enum_value;
 mod error;
 mod error_parsing;
 mod fn_args;
+mod fn_async;
 mod fn_builtin;
 mod fn_call;
 mod fn_func;
@@ -85,13 +89,17 @@ mod fn_hash;
 mod fn_native;
 mod fn_ptr;
 mod fn_register;
+mod format;
+mod generator;
 mod immutable_string;
 mod module;
 mod optimize;
 pub mod packages;
 mod parse;
 pub mod plugin;
+mod regex;
 mod scope;
+mod set;
 mod token;
 mod r#unsafe;
 
@@ -127,11 +135,17 @@ pub type FLOAT = f64;
 #[cfg(feature = "f32_float")]
 pub type FLOAT = f32;
 
+/// An exclusive integer range, created by the `..` operator in scripts.
+pub type ExclusiveRange = std::ops::Range<INT>;
+
+/// An inclusive integer range, created by the `..=` operator in scripts.
+pub type InclusiveRange = std::ops::RangeInclusive<INT>;
+
 pub use ast::{FnAccess, AST};
 pub use custom_syntax::Expression;
 pub use dynamic::Dynamic;
-pub use engine::{Engine, EvalContext, OP_CONTAINS, OP_EQUALS};
-pub use error::EvalAltResult;
+pub use engine::{Engine, EvalContext, OverflowMode, OP_CONTAINS, OP_EQUALS};
+pub use error::{CallFrame, EvalAltResult};
 pub use error_parsing::{LexError, ParseError, ParseErrorType};
 pub use fn_native::NativeCallContext;
 pub use fn_ptr::FnPtr;
@@ -168,7 +182,6 @@ pub type Identifier = ImmutableString;
 /// Alias to [`Rc`][std::rc::Rc] or [`Arc`][std::sync::Arc] depending on the `sync` feature flag.
 pub use fn_native::Shared;
 
-#[cfg(not(feature = "no_closure"))]
 use fn_native::Locked;
 
 pub(crate) use fn_hash::{
@@ -187,16 +200,51 @@ pub use fn_args::FuncArgs;
 #[cfg(not(feature = "no_function"))]
 pub use ast::ScriptFnMetadata;
 
+#[cfg(not(feature = "no_function"))]
+pub use generator::Generator;
+
+#[cfg(not(feature = "no_function"))]
+pub use enum_value::EnumValue;
+
+#[cfg(not(feature = "no_index"))]
+pub use set::Set;
+
+#[cfg(not(feature = "no_object"))]
+pub use dict::Dict;
+
+#[cfg(not(feature = "no_std"))]
+pub use datetime::{DateTime, Duration};
+
+#[cfg(feature = "async")]
+#[cfg(not(feature = "no_std"))]
+pub use fn_async::EvalFuture;
+
 /// Variable-sized array of [`Dynamic`] values.
 /// Not available under `no_index`.
 #[cfg(not(feature = "no_index"))]
 pub type Array = Vec<Dynamic>;
 
+/// Variable-sized buffer of [`u8`] values (byte array).
+/// Not available under `no_index`.
+#[cfg(not(feature = "no_index"))]
+pub type Blob = Vec<u8>;
+
 /// Hash map of [`Dynamic`] values with [`SmartString`](https://crates.io/crates/smartstring) keys.
 /// Not available under `no_object`.
+///
+/// Properties are kept sorted by name.
 #[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "preserve_order"))]
 pub type Map = std::collections::BTreeMap<Identifier, Dynamic>;
 
+/// Hash map of [`Dynamic`] values with [`SmartString`](https://crates.io/crates/smartstring) keys.
+/// Not available under `no_object`.
+///
+/// Properties are kept in the order they are inserted (feature `preserve_order`).
+#[cfg(not(feature = "no_object"))]
+#[cfg(feature = "preserve_order")]
+pub type Map = indexmap::IndexMap<Identifier, Dynamic>;
+
 #[cfg(not(feature = "no_module"))]
 pub use module::ModuleResolver;
 
@@ -227,10 +275,16 @@ pub use token::{InputStream, Token, TokenizeState, TokenizerControl, TokenizerCo
 #[cfg(feature = "internals")]
 #[deprecated = "this type is volatile and may change"]
 pub use ast::{
-    ASTNode, BinaryExpr, CustomExpr, Expr, FloatWrapper, FnCallExpr, FnCallHashes, Ident,
-    OpAssignment, ReturnType, ScriptFnDef, Stmt, StmtBlock,
+    ASTNode, BinaryExpr, CatchClause, CustomExpr, Expr, FloatWrapper, FnCallExpr, FnCallHashes,
+    Ident, OpAssignment, Pattern, ReturnType, ScriptFnDef, Stmt, StmtBlock, SwitchCases,
+    TryCatchBlock, VariantPattern,
 };
 
+#[cfg(feature = "internals")]
+#[cfg(not(feature = "no_function"))]
+#[deprecated = "this type is volatile and may change"]
+pub use ast::EnumDef;
+
 #[cfg(feature = "internals")]
 #[deprecated = "this type is volatile and may change"]
 pub use engine::{EvalState, FnResolutionCache, FnResolutionCacheEntry, Imports};
@@ -123,6 +123,23 @@ fn calc_native_fn_hash<'a>(
     combine_hashes(hash_script, hash_params)
 }
 
+/// Record the numbers of arguments, other than its number of parameters, that a script-defined
+/// function accepts.
+#[cfg(not(feature = "no_function"))]
+fn index_script_fn_arities(
+    script_fn_arities: &mut BTreeMap<u64, u64>,
+    variadic_script_fns: &mut BTreeMap<u64, u64>,
+    hash_script: u64,
+    fn_def: &crate::ast::ScriptFnDef,
+) {
+    (fn_def.num_required_params()..fn_def.params.len()).for_each(|n| {
+        script_fn_arities.insert(crate::calc_fn_hash(&fn_def.name, n), hash_script);
+    });
+    if fn_def.variadic {
+        variadic_script_fns.insert(crate::calc_fn_hash(&fn_def.name, 0), hash_script);
+    }
+}
+
 /// A module which may contain variables, sub-modules, external Rust functions,
 /// and/or script-defined functions.
 #[derive(Clone)]
@@ -142,6 +159,13 @@ pub struct Module {
     /// Flattened collection of all external Rust functions, native or scripted.
     /// including those in sub-modules.
     all_functions: BTreeMap<u64, Shared<CallableFunction>>,
+    /// Script-defined functions with default parameter values or a rest parameter, keyed by the
+    /// hash of each number of arguments they accept other than their number of parameters.
+    #[cfg(not(feature = "no_function"))]
+    script_fn_arities: BTreeMap<u64, u64>,
+    /// Script-defined functions with a rest parameter, keyed by the hash of their names.
+    #[cfg(not(feature = "no_function"))]
+    variadic_script_fns: BTreeMap<u64, u64>,
     /// Iterator functions, keyed by the type producing the iterator.
     type_iterators: BTreeMap<TypeId, IteratorFn>,
     /// Flattened collection of iterator functions, including those in sub-modules.
@@ -251,6 +275,10 @@ impl Module {
             all_variables: Default::default(),
             functions: Default::default(),
             all_functions: Default::default(),
+            #[cfg(not(feature = "no_function"))]
+            script_fn_arities: Default::default(),
+            #[cfg(not(feature = "no_function"))]
+            variadic_script_fns: Default::default(),
             type_iterators: Default::default(),
             all_type_iterators: Default::default(),
             indexed: true,
@@ -477,6 +505,19 @@ impl Module {
         let hash_script = crate::calc_fn_hash(&fn_def.name, num_params);
         let mut param_names = fn_def.params.clone();
         param_names.push("Dynamic".into());
+
+        if self.functions.contains_key(&hash_script) {
+            // The replaced function may have accepted other numbers of arguments
+            self.functions.remove(&hash_script);
+            self.index_script_fn_arities();
+        }
+        index_script_fn_arities(
+            &mut self.script_fn_arities,
+            &mut self.variadic_script_fns,
+            hash_script,
+            &fn_def,
+        );
+
         self.functions.insert(
             hash_script,
             FuncInfo {
@@ -498,6 +539,10 @@ impl Module {
 
     /// Get a shared reference to the script-defined function in the [`Module`] based on name
     /// and number of parameters.
+    ///
+    /// If there is no function with exactly `num_params` parameters, a function that can be
+    /// called with `num_params` arguments, because it has default parameter values or a rest
+    /// parameter, is returned.
     #[cfg(not(feature = "no_function"))]
     #[inline(always)]
     #[must_use]
@@ -507,9 +552,66 @@ impl Module {
         num_params: usize,
     ) -> Option<&Shared<crate::ast::ScriptFnDef>> {
         self.functions
-            .values()
-            .find(|f| f.params == num_params && f.name == name)
+            .get(&crate::calc_fn_hash(name, num_params))
             .and_then(|f| f.func.get_script_fn_def())
+            .or_else(|| {
+                self.get_script_fn_by_num_args(name, num_params)
+                    .and_then(|f| f.get_script_fn_def())
+            })
+    }
+
+    /// Get a script-defined function in the [`Module`] that can be called with a number of
+    /// arguments different from its number of parameters, because it has default parameter
+    /// values or a rest parameter.
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    #[must_use]
+    pub(crate) fn get_script_fn_by_num_args(
+        &self,
+        name: &str,
+        num_args: usize,
+    ) -> Option<&CallableFunction> {
+        self.script_fn_arities
+            .get(&crate::calc_fn_hash(name, num_args))
+            .or_else(|| self.variadic_script_fns.get(&crate::calc_fn_hash(name, 0)))
+            .and_then(|hash| self.functions.get(hash))
+            .map(|f| f.func.as_ref())
+            .filter(|f| {
+                matches!(f.get_script_fn_def(), Some(fn_def) if fn_def.accepts_num_args(num_args))
+            })
+    }
+
+    /// Rebuild the index of the numbers of arguments that script-defined functions in the
+    /// [`Module`] accept other than their numbers of parameters.
+    #[cfg(not(feature = "no_function"))]
+    fn index_script_fn_arities(&mut self) {
+        let script_fn_arities = &mut self.script_fn_arities;
+        let variadic_script_fns = &mut self.variadic_script_fns;
+
+        script_fn_arities.clear();
+        variadic_script_fns.clear();
+
+        self.functions.iter().for_each(|(&hash, f)| {
+            if let Some(fn_def) = f.func.get_script_fn_def() {
+                index_script_fn_arities(script_fn_arities, variadic_script_fns, hash, fn_def);
+            }
+        });
+    }
+
+    /// Get a shared reference to the script-defined function in the [`Module`] that can be
+    /// called with `num_positional` positional arguments followed by arguments named `names`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn get_script_fn_with_named_args(
+        &self,
+        name: &str,
+        num_positional: usize,
+        names: &[&str],
+    ) -> Option<&Shared<crate::ast::ScriptFnDef>> {
+        // Functions in the same module never overlap in the numbers of arguments they accept
+        self.get_script_fn(name, num_positional + names.len())
+            .filter(|f| f.map_named_args(num_positional, names).is_some())
     }
 
     /// Get a mutable reference to the underlying [`BTreeMap`] of sub-modules.
@@ -1150,6 +1252,8 @@ impl Module {
         self.functions.extend(other.functions.into_iter());
         self.type_iterators.extend(other.type_iterators.into_iter());
         self.all_functions.clear();
+        #[cfg(not(feature = "no_function"))]
+        self.index_script_fn_arities();
         self.all_variables.clear();
         self.all_type_iterators.clear();
         self.indexed = false;
@@ -1169,6 +1273,8 @@ impl Module {
         self.functions.extend(other.functions.into_iter());
         self.type_iterators.extend(other.type_iterators.into_iter());
         self.all_functions.clear();
+        #[cfg(not(feature = "no_function"))]
+        self.index_script_fn_arities();
         self.all_variables.clear();
         self.all_type_iterators.clear();
         self.indexed = false;
@@ -1193,6 +1299,8 @@ impl Module {
         other.functions.iter().for_each(|(&k, v)| {
             self.functions.entry(k).or_insert_with(|| v.clone());
         });
+        #[cfg(not(feature = "no_function"))]
+        self.index_script_fn_arities();
         other.type_iterators.iter().for_each(|(&k, &v)| {
             self.type_iterators.entry(k).or_insert(v);
         });
@@ -1243,6 +1351,8 @@ impl Module {
                 })
                 .map(|(&k, v)| (k, v.clone())),
         );
+        #[cfg(not(feature = "no_function"))]
+        self.index_script_fn_arities();
 
         self.type_iterators.extend(other.type_iterators.iter());
         self.all_functions.clear();
@@ -1270,6 +1380,8 @@ impl Module {
                 }
             })
             .collect();
+        #[cfg(not(feature = "no_function"))]
+        self.index_script_fn_arities();
 
         self.all_functions.clear();
         self.all_variables.clear();
@@ -1,10 +1,9 @@
 //! Module implementing the [`AST`] optimizer.
 
-use crate::ast::{Expr, OpAssignment, Stmt};
+use crate::ast::{Expr, FnCallExpr, OpAssignment, Stmt, SwitchCases};
 use crate::dynamic::AccessMode;
 use crate::engine::{KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_PRINT, KEYWORD_TYPE_OF};
 use crate::fn_builtin::get_builtin_binary_op_fn;
-use crate::fn_hash::get_hasher;
 use crate::token::Token;
 use crate::{
     calc_fn_hash, calc_fn_params_hash, combine_hashes, Dynamic, Engine, FnPtr, ImmutableString,
@@ -12,11 +11,7 @@ use crate::{
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
-use std::{
-    any::TypeId,
-    hash::{Hash, Hasher},
-    mem,
-};
+use std::{any::TypeId, hash::Hash, mem};
 
 /// Level of optimization performed.
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
@@ -107,16 +102,15 @@ impl<'a> OptimizerState<'a> {
             return None;
         }
 
-        self.variables.iter().rev().find_map(|(n, access, value)| {
-            if n == name {
-                match access {
-                    AccessMode::ReadWrite => None,
-                    AccessMode::ReadOnly => value.as_ref(),
-                }
-            } else {
-                None
-            }
-        })
+        // The latest declaration shadows all previous ones
+        self.variables
+            .iter()
+            .rev()
+            .find(|(n, _, _)| n == name)
+            .and_then(|(_, access, value)| match access {
+                AccessMode::ReadWrite => None,
+                AccessMode::ReadOnly => value.as_ref(),
+            })
     }
 }
 
@@ -206,7 +200,7 @@ fn optimize_stmt_block(
 
                     if value_expr.is_constant() {
                         state.push_var(
-                            &x.name,
+                            &x.0.name,
                             AccessMode::ReadOnly,
                             value_expr.get_literal_value(),
                         );
@@ -215,7 +209,12 @@ fn optimize_stmt_block(
                 // Add variables into the state
                 Stmt::Let(value_expr, x, _, _) => {
                     optimize_expr(value_expr, state, false);
-                    state.push_var(&x.name, AccessMode::ReadWrite, None);
+                    state.push_var(&x.0.name, AccessMode::ReadWrite, None);
+                }
+                // Add destructured variables into the state
+                Stmt::Destructure(value_expr, pattern, access, _, _) => {
+                    optimize_expr(value_expr, state, false);
+                    pattern.for_each_var(&mut |v| state.push_var(&v.name, *access, None));
                 }
                 // Optimize the statement
                 _ => optimize_stmt(stmt, state, preserve_result),
@@ -462,94 +461,122 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
         // switch const { ... }
         Stmt::Switch(match_expr, x, pos) if match_expr.is_constant() => {
             let value = match_expr.get_literal_value().unwrap();
-            let hasher = &mut get_hasher();
-            value.hash(hasher);
-            let hash = hasher.finish();
+            let match_pos = match_expr.position();
 
             state.set_dirty();
-            let table = &mut x.0;
 
-            if let Some(block) = table.get_mut(&hash) {
-                if let Some(mut condition) = mem::take(&mut block.0) {
-                    // switch const { case if condition => stmt, _ => def } => if condition { stmt } else { def }
-                    optimize_expr(&mut condition, state, false);
-
-                    let def_block = mem::take(&mut *x.1).into_vec();
-                    let def_stmt = optimize_stmt_block(def_block, state, true, true, false);
-                    let def_pos = if x.1.position().is_none() {
-                        *pos
-                    } else {
-                        x.1.position()
-                    };
-
-                    *stmt = Stmt::If(
-                        condition,
-                        Box::new((
-                            mem::take(&mut block.1),
-                            Stmt::Block(def_stmt.into_boxed_slice(), def_pos).into(),
-                        )),
-                        match_expr.position(),
-                    );
-                } else {
-                    // Promote the matched case
-                    let new_pos = block.1.position();
-                    let statements = mem::take(&mut *block.1);
-                    let statements =
-                        optimize_stmt_block(statements.into_vec(), state, true, true, false);
-                    *stmt = Stmt::Block(statements.into_boxed_slice(), new_pos);
-                }
-            } else {
-                // Promote the default case
-                let def_block = mem::take(&mut *x.1).into_vec();
-                let def_stmt = optimize_stmt_block(def_block, state, true, true, false);
-                let def_pos = if x.1.position().is_none() {
-                    *pos
-                } else {
-                    x.1.position()
+            // Collect all the cases that can match the value, in order of evaluation
+            let mut cases: StaticVec<_> = x
+                .candidates(&value)
+                .into_iter()
+                .map(|index| mem::take(&mut x.blocks[index]))
+                .collect();
+
+            // Only the cases up to the first unconditional one are reachable
+            let mut def_stmt: Stmt =
+                match cases.iter().position(|(condition, _)| condition.is_none()) {
+                    Some(index) => {
+                        cases.truncate(index + 1);
+                        let (_, block) = cases
+                            .pop()
+                            .expect("never fails because the unconditional case is kept");
+                        block.into()
+                    }
+                    None => {
+                        let def_pos = if x.def_case.position().is_none() {
+                            *pos
+                        } else {
+                            x.def_case.position()
+                        };
+                        Stmt::Block(
+                            mem::take(x.def_case.statements_mut())
+                                .into_vec()
+                                .into_boxed_slice(),
+                            def_pos,
+                        )
+                    }
                 };
-                *stmt = Stmt::Block(def_stmt.into_boxed_slice(), def_pos);
+
+            // switch const { case if condition => stmt, _ => def } => if condition { stmt } else { def }
+            while let Some((condition, block)) = cases.pop() {
+                let condition = condition.expect(
+                    "never fails because only the cases before the first unconditional one are kept",
+                );
+                def_stmt = Stmt::If(condition, Box::new((block, def_stmt.into())), match_pos);
             }
+
+            *stmt = def_stmt;
+            optimize_stmt(stmt, state, preserve_result);
         }
         // switch
         Stmt::Switch(match_expr, x, _) => {
             optimize_expr(match_expr, state, false);
-            x.0.values_mut().for_each(|block| {
-                let condition = mem::take(&mut block.0).map_or_else(
-                    || Expr::Unit(Position::NONE),
-                    |mut condition| {
-                        optimize_expr(&mut condition, state, false);
-                        condition
-                    },
-                );
 
-                match condition {
-                    Expr::Unit(_) | Expr::BoolConstant(true, _) => (),
-                    _ => {
-                        block.0 = Some(condition);
-
-                        *block.1.statements_mut() = optimize_stmt_block(
-                            mem::take(block.1.statements_mut()).into_vec(),
-                            state,
-                            preserve_result,
-                            true,
-                            false,
-                        )
-                        .into();
+            let SwitchCases {
+                blocks,
+                cases,
+                ranges,
+                conditions,
+                variants,
+                def_case,
+            } = x.as_mut();
+
+            for (index, (condition, block)) in blocks.iter_mut().enumerate() {
+                // Variables bound to the fields of a variant shadow any constants
+                let orig_variables_len = state.variables.len();
+
+                variants
+                    .iter()
+                    .filter(|p| p.index == index)
+                    .flat_map(|p| p.bindings())
+                    .for_each(|name| state.push_var(name, AccessMode::ReadWrite, None));
+
+                if let Some(mut expr) = mem::take(condition) {
+                    optimize_expr(&mut expr, state, false);
+
+                    match expr {
+                        // Always true - remove the condition
+                        Expr::BoolConstant(true, _) => state.set_dirty(),
+                        // Always false - the case can never match
+                        Expr::BoolConstant(false, _) => {
+                            if conditions.contains(&index)
+                                || ranges.iter().any(|&(_, _, _, i)| i == index)
+                                || variants.iter().any(|p| p.index == index)
+                                || cases.values().any(|v| v.contains(&index))
+                            {
+                                state.set_dirty();
+                                conditions.retain(|&mut i| i != index);
+                                ranges.retain(|&mut (_, _, _, i)| i != index);
+                                variants.retain(|p| p.index != index);
+                                cases
+                                    .values_mut()
+                                    .for_each(|v| v.retain(|&mut i| i != index));
+                                cases.retain(|_, v| !v.is_empty());
+                            }
+
+                            *condition = Some(expr);
+                            *block = Default::default();
+                            state.restore_var(orig_variables_len);
+                            continue;
+                        }
+                        _ => *condition = Some(expr),
                     }
                 }
-            });
 
-            // Remove false cases
-            while let Some((&key, _)) = x.0.iter().find(|(_, block)| match block.0 {
-                Some(Expr::BoolConstant(false, _)) => true,
-                _ => false,
-            }) {
-                state.set_dirty();
-                x.0.remove(&key);
+                *block.statements_mut() = optimize_stmt_block(
+                    mem::take(block.statements_mut()).into_vec(),
+                    state,
+                    preserve_result,
+                    true,
+                    false,
+                )
+                .into();
+
+                state.restore_var(orig_variables_len);
             }
 
-            let def_block = mem::take(x.1.statements_mut()).into_vec();
-            *x.1.statements_mut() =
+            let def_block = mem::take(def_case.statements_mut()).into_vec();
+            *def_case.statements_mut() =
                 optimize_stmt_block(def_block, state, preserve_result, true, false).into();
         }
 
@@ -608,7 +635,9 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
             *x.2.statements_mut() = optimize_stmt_block(body, state, false, true, false).into();
         }
         // let id = expr;
-        Stmt::Let(expr, _, _, _) => optimize_expr(expr, state, false),
+        Stmt::Let(expr, _, _, _) | Stmt::Destructure(expr, _, _, _, _) => {
+            optimize_expr(expr, state, false)
+        }
         // import expr as var;
         #[cfg(not(feature = "no_module"))]
         Stmt::Import(expr, _, _) => optimize_expr(expr, state, false),
@@ -622,8 +651,8 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                     state.set_dirty();
                     *stmt = Stmt::Noop(*pos);
                 }
-                // Only one statement - promote
-                [s] => {
+                // Only one statement which does not declare variables - promote
+                [s] if !s.is_variable_declaration() => {
                     state.set_dirty();
                     *stmt = mem::take(s);
                 }
@@ -631,24 +660,36 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
             }
         }
         // try { pure try_block } catch ( var ) { catch_block } -> try_block
-        Stmt::TryCatch(x, _) if x.0.iter().all(Stmt::is_pure) => {
+        Stmt::TryCatch(x, _)
+            if x.try_block.iter().all(Stmt::is_pure) && x.finally_block.is_empty() =>
+        {
             // If try block is pure, there will never be any exceptions
             state.set_dirty();
-            let try_pos = x.0.position();
-            let try_block = mem::take(&mut *x.0).into_vec();
+            let try_pos = x.try_block.position();
+            let try_block = mem::take(&mut *x.try_block).into_vec();
             *stmt = Stmt::Block(
                 optimize_stmt_block(try_block, state, false, true, false).into_boxed_slice(),
                 try_pos,
             );
         }
-        // try { try_block } catch ( var ) { catch_block }
+        // try { try_block } catch ( var ) if condition { catch_block } ... finally { finally_block }
         Stmt::TryCatch(x, _) => {
-            let try_block = mem::take(x.0.statements_mut()).into_vec();
-            *x.0.statements_mut() =
+            let try_block = mem::take(x.try_block.statements_mut()).into_vec();
+            *x.try_block.statements_mut() =
                 optimize_stmt_block(try_block, state, false, true, false).into();
-            let catch_block = mem::take(x.2.statements_mut()).into_vec();
-            *x.2.statements_mut() =
-                optimize_stmt_block(catch_block, state, false, true, false).into();
+
+            x.catch_clauses.iter_mut().for_each(|c| {
+                if let Some(ref mut condition) = c.condition {
+                    optimize_expr(condition, state, false);
+                }
+                let catch_block = mem::take(c.body.statements_mut()).into_vec();
+                *c.body.statements_mut() =
+                    optimize_stmt_block(catch_block, state, false, true, false).into();
+            });
+
+            let finally_block = mem::take(x.finally_block.statements_mut()).into_vec();
+            *x.finally_block.statements_mut() =
+                optimize_stmt_block(finally_block, state, false, true, false).into();
         }
         // func(...)
         Stmt::Expr(expr @ Expr::FnCall(_, _)) => {
@@ -681,6 +722,42 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
     }
 }
 
+/// Optimize the arguments of a function call, moving constant arguments.
+fn optimize_fn_call_args(x: &mut FnCallExpr, state: &mut OptimizerState) {
+    for arg in x.args.iter_mut() {
+        optimize_expr(arg, state, false);
+
+        // Move constant arguments
+        if let Some(value) = arg.get_literal_value() {
+            state.set_dirty();
+            x.constants.push(value);
+            *arg = Expr::Stack(x.constants.len() - 1, arg.position());
+        }
+    }
+}
+
+/// Optimize the right-hand side of a dot expression.
+///
+/// Method calls are never evaluated eagerly, as their objects are not among their arguments.
+#[cfg(not(feature = "no_object"))]
+fn optimize_dot_rhs(expr: &mut Expr, state: &mut OptimizerState) {
+    match expr {
+        // .method(args ..)
+        Expr::FnCall(x, _) => optimize_fn_call_args(x, state),
+        // .lhs.rhs
+        Expr::Dot(x, _, _) => {
+            optimize_dot_rhs(&mut x.lhs, state);
+            optimize_dot_rhs(&mut x.rhs, state);
+        }
+        // .lhs[rhs]
+        Expr::Index(x, _, _) => {
+            optimize_dot_rhs(&mut x.lhs, state);
+            optimize_expr(&mut x.rhs, state, true);
+        }
+        _ => optimize_expr(expr, state, true),
+    }
+}
+
 /// Optimize an [expression][Expr].
 fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
     // These keywords are handled specially
@@ -705,7 +782,7 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
         }
         // lhs.rhs
         #[cfg(not(feature = "no_object"))]
-        Expr::Dot(x, _) if !_chaining => match (&mut x.lhs, &mut x.rhs) {
+        Expr::Dot(x, _, _) if !_chaining => match (&mut x.lhs, &mut x.rhs) {
             // map.string
             (Expr::Map(m, pos), Expr::Property(p)) if m.0.iter().all(|(_, x)| x.is_pure()) => {
                 let prop = p.2.0.as_str();
@@ -717,17 +794,17 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
                             .unwrap_or_else(|| Expr::Unit(*pos));
             }
             // var.rhs
-            (Expr::Variable(_, _, _), rhs) => optimize_expr(rhs, state, true),
+            (Expr::Variable(_, _, _), rhs) => optimize_dot_rhs(rhs, state),
             // lhs.rhs
-            (lhs, rhs) => { optimize_expr(lhs, state, false); optimize_expr(rhs, state, true); }
+            (lhs, rhs) => { optimize_expr(lhs, state, false); optimize_dot_rhs(rhs, state); }
         }
         // ....lhs.rhs
         #[cfg(not(feature = "no_object"))]
-        Expr::Dot(x, _) => { optimize_expr(&mut x.lhs, state, false); optimize_expr(&mut x.rhs, state, _chaining); }
+        Expr::Dot(x, _, _) => { optimize_expr(&mut x.lhs, state, false); optimize_dot_rhs(&mut x.rhs, state); }
 
         // lhs[rhs]
         #[cfg(not(feature = "no_index"))]
-        Expr::Index(x, _) if !_chaining => match (&mut x.lhs, &mut x.rhs) {
+        Expr::Index(x, _, _) if !_chaining => match (&mut x.lhs, &mut x.rhs) {
             // array[int]
             (Expr::Array(a, pos), Expr::IntegerConstant(i, _))
                 if *i >= 0 && (*i as usize) < a.len() && a.iter().all(Expr::is_pure) =>
@@ -791,7 +868,7 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
         },
         // ...[lhs][rhs]
         #[cfg(not(feature = "no_index"))]
-        Expr::Index(x, _) => { optimize_expr(&mut x.lhs, state, false); optimize_expr(&mut x.rhs, state, _chaining); }
+        Expr::Index(x, _, _) => { optimize_expr(&mut x.lhs, state, false); optimize_expr(&mut x.rhs, state, _chaining); }
         // ``
         Expr::InterpolatedString(x, pos) if x.is_empty() => {
             state.set_dirty();
@@ -907,6 +984,22 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
             // lhs || rhs
             (lhs, rhs) => { optimize_expr(lhs, state, false); optimize_expr(rhs, state, false); }
         },
+        // lhs ?? rhs
+        Expr::Coalesce(x, _) => match (&mut x.lhs, &mut x.rhs) {
+            // () ?? rhs -> rhs
+            (Expr::Unit(_), rhs) => {
+                state.set_dirty();
+                optimize_expr(rhs, state, false);
+                *expr = mem::take(rhs);
+            }
+            // constant ?? rhs -> constant
+            (lhs, _) if lhs.get_literal_value().is_some() => {
+                state.set_dirty();
+                *expr = mem::take(lhs);
+            }
+            // lhs ?? rhs
+            (lhs, rhs) => { optimize_expr(lhs, state, false); optimize_expr(rhs, state, false); }
+        },
 
         // eval!
         Expr::FnCall(x, _) if x.name == KEYWORD_EVAL => {
@@ -914,7 +1007,8 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
         }
         // Fn
         Expr::FnCall(x, pos)
-            if !x.is_qualified() // Non-qualified
+            if !_chaining // not a method call
+            && !x.is_qualified() // Non-qualified
             && state.optimization_level == OptimizationLevel::Simple // simple optimizations
             && x.args.len() == 1
             && x.args[0].is_constant()
@@ -942,7 +1036,8 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
 
         // Call built-in operators
         Expr::FnCall(x, pos)
-                if !x.is_qualified() // Non-qualified
+                if !_chaining // not a method call
+                && !x.is_qualified() // Non-qualified
                 && state.optimization_level == OptimizationLevel::Simple // simple optimizations
                 && x.args.len() == 2 // binary call
                 && x.args.iter().all(Expr::is_constant) // all arguments are constants
@@ -986,8 +1081,10 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
 
         // Eagerly call functions
         Expr::FnCall(x, pos)
-                if !x.is_qualified() // Non-qualified
+                if !_chaining // not a method call
+                && !x.is_qualified() // Non-qualified
                 && state.optimization_level == OptimizationLevel::Full // full optimizations
+                && x.named_args.is_empty() // no named arguments
                 && x.args.iter().all(Expr::is_constant) // all arguments are constants
         => {
             // First search for script-defined functions (can override built-in)
@@ -1029,16 +1126,7 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
         }
 
         // id(args ..) -> optimize function call arguments
-        Expr::FnCall(x, _) => for arg in x.args.iter_mut() {
-            optimize_expr(arg, state, false);
-
-            // Move constant arguments
-            if let Some(value) = arg.get_literal_value() {
-                state.set_dirty();
-                x.constants.push(value);
-                *arg = Expr::Stack(x.constants.len()-1, arg.position());
-            }
-        },
+        Expr::FnCall(x, _) => optimize_fn_call_args(x, state),
 
         // constant-name
         Expr::Variable(_, pos, x) if x.1.is_none() && state.find_constant(&x.2).is_some() => {
@@ -1049,6 +1137,13 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
             state.set_dirty();
         }
 
+        // ...expr
+        Expr::Spread(x, _) => optimize_expr(x, state, false),
+
+        // Variant(args ...)
+        #[cfg(not(feature = "no_function"))]
+        Expr::EnumVariant(x, _) => x.2.iter_mut().for_each(|expr| optimize_expr(expr, state, false)),
+
         // Custom syntax
         Expr::Custom(x, _) => {
             if x.scope_may_be_changed {
@@ -1121,6 +1216,11 @@ pub fn optimize_into_ast(
                     access: fn_def.access,
                     body: Default::default(),
                     params: fn_def.params.clone(),
+                    param_types: fn_def.param_types.clone(),
+                    return_type: fn_def.return_type.clone(),
+                    defaults: fn_def.defaults.clone(),
+                    variadic: fn_def.variadic,
+                    is_generator: fn_def.is_generator,
                     #[cfg(not(feature = "no_closure"))]
                     externals: fn_def.externals.clone(),
                     lib: None,
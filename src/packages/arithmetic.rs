@@ -1,7 +1,8 @@
 #![allow(non_snake_case)]
 
 use crate::plugin::*;
-use crate::{def_package, EvalAltResult, Position, INT};
+use crate::{def_package, EvalAltResult, OverflowMode, Position, INT};
+use std::convert::TryFrom;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -19,94 +20,155 @@ macro_rules! gen_arithmetic_functions {
         pub mod $root { $(pub mod $arg_type {
             use super::super::*;
 
+            /// Raise a number to a power, wrapping around on overflow.
+            pub fn wrapping_power(x: $arg_type, y: INT) -> $arg_type {
+                match u32::try_from(y) {
+                    Ok(n) => x.wrapping_pow(n),
+                    // Exponentiation by squaring for indices beyond `u32`
+                    Err(_) => {
+                        let (mut base, mut n, mut result): ($arg_type, INT, $arg_type) = (x, y, 1);
+                        while n > 0 {
+                            if n & 1 == 1 {
+                                result = result.wrapping_mul(base);
+                            }
+                            base = base.wrapping_mul(base);
+                            n >>= 1;
+                        }
+                        result
+                    }
+                }
+            }
+            /// Raise a number to a power, saturating at the numeric bounds on overflow.
+            pub fn saturating_power(x: $arg_type, y: INT) -> $arg_type {
+                // Any index this large saturates, so only its parity matters
+                x.saturating_pow(u32::try_from(y).unwrap_or(if y & 1 == 0 { u32::MAX - 1 } else { u32::MAX }))
+            }
+            /// Shift a number left, saturating at the numeric bounds when bits are shifted out.
+            pub fn saturating_shift_left(x: $arg_type, y: INT) -> $arg_type {
+                match u32::try_from(y).ok().filter(|&n| n < <$arg_type>::BITS) {
+                    Some(n) if (x << n) >> n == x => x << n,
+                    // Keep the sign of the number
+                    _ => x.saturating_mul(<$arg_type>::MAX).saturating_mul(2),
+                }
+            }
+            /// Shift a number right, leaving only its sign when all bits are shifted out.
+            pub fn saturating_shift_right(x: $arg_type, y: INT) -> $arg_type {
+                match u32::try_from(y).ok().filter(|&n| n < <$arg_type>::BITS) {
+                    Some(n) => x >> n,
+                    None => x >> (<$arg_type>::BITS - 1) >> 1,
+                }
+            }
+
             #[export_module]
             pub mod functions {
                 #[rhai_fn(name = "+", return_raw)]
-                pub fn add(x: $arg_type, y: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_add(y).ok_or_else(|| make_err(format!("Addition overflow: {} + {}", x, y)))
-                    } else {
-                        Ok(x + y)
+                pub fn add(ctx: NativeCallContext, x: $arg_type, y: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
+                    match ctx.engine().overflow_mode() {
+                        OverflowMode::Wrapping => Ok(x.wrapping_add(y)),
+                        OverflowMode::Saturating => Ok(x.saturating_add(y)),
+                        OverflowMode::Checked if cfg!(not(feature = "unchecked")) => {
+                            x.checked_add(y).ok_or_else(|| make_err(format!("Addition overflow: {} + {}", x, y)))
+                        }
+                        OverflowMode::Checked => Ok(x + y),
                     }
                 }
                 #[rhai_fn(name = "-", return_raw)]
-                pub fn subtract(x: $arg_type, y: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_sub(y).ok_or_else(|| make_err(format!("Subtraction overflow: {} - {}", x, y)))
-                    } else {
-                        Ok(x - y)
+                pub fn subtract(ctx: NativeCallContext, x: $arg_type, y: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
+                    match ctx.engine().overflow_mode() {
+                        OverflowMode::Wrapping => Ok(x.wrapping_sub(y)),
+                        OverflowMode::Saturating => Ok(x.saturating_sub(y)),
+                        OverflowMode::Checked if cfg!(not(feature = "unchecked")) => {
+                            x.checked_sub(y).ok_or_else(|| make_err(format!("Subtraction overflow: {} - {}", x, y)))
+                        }
+                        OverflowMode::Checked => Ok(x - y),
                     }
                 }
                 #[rhai_fn(name = "*", return_raw)]
-                pub fn multiply(x: $arg_type, y: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_mul(y).ok_or_else(|| make_err(format!("Multiplication overflow: {} * {}", x, y)))
-                    } else {
-                        Ok(x * y)
+                pub fn multiply(ctx: NativeCallContext, x: $arg_type, y: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
+                    match ctx.engine().overflow_mode() {
+                        OverflowMode::Wrapping => Ok(x.wrapping_mul(y)),
+                        OverflowMode::Saturating => Ok(x.saturating_mul(y)),
+                        OverflowMode::Checked if cfg!(not(feature = "unchecked")) => {
+                            x.checked_mul(y).ok_or_else(|| make_err(format!("Multiplication overflow: {} * {}", x, y)))
+                        }
+                        OverflowMode::Checked => Ok(x * y),
                     }
                 }
                 #[rhai_fn(name = "/", return_raw)]
-                pub fn divide(x: $arg_type, y: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
-                    if cfg!(not(feature = "unchecked")) {
-                        // Detect division by zero
-                        if y == 0 {
-                            Err(make_err(format!("Division by zero: {} / {}", x, y)))
-                        } else {
+                pub fn divide(ctx: NativeCallContext, x: $arg_type, y: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
+                    // Detect division by zero
+                    if cfg!(not(feature = "unchecked")) && y == 0 {
+                        return Err(make_err(format!("Division by zero: {} / {}", x, y)));
+                    }
+
+                    match ctx.engine().overflow_mode() {
+                        OverflowMode::Wrapping => Ok(x.wrapping_div(y)),
+                        OverflowMode::Saturating => Ok(x.saturating_div(y)),
+                        OverflowMode::Checked if cfg!(not(feature = "unchecked")) => {
                             x.checked_div(y).ok_or_else(|| make_err(format!("Division overflow: {} / {}", x, y)))
                         }
-                    } else {
-                        Ok(x / y)
+                        OverflowMode::Checked => Ok(x / y),
                     }
                 }
                 #[rhai_fn(name = "%", return_raw)]
-                pub fn modulo(x: $arg_type, y: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_rem(y).ok_or_else(|| make_err(format!("Modulo division by zero or overflow: {} % {}", x, y)))
-                    } else {
-                        Ok(x % y)
+                pub fn modulo(ctx: NativeCallContext, x: $arg_type, y: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
+                    match ctx.engine().overflow_mode() {
+                        OverflowMode::Checked if cfg!(not(feature = "unchecked")) => {
+                            x.checked_rem(y).ok_or_else(|| make_err(format!("Modulo division by zero or overflow: {} % {}", x, y)))
+                        }
+                        OverflowMode::Checked => Ok(x % y),
+                        _ if cfg!(not(feature = "unchecked")) && y == 0 => {
+                            Err(make_err(format!("Modulo division by zero: {} % {}", x, y)))
+                        }
+                        // The only overflow, `MIN % -1`, is zero
+                        OverflowMode::Wrapping | OverflowMode::Saturating => Ok(x.wrapping_rem(y)),
                     }
                 }
                 #[rhai_fn(name = "**", return_raw)]
-                pub fn power(x: $arg_type, y: INT) -> Result<$arg_type, Box<EvalAltResult>> {
-                    if cfg!(not(feature = "unchecked")) {
-                        if cfg!(not(feature = "only_i32")) && y > (u32::MAX as INT) {
-                            Err(make_err(format!("Integer raised to too large an index: {} ~ {}", x, y)))
-                        } else if y < 0 {
-                            Err(make_err(format!("Integer raised to a negative index: {} ~ {}", x, y)))
-                        } else {
-                            x.checked_pow(y as u32).ok_or_else(|| make_err(format!("Exponential overflow: {} ~ {}", x, y)))
-                        }
-                    } else {
-                        Ok(x.pow(y as u32))
+                pub fn power(ctx: NativeCallContext, x: $arg_type, y: INT) -> Result<$arg_type, Box<EvalAltResult>> {
+                    if cfg!(not(feature = "unchecked")) && y < 0 {
+                        return Err(make_err(format!("Integer raised to a negative index: {} ~ {}", x, y)));
+                    }
+
+                    match ctx.engine().overflow_mode() {
+                        OverflowMode::Wrapping => Ok(super::wrapping_power(x, y)),
+                        OverflowMode::Saturating => Ok(super::saturating_power(x, y)),
+                        OverflowMode::Checked if cfg!(not(feature = "unchecked")) => match u32::try_from(y) {
+                            Ok(n) => x.checked_pow(n).ok_or_else(|| make_err(format!("Exponential overflow: {} ~ {}", x, y))),
+                            Err(_) => Err(make_err(format!("Integer raised to too large an index: {} ~ {}", x, y))),
+                        },
+                        OverflowMode::Checked => Ok(x.pow(y as u32)),
                     }
                 }
 
                 #[rhai_fn(name = "<<", return_raw)]
-                pub fn shift_left(x: $arg_type, y: INT) -> Result<$arg_type, Box<EvalAltResult>> {
-                    if cfg!(not(feature = "unchecked")) {
-                        if cfg!(not(feature = "only_i32")) && y > (u32::MAX as INT) {
-                            Err(make_err(format!("Left-shift by too many bits: {} << {}", x, y)))
-                        } else if y < 0 {
-                            Err(make_err(format!("Left-shift by a negative number: {} << {}", x, y)))
-                        } else {
-                            x.checked_shl(y as u32).ok_or_else(|| make_err(format!("Left-shift by too many bits: {} << {}", x, y)))
+                pub fn shift_left(ctx: NativeCallContext, x: $arg_type, y: INT) -> Result<$arg_type, Box<EvalAltResult>> {
+                    if cfg!(not(feature = "unchecked")) && y < 0 {
+                        return Err(make_err(format!("Left-shift by a negative number: {} << {}", x, y)));
+                    }
+
+                    match ctx.engine().overflow_mode() {
+                        OverflowMode::Wrapping => Ok(x.wrapping_shl(y as u32)),
+                        OverflowMode::Saturating => Ok(super::saturating_shift_left(x, y)),
+                        OverflowMode::Checked if cfg!(not(feature = "unchecked")) => {
+                            u32::try_from(y).ok().and_then(|n| x.checked_shl(n)).ok_or_else(|| make_err(format!("Left-shift by too many bits: {} << {}", x, y)))
                         }
-                    } else {
-                        Ok(x << y)
+                        OverflowMode::Checked => Ok(x << y),
                     }
                 }
                 #[rhai_fn(name = ">>", return_raw)]
-                pub fn shift_right(x: $arg_type, y: INT) -> Result<$arg_type, Box<EvalAltResult>> {
-                    if cfg!(not(feature = "unchecked")) {
-                        if cfg!(not(feature = "only_i32")) && y > (u32::MAX as INT) {
-                            Err(make_err(format!("Right-shift by too many bits: {} >> {}", x, y)))
-                        } else if y < 0 {
-                            Err(make_err(format!("Right-shift by a negative number: {} >> {}", x, y)))
-                        } else {
-                            x.checked_shr(y as u32).ok_or_else(|| make_err(format!("Right-shift by too many bits: {} >> {}", x, y)))
+                pub fn shift_right(ctx: NativeCallContext, x: $arg_type, y: INT) -> Result<$arg_type, Box<EvalAltResult>> {
+                    if cfg!(not(feature = "unchecked")) && y < 0 {
+                        return Err(make_err(format!("Right-shift by a negative number: {} >> {}", x, y)));
+                    }
+
+                    match ctx.engine().overflow_mode() {
+                        OverflowMode::Wrapping => Ok(x.wrapping_shr(y as u32)),
+                        OverflowMode::Saturating => Ok(super::saturating_shift_right(x, y)),
+                        OverflowMode::Checked if cfg!(not(feature = "unchecked")) => {
+                            u32::try_from(y).ok().and_then(|n| x.checked_shr(n)).ok_or_else(|| make_err(format!("Right-shift by too many bits: {} >> {}", x, y)))
                         }
-                    } else {
-                        Ok(x >> y)
+                        OverflowMode::Checked => Ok(x >> y),
                     }
                 }
                 #[rhai_fn(name = "&")]
@@ -143,11 +205,14 @@ macro_rules! gen_signed_functions {
             #[export_module]
             pub mod functions {
                 #[rhai_fn(name = "-", return_raw)]
-                pub fn neg(x: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_neg().ok_or_else(|| make_err(format!("Negation overflow: -{}", x)))
-                    } else {
-                        Ok(-x)
+                pub fn neg(ctx: NativeCallContext, x: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
+                    match ctx.engine().overflow_mode() {
+                        OverflowMode::Wrapping => Ok(x.wrapping_neg()),
+                        OverflowMode::Saturating => Ok(x.saturating_neg()),
+                        OverflowMode::Checked if cfg!(not(feature = "unchecked")) => {
+                            x.checked_neg().ok_or_else(|| make_err(format!("Negation overflow: -{}", x)))
+                        }
+                        OverflowMode::Checked => Ok(-x),
                     }
                 }
                 #[rhai_fn(name = "+")]
@@ -155,11 +220,14 @@ macro_rules! gen_signed_functions {
                     x
                 }
                 #[rhai_fn(return_raw)]
-                pub fn abs(x: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_abs().ok_or_else(|| make_err(format!("Negation overflow: -{}", x)))
-                    } else {
-                        Ok(x.abs())
+                pub fn abs(ctx: NativeCallContext, x: $arg_type) -> Result<$arg_type, Box<EvalAltResult>> {
+                    match ctx.engine().overflow_mode() {
+                        OverflowMode::Wrapping => Ok(x.wrapping_abs()),
+                        OverflowMode::Saturating => Ok(x.saturating_abs()),
+                        OverflowMode::Checked if cfg!(not(feature = "unchecked")) => {
+                            x.checked_abs().ok_or_else(|| make_err(format!("Negation overflow: -{}", x)))
+                        }
+                        OverflowMode::Checked => Ok(x.abs()),
                     }
                 }
                 pub fn sign(x: $arg_type) -> INT {
@@ -184,6 +252,7 @@ macro_rules! reg_functions {
 
 def_package!(crate:ArithmeticPackage:"Basic arithmetic", lib, {
     combine_with_exported_module!(lib, "int", int_functions);
+    combine_with_exported_module!(lib, "overflow", overflow_functions);
     reg_functions!(lib += signed_basic; INT);
 
     #[cfg(not(feature = "only_i32"))]
@@ -209,6 +278,10 @@ def_package!(crate:ArithmeticPackage:"Basic arithmetic", lib, {
     // Decimal functions
     #[cfg(feature = "decimal")]
     combine_with_exported_module!(lib, "decimal", decimal_functions);
+
+    // Big integer functions
+    #[cfg(feature = "bigint")]
+    combine_with_exported_module!(lib, "bigint", bigint_functions);
 });
 
 #[export_module]
@@ -224,6 +297,162 @@ mod int_functions {
     }
 }
 
+/// Check that an integer is not negative when used as an exponent or a shift.
+#[inline]
+fn check_non_negative(x: INT, y: INT, op: &str) -> Result<INT, Box<EvalAltResult>> {
+    if y < 0 {
+        Err(make_err(format!(
+            "Negative number not allowed: {} {} {}",
+            x, op, y
+        )))
+    } else {
+        Ok(y)
+    }
+}
+
+/// Check that a divisor is not zero.
+#[inline]
+fn check_divisor(x: INT, y: INT, op: &str) -> Result<INT, Box<EvalAltResult>> {
+    if y == 0 {
+        Err(make_err(format!("Division by zero: {} {} {}", x, op, y)))
+    } else {
+        Ok(y)
+    }
+}
+
+#[export_module]
+mod overflow_functions {
+    use super::arith_basic::INT::{
+        saturating_power, saturating_shift_left, saturating_shift_right, wrapping_power,
+    };
+
+    /// Add two integers, returning `()` on overflow.
+    pub fn checked_add(x: INT, y: INT) -> Dynamic {
+        x.checked_add(y).map_or(Dynamic::UNIT, Into::into)
+    }
+    /// Subtract two integers, returning `()` on overflow.
+    pub fn checked_sub(x: INT, y: INT) -> Dynamic {
+        x.checked_sub(y).map_or(Dynamic::UNIT, Into::into)
+    }
+    /// Multiply two integers, returning `()` on overflow.
+    pub fn checked_mul(x: INT, y: INT) -> Dynamic {
+        x.checked_mul(y).map_or(Dynamic::UNIT, Into::into)
+    }
+    /// Divide two integers, returning `()` on overflow or division by zero.
+    pub fn checked_div(x: INT, y: INT) -> Dynamic {
+        x.checked_div(y).map_or(Dynamic::UNIT, Into::into)
+    }
+    /// Calculate the remainder of two integers, returning `()` on overflow or division by zero.
+    pub fn checked_rem(x: INT, y: INT) -> Dynamic {
+        x.checked_rem(y).map_or(Dynamic::UNIT, Into::into)
+    }
+    /// Raise an integer to a power, returning `()` on overflow.
+    #[rhai_fn(return_raw)]
+    pub fn checked_pow(x: INT, y: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        let y = check_non_negative(x, y, "~")?;
+
+        Ok(match u32::try_from(y) {
+            Ok(n) => x.checked_pow(n),
+            // Only `0`, `1` and `-1` can be raised to such a large index
+            Err(_) if (-1..=1).contains(&x) => Some(saturating_power(x, y)),
+            Err(_) => None,
+        }
+        .map_or(Dynamic::UNIT, Into::into))
+    }
+    /// Shift an integer left, returning `()` when shifting by too many bits.
+    #[rhai_fn(return_raw)]
+    pub fn checked_shl(x: INT, y: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        let y = check_non_negative(x, y, "<<")?;
+        Ok(u32::try_from(y)
+            .ok()
+            .and_then(|n| x.checked_shl(n))
+            .map_or(Dynamic::UNIT, Into::into))
+    }
+    /// Shift an integer right, returning `()` when shifting by too many bits.
+    #[rhai_fn(return_raw)]
+    pub fn checked_shr(x: INT, y: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        let y = check_non_negative(x, y, ">>")?;
+        Ok(u32::try_from(y)
+            .ok()
+            .and_then(|n| x.checked_shr(n))
+            .map_or(Dynamic::UNIT, Into::into))
+    }
+    /// Negate an integer, returning `()` on overflow.
+    pub fn checked_neg(x: INT) -> Dynamic {
+        x.checked_neg().map_or(Dynamic::UNIT, Into::into)
+    }
+    /// Return the absolute value of an integer, returning `()` on overflow.
+    pub fn checked_abs(x: INT) -> Dynamic {
+        x.checked_abs().map_or(Dynamic::UNIT, Into::into)
+    }
+    pub fn wrapping_add(x: INT, y: INT) -> INT {
+        x.wrapping_add(y)
+    }
+    pub fn wrapping_sub(x: INT, y: INT) -> INT {
+        x.wrapping_sub(y)
+    }
+    pub fn wrapping_mul(x: INT, y: INT) -> INT {
+        x.wrapping_mul(y)
+    }
+    #[rhai_fn(return_raw)]
+    pub fn wrapping_div(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+        check_divisor(x, y, "/").map(|y| x.wrapping_div(y))
+    }
+    #[rhai_fn(return_raw)]
+    pub fn wrapping_rem(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+        check_divisor(x, y, "%").map(|y| x.wrapping_rem(y))
+    }
+    #[rhai_fn(return_raw)]
+    pub fn wrapping_pow(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+        check_non_negative(x, y, "~").map(|y| wrapping_power(x, y))
+    }
+    #[rhai_fn(return_raw)]
+    pub fn wrapping_shl(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+        check_non_negative(x, y, "<<").map(|y| x.wrapping_shl(y as u32))
+    }
+    #[rhai_fn(return_raw)]
+    pub fn wrapping_shr(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+        check_non_negative(x, y, ">>").map(|y| x.wrapping_shr(y as u32))
+    }
+    pub fn wrapping_neg(x: INT) -> INT {
+        x.wrapping_neg()
+    }
+    pub fn wrapping_abs(x: INT) -> INT {
+        x.wrapping_abs()
+    }
+    pub fn saturating_add(x: INT, y: INT) -> INT {
+        x.saturating_add(y)
+    }
+    pub fn saturating_sub(x: INT, y: INT) -> INT {
+        x.saturating_sub(y)
+    }
+    pub fn saturating_mul(x: INT, y: INT) -> INT {
+        x.saturating_mul(y)
+    }
+    #[rhai_fn(return_raw)]
+    pub fn saturating_div(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+        check_divisor(x, y, "/").map(|y| x.saturating_div(y))
+    }
+    #[rhai_fn(return_raw)]
+    pub fn saturating_pow(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+        check_non_negative(x, y, "~").map(|y| saturating_power(x, y))
+    }
+    #[rhai_fn(return_raw)]
+    pub fn saturating_shl(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+        check_non_negative(x, y, "<<").map(|y| saturating_shift_left(x, y))
+    }
+    #[rhai_fn(return_raw)]
+    pub fn saturating_shr(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+        check_non_negative(x, y, ">>").map(|y| saturating_shift_right(x, y))
+    }
+    pub fn saturating_neg(x: INT) -> INT {
+        x.saturating_neg()
+    }
+    pub fn saturating_abs(x: INT) -> INT {
+        x.saturating_abs()
+    }
+}
+
 gen_arithmetic_functions!(arith_basic => INT);
 
 #[cfg(not(feature = "only_i32"))]
@@ -544,3 +773,112 @@ pub mod decimal_functions {
         x.is_zero()
     }
 }
+
+#[cfg(feature = "bigint")]
+#[export_module]
+pub mod bigint_functions {
+    use num_bigint::{BigInt, Sign};
+    use num_traits::{Signed, ToPrimitive, Zero};
+
+    #[rhai_fn(skip, return_raw)]
+    pub fn add(x: BigInt, y: BigInt) -> Result<BigInt, Box<EvalAltResult>> {
+        Ok(x + y)
+    }
+    #[rhai_fn(skip, return_raw)]
+    pub fn subtract(x: BigInt, y: BigInt) -> Result<BigInt, Box<EvalAltResult>> {
+        Ok(x - y)
+    }
+    #[rhai_fn(skip, return_raw)]
+    pub fn multiply(x: BigInt, y: BigInt) -> Result<BigInt, Box<EvalAltResult>> {
+        Ok(x * y)
+    }
+    #[rhai_fn(skip, return_raw)]
+    pub fn divide(x: BigInt, y: BigInt) -> Result<BigInt, Box<EvalAltResult>> {
+        // Division by zero panics even under `unchecked`
+        if y.is_zero() {
+            Err(make_err(format!("Division by zero: {} / {}", x, y)))
+        } else {
+            Ok(x / y)
+        }
+    }
+    #[rhai_fn(skip, return_raw)]
+    pub fn modulo(x: BigInt, y: BigInt) -> Result<BigInt, Box<EvalAltResult>> {
+        // Division by zero panics even under `unchecked`
+        if y.is_zero() {
+            Err(make_err(format!("Modulo division by zero: {} % {}", x, y)))
+        } else {
+            Ok(x % y)
+        }
+    }
+    #[rhai_fn(skip, return_raw)]
+    pub fn power(x: BigInt, y: BigInt) -> Result<BigInt, Box<EvalAltResult>> {
+        if y.is_negative() {
+            Err(make_err(format!(
+                "Integer raised to a negative index: {} ~ {}",
+                x, y
+            )))
+        } else if let Some(n) = y.to_u32() {
+            Ok(x.pow(n))
+        } else {
+            Err(make_err(format!(
+                "Integer raised to too large an index: {} ~ {}",
+                x, y
+            )))
+        }
+    }
+    #[rhai_fn(skip, return_raw)]
+    pub fn shift_left(x: BigInt, y: BigInt) -> Result<BigInt, Box<EvalAltResult>> {
+        if y.is_negative() {
+            Err(make_err(format!(
+                "Left-shift by a negative number: {} << {}",
+                x, y
+            )))
+        } else if let Some(n) = y.to_u32() {
+            Ok(x << n)
+        } else {
+            Err(make_err(format!(
+                "Left-shift by too many bits: {} << {}",
+                x, y
+            )))
+        }
+    }
+    #[rhai_fn(skip, return_raw)]
+    pub fn shift_right(x: BigInt, y: BigInt) -> Result<BigInt, Box<EvalAltResult>> {
+        if y.is_negative() {
+            Err(make_err(format!(
+                "Right-shift by a negative number: {} >> {}",
+                x, y
+            )))
+        } else {
+            // Shifting out all bits leaves only the sign
+            Ok(x >> y.to_usize().unwrap_or(usize::MAX))
+        }
+    }
+    #[rhai_fn(name = "-")]
+    pub fn neg(x: BigInt) -> BigInt {
+        -x
+    }
+    #[rhai_fn(name = "+")]
+    pub fn plus(x: BigInt) -> BigInt {
+        x
+    }
+    pub fn abs(x: BigInt) -> BigInt {
+        x.abs()
+    }
+    pub fn sign(x: BigInt) -> INT {
+        match x.sign() {
+            Sign::Minus => -1,
+            Sign::NoSign => 0,
+            Sign::Plus => 1,
+        }
+    }
+    pub fn is_zero(x: BigInt) -> bool {
+        x.is_zero()
+    }
+    pub fn is_odd(x: BigInt) -> bool {
+        x.bit(0)
+    }
+    pub fn is_even(x: BigInt) -> bool {
+        !x.bit(0)
+    }
+}
@@ -0,0 +1,416 @@
+#![cfg(not(feature = "no_index"))]
+#![allow(non_snake_case)]
+
+use crate::plugin::*;
+use crate::{def_package, Array, Blob, Dynamic, EvalAltResult, NativeCallContext, Position, INT};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{any::TypeId, mem};
+
+#[cfg(not(feature = "no_float"))]
+use crate::FLOAT;
+
+const INT_BYTES: usize = mem::size_of::<INT>();
+
+#[cfg(not(feature = "no_float"))]
+const FLOAT_BYTES: usize = mem::size_of::<FLOAT>();
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+def_package!(crate:BasicBlobPackage:"Basic BLOB utilities.", lib, {
+    combine_with_exported_module!(lib, "blob", blob_functions);
+
+    // Register blob iterator
+    lib.set_iter(TypeId::of::<Blob>(), |blob| {
+        Box::new(blob.cast::<Blob>().into_iter().map(|b| (b as INT).into()))
+    });
+});
+
+/// Calculate the actual byte range within a BLOB of a starting position and a length.
+///
+/// A negative starting position counts from the end of the BLOB.
+/// Returns [`None`] if the range is empty.
+fn calc_range(blob_len: usize, start: INT, len: INT) -> Option<(usize, usize)> {
+    let start = if start < 0 {
+        start
+            .checked_abs()
+            .map_or(0, |n| blob_len - (n as usize).min(blob_len))
+    } else if start as usize >= blob_len {
+        return None;
+    } else {
+        start as usize
+    };
+
+    if len <= 0 {
+        None
+    } else if len as usize > blob_len - start {
+        Some((start, blob_len - start))
+    } else {
+        Some((start, len as usize))
+    }
+}
+
+/// Resize a [`Blob`], filling new bytes with a value and checking the maximum array size.
+fn resize_blob(
+    _ctx: &NativeCallContext,
+    blob: &mut Blob,
+    len: INT,
+    value: INT,
+) -> Result<(), Box<EvalAltResult>> {
+    let len = if len < 0 { 0 } else { len as usize };
+
+    // Check if blob will be over max size limit
+    #[cfg(not(feature = "unchecked"))]
+    if _ctx.engine().max_array_size() > 0 && len > _ctx.engine().max_array_size() {
+        return EvalAltResult::ErrorDataTooLarge("Size of BLOB".to_string(), Position::NONE).into();
+    }
+
+    blob.resize(len, (value & 0x00ff) as u8);
+    Ok(())
+}
+
+#[export_module]
+mod blob_functions {
+    use super::{calc_range, resize_blob, BASE64_CHARS, INT_BYTES};
+
+    #[cfg(not(feature = "no_float"))]
+    use super::FLOAT_BYTES;
+
+    #[rhai_fn(name = "blob")]
+    pub fn new_blob() -> Blob {
+        Blob::new()
+    }
+    #[rhai_fn(name = "blob", return_raw)]
+    pub fn new_blob_with_len(ctx: NativeCallContext, len: INT) -> Result<Blob, Box<EvalAltResult>> {
+        new_blob_with_value(ctx, len, 0)
+    }
+    #[rhai_fn(name = "blob", return_raw)]
+    pub fn new_blob_with_value(
+        ctx: NativeCallContext,
+        len: INT,
+        value: INT,
+    ) -> Result<Blob, Box<EvalAltResult>> {
+        let mut blob = Blob::new();
+        resize_blob(&ctx, &mut blob, len, value)?;
+        Ok(blob)
+    }
+    #[rhai_fn(name = "len", get = "len", pure)]
+    pub fn len(blob: &mut Blob) -> INT {
+        blob.len() as INT
+    }
+    #[rhai_fn(name = "push", name = "+=")]
+    pub fn push(blob: &mut Blob, item: INT) {
+        blob.push((item & 0x00ff) as u8);
+    }
+    #[rhai_fn(name = "append", name = "+=")]
+    pub fn append(blob: &mut Blob, y: Blob) {
+        if !y.is_empty() {
+            if blob.is_empty() {
+                *blob = y;
+            } else {
+                blob.extend(y);
+            }
+        }
+    }
+    #[rhai_fn(name = "+")]
+    pub fn concat(mut blob: Blob, y: Blob) -> Blob {
+        append(&mut blob, y);
+        blob
+    }
+    pub fn insert(blob: &mut Blob, position: INT, item: INT) {
+        let item = (item & 0x00ff) as u8;
+
+        if position < 0 {
+            let n = position.checked_abs().map_or(usize::MAX, |n| n as usize);
+            blob.insert(blob.len() - n.min(blob.len()), item);
+        } else if (position as usize) >= blob.len() {
+            blob.push(item);
+        } else {
+            blob.insert(position as usize, item);
+        }
+    }
+    #[rhai_fn(return_raw)]
+    pub fn pad(
+        ctx: NativeCallContext,
+        blob: &mut Blob,
+        len: INT,
+        item: INT,
+    ) -> Result<(), Box<EvalAltResult>> {
+        if len > 0 && len as usize > blob.len() {
+            resize_blob(&ctx, blob, len, item)
+        } else {
+            Ok(())
+        }
+    }
+    pub fn pop(blob: &mut Blob) -> INT {
+        blob.pop().map_or(0, |b| b as INT)
+    }
+    pub fn shift(blob: &mut Blob) -> INT {
+        if blob.is_empty() {
+            0
+        } else {
+            blob.remove(0) as INT
+        }
+    }
+    pub fn remove(blob: &mut Blob, len: INT) -> INT {
+        if len < 0 || (len as usize) >= blob.len() {
+            0
+        } else {
+            blob.remove(len as usize) as INT
+        }
+    }
+    pub fn clear(blob: &mut Blob) {
+        blob.clear();
+    }
+    pub fn truncate(blob: &mut Blob, len: INT) {
+        if len >= 0 {
+            blob.truncate(len as usize);
+        } else {
+            blob.clear();
+        }
+    }
+    pub fn chop(blob: &mut Blob, len: INT) {
+        if len as usize >= blob.len() {
+        } else if len >= 0 {
+            blob.drain(0..blob.len() - len as usize);
+        } else {
+            blob.clear();
+        }
+    }
+    pub fn reverse(blob: &mut Blob) {
+        blob.reverse();
+    }
+    pub fn contains(blob: &mut Blob, value: INT) -> bool {
+        (0..=255).contains(&value) && blob.contains(&(value as u8))
+    }
+    pub fn splice(blob: &mut Blob, start: INT, len: INT, replace: Blob) {
+        let start = if start < 0 {
+            let blob_len = blob.len();
+            start
+                .checked_abs()
+                .map_or(0, |n| blob_len - (n as usize).min(blob_len))
+        } else if start as usize >= blob.len() {
+            blob.extend(replace);
+            return;
+        } else {
+            start as usize
+        };
+
+        let len = if len < 0 {
+            0
+        } else if len as usize > blob.len() - start {
+            blob.len() - start
+        } else {
+            len as usize
+        };
+
+        blob.splice(start..start + len, replace);
+    }
+    pub fn extract(blob: &mut Blob, start: INT, len: INT) -> Blob {
+        calc_range(blob.len(), start, len)
+            .map_or_else(Blob::new, |(start, len)| blob[start..start + len].to_vec())
+    }
+    #[rhai_fn(name = "extract")]
+    pub fn extract_tail(blob: &mut Blob, start: INT) -> Blob {
+        extract(blob, start, INT::MAX)
+    }
+    #[rhai_fn(name = "split")]
+    pub fn split_at(blob: &mut Blob, start: INT) -> Blob {
+        calc_range(blob.len(), start, INT::MAX)
+            .map_or_else(Blob::new, |(start, _)| blob.split_off(start))
+    }
+    #[rhai_fn(name = "==", pure)]
+    pub fn equals(blob: &mut Blob, blob2: Blob) -> bool {
+        *blob == blob2
+    }
+    #[rhai_fn(name = "!=", pure)]
+    pub fn not_equals(blob: &mut Blob, blob2: Blob) -> bool {
+        *blob != blob2
+    }
+    #[rhai_fn(pure)]
+    pub fn to_array(blob: &mut Blob) -> Array {
+        blob.iter().map(|&b| (b as INT).into()).collect()
+    }
+
+    #[rhai_fn(name = "to_hex", pure)]
+    pub fn to_hex(blob: &mut Blob) -> ImmutableString {
+        let mut s = String::with_capacity(blob.len() * 2);
+        blob.iter().for_each(|b| s.push_str(&format!("{:02x}", b)));
+        s.into()
+    }
+    #[rhai_fn(return_raw)]
+    pub fn from_hex(s: &str) -> Result<Blob, Box<EvalAltResult>> {
+        let s = s.trim();
+
+        if s.len() % 2 != 0 {
+            return EvalAltResult::ErrorArithmetic(
+                format!("Odd number of hex digits: '{}'", s),
+                Position::NONE,
+            )
+            .into();
+        }
+
+        (0..s.len())
+            .step_by(2)
+            .map(|i| {
+                s.get(i..i + 2)
+                    .and_then(|x| u8::from_str_radix(x, 16).ok())
+                    .ok_or_else(|| {
+                        EvalAltResult::ErrorArithmetic(
+                            format!("Invalid hex string: '{}'", s),
+                            Position::NONE,
+                        )
+                        .into()
+                    })
+            })
+            .collect()
+    }
+    #[rhai_fn(pure)]
+    pub fn to_base64(blob: &mut Blob) -> ImmutableString {
+        let mut s = String::with_capacity(blob.len() * 4 / 3 + 4);
+
+        for chunk in blob.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0_u32, |n, (i, &b)| n | (b as u32) << (16 - i * 8));
+
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    s.push(BASE64_CHARS[(n >> (18 - i * 6)) as usize & 0x3f] as char);
+                } else {
+                    s.push('=');
+                }
+            }
+        }
+
+        s.into()
+    }
+    #[rhai_fn(return_raw)]
+    pub fn from_base64(s: &str) -> Result<Blob, Box<EvalAltResult>> {
+        let make_err = || {
+            EvalAltResult::ErrorArithmetic(
+                format!("Invalid base64 string: '{}'", s),
+                Position::NONE,
+            )
+            .into()
+        };
+
+        let data = s.trim().trim_end_matches('=').as_bytes();
+
+        if data.len() % 4 == 1 {
+            return Err(make_err());
+        }
+
+        let mut blob = Blob::with_capacity(data.len() * 3 / 4);
+
+        for chunk in data.chunks(4) {
+            let mut n = 0_u32;
+
+            for (i, ch) in chunk.iter().enumerate() {
+                let x = BASE64_CHARS
+                    .iter()
+                    .position(|c| c == ch)
+                    .ok_or_else(make_err)?;
+                n |= (x as u32) << (18 - i * 6);
+            }
+
+            for i in 0..chunk.len() - 1 {
+                blob.push((n >> (16 - i * 8)) as u8);
+            }
+        }
+
+        Ok(blob)
+    }
+
+    #[rhai_fn(pure)]
+    pub fn as_string(blob: &mut Blob) -> ImmutableString {
+        String::from_utf8_lossy(blob).into_owned().into()
+    }
+    #[rhai_fn(name = "to_blob")]
+    pub fn string_to_blob(s: &str) -> Blob {
+        s.as_bytes().to_vec()
+    }
+    pub fn write_utf8(blob: &mut Blob, start: INT, len: INT, s: &str) {
+        if let Some((start, len)) = calc_range(blob.len(), start, len) {
+            let len = len.min(s.len());
+            blob[start..start + len].copy_from_slice(&s.as_bytes()[..len]);
+        }
+    }
+
+    pub fn parse_le_int(blob: &mut Blob, start: INT, len: INT) -> INT {
+        let mut buf = [0_u8; INT_BYTES];
+
+        if let Some((start, len)) = calc_range(blob.len(), start, len) {
+            let len = len.min(INT_BYTES);
+            buf[..len].copy_from_slice(&blob[start..start + len]);
+        }
+
+        INT::from_le_bytes(buf)
+    }
+    pub fn parse_be_int(blob: &mut Blob, start: INT, len: INT) -> INT {
+        let mut buf = [0_u8; INT_BYTES];
+
+        if let Some((start, len)) = calc_range(blob.len(), start, len) {
+            let len = len.min(INT_BYTES);
+            buf[INT_BYTES - len..].copy_from_slice(&blob[start..start + len]);
+        }
+
+        INT::from_be_bytes(buf)
+    }
+    #[rhai_fn(name = "write_le")]
+    pub fn write_le_int(blob: &mut Blob, start: INT, len: INT, value: INT) {
+        if let Some((start, len)) = calc_range(blob.len(), start, len) {
+            let len = len.min(INT_BYTES);
+            blob[start..start + len].copy_from_slice(&value.to_le_bytes()[..len]);
+        }
+    }
+    #[rhai_fn(name = "write_be")]
+    pub fn write_be_int(blob: &mut Blob, start: INT, len: INT, value: INT) {
+        if let Some((start, len)) = calc_range(blob.len(), start, len) {
+            let len = len.min(INT_BYTES);
+            blob[start..start + len].copy_from_slice(&value.to_be_bytes()[INT_BYTES - len..]);
+        }
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    pub mod float_functions {
+        use super::{calc_range, FLOAT_BYTES};
+        use crate::{Blob, FLOAT, INT};
+
+        pub fn parse_le_float(blob: &mut Blob, start: INT, len: INT) -> FLOAT {
+            let mut buf = [0_u8; FLOAT_BYTES];
+
+            if let Some((start, len)) = calc_range(blob.len(), start, len) {
+                let len = len.min(FLOAT_BYTES);
+                buf[..len].copy_from_slice(&blob[start..start + len]);
+            }
+
+            FLOAT::from_le_bytes(buf)
+        }
+        pub fn parse_be_float(blob: &mut Blob, start: INT, len: INT) -> FLOAT {
+            let mut buf = [0_u8; FLOAT_BYTES];
+
+            if let Some((start, len)) = calc_range(blob.len(), start, len) {
+                let len = len.min(FLOAT_BYTES);
+                buf[FLOAT_BYTES - len..].copy_from_slice(&blob[start..start + len]);
+            }
+
+            FLOAT::from_be_bytes(buf)
+        }
+        #[rhai_fn(name = "write_le")]
+        pub fn write_le_float(blob: &mut Blob, start: INT, len: INT, value: FLOAT) {
+            if let Some((start, len)) = calc_range(blob.len(), start, len) {
+                let len = len.min(FLOAT_BYTES);
+                blob[start..start + len].copy_from_slice(&value.to_le_bytes()[..len]);
+            }
+        }
+        #[rhai_fn(name = "write_be")]
+        pub fn write_be_float(blob: &mut Blob, start: INT, len: INT, value: FLOAT) {
+            if let Some((start, len)) = calc_range(blob.len(), start, len) {
+                let len = len.min(FLOAT_BYTES);
+                blob[start..start + len].copy_from_slice(&value.to_be_bytes()[FLOAT_BYTES - len..]);
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+#![cfg(not(feature = "no_object"))]
+#![allow(non_snake_case)]
+
+use crate::engine::OP_EQUALS;
+use crate::plugin::*;
+use crate::{def_package, Dict, Dynamic, EvalAltResult, Map, Position, INT};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+#[cfg(not(feature = "no_index"))]
+use crate::Array;
+
+def_package!(crate:BasicDictPackage:"Basic dictionary utilities.", lib, {
+    combine_with_exported_module!(lib, "dict", dict_functions);
+});
+
+/// Set the value of a key in a [`Dict`], raising an error if the key cannot be hashed.
+#[cfg(not(feature = "no_index"))]
+fn insert_entry(dict: &mut Dict, key: Dynamic, value: Dynamic) -> Result<(), Box<EvalAltResult>> {
+    dict.insert(key, value).map(|_| ()).map_err(|typ| {
+        EvalAltResult::ErrorMismatchDataType(
+            "a hashable value".to_string(),
+            typ.to_string(),
+            Position::NONE,
+        )
+        .into()
+    })
+}
+
+#[export_module]
+mod dict_functions {
+    #[cfg(not(feature = "no_index"))]
+    use super::insert_entry;
+
+    #[rhai_fn(name = "dict")]
+    pub fn new_dict() -> Dict {
+        Dict::new()
+    }
+    #[rhai_fn(name = "dict")]
+    pub fn new_dict_from_map(map: Map) -> Dict {
+        map.into_iter()
+            .map(|(k, v)| (k.as_str().into(), v))
+            .collect()
+    }
+    #[rhai_fn(name = "len", get = "len", pure)]
+    pub fn len(dict: &mut Dict) -> INT {
+        dict.len() as INT
+    }
+    #[rhai_fn(name = "contains", name = "has", pure)]
+    pub fn contains(dict: &mut Dict, key: Dynamic) -> bool {
+        dict.contains_key(&key)
+    }
+    pub fn remove(dict: &mut Dict, key: Dynamic) -> Dynamic {
+        dict.remove(&key).unwrap_or_else(|| ().into())
+    }
+    pub fn clear(dict: &mut Dict) {
+        dict.clear();
+    }
+    #[rhai_fn(name = "mixin", name = "+=")]
+    pub fn mixin(dict: &mut Dict, dict2: Dict) {
+        dict.extend(dict2);
+    }
+    #[rhai_fn(name = "+")]
+    pub fn merge(mut dict: Dict, dict2: Dict) -> Dict {
+        dict.extend(dict2);
+        dict
+    }
+    #[rhai_fn(name = "==", return_raw, pure)]
+    pub fn equals(
+        ctx: NativeCallContext,
+        dict: &mut Dict,
+        mut dict2: Dict,
+    ) -> Result<bool, Box<EvalAltResult>> {
+        if dict.len() != dict2.len() {
+            return Ok(false);
+        }
+        if dict.is_empty() {
+            return Ok(true);
+        }
+
+        for (k, v1) in dict.iter() {
+            if let Some(v2) = dict2.get_mut(k) {
+                let equals = ctx
+                    .call_fn_dynamic_raw(OP_EQUALS, true, &mut [&mut v1.clone(), v2])
+                    .map(|v| v.as_bool().unwrap_or(false))?;
+
+                if !equals {
+                    return Ok(false);
+                }
+            } else {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+    #[rhai_fn(name = "!=", return_raw, pure)]
+    pub fn not_equals(
+        ctx: NativeCallContext,
+        dict: &mut Dict,
+        dict2: Dict,
+    ) -> Result<bool, Box<EvalAltResult>> {
+        equals(ctx, dict, dict2).map(|r| !r)
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    pub mod indexing {
+        #[rhai_fn(name = "dict", return_raw)]
+        pub fn new_dict_from_array(array: Array) -> Result<Dict, Box<EvalAltResult>> {
+            let mut dict = Dict::new();
+
+            for item in array {
+                let typ = item.type_name();
+                let pair = item.try_cast::<Array>().filter(|pair| pair.len() == 2);
+
+                match pair {
+                    Some(pair) => {
+                        let mut pair = pair.into_iter();
+                        let key = pair.next().expect("`pair` has two items");
+                        let value = pair.next().expect("`pair` has two items");
+                        insert_entry(&mut dict, key, value)?;
+                    }
+                    None => {
+                        return EvalAltResult::ErrorMismatchDataType(
+                            "a [key, value] pair".into(),
+                            typ.into(),
+                            Position::NONE,
+                        )
+                        .into()
+                    }
+                }
+            }
+
+            Ok(dict)
+        }
+        #[rhai_fn(pure)]
+        pub fn keys(dict: &mut Dict) -> Array {
+            dict.keys().cloned().collect()
+        }
+        #[rhai_fn(pure)]
+        pub fn values(dict: &mut Dict) -> Array {
+            dict.values().cloned().collect()
+        }
+    }
+}
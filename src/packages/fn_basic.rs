@@ -5,6 +5,12 @@ use std::prelude::v1::*;
 
 def_package!(crate:BasicFnPackage:"Basic Fn functions.", lib, {
     combine_with_exported_module!(lib, "FnPtr", fn_ptr_functions);
+
+    #[cfg(not(feature = "no_function"))]
+    combine_with_exported_module!(lib, "generator", generator_functions);
+
+    #[cfg(not(feature = "no_function"))]
+    combine_with_exported_module!(lib, "enum", enum_functions);
 });
 
 #[export_module]
@@ -32,6 +38,78 @@ mod fn_ptr_functions {
     }
 }
 
+#[cfg(not(feature = "no_function"))]
+#[export_module]
+mod generator_functions {
+    use crate::engine::EvalState;
+    use crate::{Generator, StaticVec};
+
+    /// Run the generator up to the next `yield` statement and return the value yielded,
+    /// or `()` if the generator has finished.
+    #[rhai_fn(return_raw)]
+    pub fn next(
+        ctx: NativeCallContext,
+        generator: &mut Generator,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        ctx.engine()
+            .resume_generator(
+                &mut EvalState::new(),
+                &ctx.iter_namespaces().collect::<StaticVec<_>>(),
+                generator,
+                Position::NONE,
+                0,
+            )
+            .map(Option::unwrap_or_default)
+    }
+    #[rhai_fn(name = "is_done", get = "is_done", pure)]
+    pub fn is_done(generator: &mut Generator) -> bool {
+        generator.is_done()
+    }
+}
+
+#[cfg(not(feature = "no_function"))]
+#[export_module]
+mod enum_functions {
+    use crate::engine::OP_EQUALS;
+    use crate::EnumValue;
+
+    /// Are two `enum` values the same variant of the same `enum`, with equal fields?
+    #[rhai_fn(name = "==", return_raw, pure)]
+    pub fn equals(
+        ctx: NativeCallContext,
+        value: &mut EnumValue,
+        mut value2: EnumValue,
+    ) -> Result<bool, Box<EvalAltResult>> {
+        if !value.is_same_variant(&value2) {
+            return Ok(false);
+        }
+
+        for (v1, v2) in value
+            .fields_mut()
+            .iter_mut()
+            .zip(value2.fields_mut().iter_mut())
+        {
+            let equals = ctx
+                .call_fn_dynamic_raw(OP_EQUALS, true, &mut [v1, v2])
+                .map(|v| v.as_bool().unwrap_or(false))?;
+
+            if !equals {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+    #[rhai_fn(name = "!=", return_raw, pure)]
+    pub fn not_equals(
+        ctx: NativeCallContext,
+        value: &mut EnumValue,
+        value2: EnumValue,
+    ) -> Result<bool, Box<EvalAltResult>> {
+        equals(ctx, value, value2).map(|r| !r)
+    }
+}
+
 #[cfg(not(feature = "no_function"))]
 #[cfg(not(feature = "no_index"))]
 #[cfg(not(feature = "no_object"))]
@@ -1,5 +1,6 @@
 use crate::dynamic::Variant;
-use crate::{def_package, EvalAltResult, INT};
+use crate::plugin::*;
+use crate::{def_package, EvalAltResult, ExclusiveRange, InclusiveRange, INT};
 use std::ops::Range;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -442,6 +443,14 @@ def_package!(crate:BasicIteratorPackage:"Basic range iterators.", lib, {
     #[cfg(feature = "metadata")]
     lib.update_fn_metadata(_hash, &["string: &str", "Iterator<Item=char>"]);
 
+    let _hash = lib.set_native_fn("chars_rev", |string| {
+        let mut stream = CharsStream::new(string, 0, INT::MAX);
+        stream.0.reverse();
+        Ok(stream)
+    });
+    #[cfg(feature = "metadata")]
+    lib.update_fn_metadata(_hash, &["string: &str", "Iterator<Item=char>"]);
+
     // Register bit-field iterator
     lib.set_iterator::<BitRange>();
 
@@ -456,4 +465,61 @@ def_package!(crate:BasicIteratorPackage:"Basic range iterators.", lib, {
     let _hash = lib.set_native_fn("bits", |value| BitRange::new(value, 0, INT::MAX));
     #[cfg(feature = "metadata")]
     lib.update_fn_metadata(_hash, &["value: INT", "Iterator<Item=bool>"]);
+
+    // Register inclusive range iterator and range functions
+    lib.set_iterator::<InclusiveRange>();
+
+    combine_with_exported_module!(lib, "range", range_functions);
 });
+
+#[export_module]
+mod range_functions {
+    #[rhai_fn(name = "..")]
+    pub fn exclusive_range(from: INT, to: INT) -> ExclusiveRange {
+        from..to
+    }
+    #[rhai_fn(name = "..=")]
+    pub fn inclusive_range(from: INT, to: INT) -> InclusiveRange {
+        from..=to
+    }
+    #[rhai_fn(get = "start", name = "start", pure)]
+    pub fn start(range: &mut ExclusiveRange) -> INT {
+        range.start
+    }
+    #[rhai_fn(get = "end", name = "end", pure)]
+    pub fn end(range: &mut ExclusiveRange) -> INT {
+        range.end
+    }
+    #[rhai_fn(get = "is_inclusive", name = "is_inclusive", pure)]
+    pub fn is_inclusive(_range: &mut ExclusiveRange) -> bool {
+        false
+    }
+    #[rhai_fn(get = "is_exclusive", name = "is_exclusive", pure)]
+    pub fn is_exclusive(_range: &mut ExclusiveRange) -> bool {
+        true
+    }
+    #[rhai_fn(name = "contains", pure)]
+    pub fn contains(range: &mut ExclusiveRange, value: INT) -> bool {
+        range.contains(&value)
+    }
+    #[rhai_fn(get = "start", name = "start", pure)]
+    pub fn start_inclusive(range: &mut InclusiveRange) -> INT {
+        *range.start()
+    }
+    #[rhai_fn(get = "end", name = "end", pure)]
+    pub fn end_inclusive(range: &mut InclusiveRange) -> INT {
+        *range.end()
+    }
+    #[rhai_fn(get = "is_inclusive", name = "is_inclusive", pure)]
+    pub fn is_inclusive_inclusive(_range: &mut InclusiveRange) -> bool {
+        true
+    }
+    #[rhai_fn(get = "is_exclusive", name = "is_exclusive", pure)]
+    pub fn is_exclusive_inclusive(_range: &mut InclusiveRange) -> bool {
+        false
+    }
+    #[rhai_fn(name = "contains", pure)]
+    pub fn contains_inclusive(range: &mut InclusiveRange, value: INT) -> bool {
+        range.contains(&value)
+    }
+}
@@ -27,7 +27,12 @@ mod map_functions {
         map.clear();
     }
     pub fn remove(map: &mut Map, name: ImmutableString) -> Dynamic {
-        map.remove(name.as_str()).unwrap_or_else(|| ().into())
+        #[cfg(not(feature = "preserve_order"))]
+        let value = map.remove(name.as_str());
+        #[cfg(feature = "preserve_order")]
+        let value = map.shift_remove(name.as_str());
+
+        value.unwrap_or_else(|| ().into())
     }
     #[rhai_fn(name = "mixin", name = "+=")]
     pub fn mixin(map: &mut Map, map2: Map) {
@@ -21,6 +21,9 @@ use rust_decimal::Decimal;
 #[cfg(feature = "decimal")]
 use super::arithmetic::make_err;
 
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+
 #[allow(dead_code)]
 #[cfg(feature = "only_i32")]
 pub const MAX_INT: INT = i32::MAX;
@@ -41,7 +44,7 @@ macro_rules! gen_conversion_as_functions {
     }
 }
 
-#[cfg(feature = "decimal")]
+#[cfg(any(feature = "decimal", feature = "bigint"))]
 macro_rules! gen_conversion_into_functions {
     ($root:ident => $func_name:ident ( $($arg_type:ident),+ ) -> $result_type:ty) => {
         pub mod $root { $(pub mod $arg_type {
@@ -107,6 +110,23 @@ def_package!(crate:BasicMathPackage:"Basic mathematic functions.", lib, {
         #[cfg(not(feature = "only_i64"))]
         reg_functions!(lib += numbers_to_decimal::to_decimal(i8, u8, i16, u16, i32, u32, i64, u64));
     }
+
+    // Big integer functions
+    #[cfg(feature = "bigint")]
+    {
+        combine_with_exported_module!(lib, "bigint", bigint_functions);
+
+        reg_functions!(lib += basic_to_bigint::to_bigint(INT));
+
+        #[cfg(not(feature = "only_i32"))]
+        #[cfg(not(feature = "only_i64"))]
+        {
+            reg_functions!(lib += numbers_to_bigint::to_bigint(i8, u8, i16, u16, i32, u32, i64, u64));
+
+            #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
+            reg_functions!(lib += num_128_to_bigint::to_bigint(i128, u128));
+        }
+    }
 });
 
 #[export_module]
@@ -476,6 +496,97 @@ mod decimal_functions {
     }
 }
 
+#[cfg(feature = "bigint")]
+#[export_module]
+mod bigint_functions {
+    use num_traits::{Num, ToPrimitive};
+
+    #[rhai_fn(return_raw)]
+    pub fn parse_bigint(s: &str) -> Result<BigInt, Box<EvalAltResult>> {
+        parse_bigint_radix(s, 10)
+    }
+    #[rhai_fn(name = "parse_bigint", return_raw)]
+    pub fn parse_bigint_radix(s: &str, radix: INT) -> Result<BigInt, Box<EvalAltResult>> {
+        if radix < 2 || radix > 36 {
+            return EvalAltResult::ErrorArithmetic(
+                format!("Invalid radix: '{}'", radix),
+                Position::NONE,
+            )
+            .into();
+        }
+
+        BigInt::from_str_radix(s.trim(), radix as u32).map_err(|err| {
+            EvalAltResult::ErrorArithmetic(
+                format!("Error parsing big integer '{}': {}", s, err),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+    #[rhai_fn(name = "to_int", return_raw)]
+    pub fn bigint_to_int(x: BigInt) -> Result<INT, Box<EvalAltResult>> {
+        #[cfg(not(feature = "only_i32"))]
+        let n = x.to_i64();
+        #[cfg(feature = "only_i32")]
+        let n = x.to_i32();
+
+        n.ok_or_else(|| {
+            EvalAltResult::ErrorArithmetic(
+                format!("Integer overflow: to_int({})", x),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    pub mod float {
+        use num_traits::FromPrimitive;
+
+        #[rhai_fn(name = "to_float")]
+        pub fn bigint_to_float(x: BigInt) -> FLOAT {
+            #[cfg(not(feature = "f32_float"))]
+            return x.to_f64().unwrap_or(FLOAT::NAN);
+            #[cfg(feature = "f32_float")]
+            return x.to_f32().unwrap_or(FLOAT::NAN);
+        }
+        #[rhai_fn(name = "to_bigint", return_raw)]
+        pub fn float_to_bigint(x: FLOAT) -> Result<BigInt, Box<EvalAltResult>> {
+            #[cfg(not(feature = "f32_float"))]
+            let n = BigInt::from_f64(x);
+            #[cfg(feature = "f32_float")]
+            let n = BigInt::from_f32(x);
+
+            n.ok_or_else(|| {
+                EvalAltResult::ErrorArithmetic(
+                    format!("Cannot convert to big integer: to_bigint({})", x),
+                    Position::NONE,
+                )
+                .into()
+            })
+        }
+    }
+
+    #[cfg(feature = "decimal")]
+    pub mod decimal {
+        use num_traits::FromPrimitive;
+
+        #[rhai_fn(name = "to_decimal", return_raw)]
+        pub fn bigint_to_decimal(x: BigInt) -> Result<Decimal, Box<EvalAltResult>> {
+            x.to_i128()
+                .and_then(Decimal::from_i128)
+                .ok_or_else(|| make_err(format!("Cannot convert to Decimal: to_decimal({})", x)))
+        }
+        #[rhai_fn(name = "to_bigint")]
+        pub fn decimal_to_bigint(x: Decimal) -> BigInt {
+            x.trunc()
+                .to_i128()
+                .map(BigInt::from)
+                .expect("never fails because a truncated Decimal fits into i128")
+        }
+    }
+}
+
 #[cfg(not(feature = "no_float"))]
 gen_conversion_as_functions!(basic_to_float => to_float (INT) -> FLOAT);
 
@@ -508,3 +619,17 @@ gen_conversion_into_functions!(basic_to_decimal => to_decimal (INT) -> Decimal);
 #[cfg(not(feature = "only_i32"))]
 #[cfg(not(feature = "only_i64"))]
 gen_conversion_into_functions!(numbers_to_decimal => to_decimal (i8, u8, i16, u16, i32, u32, i64, u64) -> Decimal);
+
+#[cfg(feature = "bigint")]
+gen_conversion_into_functions!(basic_to_bigint => to_bigint (INT) -> BigInt);
+
+#[cfg(feature = "bigint")]
+#[cfg(not(feature = "only_i32"))]
+#[cfg(not(feature = "only_i64"))]
+gen_conversion_into_functions!(numbers_to_bigint => to_bigint (i8, u8, i16, u16, i32, u32, i64, u64) -> BigInt);
+
+#[cfg(feature = "bigint")]
+#[cfg(not(feature = "only_i32"))]
+#[cfg(not(feature = "only_i64"))]
+#[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
+gen_conversion_into_functions!(num_128_to_bigint => to_bigint (i128, u128) -> BigInt);
@@ -4,6 +4,8 @@ use crate::{Module, Shared};
 
 pub(crate) mod arithmetic;
 mod array_basic;
+mod blob_basic;
+mod dict_basic;
 mod fn_basic;
 mod iter_basic;
 mod lang_core;
@@ -12,13 +14,20 @@ mod map_basic;
 mod math_basic;
 mod pkg_core;
 mod pkg_std;
-mod string_basic;
+pub(crate) mod random_basic;
+mod regex_basic;
+mod set_basic;
+pub(crate) mod string_basic;
 mod string_more;
 mod time_basic;
 
 pub use arithmetic::ArithmeticPackage;
 #[cfg(not(feature = "no_index"))]
 pub use array_basic::BasicArrayPackage;
+#[cfg(not(feature = "no_index"))]
+pub use blob_basic::BasicBlobPackage;
+#[cfg(not(feature = "no_object"))]
+pub use dict_basic::BasicDictPackage;
 pub use fn_basic::BasicFnPackage;
 pub use iter_basic::BasicIteratorPackage;
 pub use logic::LogicPackage;
@@ -27,6 +36,10 @@ pub use map_basic::BasicMapPackage;
 pub use math_basic::BasicMathPackage;
 pub use pkg_core::CorePackage;
 pub use pkg_std::StandardPackage;
+pub use random_basic::RandomPackage;
+pub use regex_basic::RegexPackage;
+#[cfg(not(feature = "no_index"))]
+pub use set_basic::BasicSetPackage;
 pub use string_basic::BasicStringPackage;
 pub use string_more::MoreStringPackage;
 #[cfg(not(feature = "no_std"))]
@@ -0,0 +1,204 @@
+#![allow(non_snake_case)]
+
+use super::arithmetic::make_err as make_arithmetic_err;
+use crate::plugin::*;
+use crate::{def_package, EvalAltResult, ExclusiveRange, InclusiveRange, INT};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+#[cfg(not(feature = "no_float"))]
+use crate::FLOAT;
+
+#[cfg(not(feature = "no_index"))]
+use crate::Array;
+
+def_package!(crate:RandomPackage:"Random number generation, seedable via `Engine::set_random_seed` or per evaluation via `Engine::eval_ast_with_scope_and_seed`.", lib, {
+    combine_with_exported_module!(lib, "random", random_functions);
+});
+
+/// A fast, non-cryptographic pseudo-random number generator (`xoshiro256**`).
+///
+/// The same seed always produces the same sequence of numbers, on all platforms.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub(crate) struct Rng([u64; 4]);
+
+impl Rng {
+    /// Create a generator from a seed, expanding it into the full state via `SplitMix64`.
+    #[must_use]
+    pub fn from_seed(seed: u64) -> Self {
+        let mut seed = seed;
+        let mut next = || {
+            seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        Self([next(), next(), next(), next()])
+    }
+    /// Create a generator from a random seed.
+    #[must_use]
+    pub fn from_entropy() -> Self {
+        use std::hash::{BuildHasher, Hasher};
+
+        let mut hasher = ahash::RandomState::new().build_hasher();
+        hasher.write_u64(0);
+        Self::from_seed(hasher.finish())
+    }
+    /// Get the next random 64-bit number.
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.0;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        result
+    }
+    /// Get a random number in `0..n`, without bias.
+    pub fn next_below(&mut self, n: u64) -> u64 {
+        // Lemire's multiply-and-reject method
+        let threshold = n.wrapping_neg() % n;
+
+        loop {
+            let m = self.next_u64() as u128 * n as u128;
+
+            if (m as u64) >= threshold {
+                return (m >> 64) as u64;
+            }
+        }
+    }
+    /// Get a random floating-point number in `0.0..1.0`.
+    #[cfg(not(feature = "no_float"))]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1_u64 << 53) as f64)
+    }
+}
+
+/// Run a function with the random number generator seeded for the current evaluation, if any,
+/// otherwise with that of the [`Engine`][crate::Engine], seeding it randomly upon first use if no seed has been set.
+fn with_rng<T>(ctx: &NativeCallContext, f: impl FnOnce(&mut Rng) -> T) -> T {
+    if let Some(rng) = ctx.rng() {
+        #[cfg(not(feature = "sync"))]
+        let mut rng = rng.borrow_mut();
+        #[cfg(feature = "sync")]
+        let mut rng = rng
+            .write()
+            .expect("never fails because the lock is never held while panicking");
+
+        return f(&mut rng);
+    }
+
+    #[cfg(not(feature = "sync"))]
+    let mut rng = ctx.engine().rng.borrow_mut();
+    #[cfg(feature = "sync")]
+    let mut rng = ctx
+        .engine()
+        .rng
+        .write()
+        .expect("never fails because the lock is never held while panicking");
+
+    f(rng.get_or_insert_with(Rng::from_entropy))
+}
+
+/// Get a random integer between `min` and `max` (both inclusive).
+fn rand_between(ctx: &NativeCallContext, min: INT, max: INT) -> Result<INT, Box<EvalAltResult>> {
+    if min > max {
+        return Err(make_arithmetic_err(format!(
+            "Invalid range for random number: {} to {}",
+            min, max
+        )));
+    }
+
+    let span = (max as i128 - min as i128) as u128 + 1;
+
+    Ok(with_rng(ctx, |rng| {
+        if span > u64::MAX as u128 {
+            rng.next_u64() as INT
+        } else {
+            (min as i128 + rng.next_below(span as u64) as i128) as INT
+        }
+    }))
+}
+
+#[export_module]
+mod random_functions {
+    use super::{rand_between, with_rng};
+
+    #[rhai_fn(name = "rand")]
+    pub fn rand(ctx: NativeCallContext) -> INT {
+        with_rng(&ctx, |rng| rng.next_u64() as INT)
+    }
+    #[rhai_fn(name = "rand", return_raw)]
+    pub fn rand_range(
+        ctx: NativeCallContext,
+        min: INT,
+        max: INT,
+    ) -> Result<INT, Box<EvalAltResult>> {
+        rand_between(&ctx, min, max)
+    }
+    #[rhai_fn(name = "rand", return_raw)]
+    pub fn rand_exclusive_range(
+        ctx: NativeCallContext,
+        range: ExclusiveRange,
+    ) -> Result<INT, Box<EvalAltResult>> {
+        if range.start >= range.end {
+            return Err(make_arithmetic_err(format!(
+                "Invalid range for random number: {}..{}",
+                range.start, range.end
+            )));
+        }
+        rand_between(&ctx, range.start, range.end - 1)
+    }
+    #[rhai_fn(name = "rand", return_raw)]
+    pub fn rand_inclusive_range(
+        ctx: NativeCallContext,
+        range: InclusiveRange,
+    ) -> Result<INT, Box<EvalAltResult>> {
+        rand_between(&ctx, *range.start(), *range.end())
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    pub mod float_functions {
+        pub fn rand_float(ctx: NativeCallContext) -> FLOAT {
+            with_rng(&ctx, |rng| rng.next_f64() as FLOAT)
+        }
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    pub mod array_functions {
+        pub fn shuffle(ctx: NativeCallContext, array: &mut Array) {
+            with_rng(&ctx, |rng| {
+                // Fisher-Yates shuffle
+                for i in (1..array.len()).rev() {
+                    let j = rng.next_below(i as u64 + 1) as usize;
+                    array.swap(i, j);
+                }
+            });
+        }
+        #[rhai_fn(pure)]
+        pub fn sample(ctx: NativeCallContext, array: &mut Array, size: INT) -> Array {
+            if size <= 0 || array.is_empty() {
+                return Array::new();
+            }
+
+            let size = (size as usize).min(array.len());
+            let mut indices: Vec<usize> = (0..array.len()).collect();
+
+            with_rng(&ctx, |rng| {
+                // Partial Fisher-Yates shuffle
+                for i in 0..size {
+                    let j = i + rng.next_below((indices.len() - i) as u64) as usize;
+                    indices.swap(i, j);
+                }
+            });
+
+            indices[..size].iter().map(|&i| array[i].clone()).collect()
+        }
+    }
+}
@@ -0,0 +1,312 @@
+#![allow(non_snake_case)]
+
+use crate::plugin::*;
+use crate::regex::{Captures, Regex, Steps, StepsExhausted};
+use crate::{def_package, EvalAltResult, ImmutableString, Position, Shared};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+#[cfg(not(feature = "no_index"))]
+use crate::{Array, INT};
+
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+use crate::Map;
+
+/// Expectation message for the entire match of a capture group list returned by a match.
+const MATCHED: &str = "never fails because the entire match is always captured";
+
+/// Maximum number of compiled patterns cached by an [`Engine`][crate::Engine].
+///
+/// When the cache is full, one pattern is evicted for each new pattern compiled.
+const MAX_CACHED_PATTERNS: usize = 256;
+
+def_package!(crate:RegexPackage:"Regular expression utilities.", lib, {
+    combine_with_exported_module!(lib, "regex", regex_functions);
+});
+
+/// Get a compiled pattern from the cache of the [`Engine`][crate::Engine], compiling it if
+/// necessary.
+fn get_regex(ctx: &NativeCallContext, pattern: &str) -> Result<Shared<Regex>, Box<EvalAltResult>> {
+    let engine = ctx.engine();
+
+    {
+        #[cfg(not(feature = "sync"))]
+        let cache = engine.regex_cache.borrow();
+        #[cfg(feature = "sync")]
+        let cache = engine
+            .regex_cache
+            .read()
+            .expect("never fails because the lock is never held while panicking");
+
+        if let Some(regex) = cache.get(pattern) {
+            return Ok(regex.clone());
+        }
+    }
+
+    let regex: Shared<Regex> = Regex::new(pattern)
+        .map_err(|err| format!("Invalid regular expression '{}': {}", pattern, err))?
+        .into();
+
+    #[cfg(not(feature = "sync"))]
+    let mut cache = engine.regex_cache.borrow_mut();
+    #[cfg(feature = "sync")]
+    let mut cache = engine
+        .regex_cache
+        .write()
+        .expect("never fails because the lock is never held while panicking");
+
+    // Make room by evicting a single pattern, which is compiled again if used later
+    if cache.len() >= MAX_CACHED_PATTERNS {
+        if let Some(key) = cache.keys().next().cloned() {
+            cache.remove(&key);
+        }
+    }
+    cache.insert(pattern.into(), regex.clone());
+
+    Ok(regex)
+}
+
+/// Run a match with a budget of steps drawn from the operations left to the current evaluation,
+/// counting the steps taken as operations.
+#[inline]
+fn with_steps<T>(
+    _ctx: &NativeCallContext,
+    f: impl FnOnce(&mut Steps) -> Result<T, StepsExhausted>,
+) -> Result<T, Box<EvalAltResult>> {
+    #[cfg(not(feature = "unchecked"))]
+    {
+        let max_operations = _ctx.engine().max_operations();
+        let max = if max_operations > 0 {
+            Some(max_operations.saturating_sub(_ctx.operations()))
+        } else {
+            None
+        };
+
+        let mut steps = Steps::new(max);
+        let result = f(&mut steps);
+        _ctx.add_operations(steps.taken());
+        return result.map_err(Into::into);
+    }
+    #[cfg(feature = "unchecked")]
+    return f(&mut Steps::new(None)).map_err(Into::into);
+}
+
+impl From<StepsExhausted> for Box<EvalAltResult> {
+    #[inline(always)]
+    fn from(_: StepsExhausted) -> Self {
+        EvalAltResult::ErrorTooManyOperations(Position::NONE).into()
+    }
+}
+
+/// Convert the capture groups of a match into an array, or into an object map if the pattern
+/// has named groups.
+#[cfg(not(feature = "no_index"))]
+fn captures_to_dynamic(regex: &Regex, text: &str, captures: &Captures) -> Dynamic {
+    let group = |i: usize| match (captures[i * 2], captures[i * 2 + 1]) {
+        (Some(start), Some(end)) => text[start..end].to_string().into(),
+        _ => Dynamic::UNIT,
+    };
+
+    #[cfg(not(feature = "no_object"))]
+    if regex.names().iter().any(Option::is_some) {
+        return regex
+            .names()
+            .iter()
+            .enumerate()
+            .map(|(i, name)| match name {
+                Some(name) => (name.clone(), group(i)),
+                None => (i.to_string().into(), group(i)),
+            })
+            .collect::<Map>()
+            .into();
+    }
+
+    (0..regex.names().len())
+        .map(group)
+        .collect::<Array>()
+        .into()
+}
+
+/// Expand group references (`$1`, `${1}`, `$name`, `${name}`) in a replacement string.
+fn expand_replacement(
+    regex: &Regex,
+    text: &str,
+    captures: &Captures,
+    replacement: &str,
+    output: &mut String,
+) {
+    let group = |name: &str| {
+        let index = match name.parse::<usize>() {
+            Ok(index) => Some(index),
+            Err(_) => regex
+                .names()
+                .iter()
+                .position(|n| n.as_deref() == Some(name)),
+        };
+        index
+            .filter(|&i| i < regex.names().len())
+            .and_then(|i| match (captures[i * 2], captures[i * 2 + 1]) {
+                (Some(start), Some(end)) => Some(&text[start..end]),
+                _ => None,
+            })
+            .unwrap_or("")
+    };
+
+    let mut rest = replacement;
+
+    while let Some(index) = rest.find('$') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+
+        if let Some(stripped) = rest.strip_prefix('$') {
+            output.push('$');
+            rest = stripped;
+        } else if let Some(name) = rest.strip_prefix('{').and_then(|r| r.split('}').next()) {
+            if rest[1 + name.len()..].starts_with('}') {
+                output.push_str(group(name));
+                rest = &rest[name.len() + 2..];
+            } else {
+                output.push('$');
+            }
+        } else {
+            let len = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+
+            if len == 0 {
+                output.push('$');
+            } else {
+                output.push_str(group(&rest[..len]));
+                rest = &rest[len..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+}
+
+#[export_module]
+mod regex_functions {
+    use super::{expand_replacement, get_regex, with_steps};
+
+    #[cfg(not(feature = "no_index"))]
+    use super::captures_to_dynamic;
+
+    #[rhai_fn(return_raw)]
+    pub fn matches(
+        ctx: NativeCallContext,
+        text: &str,
+        pattern: &str,
+    ) -> Result<bool, Box<EvalAltResult>> {
+        let regex = get_regex(&ctx, pattern)?;
+        let captures = with_steps(&ctx, |steps| regex.captures_at(text, 0, steps))?;
+        Ok(captures.is_some())
+    }
+    #[rhai_fn(return_raw)]
+    pub fn replace_regex(
+        ctx: NativeCallContext,
+        text: &mut ImmutableString,
+        pattern: &str,
+        replacement: &str,
+    ) -> Result<(), Box<EvalAltResult>> {
+        let regex = get_regex(&ctx, pattern)?;
+        let mut result = String::with_capacity(text.len());
+        let mut last = 0;
+
+        with_steps(&ctx, |steps| {
+            regex.for_each_match(text, steps, |captures| {
+                let (start, end) = captures[0].zip(captures[1]).expect(MATCHED);
+                result.push_str(&text[last..start]);
+                expand_replacement(&regex, text, captures, replacement, &mut result);
+                last = end;
+                true
+            })
+        })?;
+
+        result.push_str(&text[last..]);
+
+        // Check if string will be over max size limit
+        #[cfg(not(feature = "unchecked"))]
+        if ctx.engine().max_string_size() > 0 && result.len() > ctx.engine().max_string_size() {
+            return EvalAltResult::ErrorDataTooLarge(
+                "Length of string".to_string(),
+                Position::NONE,
+            )
+            .into();
+        }
+
+        *text = result.into();
+        Ok(())
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    pub mod arrays {
+        #[rhai_fn(return_raw)]
+        pub fn find(
+            ctx: NativeCallContext,
+            text: &str,
+            pattern: &str,
+        ) -> Result<Dynamic, Box<EvalAltResult>> {
+            let regex = get_regex(&ctx, pattern)?;
+
+            let captures = with_steps(&ctx, |steps| regex.captures_at(text, 0, steps))?;
+
+            Ok(match captures {
+                Some(captures) => captures_to_dynamic(&regex, text, &captures),
+                None => Dynamic::UNIT,
+            })
+        }
+        #[rhai_fn(return_raw)]
+        pub fn find_all(
+            ctx: NativeCallContext,
+            text: &str,
+            pattern: &str,
+        ) -> Result<Array, Box<EvalAltResult>> {
+            let regex = get_regex(&ctx, pattern)?;
+            let mut result = Array::new();
+
+            with_steps(&ctx, |steps| {
+                regex.for_each_match(text, steps, |captures| {
+                    result.push(captures_to_dynamic(&regex, text, captures));
+                    true
+                })
+            })?;
+
+            Ok(result)
+        }
+        #[rhai_fn(name = "split_regex", return_raw)]
+        pub fn split_regex(
+            ctx: NativeCallContext,
+            text: &str,
+            pattern: &str,
+        ) -> Result<Array, Box<EvalAltResult>> {
+            splitn_regex(ctx, text, pattern, 0)
+        }
+        #[rhai_fn(name = "split_regex", return_raw)]
+        pub fn splitn_regex(
+            ctx: NativeCallContext,
+            text: &str,
+            pattern: &str,
+            segments: INT,
+        ) -> Result<Array, Box<EvalAltResult>> {
+            let regex = get_regex(&ctx, pattern)?;
+            let mut result = Array::new();
+            let mut last = 0;
+
+            if segments != 1 {
+                with_steps(&ctx, |steps| {
+                    regex.for_each_match(text, steps, |captures| {
+                        let (start, end) = captures[0].zip(captures[1]).expect(MATCHED);
+                        result.push(text[last..start].to_string().into());
+                        last = end;
+                        segments < 1 || result.len() < segments as usize - 1
+                    })
+                })?;
+            }
+
+            result.push(text[last..].to_string().into());
+            Ok(result)
+        }
+    }
+}
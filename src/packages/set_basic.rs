@@ -0,0 +1,111 @@
+#![cfg(not(feature = "no_index"))]
+#![allow(non_snake_case)]
+
+use crate::plugin::*;
+use crate::{def_package, Array, Dynamic, EvalAltResult, Position, Set, INT};
+use std::any::TypeId;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+def_package!(crate:BasicSetPackage:"Basic set utilities.", lib, {
+    combine_with_exported_module!(lib, "set", set_functions);
+
+    // Register set iterator
+    lib.set_iter(TypeId::of::<Set>(), |set| Box::new(set.cast::<Set>().into_iter()));
+});
+
+/// Add a value into a [`Set`], raising an error if the value cannot be hashed.
+fn insert_value(set: &mut Set, value: Dynamic) -> Result<bool, Box<EvalAltResult>> {
+    set.insert(value).map_err(|typ| {
+        EvalAltResult::ErrorMismatchDataType(
+            "a hashable value".to_string(),
+            typ.to_string(),
+            Position::NONE,
+        )
+        .into()
+    })
+}
+
+#[export_module]
+mod set_functions {
+    use super::insert_value;
+
+    #[rhai_fn(name = "set")]
+    pub fn new_set() -> Set {
+        Set::new()
+    }
+    #[rhai_fn(name = "set", return_raw)]
+    pub fn new_set_from_array(array: Array) -> Result<Set, Box<EvalAltResult>> {
+        let mut set = Set::new();
+
+        for item in array {
+            insert_value(&mut set, item)?;
+        }
+
+        Ok(set)
+    }
+    #[rhai_fn(name = "len", get = "len", pure)]
+    pub fn len(set: &mut Set) -> INT {
+        set.len() as INT
+    }
+    #[rhai_fn(pure)]
+    pub fn contains(set: &mut Set, value: Dynamic) -> bool {
+        set.contains(&value)
+    }
+    #[rhai_fn(return_raw)]
+    pub fn insert(set: &mut Set, value: Dynamic) -> Result<bool, Box<EvalAltResult>> {
+        insert_value(set, value)
+    }
+    #[rhai_fn(name = "+=", return_raw)]
+    pub fn add_value(set: &mut Set, value: Dynamic) -> Result<(), Box<EvalAltResult>> {
+        insert_value(set, value).map(|_| ())
+    }
+    pub fn remove(set: &mut Set, value: Dynamic) -> bool {
+        set.remove(&value).is_some()
+    }
+    pub fn clear(set: &mut Set) {
+        set.clear();
+    }
+    #[rhai_fn(name = "union", name = "+=")]
+    pub fn union_with(set: &mut Set, other: Set) {
+        set.extend_from_set(other);
+    }
+    #[rhai_fn(name = "+")]
+    pub fn union(mut set: Set, other: Set) -> Set {
+        set.extend_from_set(other);
+        set
+    }
+    #[rhai_fn(name = "intersection")]
+    pub fn intersect_with(set: &mut Set, other: Set) {
+        set.retain_in(&other);
+    }
+    #[rhai_fn(name = "difference", name = "-=")]
+    pub fn difference_with(set: &mut Set, other: Set) {
+        set.remove_all_in(&other);
+    }
+    #[rhai_fn(name = "-")]
+    pub fn difference(mut set: Set, other: Set) -> Set {
+        set.remove_all_in(&other);
+        set
+    }
+    #[rhai_fn(pure)]
+    pub fn is_subset(set: &mut Set, other: Set) -> bool {
+        set.is_subset(&other)
+    }
+    #[rhai_fn(pure)]
+    pub fn is_superset(set: &mut Set, other: Set) -> bool {
+        other.is_subset(set)
+    }
+    #[rhai_fn(name = "==", pure)]
+    pub fn equals(set: &mut Set, other: Set) -> bool {
+        *set == other
+    }
+    #[rhai_fn(name = "!=", pure)]
+    pub fn not_equals(set: &mut Set, other: Set) -> bool {
+        *set != other
+    }
+    #[rhai_fn(pure)]
+    pub fn to_array(set: &mut Set) -> Array {
+        set.iter().cloned().collect()
+    }
+}
@@ -14,12 +14,48 @@ use crate::Map;
 
 pub const FUNC_TO_STRING: &'static str = "to_string";
 pub const FUNC_TO_DEBUG: &'static str = "to_debug";
+pub const FUNC_FORMAT: &'static str = "format";
+
+/// Maximum number of arguments to `format` after the format string.
+pub const MAX_FORMAT_ARGS: usize = 16;
 
 def_package!(crate:BasicStringPackage:"Basic string utilities, including printing.", lib, {
     combine_with_exported_module!(lib, "print_debug", print_debug_functions);
     combine_with_exported_module!(lib, "number_formatting", number_formatting);
+
+    reg_format_functions(lib);
 });
 
+// Register `format` with any number of arguments up to `MAX_FORMAT_ARGS`
+
+fn reg_format_functions(lib: &mut Module) {
+    let mut arg_types = vec![TypeId::of::<ImmutableString>()];
+
+    for _n in 0..=MAX_FORMAT_ARGS {
+        let _hash = lib.set_raw_fn(
+            FUNC_FORMAT,
+            FnNamespace::Global,
+            FnAccess::Public,
+            &arg_types,
+            |ctx, args| {
+                let fmt = args[0].clone_cast::<ImmutableString>();
+                crate::format::format_args(&ctx, &fmt, &mut args[1..]).map(ImmutableString::from)
+            },
+        );
+
+        #[cfg(feature = "metadata")]
+        {
+            let mut params = vec!["format: &str".to_string()];
+            params.extend((1..=_n).map(|i| format!("arg{}: Dynamic", i)));
+            params.push("String".to_string());
+            let params: Vec<_> = params.iter().map(|p| p.as_str()).collect();
+            lib.update_fn_metadata(_hash, &params);
+        }
+
+        arg_types.push(TypeId::of::<Dynamic>());
+    }
+}
+
 // Register print and debug
 
 #[inline(always)]
@@ -294,4 +330,21 @@ mod number_formatting {
             }
         }
     }
+    #[cfg(feature = "bigint")]
+    pub mod bigint {
+        use num_bigint::BigInt;
+
+        #[rhai_fn(name = "to_hex")]
+        pub fn bigint_to_hex(value: BigInt) -> ImmutableString {
+            to_hex(value)
+        }
+        #[rhai_fn(name = "to_octal")]
+        pub fn bigint_to_octal(value: BigInt) -> ImmutableString {
+            to_octal(value)
+        }
+        #[rhai_fn(name = "to_binary")]
+        pub fn bigint_to_binary(value: BigInt) -> ImmutableString {
+            to_binary(value)
+        }
+    }
 }
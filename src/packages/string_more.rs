@@ -99,6 +99,72 @@ mod string_functions {
             *string = trimmed.to_string().into();
         }
     }
+    pub fn trim_start(string: &mut ImmutableString) {
+        let trimmed = string.trim_start();
+
+        if trimmed.len() < string.len() {
+            *string = trimmed.to_string().into();
+        }
+    }
+    pub fn trim_end(string: &mut ImmutableString) {
+        let trimmed = string.trim_end();
+
+        if trimmed.len() < string.len() {
+            *string = trimmed.to_string().into();
+        }
+    }
+    pub fn reverse(string: &mut ImmutableString) {
+        if string.len() > 1 {
+            *string = string.chars().rev().collect::<String>().into();
+        }
+    }
+    #[rhai_fn(return_raw)]
+    pub fn repeat(
+        _ctx: NativeCallContext,
+        string: &str,
+        count: INT,
+    ) -> Result<ImmutableString, Box<crate::EvalAltResult>> {
+        if count <= 0 || string.is_empty() {
+            return Ok(ImmutableString::new());
+        }
+
+        let len = match string.len().checked_mul(count as usize) {
+            Some(len) if count as u64 <= usize::MAX as u64 && len <= isize::MAX as usize => len,
+            _ => {
+                return crate::EvalAltResult::ErrorDataTooLarge(
+                    "Length of string".to_string(),
+                    crate::Position::NONE,
+                )
+                .into()
+            }
+        };
+
+        // Check if string will be over max size limit
+        #[cfg(not(feature = "unchecked"))]
+        if _ctx.engine().max_string_size() > 0 && len > _ctx.engine().max_string_size() {
+            return crate::EvalAltResult::ErrorDataTooLarge(
+                "Length of string".to_string(),
+                crate::Position::NONE,
+            )
+            .into();
+        }
+
+        let mut result = String::new();
+
+        if result.try_reserve_exact(len).is_err() {
+            return crate::EvalAltResult::ErrorDataTooLarge(
+                "Length of string".to_string(),
+                crate::Position::NONE,
+            )
+            .into();
+        }
+
+        for _ in 0..count {
+            result.push_str(string);
+        }
+
+        Ok(result.into())
+    }
 
     pub fn to_upper(string: &str) -> ImmutableString {
         string.to_uppercase().into()
@@ -146,6 +212,40 @@ mod string_functions {
         *character = to_lower_char(*character)
     }
 
+    pub fn eq_ignore_case(string1: &str, string2: &str) -> bool {
+        compare_ignore_case(string1, string2) == 0
+    }
+    pub fn compare_ignore_case(string1: &str, string2: &str) -> INT {
+        let chars1 = string1.chars().flat_map(char::to_lowercase);
+        let chars2 = string2.chars().flat_map(char::to_lowercase);
+        chars1.cmp(chars2) as INT
+    }
+
+    #[rhai_fn(name = "contains")]
+    pub fn contains(string: &str, find_string: &str) -> bool {
+        string.contains(find_string)
+    }
+    #[rhai_fn(name = "contains")]
+    pub fn contains_char(string: &str, character: char) -> bool {
+        string.contains(character)
+    }
+    #[rhai_fn(name = "starts_with")]
+    pub fn starts_with(string: &str, prefix: &str) -> bool {
+        string.starts_with(prefix)
+    }
+    #[rhai_fn(name = "starts_with")]
+    pub fn starts_with_char(string: &str, character: char) -> bool {
+        string.starts_with(character)
+    }
+    #[rhai_fn(name = "ends_with")]
+    pub fn ends_with(string: &str, suffix: &str) -> bool {
+        string.ends_with(suffix)
+    }
+    #[rhai_fn(name = "ends_with")]
+    pub fn ends_with_char(string: &str, character: char) -> bool {
+        string.ends_with(character)
+    }
+
     #[rhai_fn(name = "index_of")]
     pub fn index_of_char_starting_from(string: &str, character: char, start: INT) -> INT {
         let start = if start < 0 {
@@ -460,6 +560,49 @@ mod string_functions {
         Ok(())
     }
 
+    #[cfg(feature = "unicode")]
+    pub mod unicode_functions {
+        use crate::ImmutableString;
+        use unicode_normalization::UnicodeNormalization;
+        use unicode_segmentation::UnicodeSegmentation;
+
+        #[rhai_fn(name = "grapheme_len", get = "grapheme_len")]
+        pub fn grapheme_len(string: &str) -> INT {
+            string.graphemes(true).count() as INT
+        }
+        pub fn reverse_graphemes(string: &mut ImmutableString) {
+            if string.len() > 1 {
+                *string = string.graphemes(true).rev().collect::<String>().into();
+            }
+        }
+
+        pub fn to_nfc(string: &str) -> ImmutableString {
+            string.nfc().collect::<String>().into()
+        }
+        pub fn to_nfd(string: &str) -> ImmutableString {
+            string.nfd().collect::<String>().into()
+        }
+        pub fn to_nfkc(string: &str) -> ImmutableString {
+            string.nfkc().collect::<String>().into()
+        }
+        pub fn to_nfkd(string: &str) -> ImmutableString {
+            string.nfkd().collect::<String>().into()
+        }
+    }
+
+    #[cfg(all(feature = "unicode", not(feature = "no_index")))]
+    pub mod unicode_arrays {
+        use crate::Array;
+        use unicode_segmentation::UnicodeSegmentation;
+
+        pub fn graphemes(string: &str) -> Array {
+            string
+                .graphemes(true)
+                .map(|g| g.to_string().into())
+                .collect()
+        }
+    }
+
     #[cfg(not(feature = "no_index"))]
     pub mod arrays {
         use crate::{Array, ImmutableString};
@@ -3,6 +3,7 @@
 use super::{arithmetic::make_err as make_arithmetic_err, math_basic::MAX_INT};
 use crate::plugin::*;
 use crate::{def_package, Dynamic, EvalAltResult, INT};
+use std::convert::TryFrom;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -18,6 +19,7 @@ use instant::{Duration, Instant};
 def_package!(crate:BasicTimePackage:"Basic timing utilities.", lib, {
     // Register date/time functions
     combine_with_exported_module!(lib, "time", time_functions);
+    combine_with_exported_module!(lib, "datetime", datetime_functions);
 });
 
 #[export_module]
@@ -249,3 +251,353 @@ mod time_functions {
         timestamp >= timestamp2
     }
 }
+
+/// Convert a number into `T`, raising an arithmetic error if it does not fit.
+fn to_int<T: TryFrom<N>, N: Copy + std::fmt::Display>(
+    value: N,
+    what: &str,
+) -> Result<T, Box<EvalAltResult>> {
+    T::try_from(value)
+        .map_err(|_| make_arithmetic_err(format!("Integer overflow for {}: {}", what, value)))
+}
+
+/// Create a [`DateTime`][crate::DateTime] from its components, raising an arithmetic error if it
+/// is invalid.
+fn make_datetime(
+    year: INT,
+    month: INT,
+    day: INT,
+    hour: INT,
+    minute: INT,
+    second: INT,
+) -> Result<crate::DateTime, Box<EvalAltResult>> {
+    let part = |n: INT| u32::try_from(n).unwrap_or(u32::MAX);
+
+    crate::DateTime::from_ymd_hms(
+        year as i64,
+        part(month),
+        part(day),
+        part(hour),
+        part(minute),
+        part(second),
+    )
+    .ok_or_else(|| {
+        make_arithmetic_err(format!(
+            "Invalid date/time: {}-{}-{} {}:{}:{}",
+            year, month, day, hour, minute, second
+        ))
+    })
+}
+
+#[export_module]
+mod datetime_functions {
+    use super::{make_datetime, to_int};
+    use crate::{DateTime, Duration, ImmutableString};
+    use std::convert::TryFrom;
+
+    #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
+    pub mod clock {
+        pub fn now() -> DateTime {
+            DateTime::now()
+        }
+    }
+
+    #[rhai_fn(name = "datetime", return_raw)]
+    pub fn new_date(year: INT, month: INT, day: INT) -> Result<DateTime, Box<EvalAltResult>> {
+        make_datetime(year, month, day, 0, 0, 0)
+    }
+    #[rhai_fn(name = "datetime", return_raw)]
+    pub fn new_datetime(
+        year: INT,
+        month: INT,
+        day: INT,
+        hour: INT,
+        minute: INT,
+        second: INT,
+    ) -> Result<DateTime, Box<EvalAltResult>> {
+        make_datetime(year, month, day, hour, minute, second)
+    }
+    #[rhai_fn(return_raw)]
+    pub fn from_unix(seconds: INT) -> Result<DateTime, Box<EvalAltResult>> {
+        DateTime::from_unix_timestamp(seconds as i64).ok_or_else(|| {
+            make_arithmetic_err(format!("Date/time out of range: {} second(s)", seconds))
+        })
+    }
+    #[rhai_fn(name = "parse_datetime", return_raw)]
+    pub fn parse(text: &str) -> Result<DateTime, Box<EvalAltResult>> {
+        DateTime::parse(text)
+            .map_err(|err| make_arithmetic_err(format!("Invalid date/time '{}': {}", text, err)))
+    }
+    #[rhai_fn(name = "parse_datetime", return_raw)]
+    pub fn parse_with_format(text: &str, format: &str) -> Result<DateTime, Box<EvalAltResult>> {
+        DateTime::parse_with_format(text, format)
+            .map_err(|err| make_arithmetic_err(format!("Invalid date/time '{}': {}", text, err)))
+    }
+
+    #[rhai_fn(name = "year", get = "year", pure)]
+    pub fn year(dt: &mut DateTime) -> INT {
+        dt.year() as INT
+    }
+    #[rhai_fn(name = "month", get = "month", pure)]
+    pub fn month(dt: &mut DateTime) -> INT {
+        dt.month() as INT
+    }
+    #[rhai_fn(name = "day", get = "day", pure)]
+    pub fn day(dt: &mut DateTime) -> INT {
+        dt.day() as INT
+    }
+    #[rhai_fn(name = "hour", get = "hour", pure)]
+    pub fn hour(dt: &mut DateTime) -> INT {
+        dt.hour() as INT
+    }
+    #[rhai_fn(name = "minute", get = "minute", pure)]
+    pub fn minute(dt: &mut DateTime) -> INT {
+        dt.minute() as INT
+    }
+    #[rhai_fn(name = "second", get = "second", pure)]
+    pub fn second(dt: &mut DateTime) -> INT {
+        dt.second() as INT
+    }
+    #[rhai_fn(name = "nanosecond", get = "nanosecond", pure)]
+    pub fn nanosecond(dt: &mut DateTime) -> INT {
+        dt.nanosecond() as INT
+    }
+    #[rhai_fn(name = "weekday", get = "weekday", pure)]
+    pub fn weekday(dt: &mut DateTime) -> INT {
+        dt.weekday() as INT
+    }
+    #[rhai_fn(name = "day_of_year", get = "day_of_year", pure)]
+    pub fn day_of_year(dt: &mut DateTime) -> INT {
+        dt.day_of_year() as INT
+    }
+    #[rhai_fn(name = "offset", get = "offset", pure)]
+    pub fn offset(dt: &mut DateTime) -> INT {
+        dt.offset() as INT
+    }
+    #[rhai_fn(name = "unix_timestamp", get = "unix_timestamp", pure, return_raw)]
+    pub fn unix_timestamp(dt: &mut DateTime) -> Result<INT, Box<EvalAltResult>> {
+        to_int(dt.unix_timestamp(), "unix_timestamp")
+    }
+
+    #[rhai_fn(return_raw)]
+    pub fn with_offset(dt: DateTime, seconds: INT) -> Result<DateTime, Box<EvalAltResult>> {
+        i32::try_from(seconds)
+            .ok()
+            .and_then(|offset| dt.with_offset(offset))
+            .ok_or_else(|| {
+                make_arithmetic_err(format!("Invalid offset from UTC: {} second(s)", seconds))
+            })
+    }
+    pub fn to_utc(dt: DateTime) -> DateTime {
+        dt.to_utc()
+    }
+    #[rhai_fn(return_raw)]
+    pub fn add_months(dt: DateTime, months: INT) -> Result<DateTime, Box<EvalAltResult>> {
+        dt.checked_add_months(months as i64).ok_or_else(|| {
+            make_arithmetic_err(format!(
+                "Date/time overflow when adding {} month(s)",
+                months
+            ))
+        })
+    }
+    #[rhai_fn(return_raw)]
+    pub fn add_years(dt: DateTime, years: INT) -> Result<DateTime, Box<EvalAltResult>> {
+        (years as i64)
+            .checked_mul(12)
+            .and_then(|months| dt.checked_add_months(months))
+            .ok_or_else(|| {
+                make_arithmetic_err(format!("Date/time overflow when adding {} year(s)", years))
+            })
+    }
+    #[rhai_fn(return_raw, pure)]
+    pub fn format(dt: &mut DateTime, format: &str) -> Result<String, Box<EvalAltResult>> {
+        dt.format(format)
+            .map_err(|err| make_arithmetic_err(format!("Invalid date/time format: {}", err)))
+    }
+    #[rhai_fn(name = "print", name = "to_string", pure)]
+    pub fn print_datetime(dt: &mut DateTime) -> ImmutableString {
+        dt.to_string().into()
+    }
+    #[rhai_fn(name = "debug", name = "to_debug", pure)]
+    pub fn debug_datetime(dt: &mut DateTime) -> ImmutableString {
+        format!("{:?}", dt).into()
+    }
+
+    #[rhai_fn(return_raw, name = "+")]
+    pub fn add(dt: DateTime, duration: Duration) -> Result<DateTime, Box<EvalAltResult>> {
+        dt.checked_add(duration).ok_or_else(|| {
+            make_arithmetic_err(format!("Date/time overflow when adding {}", duration))
+        })
+    }
+    #[rhai_fn(return_raw, name = "+=")]
+    pub fn add_assign(dt: &mut DateTime, duration: Duration) -> Result<(), Box<EvalAltResult>> {
+        *dt = add(*dt, duration)?;
+        Ok(())
+    }
+    #[rhai_fn(return_raw, name = "-")]
+    pub fn subtract(dt: DateTime, duration: Duration) -> Result<DateTime, Box<EvalAltResult>> {
+        dt.checked_sub(duration).ok_or_else(|| {
+            make_arithmetic_err(format!("Date/time overflow when subtracting {}", duration))
+        })
+    }
+    #[rhai_fn(return_raw, name = "-=")]
+    pub fn subtract_assign(
+        dt: &mut DateTime,
+        duration: Duration,
+    ) -> Result<(), Box<EvalAltResult>> {
+        *dt = subtract(*dt, duration)?;
+        Ok(())
+    }
+    #[rhai_fn(name = "-")]
+    pub fn diff(dt: DateTime, dt2: DateTime) -> Duration {
+        dt.duration_since(&dt2)
+    }
+
+    #[rhai_fn(name = "==")]
+    pub fn eq(dt: DateTime, dt2: DateTime) -> bool {
+        dt == dt2
+    }
+    #[rhai_fn(name = "!=")]
+    pub fn ne(dt: DateTime, dt2: DateTime) -> bool {
+        dt != dt2
+    }
+    #[rhai_fn(name = "<")]
+    pub fn lt(dt: DateTime, dt2: DateTime) -> bool {
+        dt < dt2
+    }
+    #[rhai_fn(name = "<=")]
+    pub fn lte(dt: DateTime, dt2: DateTime) -> bool {
+        dt <= dt2
+    }
+    #[rhai_fn(name = ">")]
+    pub fn gt(dt: DateTime, dt2: DateTime) -> bool {
+        dt > dt2
+    }
+    #[rhai_fn(name = ">=")]
+    pub fn gte(dt: DateTime, dt2: DateTime) -> bool {
+        dt >= dt2
+    }
+
+    pub mod duration_functions {
+        pub fn days(days: INT) -> Duration {
+            Duration::from_days(days as i64)
+        }
+        pub fn hours(hours: INT) -> Duration {
+            Duration::from_hours(hours as i64)
+        }
+        pub fn minutes(minutes: INT) -> Duration {
+            Duration::from_mins(minutes as i64)
+        }
+        pub fn seconds(seconds: INT) -> Duration {
+            Duration::from_secs(seconds as i64)
+        }
+        pub fn milliseconds(milliseconds: INT) -> Duration {
+            Duration::from_millis(milliseconds as i64)
+        }
+        #[rhai_fn(name = "total_days", get = "total_days", pure, return_raw)]
+        pub fn total_days(duration: &mut Duration) -> Result<INT, Box<EvalAltResult>> {
+            to_int(duration.as_secs() / 86_400, "total_days")
+        }
+        #[rhai_fn(name = "total_hours", get = "total_hours", pure, return_raw)]
+        pub fn total_hours(duration: &mut Duration) -> Result<INT, Box<EvalAltResult>> {
+            to_int(duration.as_secs() / 3600, "total_hours")
+        }
+        #[rhai_fn(name = "total_minutes", get = "total_minutes", pure, return_raw)]
+        pub fn total_minutes(duration: &mut Duration) -> Result<INT, Box<EvalAltResult>> {
+            to_int(duration.as_secs() / 60, "total_minutes")
+        }
+        #[rhai_fn(name = "total_seconds", get = "total_seconds", pure, return_raw)]
+        pub fn total_seconds(duration: &mut Duration) -> Result<INT, Box<EvalAltResult>> {
+            to_int(duration.as_secs(), "total_seconds")
+        }
+        #[rhai_fn(
+            name = "total_milliseconds",
+            get = "total_milliseconds",
+            pure,
+            return_raw
+        )]
+        pub fn total_milliseconds(duration: &mut Duration) -> Result<INT, Box<EvalAltResult>> {
+            to_int(duration.as_millis(), "total_milliseconds")
+        }
+        pub fn abs(duration: Duration) -> Duration {
+            duration.abs()
+        }
+        #[rhai_fn(name = "print", name = "to_string", pure)]
+        pub fn print_duration(duration: &mut Duration) -> ImmutableString {
+            duration.to_string().into()
+        }
+        #[rhai_fn(name = "debug", name = "to_debug", pure)]
+        pub fn debug_duration(duration: &mut Duration) -> ImmutableString {
+            duration.to_string().into()
+        }
+
+        #[rhai_fn(return_raw, name = "+")]
+        pub fn add(
+            duration: Duration,
+            duration2: Duration,
+        ) -> Result<Duration, Box<EvalAltResult>> {
+            duration.checked_add(duration2).ok_or_else(|| {
+                make_arithmetic_err(format!("Duration overflow: {} + {}", duration, duration2))
+            })
+        }
+        #[rhai_fn(return_raw, name = "-")]
+        pub fn subtract(
+            duration: Duration,
+            duration2: Duration,
+        ) -> Result<Duration, Box<EvalAltResult>> {
+            duration.checked_sub(duration2).ok_or_else(|| {
+                make_arithmetic_err(format!("Duration overflow: {} - {}", duration, duration2))
+            })
+        }
+        #[rhai_fn(name = "-")]
+        pub fn neg(duration: Duration) -> Duration {
+            -duration
+        }
+        #[rhai_fn(return_raw, name = "*")]
+        pub fn multiply(duration: Duration, factor: INT) -> Result<Duration, Box<EvalAltResult>> {
+            duration.checked_mul(factor as i128).ok_or_else(|| {
+                make_arithmetic_err(format!("Duration overflow: {} * {}", duration, factor))
+            })
+        }
+        #[rhai_fn(return_raw, name = "/")]
+        pub fn divide(duration: Duration, divisor: INT) -> Result<Duration, Box<EvalAltResult>> {
+            duration.checked_div(divisor as i128).ok_or_else(|| {
+                make_arithmetic_err(format!("Division by zero: {} / {}", duration, divisor))
+            })
+        }
+
+        #[rhai_fn(name = "==")]
+        pub fn eq(duration: Duration, duration2: Duration) -> bool {
+            duration == duration2
+        }
+        #[rhai_fn(name = "!=")]
+        pub fn ne(duration: Duration, duration2: Duration) -> bool {
+            duration != duration2
+        }
+        #[rhai_fn(name = "<")]
+        pub fn lt(duration: Duration, duration2: Duration) -> bool {
+            duration < duration2
+        }
+        #[rhai_fn(name = "<=")]
+        pub fn lte(duration: Duration, duration2: Duration) -> bool {
+            duration <= duration2
+        }
+        #[rhai_fn(name = ">")]
+        pub fn gt(duration: Duration, duration2: Duration) -> bool {
+            duration > duration2
+        }
+        #[rhai_fn(name = ">=")]
+        pub fn gte(duration: Duration, duration2: Duration) -> bool {
+            duration >= duration2
+        }
+    }
+    #[cfg(not(feature = "no_float"))]
+    pub mod float_functions {
+        #[rhai_fn(name = "seconds", return_raw)]
+        pub fn seconds_float(seconds: FLOAT) -> Result<Duration, Box<EvalAltResult>> {
+            Duration::from_secs_f64(seconds as f64).ok_or_else(|| {
+                make_arithmetic_err(format!("Invalid duration: {} second(s)", seconds))
+            })
+        }
+    }
+}
@@ -1,18 +1,18 @@
 //! Main module defining the lexer and parser.
 
 use crate::ast::{
-    BinaryExpr, CustomExpr, Expr, FnCallExpr, FnCallHashes, Ident, OpAssignment, ReturnType,
-    ScriptFnDef, Stmt, StmtBlock,
+    BinaryExpr, CatchClause, CustomExpr, Expr, FnCallExpr, FnCallHashes, Ident, OpAssignment,
+    Pattern, ReturnType, ScriptFnDef, Stmt, StmtBlock, SwitchCases, TryCatchBlock, VariantPattern,
 };
 use crate::custom_syntax::{
     CustomSyntax, CUSTOM_SYNTAX_MARKER_BLOCK, CUSTOM_SYNTAX_MARKER_BOOL, CUSTOM_SYNTAX_MARKER_EXPR,
     CUSTOM_SYNTAX_MARKER_IDENT, CUSTOM_SYNTAX_MARKER_INT, CUSTOM_SYNTAX_MARKER_STRING,
 };
 use crate::dynamic::{AccessMode, Union};
-use crate::engine::{Precedence, KEYWORD_THIS, OP_CONTAINS};
-use crate::fn_hash::get_hasher;
+use crate::engine::{Precedence, KEYWORD_FN_PTR, KEYWORD_THIS, OP_CONTAINS};
 use crate::module::NamespaceRef;
 use crate::optimize::{optimize_into_ast, OptimizationLevel};
+use crate::packages::string_basic::FUNC_FORMAT;
 use crate::token::{
     is_keyword_function, is_valid_identifier, Token, TokenStream, TokenizerControl,
 };
@@ -24,7 +24,6 @@ use crate::{
 use std::prelude::v1::*;
 use std::{
     collections::BTreeMap,
-    hash::{Hash, Hasher},
     num::{NonZeroU8, NonZeroUsize},
 };
 
@@ -32,7 +31,15 @@ use std::{
 use crate::{custom_syntax::CUSTOM_SYNTAX_MARKER_FLOAT, FLOAT};
 
 #[cfg(not(feature = "no_function"))]
-use crate::FnAccess;
+use crate::{
+    ast::{ASTNode, EnumDef},
+    fn_hash::get_hasher,
+    token::{is_overloadable_operator, is_overloadable_unary_operator},
+    FnAccess,
+};
+
+#[cfg(not(feature = "no_function"))]
+use std::hash::{Hash, Hasher};
 
 type PERR = ParseErrorType;
 
@@ -83,8 +90,9 @@ pub struct ParseState<'e> {
     tokenizer_control: TokenizerControl,
     /// Interned strings.
     interned_strings: IdentifierBuilder,
-    /// Encapsulates a local stack with variable names to simulate an actual runtime scope.
-    stack: Vec<(Identifier, AccessMode)>,
+    /// Encapsulates a local stack with variable names, access modes and declared types
+    /// to simulate an actual runtime scope.
+    stack: Vec<(Identifier, AccessMode, Option<Identifier>)>,
     /// Size of the local variables stack upon entry of the current block scope.
     entry_stack_len: usize,
     /// Tracks a list of external variables (variables that are not explicitly declared in the scope).
@@ -99,6 +107,9 @@ pub struct ParseState<'e> {
     /// Encapsulates a local stack with imported [module][crate::Module] names.
     #[cfg(not(feature = "no_module"))]
     modules: StaticVec<Identifier>,
+    /// List of `enum` declarations parsed so far.
+    #[cfg(not(feature = "no_function"))]
+    enums: StaticVec<Shared<EnumDef>>,
     /// Maximum levels of expression nesting.
     #[cfg(not(feature = "unchecked"))]
     max_expr_depth: Option<NonZeroUsize>,
@@ -130,6 +141,8 @@ impl<'e> ParseState<'e> {
             entry_stack_len: 0,
             #[cfg(not(feature = "no_module"))]
             modules: Default::default(),
+            #[cfg(not(feature = "no_function"))]
+            enums: Default::default(),
         }
     }
 
@@ -150,7 +163,7 @@ impl<'e> ParseState<'e> {
             .iter()
             .rev()
             .enumerate()
-            .find(|(_, (n, _))| {
+            .find(|(_, (n, _, _))| {
                 if n == SCOPE_SEARCH_BARRIER_MARKER {
                     // Do not go beyond the barrier
                     barrier = true;
@@ -177,6 +190,21 @@ impl<'e> ParseState<'e> {
         }
     }
 
+    /// Find the declared type of a variable in the [`ParseState`], searching in reverse.
+    ///
+    /// Returns `None` when the variable name is not found, or it has no declared type.
+    #[cfg(not(feature = "no_closure"))]
+    #[inline]
+    #[must_use]
+    pub fn find_var_type(&self, name: &str) -> Option<&Identifier> {
+        self.stack
+            .iter()
+            .rev()
+            .take_while(|(n, _, _)| n != SCOPE_SEARCH_BARRIER_MARKER)
+            .find(|(n, _, _)| n == name)
+            .and_then(|(_, _, typ)| typ.as_ref())
+    }
+
     /// Find a module by name in the [`ParseState`], searching in reverse.
     ///
     /// Returns the offset to be deducted from `Stack::len`,
@@ -199,6 +227,16 @@ impl<'e> ParseState<'e> {
             .and_then(|(i, _)| NonZeroUsize::new(i + 1))
     }
 
+    /// Find an `enum` declaration by name in the [`ParseState`].
+    ///
+    /// Returns `None` when no `enum` with that name has been declared so far.
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    #[must_use]
+    pub fn find_enum(&self, name: &str) -> Option<&Shared<EnumDef>> {
+        self.enums.iter().find(|def| def.name == name)
+    }
+
     /// Get an interned string, creating one if it is not yet interned.
     #[inline(always)]
     #[must_use]
@@ -226,6 +264,13 @@ struct ParseSettings {
     allow_switch_expr: bool,
     /// Is statement-expression allowed?
     allow_stmt_expr: bool,
+    /// Is a format specifier allowed after the last statement of the block (e.g. `${x:.2}`)?
+    allow_format_spec: bool,
+    /// Is a `yield` statement allowed?
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    allow_yield: bool,
     /// Current expression nesting level.
     level: usize,
 }
@@ -389,6 +434,304 @@ fn parse_var_name(input: &mut TokenStream) -> Result<(String, Position), ParseEr
     }
 }
 
+/// Parse a type annotation, after the `:` or `->`.
+fn parse_type_annotation(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+) -> Result<Identifier, ParseError> {
+    let (name, pos) = match input.next().expect(NEVER_ENDS) {
+        (Token::Identifier(s), pos) => (s, pos),
+        (Token::Reserved(s), pos) if s == KEYWORD_FN_PTR => (s, pos),
+        // ()
+        (Token::LeftParen, pos) => match input.next().expect(NEVER_ENDS) {
+            (Token::RightParen, _) => ("()".to_string(), pos),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::RightParen.into(),
+                    "for the unit type '()'".into(),
+                )
+                .into_err(pos))
+            }
+        },
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => return Err(PERR::ExprExpected("a type name".into()).into_err(pos)),
+    };
+
+    if state.engine.is_type_name(&name) {
+        Ok(state.get_identifier(name))
+    } else {
+        Err(PERR::UnknownType(name).into_err(pos))
+    }
+}
+
+/// Reject a constant value that obviously does not match a type annotation.
+///
+/// `target` describes what is annotated, e.g. "for parameter 'x'".
+fn check_constant_type(
+    state: &ParseState,
+    value: &Dynamic,
+    typ: &str,
+    target: impl FnOnce() -> String,
+    pos: Position,
+) -> Result<(), ParseError> {
+    if state.engine.matches_type_name(value, typ) {
+        Ok(())
+    } else {
+        let actual = state.engine.map_type_name(value.type_name());
+        Err(PERR::MismatchedType(format!("{} {}", typ, target()), actual.into()).into_err(pos))
+    }
+}
+
+/// Parse a destructuring pattern, or a variable name.
+fn parse_pattern(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    settings: ParseSettings,
+) -> Result<Pattern, ParseError> {
+    #[cfg(not(feature = "unchecked"))]
+    settings.ensure_level_within_max_limit(state.max_expr_depth)?;
+
+    let pattern = match input.peek().expect(NEVER_ENDS) {
+        // _
+        (Token::Underscore, pos) => {
+            let pos = *pos;
+            eat_token(input, Token::Underscore);
+            Pattern::Ignore(pos)
+        }
+
+        // [ pattern, ... ]
+        #[cfg(not(feature = "no_index"))]
+        (Token::LeftBracket, _) => {
+            let pos = eat_token(input, Token::LeftBracket);
+
+            let mut items = StaticVec::new();
+            let mut rest = None;
+
+            loop {
+                const MISSING_RBRACKET: &str = "to end this array pattern";
+
+                match input.peek().expect(NEVER_ENDS) {
+                    (Token::RightBracket, _) => {
+                        eat_token(input, Token::RightBracket);
+                        break;
+                    }
+                    // .. rest ]
+                    (Token::ExclusiveRange, pos) => {
+                        let pos = *pos;
+                        eat_token(input, Token::ExclusiveRange);
+                        rest = Some(match input.peek().expect(NEVER_ENDS) {
+                            (Token::RightBracket, _) => Pattern::Ignore(pos),
+                            _ => parse_pattern(input, state, settings.level_up())?,
+                        });
+                        match input.next().expect(NEVER_ENDS) {
+                            (Token::RightBracket, _) => break,
+                            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                            (_, pos) => {
+                                return Err(PERR::MissingToken(
+                                    Token::RightBracket.into(),
+                                    MISSING_RBRACKET.into(),
+                                )
+                                .into_err(pos))
+                            }
+                        }
+                    }
+                    _ => items.push(parse_pattern(input, state, settings.level_up())?),
+                }
+
+                match input.next().expect(NEVER_ENDS) {
+                    (Token::Comma, _) => (),
+                    (Token::RightBracket, _) => break,
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, pos) => {
+                        return Err(PERR::MissingToken(
+                            Token::RightBracket.into(),
+                            MISSING_RBRACKET.into(),
+                        )
+                        .into_err(pos))
+                    }
+                }
+            }
+
+            items.shrink_to_fit();
+            Pattern::Array((items, rest).into(), pos)
+        }
+
+        // #{ prop: pattern, ... }
+        #[cfg(not(feature = "no_object"))]
+        (Token::MapStart, _) => {
+            let pos = eat_token(input, Token::MapStart);
+
+            let mut props = StaticVec::<(Ident, Pattern)>::new();
+            let mut rest = None;
+
+            loop {
+                const MISSING_RBRACE: &str = "to end this object map pattern";
+
+                let (name, pos) = match input.next().expect(NEVER_ENDS) {
+                    (Token::RightBrace, _) => break,
+                    // .. rest }
+                    (Token::ExclusiveRange, pos) => {
+                        rest = Some(match input.peek().expect(NEVER_ENDS) {
+                            (Token::RightBrace, _) => Pattern::Ignore(pos),
+                            _ => parse_pattern(input, state, settings.level_up())?,
+                        });
+                        match input.next().expect(NEVER_ENDS) {
+                            (Token::RightBrace, _) => break,
+                            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                            (_, pos) => {
+                                return Err(PERR::MissingToken(
+                                    Token::RightBrace.into(),
+                                    MISSING_RBRACE.into(),
+                                )
+                                .into_err(pos))
+                            }
+                        }
+                    }
+                    (Token::Identifier(s), pos) | (Token::StringConstant(s), pos) => {
+                        if props.iter().any(|(p, _)| p.name == s) {
+                            return Err(PERR::DuplicatedProperty(s).into_err(pos));
+                        }
+                        (s, pos)
+                    }
+                    (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
+                        return Err(PERR::Reserved(s).into_err(pos));
+                    }
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, pos) => return Err(PERR::PropertyExpected.into_err(pos)),
+                };
+
+                let prop = Ident {
+                    name: state.get_identifier(name),
+                    pos,
+                };
+
+                // prop: pattern
+                let pattern = if match_token(input, Token::Colon).0 {
+                    parse_pattern(input, state, settings.level_up())?
+                } else if is_valid_identifier(prop.name.chars()) {
+                    Pattern::Var(prop.clone())
+                } else {
+                    return Err(PERR::MissingToken(
+                        Token::Colon.into(),
+                        format!("to bind the property '{}' to a variable", prop.name),
+                    )
+                    .into_err(input.peek().expect(NEVER_ENDS).1));
+                };
+
+                props.push((prop, pattern));
+
+                match input.next().expect(NEVER_ENDS) {
+                    (Token::Comma, _) => (),
+                    (Token::RightBrace, _) => break,
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, pos) => {
+                        return Err(PERR::MissingToken(
+                            Token::RightBrace.into(),
+                            MISSING_RBRACE.into(),
+                        )
+                        .into_err(pos))
+                    }
+                }
+            }
+
+            props.shrink_to_fit();
+            Pattern::Map((props, rest).into(), pos)
+        }
+
+        // name
+        _ => {
+            let (name, pos) = parse_var_name(input)?;
+            Pattern::Var(Ident {
+                name: state.get_identifier(name),
+                pos,
+            })
+        }
+    };
+
+    // Check for duplicated variables
+    let mut duplicated = None;
+    {
+        let mut names = StaticVec::<&Ident>::new();
+
+        pattern.for_each_var(&mut |v| {
+            if duplicated.is_none() && names.iter().any(|n| n.name == v.name) {
+                duplicated = Some(v.clone());
+            }
+            names.push(v);
+        });
+    }
+
+    match duplicated {
+        Some(v) => Err(PERR::DuplicatedVariable(v.name.into()).into_err(v.pos)),
+        None => Ok(pattern),
+    }
+}
+
+/// Is the next token the start of a destructuring pattern?
+#[inline(always)]
+fn is_pattern_start(input: &mut TokenStream) -> bool {
+    match input.peek().expect(NEVER_ENDS).0 {
+        #[cfg(not(feature = "no_index"))]
+        Token::LeftBracket => true,
+        #[cfg(not(feature = "no_object"))]
+        Token::MapStart => true,
+        _ => false,
+    }
+}
+
+/// Create a [`Stmt::Destructure`] that unpacks a hidden variable (e.g. a function parameter or
+/// `for` loop variable) according to a pattern, declaring all the bound variables.
+fn make_destructure_stmt(
+    state: &mut ParseState,
+    var_name: Identifier,
+    pattern: Pattern,
+    access: AccessMode,
+) -> Stmt {
+    let pos = pattern.position();
+    pattern.for_each_var(&mut |v| state.stack.push((v.name.clone(), access, None)));
+    let expr = Expr::Variable(None, pos, (None, None, var_name).into());
+    Stmt::Destructure(expr, pattern.into(), access, false, pos)
+}
+
+/// Does a pattern bind a variable with a particular name?
+#[cfg(not(feature = "no_function"))]
+fn pattern_binds(pattern: &Pattern, name: &str) -> bool {
+    let mut found = false;
+    pattern.for_each_var(&mut |v| found |= v.name == name);
+    found
+}
+
+/// Check that the variables bound by a parameter pattern do not duplicate other parameters.
+#[cfg(not(feature = "no_function"))]
+fn check_param_pattern(
+    fn_name: &str,
+    pattern: &Pattern,
+    param_name: &str,
+    params: &[(Identifier, Position)],
+    patterns: &[(Identifier, Pattern)],
+) -> Result<(), ParseError> {
+    let mut duplicated = if params.iter().any(|(p, _)| p == param_name) {
+        Some((param_name.to_string(), pattern.position()))
+    } else {
+        None
+    };
+
+    pattern.for_each_var(&mut |v| {
+        if duplicated.is_none()
+            && (params.iter().any(|(p, _)| p == &v.name)
+                || patterns.iter().any(|(_, p)| pattern_binds(p, &v.name)))
+        {
+            duplicated = Some((v.name.to_string(), v.pos));
+        }
+    });
+
+    match duplicated {
+        Some((s, pos)) => Err(PERR::FnDuplicatedParam(fn_name.to_string(), s).into_err(pos)),
+        None => Ok(()),
+    }
+}
+
 /// Parse `(` expr `)`
 fn parse_paren_expr(
     input: &mut TokenStream,
@@ -487,11 +830,70 @@ fn parse_fn_call(
     }
 
     let settings = settings.level_up();
+    let mut named_args = StaticVec::new();
 
     loop {
         match input.peek().expect(NEVER_ENDS) {
             // id(...args, ) - handle trailing comma
             (Token::RightParen, _) => (),
+            #[cfg(not(feature = "no_function"))]
+            (Token::Identifier(_), _) => {
+                // Parsing a possible argument name records it as an external variable
+                #[cfg(not(feature = "no_closure"))]
+                let num_externals = state.external_vars.len();
+
+                match parse_expr(input, state, lib, settings)? {
+                    // id(...args, name: expr)
+                    Expr::Variable(_, pos, x)
+                        if x.1.is_none() && match_token(input, Token::Colon).0 =>
+                    {
+                        let name = x.2;
+
+                        #[cfg(not(feature = "no_closure"))]
+                        if state.external_vars.len() > num_externals {
+                            state.external_vars.remove(&name);
+                        }
+
+                        if capture {
+                            return Err(PERR::MalformedCapture(
+                                "named arguments do not support capturing".into(),
+                            )
+                            .into_err(pos));
+                        }
+                        if args.iter().any(|a| matches!(a, Expr::Spread(_, _))) {
+                            return Err(PERR::MalformedCallExpr(format!(
+                                "Named arguments cannot be used with spread arguments in function call '{}'",
+                                id
+                            ))
+                            .into_err(pos));
+                        }
+                        if named_args.iter().any(|n: &Ident| n.name == name) {
+                            return Err(PERR::MalformedCallExpr(format!(
+                                "Duplicated named argument '{}' in function call '{}'",
+                                name, id
+                            ))
+                            .into_err(pos));
+                        }
+
+                        args.push(parse_expr(input, state, lib, settings)?);
+                        named_args.push(Ident { name, pos });
+                    }
+                    expr if !named_args.is_empty() => {
+                        return Err(positional_after_named_err(&id, expr.position()))
+                    }
+                    expr => args.push(expr),
+                }
+            }
+            (_, pos) if !named_args.is_empty() => {
+                return Err(positional_after_named_err(&id, *pos))
+            }
+            // id(...args, ...expr)
+            #[cfg(not(feature = "no_index"))]
+            (Token::Spread, _) => {
+                let pos = eat_token(input, Token::Spread);
+                let expr = parse_expr(input, state, lib, settings)?;
+                args.push(Expr::Spread(expr.into(), pos));
+            }
             _ => args.push(parse_expr(input, state, lib, settings)?),
         }
 
@@ -521,6 +923,7 @@ fn parse_fn_call(
                 };
 
                 args.shrink_to_fit();
+                named_args.shrink_to_fit();
 
                 return Ok(FnCallExpr {
                     name: state.get_identifier(id),
@@ -528,6 +931,7 @@ fn parse_fn_call(
                     namespace,
                     hashes,
                     args,
+                    named_args,
                     ..Default::default()
                 }
                 .into_fn_call_expr(settings.pos));
@@ -558,14 +962,26 @@ fn parse_fn_call(
     }
 }
 
+/// Make an error for a positional argument following named arguments in a function call.
+fn positional_after_named_err(id: &str, pos: Position) -> ParseError {
+    PERR::MalformedCallExpr(format!(
+        "Positional arguments must come before named arguments in function call '{}'",
+        id
+    ))
+    .into_err(pos)
+}
+
 /// Parse an indexing chain.
 /// Indexing binds to the right, so this call parses all possible levels of indexing following in the input.
+///
+/// `optional` is `true` if this level of indexing is started by `?[` instead of `[`.
 #[cfg(not(feature = "no_index"))]
 fn parse_index_chain(
     input: &mut TokenStream,
     state: &mut ParseState,
     lib: &mut FunctionsLib,
     lhs: Expr,
+    optional: bool,
     mut settings: ParseSettings,
 ) -> Result<Expr, ParseError> {
     #[cfg(not(feature = "unchecked"))]
@@ -643,6 +1059,10 @@ fn parse_index_chain(
             _ => (),
         },
 
+        // dict[???] - dictionaries can be indexed by any hashable value
+        #[cfg(not(feature = "no_object"))]
+        _ if !lhs.is_constant() => (),
+
         // lhs[float]
         #[cfg(not(feature = "no_float"))]
         x @ Expr::FloatConstant(_, _) => {
@@ -691,21 +1111,31 @@ fn parse_index_chain(
             // Any more indexing following?
             match input.peek().expect(NEVER_ENDS) {
                 // If another indexing level, right-bind it
-                (Token::LeftBracket, _) => {
+                (Token::LeftBracket, _) | (Token::QuestionBracket, _) => {
+                    let (next_token, next_pos) = input.next().expect(NEVER_ENDS);
                     let prev_pos = settings.pos;
-                    settings.pos = eat_token(input, Token::LeftBracket);
+                    settings.pos = next_pos;
+                    let next_optional = next_token == Token::QuestionBracket;
                     // Recursively parse the indexing chain, right-binding each
-                    let idx_expr =
-                        parse_index_chain(input, state, lib, idx_expr, settings.level_up())?;
+                    let idx_expr = parse_index_chain(
+                        input,
+                        state,
+                        lib,
+                        idx_expr,
+                        next_optional,
+                        settings.level_up(),
+                    )?;
                     // Indexing binds to right
                     Ok(Expr::Index(
                         BinaryExpr { lhs, rhs: idx_expr }.into(),
+                        optional,
                         prev_pos,
                     ))
                 }
                 // Otherwise terminate the indexing chain
                 _ => Ok(Expr::Index(
                     BinaryExpr { lhs, rhs: idx_expr }.into(),
+                    optional,
                     settings.pos,
                 )),
             }
@@ -758,6 +1188,12 @@ fn parse_array_literal(
                         .into_err(*pos),
                 )
             }
+            // [ ...expr ]
+            (Token::Spread, _) => {
+                let pos = eat_token(input, Token::Spread);
+                let expr = parse_expr(input, state, lib, settings.level_up())?;
+                arr.push(Expr::Spread(expr.into(), pos));
+            }
             _ => {
                 let expr = parse_expr(input, state, lib, settings.level_up())?;
                 arr.push(expr);
@@ -825,48 +1261,6 @@ fn parse_map_literal(
             _ => (),
         }
 
-        let (name, pos) = match input.next().expect(NEVER_ENDS) {
-            (Token::Identifier(s), pos) | (Token::StringConstant(s), pos) => {
-                if map.iter().any(|(p, _)| p.name == s) {
-                    return Err(PERR::DuplicatedProperty(s).into_err(pos));
-                }
-                (s, pos)
-            }
-            (Token::InterpolatedString(_), pos) => return Err(PERR::PropertyExpected.into_err(pos)),
-            (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
-                return Err(PERR::Reserved(s).into_err(pos));
-            }
-            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
-            (Token::EOF, pos) => {
-                return Err(
-                    PERR::MissingToken(Token::RightBrace.into(), MISSING_RBRACE.into())
-                        .into_err(pos),
-                );
-            }
-            (_, pos) if map.is_empty() => {
-                return Err(
-                    PERR::MissingToken(Token::RightBrace.into(), MISSING_RBRACE.into())
-                        .into_err(pos),
-                );
-            }
-            (_, pos) => return Err(PERR::PropertyExpected.into_err(pos)),
-        };
-
-        match input.next().expect(NEVER_ENDS) {
-            (Token::Colon, _) => (),
-            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
-            (_, pos) => {
-                return Err(PERR::MissingToken(
-                    Token::Colon.into(),
-                    format!(
-                        "to follow the property '{}' in this object map literal",
-                        name
-                    ),
-                )
-                .into_err(pos))
-            }
-        };
-
         #[cfg(not(feature = "unchecked"))]
         if state.engine.max_map_size() > 0 && map.len() >= state.engine.max_map_size() {
             return Err(PERR::LiteralTooLarge(
@@ -876,10 +1270,63 @@ fn parse_map_literal(
             .into_err(input.peek().expect(NEVER_ENDS).1));
         }
 
-        let expr = parse_expr(input, state, lib, settings.level_up())?;
-        let name = state.get_identifier(name);
-        template.insert(name.clone().into(), Default::default());
-        map.push((Ident { name, pos }, expr));
+        if let (true, pos) = match_token(input, Token::Spread) {
+            // #{ ...expr }
+            let expr = parse_expr(input, state, lib, settings.level_up())?;
+            let name = state.get_identifier("");
+            map.push((Ident { name, pos }, Expr::Spread(expr.into(), pos)));
+        } else {
+            let (name, pos) = match input.next().expect(NEVER_ENDS) {
+                (Token::Identifier(s), pos) | (Token::StringConstant(s), pos) => {
+                    if template.contains_key(s.as_str()) {
+                        return Err(PERR::DuplicatedProperty(s).into_err(pos));
+                    }
+                    (s, pos)
+                }
+                (Token::InterpolatedString(_), pos) => {
+                    return Err(PERR::PropertyExpected.into_err(pos))
+                }
+                (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
+                    return Err(PERR::Reserved(s).into_err(pos));
+                }
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (Token::EOF, pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::RightBrace.into(),
+                        MISSING_RBRACE.into(),
+                    )
+                    .into_err(pos));
+                }
+                (_, pos) if map.is_empty() => {
+                    return Err(PERR::MissingToken(
+                        Token::RightBrace.into(),
+                        MISSING_RBRACE.into(),
+                    )
+                    .into_err(pos));
+                }
+                (_, pos) => return Err(PERR::PropertyExpected.into_err(pos)),
+            };
+
+            match input.next().expect(NEVER_ENDS) {
+                (Token::Colon, _) => (),
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (_, pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::Colon.into(),
+                        format!(
+                            "to follow the property '{}' in this object map literal",
+                            name
+                        ),
+                    )
+                    .into_err(pos))
+                }
+            };
+
+            let expr = parse_expr(input, state, lib, settings.level_up())?;
+            let name = state.get_identifier(name);
+            template.insert(name.clone().into(), Default::default());
+            map.push((Ident { name, pos }, expr));
+        }
 
         match input.peek().expect(NEVER_ENDS) {
             (Token::Comma, _) => {
@@ -935,14 +1382,18 @@ fn parse_switch(
         }
     }
 
-    let mut table = BTreeMap::<u64, Box<(Option<Expr>, StmtBlock)>>::new();
+    let mut cases = SwitchCases::default();
     let mut def_pos = Position::NONE;
     let mut def_stmt = None;
 
     loop {
         const MISSING_RBRACE: &str = "to end this switch block";
 
-        let (expr, condition) = match input.peek().expect(NEVER_ENDS) {
+        let orig_stack_len = state.stack.len();
+        #[allow(unused_mut)]
+        let mut patterns = StaticVec::<VariantPattern>::new();
+
+        let (values, condition) = match input.peek().expect(NEVER_ENDS) {
             (Token::RightBrace, _) => {
                 eat_token(input, Token::RightBrace);
                 break;
@@ -968,36 +1419,69 @@ fn parse_switch(
             (Token::Underscore, pos) => return Err(PERR::DuplicatedSwitchCase.into_err(*pos)),
             _ if def_stmt.is_some() => return Err(PERR::WrongSwitchDefaultCase.into_err(def_pos)),
 
+            // if condition => ...
+            (Token::If, _) => {
+                eat_token(input, Token::If);
+                let condition = parse_expr(input, state, lib, settings.level_up())?;
+                (Some(StaticVec::new()), Some(condition))
+            }
+
+            // value | value | ... if condition => ...
             _ => {
-                let case_expr = Some(parse_expr(input, state, lib, settings.level_up())?);
+                let mut values = StaticVec::new();
+
+                loop {
+                    // Variant(field, ...)
+                    #[cfg(not(feature = "no_function"))]
+                    if let (Token::Identifier(_), _) = input.peek().expect(NEVER_ENDS) {
+                        let index = cases.blocks.len();
+                        patterns.push(parse_variant_pattern(input, state, index)?);
+
+                        if !match_token(input, Token::Pipe).0 {
+                            break;
+                        }
+                        continue;
+                    }
+
+                    // Parse up to (but not including) the `|` separator
+                    let value = parse_unary(input, state, lib, settings.level_up())?;
+                    let value = parse_binary_op(
+                        input,
+                        state,
+                        lib,
+                        Token::Pipe.precedence(),
+                        value,
+                        settings.level_up(),
+                    )?;
+                    values.push(value);
+
+                    if !match_token(input, Token::Pipe).0 {
+                        break;
+                    }
+                }
+
+                // Variables bound to the fields of a variant
+                #[cfg(not(feature = "no_function"))]
+                if patterns.iter().any(|p| p.bindings().next().is_some()) {
+                    if values.len() + patterns.len() > 1 {
+                        return Err(PERR::WrongVariantBindings.into_err(patterns[0].pos));
+                    }
+                    for name in patterns[0].bindings() {
+                        state
+                            .stack
+                            .push((name.clone(), AccessMode::ReadWrite, None));
+                    }
+                }
 
                 let condition = if match_token(input, Token::If).0 {
                     Some(parse_expr(input, state, lib, settings.level_up())?)
                 } else {
                     None
                 };
-                (case_expr, condition)
+                (Some(values), condition)
             }
         };
 
-        let hash = if let Some(expr) = expr {
-            if let Some(value) = expr.get_literal_value() {
-                let hasher = &mut get_hasher();
-                value.hash(hasher);
-                let hash = hasher.finish();
-
-                if table.contains_key(&hash) {
-                    return Err(PERR::DuplicatedSwitchCase.into_err(expr.position()));
-                }
-
-                Some(hash)
-            } else {
-                return Err(PERR::ExprExpected("a literal".to_string()).into_err(expr.position()));
-            }
-        } else {
-            None
-        };
-
         match input.next().expect(NEVER_ENDS) {
             (Token::DoubleArrow, _) => (),
             (Token::LexError(err), pos) => return Err(err.into_err(pos)),
@@ -1012,13 +1496,48 @@ fn parse_switch(
 
         let stmt = parse_stmt(input, state, lib, settings.level_up())?;
 
+        state.stack.truncate(orig_stack_len);
+
         let need_comma = !stmt.is_self_terminated();
 
-        def_stmt = if let Some(hash) = hash {
-            table.insert(hash, (condition, stmt.into()).into());
-            None
-        } else {
-            Some(stmt.into())
+        def_stmt = match values {
+            // Pure condition
+            Some(values) if values.is_empty() && patterns.is_empty() => {
+                cases.conditions.push(cases.blocks.len());
+                cases.blocks.push((condition, stmt.into()));
+                None
+            }
+            Some(values) => {
+                let index = cases.blocks.len();
+                cases.blocks.push((condition, stmt.into()));
+                cases.variants.extend(patterns);
+
+                for expr in values {
+                    if let Some((start, end, inclusive)) = expr.get_literal_range() {
+                        cases.ranges.push((start, end, inclusive, index));
+                    } else if let Some(value) = expr.get_literal_value() {
+                        let hash = SwitchCases::hash_value(&value);
+
+                        let blocks = &cases.blocks;
+                        let indices = cases.cases.entry(hash).or_default();
+
+                        // A value with an unconditional case can never reach any later case
+                        if indices.iter().any(|&i| blocks[i].0.is_none()) {
+                            return Err(PERR::DuplicatedSwitchCase.into_err(expr.position()));
+                        }
+
+                        if !indices.contains(&index) {
+                            indices.push(index);
+                        }
+                    } else {
+                        return Err(
+                            PERR::ExprExpected("a literal".to_string()).into_err(expr.position())
+                        );
+                    }
+                }
+                None
+            }
+            None => Some(stmt.into()),
         };
 
         match input.peek().expect(NEVER_ENDS) {
@@ -1044,13 +1563,169 @@ fn parse_switch(
         }
     }
 
-    let def_stmt_block = def_stmt.unwrap_or_else(|| Stmt::Noop(Position::NONE).into());
+    cases.def_case = def_stmt.unwrap_or_else(|| Stmt::Noop(Position::NONE).into());
+    cases.blocks.shrink_to_fit();
+    cases.ranges.shrink_to_fit();
+    cases.variants.shrink_to_fit();
 
-    Ok(Stmt::Switch(
-        item,
-        (table, def_stmt_block).into(),
-        settings.pos,
-    ))
+    Ok(Stmt::Switch(item, cases.into(), settings.pos))
+}
+
+/// Parse a `switch` case matching a variant of an `enum`, e.g. `Shape::Rect(w, _)` or `Empty`.
+///
+/// The variant must be declared by an `enum` parsed so far.  Without the `enum` name, it must be
+/// declared by only one `enum`.
+#[cfg(not(feature = "no_function"))]
+fn parse_variant_pattern(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    index: usize,
+) -> Result<VariantPattern, ParseError> {
+    let (name, pos) = match input.next().expect(NEVER_ENDS) {
+        (Token::Identifier(s), pos) => (s, pos),
+        (token, _) => unreachable!("expecting Token::Identifier, but gets {:?}", token),
+    };
+
+    // Enum::Variant
+    let (def, name) = if match_token(input, Token::DoubleColon).0 {
+        let variant = match input.next().expect(NEVER_ENDS) {
+            (Token::Identifier(s), _) => s,
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::MissingSymbol("Expecting name of variant".into()).into_err(pos))
+            }
+        };
+        let def = state
+            .find_enum(&name)
+            .filter(|def| def.variants.iter().any(|(v, _)| v == &variant))
+            .ok_or_else(|| PERR::UnknownVariant(format!("{}::{}", name, variant)).into_err(pos))?;
+        (def.clone(), variant)
+    } else {
+        let mut defs = state
+            .enums
+            .iter()
+            .filter(|def| def.variants.iter().any(|(v, _)| v == &name));
+
+        match (defs.next(), defs.next()) {
+            (Some(def), None) => (def.clone(), name),
+            (Some(_), Some(_)) => return Err(PERR::AmbiguousVariant(name).into_err(pos)),
+            (None, _) if matches!(input.peek().expect(NEVER_ENDS).0, Token::LeftParen) => {
+                return Err(PERR::UnknownVariant(name).into_err(pos))
+            }
+            (None, _) => return Err(PERR::ExprExpected("a literal".to_string()).into_err(pos)),
+        }
+    };
+
+    let mut fields = StaticVec::new();
+
+    if match_token(input, Token::LeftParen).0 && !match_token(input, Token::RightParen).0 {
+        loop {
+            match input.next().expect(NEVER_ENDS) {
+                (Token::Underscore, _) => fields.push(None),
+                (Token::Identifier(s), pos) => {
+                    if fields.iter().flatten().any(|f| f == &s) {
+                        return Err(PERR::DuplicatedVariable(s).into_err(pos));
+                    }
+                    fields.push(Some(state.get_identifier(s)));
+                }
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+            }
+
+            match input.next().expect(NEVER_ENDS) {
+                (Token::RightParen, _) => break,
+                (Token::Comma, _) => (),
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (_, pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::RightParen.into(),
+                        format!("to close the fields of variant '{}'", name),
+                    )
+                    .into_err(pos))
+                }
+            }
+        }
+    }
+
+    let num_fields = def
+        .variants
+        .iter()
+        .find(|(v, _)| v == &name)
+        .map_or(0, |(_, f)| f.len());
+
+    if fields.len() != num_fields {
+        let name = format!("{}::{}", def.name, name);
+        return Err(PERR::WrongVariantFields(name, num_fields).into_err(pos));
+    }
+
+    Ok(VariantPattern {
+        enum_name: def.name.clone(),
+        name: state.get_identifier(name),
+        fields,
+        index,
+        pos,
+    })
+}
+
+/// Parse the construction of a variant of an `enum`, e.g. `Shape::Rect(3, 4)` or `Shape::Empty`.
+#[cfg(not(feature = "no_function"))]
+fn parse_enum_variant(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    lib: &mut FunctionsLib,
+    def: Shared<EnumDef>,
+    settings: ParseSettings,
+) -> Result<Expr, ParseError> {
+    #[cfg(not(feature = "unchecked"))]
+    settings.ensure_level_within_max_limit(state.max_expr_depth)?;
+
+    // Enum::Variant ...
+    eat_token(input, Token::DoubleColon);
+
+    let (variant, pos) = match input.next().expect(NEVER_ENDS) {
+        (Token::Identifier(s), pos) => (s, pos),
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => {
+            return Err(PERR::MissingSymbol("Expecting name of variant".into()).into_err(pos))
+        }
+    };
+
+    let (index, num_fields) = match def.variants.iter().position(|(v, _)| v == &variant) {
+        Some(index) => (index, def.variants[index].1.len()),
+        None => {
+            let name = format!("{}::{}", def.name, variant);
+            return Err(PERR::UnknownVariant(name).into_err(pos));
+        }
+    };
+
+    // Enum::Variant(field, ...)
+    let mut args = StaticVec::new();
+
+    if match_token(input, Token::LeftParen).0 && !match_token(input, Token::RightParen).0 {
+        loop {
+            args.push(parse_expr(input, state, lib, settings.level_up())?);
+
+            match input.next().expect(NEVER_ENDS) {
+                (Token::RightParen, _) => break,
+                (Token::Comma, _) => (),
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (_, pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::RightParen.into(),
+                        format!("to close the fields of variant '{}'", variant),
+                    )
+                    .into_err(pos))
+                }
+            }
+        }
+    }
+
+    if args.len() != num_fields {
+        let name = format!("{}::{}", def.name, variant);
+        return Err(PERR::WrongVariantFields(name, num_fields).into_err(pos));
+    }
+
+    Ok(Expr::EnumVariant((def, index, args).into(), settings.pos))
 }
 
 /// Parse a primary expression.
@@ -1095,6 +1770,12 @@ fn parse_primary(
             input.next().expect(NEVER_ENDS);
             Expr::DynamicConstant(Box::new(x), settings.pos)
         }
+        #[cfg(feature = "bigint")]
+        Token::BigIntConstant(x) => {
+            let x = x.clone().into();
+            input.next().expect(NEVER_ENDS);
+            Expr::DynamicConstant(Box::new(x), settings.pos)
+        }
 
         // { - block statement as expression
         Token::LeftBrace if settings.allow_stmt_expr => {
@@ -1119,6 +1800,7 @@ fn parse_primary(
         #[cfg(not(feature = "no_function"))]
         Token::Pipe | Token::Or if settings.allow_anonymous_fn => {
             let mut new_state = ParseState::new(state.engine, state.tokenizer_control.clone());
+            new_state.enums = state.enums.clone();
 
             #[cfg(not(feature = "unchecked"))]
             {
@@ -1129,7 +1811,9 @@ fn parse_primary(
                 allow_if_expr: true,
                 allow_switch_expr: true,
                 allow_stmt_expr: true,
+                allow_format_spec: false,
                 allow_anonymous_fn: true,
+                allow_yield: true,
                 is_global: false,
                 is_function_scope: true,
                 is_breakable: false,
@@ -1137,13 +1821,29 @@ fn parse_primary(
                 pos: settings.pos,
             };
 
-            let (expr, func) = parse_anon_fn(input, &mut new_state, lib, settings)?;
+            #[allow(unused_mut)]
+            let (expr, mut func) = parse_anon_fn(input, &mut new_state, lib, settings)?;
 
             #[cfg(not(feature = "no_closure"))]
             new_state.external_vars.iter().for_each(|(closure, &pos)| {
                 state.access_var(closure, pos);
             });
 
+            // Captured variables keep their declared types
+            #[cfg(not(feature = "no_closure"))]
+            if new_state
+                .external_vars
+                .keys()
+                .any(|name| state.find_var_type(name).is_some())
+            {
+                func.param_types = new_state
+                    .external_vars
+                    .keys()
+                    .map(|name| state.find_var_type(name).cloned())
+                    .collect();
+                func.param_types.resize(func.params.len(), None);
+            }
+
             let hash_script = calc_fn_hash(&func.name, func.params.len());
             lib.insert(hash_script, func.into());
 
@@ -1161,7 +1861,10 @@ fn parse_primary(
             }
 
             loop {
-                let expr = match parse_block(input, state, lib, settings.level_up())? {
+                let mut settings = settings.level_up();
+                settings.allow_format_spec = true;
+
+                let expr = match parse_block(input, state, lib, settings)? {
                     block @ Stmt::Block(_, _) => Expr::Stmt(Box::new(block.into())),
                     stmt => unreachable!("expecting Stmt::Block, but gets {:?}", stmt),
                 };
@@ -1228,6 +1931,20 @@ fn parse_primary(
                         (None, None, state.get_identifier(s)).into(),
                     )
                 }
+                // Variant of an enum
+                #[cfg(not(feature = "no_function"))]
+                Token::DoubleColon if state.find_enum(&s).is_some() => {
+                    #[cfg(not(feature = "no_closure"))]
+                    {
+                        // Once the identifier consumed we must enable next variables capturing
+                        state.allow_capture = true;
+                    }
+                    let def = state
+                        .find_enum(&s)
+                        .cloned()
+                        .expect("never fails because the enum exists");
+                    parse_enum_variant(input, state, lib, def, settings.level_up())?
+                }
                 // Namespace qualification
                 #[cfg(not(feature = "no_module"))]
                 Token::DoubleColon => {
@@ -1375,12 +2092,13 @@ fn parse_primary(
             }
             // Indexing
             #[cfg(not(feature = "no_index"))]
-            (expr, Token::LeftBracket) => {
-                parse_index_chain(input, state, lib, expr, settings.level_up())?
+            (expr, token @ Token::LeftBracket) | (expr, token @ Token::QuestionBracket) => {
+                let optional = token == Token::QuestionBracket;
+                parse_index_chain(input, state, lib, expr, optional, settings.level_up())?
             }
             // Property access
             #[cfg(not(feature = "no_object"))]
-            (expr, Token::Period) => {
+            (expr, token @ Token::Period) | (expr, token @ Token::Elvis) => {
                 // Expression after dot must start with an identifier
                 match input.peek().expect(NEVER_ENDS) {
                     (Token::Identifier(_), _) => {
@@ -1395,8 +2113,9 @@ fn parse_primary(
                 }
 
                 let rhs = parse_primary(input, state, lib, settings.level_up())?;
+                let optional = token == Token::Elvis;
 
-                make_dot_expr(state, expr, rhs, tail_pos)?
+                make_dot_expr(state, expr, rhs, optional, tail_pos)?
             }
             // Unknown postfix operator
             (expr, token) => unreachable!(
@@ -1410,7 +2129,7 @@ fn parse_primary(
     // Cache the hash key for namespace-qualified variables
     match root_expr {
         Expr::Variable(_, _, ref mut x) if x.1.is_some() => Some(x.as_mut()),
-        Expr::Index(ref mut x, _) | Expr::Dot(ref mut x, _) => match x.lhs {
+        Expr::Index(ref mut x, _, _) | Expr::Dot(ref mut x, _, _) => match x.lhs {
             Expr::Variable(_, _, ref mut x) if x.1.is_some() => Some(x.as_mut()),
             _ => None,
         },
@@ -1539,13 +2258,15 @@ fn make_assignment_stmt<'a>(
     #[must_use]
     fn check_lvalue(expr: &Expr, parent_is_dot: bool) -> Option<Position> {
         match expr {
-            Expr::Index(x, _) | Expr::Dot(x, _) if parent_is_dot => match x.lhs {
-                Expr::Property(_) => check_lvalue(&x.rhs, matches!(expr, Expr::Dot(_, _))),
+            // Optional chains cannot be assigned to
+            Expr::Index(_, true, pos) | Expr::Dot(_, true, pos) => Some(*pos),
+            Expr::Index(x, _, _) | Expr::Dot(x, _, _) if parent_is_dot => match x.lhs {
+                Expr::Property(_) => check_lvalue(&x.rhs, matches!(expr, Expr::Dot(_, _, _))),
                 ref e => Some(e.position()),
             },
-            Expr::Index(x, _) | Expr::Dot(x, _) => match x.lhs {
+            Expr::Index(x, _, _) | Expr::Dot(x, _, _) => match x.lhs {
                 Expr::Property(_) => unreachable!("unexpected Expr::Property in indexing"),
-                _ => check_lvalue(&x.rhs, matches!(expr, Expr::Dot(_, _))),
+                _ => check_lvalue(&x.rhs, matches!(expr, Expr::Dot(_, _, _))),
             },
             Expr::Property(_) if parent_is_dot => None,
             Expr::Property(_) => unreachable!("unexpected Expr::Property in indexing"),
@@ -1586,9 +2307,13 @@ fn make_assignment_stmt<'a>(
                 }
             }
         }
+        // xxx?[???]... = rhs, xxx?.prop... = rhs
+        Expr::Index(_, true, pos) | Expr::Dot(_, true, pos) => {
+            Err(PERR::AssignmentToInvalidLHS("".to_string()).into_err(pos))
+        }
         // xxx[???]... = rhs, xxx.prop... = rhs
-        Expr::Index(ref x, _) | Expr::Dot(ref x, _) => {
-            match check_lvalue(&x.rhs, matches!(lhs, Expr::Dot(_, _))) {
+        Expr::Index(ref x, _, _) | Expr::Dot(ref x, _, _) => {
+            match check_lvalue(&x.rhs, matches!(lhs, Expr::Dot(_, _, _))) {
                 None => match x.lhs {
                     // var[???] = rhs, var.??? = rhs
                     Expr::Variable(_, _, _) => {
@@ -1602,8 +2327,8 @@ fn make_assignment_stmt<'a>(
                 Some(pos) => Err(PERR::AssignmentToInvalidLHS("".to_string()).into_err(pos)),
             }
         }
-        // ??? && ??? = rhs, ??? || ??? = rhs
-        Expr::And(_, _) | Expr::Or(_, _) => Err(LexError::ImproperSymbol(
+        // ??? && ??? = rhs, ??? || ??? = rhs, ??? ?? ??? = rhs
+        Expr::And(_, _) | Expr::Or(_, _) | Expr::Coalesce(_, _) => Err(LexError::ImproperSymbol(
             "=".to_string(),
             "Possibly a typo of '=='?".to_string(),
         )
@@ -1641,19 +2366,22 @@ fn parse_op_assignment_stmt(
 }
 
 /// Make a dot expression.
+///
+/// `optional` is `true` for optional chaining (`?.`).
 #[cfg(not(feature = "no_object"))]
 fn make_dot_expr(
     state: &mut ParseState,
     lhs: Expr,
     rhs: Expr,
+    optional: bool,
     op_pos: Position,
 ) -> Result<Expr, ParseError> {
     Ok(match (lhs, rhs) {
         // idx_lhs[idx_expr].rhs
         // Attach dot chain to the bottom level of indexing chain
-        (Expr::Index(mut x, pos), rhs) => {
-            x.rhs = make_dot_expr(state, x.rhs, rhs, op_pos)?;
-            Expr::Index(x, pos)
+        (Expr::Index(mut x, opt, pos), rhs) => {
+            x.rhs = make_dot_expr(state, x.rhs, rhs, optional, op_pos)?;
+            Expr::Index(x, opt, pos)
         }
         // lhs.id
         (lhs, Expr::Variable(_, var_pos, x)) if x.1.is_none() => {
@@ -1669,7 +2397,7 @@ fn make_dot_expr(
                 (state.get_identifier(ident).into(), var_pos),
             )));
 
-            Expr::Dot(BinaryExpr { lhs, rhs }.into(), op_pos)
+            Expr::Dot(BinaryExpr { lhs, rhs }.into(), optional, op_pos)
         }
         // lhs.module::id - syntax error
         (_, Expr::Variable(_, _, x)) if x.1.is_some() => {
@@ -1677,9 +2405,11 @@ fn make_dot_expr(
                 .into_err(x.1.expect("never fails because the namespace is `Some`").0[0].pos))
         }
         // lhs.prop
-        (lhs, prop @ Expr::Property(_)) => Expr::Dot(BinaryExpr { lhs, rhs: prop }.into(), op_pos),
+        (lhs, prop @ Expr::Property(_)) => {
+            Expr::Dot(BinaryExpr { lhs, rhs: prop }.into(), optional, op_pos)
+        }
         // lhs.dot_lhs.dot_rhs
-        (lhs, Expr::Dot(x, pos)) => match x.lhs {
+        (lhs, Expr::Dot(x, opt, pos)) => match x.lhs {
             Expr::Variable(_, _, _) | Expr::Property(_) => {
                 let rhs = Expr::Dot(
                     BinaryExpr {
@@ -1687,9 +2417,24 @@ fn make_dot_expr(
                         rhs: x.rhs,
                     }
                     .into(),
+                    opt,
                     pos,
                 );
-                Expr::Dot(BinaryExpr { lhs, rhs }.into(), op_pos)
+                Expr::Dot(BinaryExpr { lhs, rhs }.into(), optional, op_pos)
+            }
+            Expr::FnCall(func, func_pos) if !func.named_args.is_empty() => {
+                return Err(PERR::MalformedCallExpr(
+                    "method-call style does not support named arguments".into(),
+                )
+                .into_err(func_pos))
+            }
+            Expr::FnCall(func, func_pos)
+                if func.args.iter().any(|a| matches!(a, Expr::Spread(_, _))) =>
+            {
+                return Err(PERR::MalformedCallExpr(
+                    "method-call style does not support spread arguments".into(),
+                )
+                .into_err(func_pos))
             }
             Expr::FnCall(mut func, func_pos) => {
                 // Recalculate hash
@@ -1704,23 +2449,25 @@ fn make_dot_expr(
                         rhs: x.rhs,
                     }
                     .into(),
+                    opt,
                     pos,
                 );
-                Expr::Dot(BinaryExpr { lhs, rhs }.into(), op_pos)
+                Expr::Dot(BinaryExpr { lhs, rhs }.into(), optional, op_pos)
             }
             _ => unreachable!("invalid dot expression: {:?}", x.lhs),
         },
         // lhs.idx_lhs[idx_rhs]
-        (lhs, Expr::Index(x, pos)) => {
+        (lhs, Expr::Index(x, opt, pos)) => {
             let rhs = Expr::Index(
                 BinaryExpr {
                     lhs: x.lhs.into_property(state),
                     rhs: x.rhs,
                 }
                 .into(),
+                opt,
                 pos,
             );
-            Expr::Dot(BinaryExpr { lhs, rhs }.into(), op_pos)
+            Expr::Dot(BinaryExpr { lhs, rhs }.into(), optional, op_pos)
         }
         // lhs.nnn::func(...)
         (_, Expr::FnCall(x, _)) if x.is_qualified() => {
@@ -1748,6 +2495,20 @@ fn make_dot_expr(
             )
             .into_err(pos))
         }
+        // lhs.func(..., name: expr)
+        (_, Expr::FnCall(x, pos)) if !x.named_args.is_empty() => {
+            return Err(PERR::MalformedCallExpr(
+                "method-call style does not support named arguments".into(),
+            )
+            .into_err(pos))
+        }
+        // lhs.func(..., ...expr)
+        (_, Expr::FnCall(x, pos)) if x.args.iter().any(|a| matches!(a, Expr::Spread(_, _))) => {
+            return Err(PERR::MalformedCallExpr(
+                "method-call style does not support spread arguments".into(),
+            )
+            .into_err(pos))
+        }
         // lhs.func(...)
         (lhs, Expr::FnCall(mut func, func_pos)) => {
             // Recalculate hash
@@ -1756,7 +2517,7 @@ fn make_dot_expr(
                 calc_fn_hash(&func.name, func.args.len() + 1),
             );
             let rhs = Expr::FnCall(func, func_pos);
-            Expr::Dot(BinaryExpr { lhs, rhs }.into(), op_pos)
+            Expr::Dot(BinaryExpr { lhs, rhs }.into(), optional, op_pos)
         }
         // lhs.rhs
         (_, rhs) => return Err(PERR::PropertyExpected.into_err(rhs.position())),
@@ -1862,6 +2623,11 @@ fn parse_binary_op(
             | Token::Pipe
             | Token::XOr => FnCallExpr { args, ..op_base }.into_fn_call_expr(pos),
 
+            // Range operators
+            Token::ExclusiveRange | Token::InclusiveRange => {
+                FnCallExpr { args, ..op_base }.into_fn_call_expr(pos)
+            }
+
             // '!=' defaults to true when passed invalid operands
             Token::NotEqualsTo => FnCallExpr { args, ..op_base }.into_fn_call_expr(pos),
 
@@ -1904,6 +2670,22 @@ fn parse_binary_op(
                     pos,
                 )
             }
+            Token::DoubleQuestion => {
+                let rhs = args
+                    .pop()
+                    .expect("never fails because `??` has two arguments");
+                let current_lhs = args
+                    .pop()
+                    .expect("never fails because `??` has two arguments");
+                Expr::Coalesce(
+                    BinaryExpr {
+                        lhs: current_lhs,
+                        rhs,
+                    }
+                    .into(),
+                    pos,
+                )
+            }
             Token::In => {
                 // Swap the arguments
                 let current_lhs = args.remove(0);
@@ -1965,7 +2747,7 @@ fn parse_custom_syntax(
         // Add a barrier variable to the stack so earlier variables will not be matched.
         // Variable searches stop at the first barrier.
         let empty = state.get_identifier(SCOPE_SEARCH_BARRIER_MARKER);
-        state.stack.push((empty, AccessMode::ReadWrite));
+        state.stack.push((empty, AccessMode::ReadWrite, None));
     }
 
     let parse_func = syntax.parse.as_ref();
@@ -2104,6 +2886,12 @@ fn parse_expr(
 
     settings.pos = input.peek().expect(NEVER_ENDS).1;
 
+    // `yield` cannot be suspended in the middle of an expression
+    #[cfg(not(feature = "no_function"))]
+    {
+        settings.allow_yield = false;
+    }
+
     // Check if it is a custom syntax.
     if !state.engine.custom_syntax.is_empty() {
         let (token, pos) = input.peek().expect(NEVER_ENDS);
@@ -2261,37 +3049,57 @@ fn parse_for(
     settings.pos = eat_token(input, Token::For);
 
     // for name ...
-    let (name, name_pos, counter_name, counter_pos) = if match_token(input, Token::LeftParen).0 {
-        // ( name, counter )
-        let (name, name_pos) = parse_var_name(input)?;
-        let (has_comma, pos) = match_token(input, Token::Comma);
-        if !has_comma {
-            return Err(PERR::MissingToken(
-                Token::Comma.into(),
-                "after the iteration variable name".into(),
-            )
-            .into_err(pos));
+    let parse_loop_var = |input: &mut TokenStream, state: &mut ParseState| {
+        if is_pattern_start(input) {
+            let pattern = parse_pattern(input, state, settings.level_up())?;
+            Ok((pattern.to_string(), pattern.position(), Some(pattern)))
+        } else {
+            parse_var_name(input).map(|(name, pos)| (name, pos, None))
         }
-        let (counter_name, counter_pos) = parse_var_name(input)?;
+    };
 
-        if counter_name == name {
-            return Err(PERR::DuplicatedVariable(counter_name).into_err(counter_pos));
-        }
+    let (name, name_pos, pattern, counter_name, counter_pos) =
+        if match_token(input, Token::LeftParen).0 {
+            // ( name, counter )
+            let (name, name_pos, pattern) = parse_loop_var(input, state)?;
+            let (has_comma, pos) = match_token(input, Token::Comma);
+            if !has_comma {
+                return Err(PERR::MissingToken(
+                    Token::Comma.into(),
+                    "after the iteration variable name".into(),
+                )
+                .into_err(pos));
+            }
+            let (counter_name, counter_pos) = parse_var_name(input)?;
 
-        let (has_close_paren, pos) = match_token(input, Token::RightParen);
-        if !has_close_paren {
-            return Err(PERR::MissingToken(
-                Token::RightParen.into(),
-                "to close the iteration variable".into(),
+            let mut is_duplicated = counter_name == name;
+            if let Some(ref pattern) = pattern {
+                pattern.for_each_var(&mut |v| is_duplicated |= v.name == counter_name);
+            }
+            if is_duplicated {
+                return Err(PERR::DuplicatedVariable(counter_name).into_err(counter_pos));
+            }
+
+            let (has_close_paren, pos) = match_token(input, Token::RightParen);
+            if !has_close_paren {
+                return Err(PERR::MissingToken(
+                    Token::RightParen.into(),
+                    "to close the iteration variable".into(),
+                )
+                .into_err(pos));
+            }
+            (
+                name,
+                name_pos,
+                pattern,
+                Some(counter_name),
+                Some(counter_pos),
             )
-            .into_err(pos));
-        }
-        (name, name_pos, Some(counter_name), Some(counter_pos))
-    } else {
-        // name
-        let (name, name_pos) = parse_var_name(input)?;
-        (name, name_pos, None, None)
-    };
+        } else {
+            // name
+            let (name, name_pos, pattern) = parse_loop_var(input, state)?;
+            (name, name_pos, pattern, None, None)
+        };
 
     // for name in ...
     match input.next().expect(NEVER_ENDS) {
@@ -2315,16 +3123,27 @@ fn parse_for(
         let counter_var = state.get_identifier(name);
         state
             .stack
-            .push((counter_var.clone(), AccessMode::ReadWrite));
+            .push((counter_var.clone(), AccessMode::ReadWrite, None));
         Some(counter_var)
     } else {
         None
     };
     let loop_var = state.get_identifier(name);
-    state.stack.push((loop_var.clone(), AccessMode::ReadWrite));
+    state
+        .stack
+        .push((loop_var.clone(), AccessMode::ReadWrite, None));
+
+    // for [pattern] in expr { body } -> for hidden in expr { let [pattern] = hidden; body }
+    let destructure = pattern.map(|pattern| {
+        make_destructure_stmt(state, loop_var.clone(), pattern, AccessMode::ReadWrite)
+    });
 
     settings.is_breakable = true;
-    let body = parse_block(input, state, lib, settings.level_up())?;
+    let mut body: StmtBlock = parse_block(input, state, lib, settings.level_up())?.into();
+
+    if let Some(stmt) = destructure {
+        body.statements_mut().insert(0, stmt);
+    }
 
     state.stack.truncate(prev_stack_len);
 
@@ -2339,7 +3158,7 @@ fn parse_for(
                 name,
                 pos: counter_pos.expect("never fails because `counter_var` is `Some`"),
             }),
-            body.into(),
+            body,
         )),
         settings.pos,
     ))
@@ -2360,6 +3179,33 @@ fn parse_let(
     // let/const... (specified in `var_type`)
     settings.pos = input.next().expect(NEVER_ENDS).1;
 
+    // let pattern = ...
+    if is_pattern_start(input) {
+        let pattern = parse_pattern(input, state, settings.level_up())?;
+
+        let expr = match input.next().expect(NEVER_ENDS) {
+            (Token::Equals, _) => parse_expr(input, state, lib, settings.level_up())?,
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::Equals.into(),
+                    "to provide a value to destructure".into(),
+                )
+                .into_err(pos))
+            }
+        };
+
+        pattern.for_each_var(&mut |v| state.stack.push((v.name.clone(), var_type, None)));
+
+        return Ok(Stmt::Destructure(
+            expr,
+            pattern.into(),
+            var_type,
+            export,
+            settings.pos,
+        ));
+    }
+
     // let name ...
     let (name, pos) = parse_var_name(input)?;
 
@@ -2369,6 +3215,13 @@ fn parse_let(
         pos,
     };
 
+    // let name: type ...
+    let typ = if match_token(input, Token::Colon).0 {
+        Some(parse_type_annotation(input, state)?)
+    } else {
+        None
+    };
+
     // let name = ...
     let expr = if match_token(input, Token::Equals).0 {
         // let name = expr
@@ -2377,13 +3230,30 @@ fn parse_let(
         Expr::Unit(Position::NONE)
     };
 
-    state.stack.push((name, var_type));
+    if let (Some(typ), Some(value)) = (&typ, expr.get_literal_value()) {
+        let pos = if expr.position().is_none() {
+            pos
+        } else {
+            expr.position()
+        };
+        check_constant_type(
+            state,
+            &value,
+            typ,
+            || format!("for variable '{}'", var_def.name),
+            pos,
+        )?;
+    }
+
+    state.stack.push((name, var_type, typ.clone()));
+
+    let var_def = (var_def, typ).into();
 
     match var_type {
         // let name = expr
-        AccessMode::ReadWrite => Ok(Stmt::Let(expr, var_def.into(), export, settings.pos)),
+        AccessMode::ReadWrite => Ok(Stmt::Let(expr, var_def, export, settings.pos)),
         // const name = { expr:constant }
-        AccessMode::ReadOnly => Ok(Stmt::Const(expr, var_def.into(), export, settings.pos)),
+        AccessMode::ReadOnly => Ok(Stmt::Const(expr, var_def, export, settings.pos)),
     }
 }
 
@@ -2525,6 +3395,9 @@ fn parse_block(
 
     let mut statements = Vec::with_capacity(8);
 
+    let allow_format_spec = settings.allow_format_spec;
+    settings.allow_format_spec = false;
+
     let prev_entry_stack_len = state.entry_stack_len;
     state.entry_stack_len = state.stack.len();
 
@@ -2568,6 +3441,58 @@ fn parse_block(
                 eat_token(input, Token::RightBrace);
                 break;
             }
+            // ${ ... stmt:spec }
+            (Token::Colon, _) if allow_format_spec => {
+                let pos = eat_token(input, Token::Colon);
+
+                // Make sure to read the following as a format specifier
+                let mut control = state.tokenizer_control.get();
+                control.is_within_format_spec = true;
+                state.tokenizer_control.set(control);
+
+                let spec = match input.next().expect(NEVER_ENDS) {
+                    (Token::StringConstant(s), _) => s,
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (token, _) => unreachable!(
+                        "expected a format specifier within an interpolated string literal, but gets {:?}",
+                        token
+                    ),
+                };
+
+                // Turn the value into a call to `format`
+                let value = match statements
+                    .pop()
+                    .expect("never fails because the block has statements")
+                {
+                    Stmt::Expr(expr) => expr,
+                    stmt => Expr::Stmt(Box::new(stmt.into())),
+                };
+
+                let mut args = StaticVec::new();
+                args.push(Expr::StringConstant(format!("{{:{}}}", spec).into(), pos));
+                args.push(value);
+
+                let expr = FnCallExpr {
+                    name: state.get_identifier(FUNC_FORMAT),
+                    hashes: FnCallHashes::from_script(calc_fn_hash(FUNC_FORMAT, 2)),
+                    args,
+                    ..Default::default()
+                }
+                .into_fn_call_expr(pos);
+
+                statements.push(Stmt::Expr(expr));
+
+                match input.next().expect(NEVER_ENDS) {
+                    (Token::RightBrace, _) => break,
+                    (_, pos) => {
+                        return Err(PERR::MissingToken(
+                            Token::RightBrace.into(),
+                            "to terminate this interpolation".into(),
+                        )
+                        .into_err(pos))
+                    }
+                }
+            }
             // { ... stmt;
             (Token::SemiColon, _) if need_semicolon => {
                 eat_token(input, Token::SemiColon);
@@ -2687,6 +3612,12 @@ fn parse_stmt(
         #[cfg(not(feature = "no_function"))]
         Token::Fn if !settings.is_global => Err(PERR::WrongFnDefinition.into_err(settings.pos)),
 
+        // enum ...
+        #[cfg(not(feature = "no_function"))]
+        Token::Enum if !settings.is_global => Err(PERR::WrongEnumDefinition.into_err(settings.pos)),
+        #[cfg(not(feature = "no_function"))]
+        Token::Enum => parse_enum(input, state),
+
         #[cfg(not(feature = "no_function"))]
         Token::Fn | Token::Private => {
             let access = if matches!(token, Token::Private) {
@@ -2700,6 +3631,7 @@ fn parse_stmt(
                 (Token::Fn, pos) => {
                     let mut new_state =
                         ParseState::new(state.engine, state.tokenizer_control.clone());
+                    new_state.enums = state.enums.clone();
 
                     #[cfg(not(feature = "unchecked"))]
                     {
@@ -2710,7 +3642,9 @@ fn parse_stmt(
                         allow_if_expr: true,
                         allow_switch_expr: true,
                         allow_stmt_expr: true,
+                        allow_format_spec: false,
                         allow_anonymous_fn: true,
+                        allow_yield: true,
                         is_global: false,
                         is_function_scope: true,
                         is_breakable: false,
@@ -2731,10 +3665,23 @@ fn parse_stmt(
 
                     let hash = calc_fn_hash(&func.name, func.params.len());
 
-                    if lib.contains_key(&hash) {
+                    // Functions with default parameter values must not overlap in the
+                    // numbers of arguments they can be called with
+                    let num_required = func.num_required_params();
+
+                    if let Some(num_params) =
+                        lib.values().filter(|f| f.name == func.name).find_map(|f| {
+                            let n = f.num_required_params().max(num_required);
+                            if f.accepts_num_args(n) && func.accepts_num_args(n) {
+                                Some(n)
+                            } else {
+                                None
+                            }
+                        })
+                    {
                         return Err(PERR::FnDuplicatedDefinition(
                             func.name.to_string(),
-                            func.params.len(),
+                            num_params,
                         )
                         .into_err(pos));
                     }
@@ -2800,6 +3747,26 @@ fn parse_stmt(
             }
         }
 
+        #[cfg(not(feature = "no_function"))]
+        Token::Yield if !settings.allow_yield => Err(PERR::WrongYield.into_err(settings.pos)),
+
+        #[cfg(not(feature = "no_function"))]
+        Token::Yield => {
+            let pos = eat_token(input, Token::Yield);
+
+            match input.peek().expect(NEVER_ENDS) {
+                // `yield` at end of block
+                (Token::RightBrace, _) => Ok(Stmt::Yield(None, pos)),
+                // `yield;`
+                (Token::SemiColon, _) => Ok(Stmt::Yield(None, pos)),
+                // `yield` with expression
+                (_, _) => {
+                    let expr = parse_expr(input, state, lib, settings.level_up())?;
+                    Ok(Stmt::Yield(Some(expr), pos))
+                }
+            }
+        }
+
         Token::Try => parse_try_catch(input, state, lib, settings.level_up()),
 
         Token::Let => parse_let(input, state, lib, ReadWrite, false, settings.level_up()),
@@ -2831,47 +3798,189 @@ fn parse_try_catch(
     // try ...
     settings.pos = eat_token(input, Token::Try);
 
+    #[cfg(not(feature = "no_function"))]
+    {
+        settings.allow_yield = false;
+    }
+
     // try { body }
     let body = parse_block(input, state, lib, settings.level_up())?;
 
-    // try { body } catch
-    let (matched, catch_pos) = match_token(input, Token::Catch);
+    // try { body } catch ...
+    let mut catch_clauses = StaticVec::new();
 
-    if !matched {
-        return Err(
-            PERR::MissingToken(Token::Catch.into(), "for the 'try' statement".into())
-                .into_err(catch_pos),
-        );
-    }
+    while match_token(input, Token::Catch).0 {
+        let prev_stack_len = state.stack.len();
 
-    // try { body } catch (
-    let var_def = if match_token(input, Token::LeftParen).0 {
-        let (name, pos) = parse_var_name(input)?;
-        let (matched, err_pos) = match_token(input, Token::RightParen);
+        // try { body } catch (
+        let (var, condition) = if match_token(input, Token::LeftParen).0 {
+            let (name, pos) = parse_var_name(input)?;
+            let (matched, err_pos) = match_token(input, Token::RightParen);
 
-        if !matched {
-            return Err(PERR::MissingToken(
-                Token::RightParen.into(),
-                "to enclose the catch variable".into(),
-            )
-            .into_err(err_pos));
-        }
+            if !matched {
+                return Err(PERR::MissingToken(
+                    Token::RightParen.into(),
+                    "to enclose the catch variable".into(),
+                )
+                .into_err(err_pos));
+            }
+
+            let name = state.get_identifier(name);
+            state
+                .stack
+                .push((name.clone(), AccessMode::ReadWrite, None));
+
+            // try { body } catch ( var ) if condition
+            let condition = if match_token(input, Token::If).0 {
+                ensure_not_statement_expr(input, "a boolean")?;
+                Some(parse_expr(input, state, lib, settings.level_up())?.ensure_bool_expr()?)
+            } else {
+                None
+            };
 
-        let name = state.get_identifier(name);
-        Some(Ident { name, pos })
+            (Some(Ident { name, pos }), condition)
+        } else {
+            (None, None)
+        };
+
+        // try { body } catch ( var ) { catch_block }
+        let catch_body = parse_block(input, state, lib, settings.level_up())?;
+
+        state.stack.truncate(prev_stack_len);
+
+        catch_clauses.push(CatchClause {
+            var,
+            condition,
+            body: catch_body.into(),
+        });
+    }
+
+    // try { body } ... finally { finally_block }
+    let finally_body = if match_token(input, Token::Finally).0 {
+        parse_block(input, state, lib, settings.level_up())?.into()
+    } else if catch_clauses.is_empty() {
+        let (_, pos) = input.peek().expect(NEVER_ENDS);
+        return Err(
+            PERR::MissingToken(Token::Catch.into(), "for the 'try' statement".into())
+                .into_err(*pos),
+        );
     } else {
-        None
+        Default::default()
     };
 
-    // try { body } catch ( var ) { catch_block }
-    let catch_body = parse_block(input, state, lib, settings.level_up())?;
-
     Ok(Stmt::TryCatch(
-        (body.into(), var_def, catch_body.into()).into(),
+        TryCatchBlock {
+            try_block: body.into(),
+            catch_clauses,
+            finally_block: finally_body,
+        }
+        .into(),
         settings.pos,
     ))
 }
 
+/// Parse an `enum` declaration, making its variants available to the rest of the script.
+#[cfg(not(feature = "no_function"))]
+fn parse_enum(input: &mut TokenStream, state: &mut ParseState) -> Result<Stmt, ParseError> {
+    // enum ...
+    let pos = eat_token(input, Token::Enum);
+
+    let name = match input.next().expect(NEVER_ENDS) {
+        (Token::Identifier(s), pos) if state.find_enum(&s).is_some() => {
+            return Err(PERR::EnumDuplicatedDefinition(s).into_err(pos))
+        }
+        (Token::Identifier(s), _) => state.get_identifier(s),
+        (Token::Reserved(s), pos) => return Err(PERR::Reserved(s).into_err(pos)),
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => return Err(PERR::MissingSymbol("Expecting name of enum".into()).into_err(pos)),
+    };
+
+    match input.next().expect(NEVER_ENDS) {
+        (Token::LeftBrace, _) => (),
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => {
+            return Err(PERR::MissingToken(
+                Token::LeftBrace.into(),
+                format!("to start the variants of enum '{}'", name),
+            )
+            .into_err(pos))
+        }
+    }
+
+    let mut variants = StaticVec::<(Identifier, StaticVec<Identifier>)>::new();
+
+    loop {
+        // Variant(field, ...)
+        let (variant, variant_pos) = match input.next().expect(NEVER_ENDS) {
+            (Token::RightBrace, _) => break,
+            (Token::Identifier(s), pos) => (state.get_identifier(s), pos),
+            (Token::Reserved(s), pos) => return Err(PERR::Reserved(s).into_err(pos)),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::MissingSymbol("Expecting name of variant".into()).into_err(pos))
+            }
+        };
+
+        let mut fields = StaticVec::new();
+
+        if match_token(input, Token::LeftParen).0 && !match_token(input, Token::RightParen).0 {
+            loop {
+                match input.next().expect(NEVER_ENDS) {
+                    (Token::Identifier(s), pos) => {
+                        if fields.iter().any(|f| f == &s) {
+                            return Err(
+                                PERR::FnDuplicatedParam(variant.to_string(), s).into_err(pos)
+                            );
+                        }
+                        fields.push(state.get_identifier(s));
+                    }
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+                }
+
+                match input.next().expect(NEVER_ENDS) {
+                    (Token::RightParen, _) => break,
+                    (Token::Comma, _) => (),
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, pos) => {
+                        return Err(PERR::MissingToken(
+                            Token::RightParen.into(),
+                            format!("to close the fields of variant '{}'", variant),
+                        )
+                        .into_err(pos))
+                    }
+                }
+            }
+        }
+
+        if variants.iter().any(|(v, _)| v == &variant) {
+            return Err(
+                PERR::EnumDuplicatedVariant(name.to_string(), variant.to_string())
+                    .into_err(variant_pos),
+            );
+        }
+
+        variants.push((variant, fields));
+
+        match input.next().expect(NEVER_ENDS) {
+            (Token::RightBrace, _) => break,
+            (Token::Comma, _) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::Comma.into(),
+                    format!("to separate the variants of enum '{}'", name),
+                )
+                .into_err(pos))
+            }
+        }
+    }
+
+    state.enums.push(EnumDef { name, variants }.into());
+
+    Ok(Stmt::Noop(pos))
+}
+
 /// Parse a function definition.
 #[cfg(not(feature = "no_function"))]
 fn parse_fn(
@@ -2895,35 +4004,163 @@ fn parse_fn(
         Err(_) => return Err(PERR::FnMissingName.into_err(pos)),
     };
 
+    // fn op "operator"(...)
+    let is_operator =
+        name == "op" && matches!(input.peek().expect(NEVER_ENDS).0, Token::StringConstant(_));
+
+    let name = if is_operator {
+        match input.next().expect(NEVER_ENDS) {
+            (Token::StringConstant(s), _)
+                if is_overloadable_operator(&s) || is_overloadable_unary_operator(&s) =>
+            {
+                s
+            }
+            (Token::StringConstant(s), pos) => return Err(PERR::FnInvalidOperator(s).into_err(pos)),
+            (token, _) => unreachable!("expecting Token::StringConstant, but gets {:?}", token),
+        }
+    } else {
+        name
+    };
+
     match input.peek().expect(NEVER_ENDS) {
         (Token::LeftParen, _) => eat_token(input, Token::LeftParen),
         (_, pos) => return Err(PERR::FnMissingParams(name).into_err(*pos)),
     };
 
     let mut params: StaticVec<_> = Default::default();
+    let mut param_types = StaticVec::<Option<Identifier>>::new();
+    let mut patterns = StaticVec::<(Identifier, Pattern)>::new();
+    let mut defaults = StaticVec::new();
+    #[cfg(not(feature = "no_index"))]
+    let mut variadic = false;
+    #[cfg(feature = "no_index")]
+    let variadic = false;
 
     if !match_token(input, Token::RightParen).0 {
         let sep_err = format!("to separate the parameters of function '{}'", name);
 
         loop {
-            match input.next().expect(NEVER_ENDS) {
-                (Token::RightParen, _) => break,
-                (Token::Identifier(s), pos) => {
-                    if params.iter().any(|(p, _)| p == &s) {
-                        return Err(PERR::FnDuplicatedParam(name, s).into_err(pos));
+            // ...rest
+            #[cfg(not(feature = "no_index"))]
+            if match_token(input, Token::Spread).0 {
+                match input.next().expect(NEVER_ENDS) {
+                    (Token::Identifier(s), pos) => {
+                        if params.iter().any(|(p, _)| p == &s)
+                            || patterns.iter().any(|(_, p)| pattern_binds(p, &s))
+                        {
+                            return Err(PERR::FnDuplicatedParam(name, s).into_err(pos));
+                        }
+                        let s = state.get_identifier(s);
+                        state.stack.push((s.clone(), AccessMode::ReadWrite, None));
+                        params.push((s, pos));
+                        param_types.push(None);
+                        variadic = true;
                     }
-                    let s = state.get_identifier(s);
-                    state.stack.push((s.clone(), AccessMode::ReadWrite));
-                    params.push((s, pos))
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
                 }
-                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
-                (_, pos) => {
-                    return Err(PERR::MissingToken(
-                        Token::RightParen.into(),
-                        format!("to close the parameters list of function '{}'", name),
-                    )
-                    .into_err(pos))
+
+                match input.next().expect(NEVER_ENDS) {
+                    (Token::RightParen, _) => break,
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, pos) => {
+                        return Err(PERR::MissingToken(
+                            Token::RightParen.into(),
+                            format!(
+                                "as the rest parameter must be the last parameter of function '{}'",
+                                name
+                            ),
+                        )
+                        .into_err(pos))
+                    }
+                }
+            }
+
+            // [pattern] or #{pattern}
+            if is_pattern_start(input) {
+                let pattern = parse_pattern(input, state, settings.level_up())?;
+                let s = pattern.to_string();
+                check_param_pattern(&name, &pattern, &s, &params, &patterns)?;
+                let s = state.get_identifier(s);
+                state.stack.push((s.clone(), AccessMode::ReadWrite, None));
+                params.push((s.clone(), pattern.position()));
+                param_types.push(None);
+                patterns.push((s, pattern));
+            } else {
+                match input.next().expect(NEVER_ENDS) {
+                    (Token::RightParen, _) => break,
+                    (Token::Identifier(s), pos) => {
+                        if params.iter().any(|(p, _)| p == &s)
+                            || patterns.iter().any(|(_, p)| pattern_binds(p, &s))
+                        {
+                            return Err(PERR::FnDuplicatedParam(name, s).into_err(pos));
+                        }
+                        let s = state.get_identifier(s);
+
+                        // param: type
+                        let typ = if match_token(input, Token::Colon).0 {
+                            Some(parse_type_annotation(input, state)?)
+                        } else {
+                            None
+                        };
+                        state
+                            .stack
+                            .push((s.clone(), AccessMode::ReadWrite, typ.clone()));
+                        params.push((s, pos));
+                        param_types.push(typ);
+                    }
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, pos) => {
+                        return Err(PERR::MissingToken(
+                            Token::RightParen.into(),
+                            format!("to close the parameters list of function '{}'", name),
+                        )
+                        .into_err(pos))
+                    }
+                }
+            }
+
+            // param = default
+            if match_token(input, Token::Equals).0 {
+                let expr = parse_expr(input, state, lib, settings.level_up())?;
+
+                match expr.get_literal_value() {
+                    Some(value) => {
+                        let (param, _) = params
+                            .last()
+                            .expect("never fails because a parameter was just added");
+
+                        if let Some(Some(typ)) = param_types.last() {
+                            check_constant_type(
+                                state,
+                                &value,
+                                typ,
+                                || format!("for parameter '{}'", param),
+                                expr.position(),
+                            )?;
+                        }
+
+                        defaults.push(value)
+                    }
+                    None => {
+                        return Err(
+                            PERR::ExprExpected("a literal".to_string()).into_err(expr.position())
+                        )
+                    }
                 }
+            } else if !defaults.is_empty() {
+                let (param, pos) = params
+                    .last()
+                    .expect("never fails because a parameter was just added");
+
+                return Err(PERR::MissingToken(
+                    Token::Equals.into(),
+                    format!(
+                        "to provide a default value for parameter '{}' of function '{}'",
+                        param, name
+                    ),
+                )
+                .into_err(*pos));
             }
 
             match input.next().expect(NEVER_ENDS) {
@@ -2937,8 +4174,33 @@ fn parse_fn(
         }
     }
 
+    // fn op "operator"(a, b) or fn op "operator"(a)
+    if is_operator
+        && (variadic
+            || !match params.len() {
+                1 => is_overloadable_unary_operator(&name),
+                2 => is_overloadable_operator(&name),
+                _ => false,
+            })
+    {
+        return Err(PERR::FnInvalidOperator(name).into_err(pos));
+    }
+
+    // fn name(...) -> type
+    let return_type = if match_token(input, Token::SingleArrow).0 {
+        Some(parse_type_annotation(input, state)?)
+    } else {
+        None
+    };
+
+    // fn name([pattern]) { body } -> fn name(hidden) { let [pattern] = hidden; body }
+    let destructures: StaticVec<_> = patterns
+        .into_iter()
+        .map(|(s, pattern)| make_destructure_stmt(state, s, pattern, AccessMode::ReadWrite))
+        .collect();
+
     // Parse function body
-    let body = match input.peek().expect(NEVER_ENDS) {
+    let mut body: StmtBlock = match input.peek().expect(NEVER_ENDS) {
         (Token::LeftBrace, _) => {
             settings.is_breakable = false;
             parse_block(input, state, lib, settings.level_up())?
@@ -2947,8 +4209,28 @@ fn parse_fn(
     }
     .into();
 
+    if !destructures.is_empty() {
+        body.statements_mut().insert_many(0, destructures);
+    }
+
+    if let Some(ref typ) = return_type {
+        check_return_types(state, &body, typ)?;
+    }
+
+    let is_generator = body.iter().any(Stmt::contains_yield);
+
+    if !is_generator {
+        mark_tail_calls(body.statements_mut());
+    }
+
     let mut params: StaticVec<_> = params.into_iter().map(|(p, _)| p).collect();
     params.shrink_to_fit();
+    defaults.shrink_to_fit();
+
+    if param_types.iter().all(Option::is_none) {
+        param_types.clear();
+    }
+    param_types.shrink_to_fit();
 
     #[cfg(not(feature = "no_closure"))]
     let externals = state
@@ -2963,6 +4245,11 @@ fn parse_fn(
         name: state.get_identifier(&name),
         access,
         params,
+        param_types,
+        return_type,
+        defaults,
+        variadic,
+        is_generator,
         #[cfg(not(feature = "no_closure"))]
         externals,
         body,
@@ -2975,6 +4262,232 @@ fn parse_fn(
     })
 }
 
+/// Mark all calls in tail position in the body of a script-defined function as tail calls,
+/// i.e. calls returned by `return` statements and calls making up the last expression of the
+/// body, including through `if` and `switch` branches.
+///
+/// Calls inside `try` statements are not in tail position because the `catch` and `finally`
+/// blocks must still run after them.
+#[cfg(not(feature = "no_function"))]
+fn mark_tail_calls(body: &mut [Stmt]) {
+    mark_return_tail_calls(body);
+    mark_trailing_tail_call(body);
+}
+
+/// Mark all `return` calls in a block of statements as tail calls.
+#[cfg(not(feature = "no_function"))]
+fn mark_return_tail_calls(statements: &mut [Stmt]) {
+    statements.iter_mut().for_each(|stmt| match stmt {
+        Stmt::Return(ReturnType::Return, Some(expr), _) => mark_tail_call_expr(expr),
+        Stmt::If(_, x, _) => {
+            mark_return_tail_calls(x.0.statements_mut());
+            mark_return_tail_calls(x.1.statements_mut());
+        }
+        Stmt::Switch(_, x, _) => {
+            x.blocks
+                .iter_mut()
+                .for_each(|(_, block)| mark_return_tail_calls(block.statements_mut()));
+            mark_return_tail_calls(x.def_case.statements_mut());
+        }
+        Stmt::While(_, x, _) | Stmt::Do(x, _, _, _) => mark_return_tail_calls(x.statements_mut()),
+        Stmt::For(_, x, _) => mark_return_tail_calls(x.2.statements_mut()),
+        Stmt::Block(x, _) => mark_return_tail_calls(x),
+        _ => (),
+    })
+}
+
+/// Mark the call making up the value of a block of statements (i.e. its last statement),
+/// if any, as a tail call.
+#[cfg(not(feature = "no_function"))]
+fn mark_trailing_tail_call(statements: &mut [Stmt]) {
+    match statements.last_mut() {
+        Some(Stmt::Expr(Expr::Stmt(x))) => mark_trailing_tail_call(x.statements_mut()),
+        Some(Stmt::Expr(expr)) => mark_tail_call_expr(expr),
+        Some(Stmt::FnCall(x, _)) => mark_tail_fn_call(x),
+        Some(Stmt::If(_, x, _)) => {
+            mark_trailing_tail_call(x.0.statements_mut());
+            mark_trailing_tail_call(x.1.statements_mut());
+        }
+        Some(Stmt::Switch(_, x, _)) => {
+            x.blocks
+                .iter_mut()
+                .for_each(|(_, block)| mark_trailing_tail_call(block.statements_mut()));
+            mark_trailing_tail_call(x.def_case.statements_mut());
+        }
+        Some(Stmt::Block(x, _)) => mark_trailing_tail_call(x),
+        _ => (),
+    }
+}
+
+/// Mark a call expression in tail position as a tail call.
+///
+/// Besides normal function calls, method calls on `this` and calls to function pointers
+/// (via `call`) held in variables are also marked.
+#[cfg(not(feature = "no_function"))]
+fn mark_tail_call_expr(expr: &mut Expr) {
+    match expr {
+        Expr::FnCall(x, _) => mark_tail_fn_call(x),
+        #[cfg(not(feature = "no_object"))]
+        Expr::Dot(x, false, _) => match (&x.lhs, &mut x.rhs) {
+            (Expr::Variable(_, _, v), Expr::FnCall(f, _))
+                if v.1.is_none() && is_tail_callable(f) =>
+            {
+                f.is_tail_call = v.2 == KEYWORD_THIS || f.name == crate::engine::KEYWORD_FN_PTR_CALL
+            }
+            _ => (),
+        },
+        _ => (),
+    }
+}
+
+/// Mark a function call in tail position as a tail call.
+#[cfg(not(feature = "no_function"))]
+fn mark_tail_fn_call(x: &mut FnCallExpr) {
+    if !x.is_qualified() && is_tail_callable(x) {
+        if x.name == crate::engine::KEYWORD_FN_PTR_CALL {
+            // The function pointer must be a variable, so it can be read again if the call
+            // turns out not to be a tail call
+            x.is_tail_call = x.args.first().map_or(false, |a| a.is_variable_access(true));
+        } else {
+            x.is_tail_call = x.hashes.script.is_some() && !is_keyword_function(&x.name);
+        }
+    }
+}
+
+/// Can a function call be made in tail position, i.e. does it have no captures, named arguments
+/// or spread arguments?
+#[cfg(not(feature = "no_function"))]
+fn is_tail_callable(x: &FnCallExpr) -> bool {
+    !x.capture && x.named_args.is_empty() && !x.args.iter().any(|a| matches!(a, Expr::Spread(_, _)))
+}
+
+/// Reject constant return values of a function that obviously do not match its return type.
+#[cfg(not(feature = "no_function"))]
+fn check_return_types(state: &ParseState, body: &StmtBlock, typ: &str) -> Result<(), ParseError> {
+    // Calling a generator function returns the generator
+    if body.iter().any(Stmt::contains_yield) {
+        return Ok(());
+    }
+
+    let target = || "for the return value".to_string();
+    let mut result = Ok(());
+
+    let path = &mut Default::default();
+
+    body.iter().all(|stmt| {
+        stmt.walk(path, &mut |path| {
+            let (value, pos) = match path.last() {
+                Some(ASTNode::Stmt(Stmt::Return(ReturnType::Return, None, pos))) => {
+                    (Dynamic::UNIT, *pos)
+                }
+                Some(ASTNode::Stmt(Stmt::Return(ReturnType::Return, Some(expr), _))) => {
+                    match expr.get_literal_value() {
+                        Some(value) => (value, expr.position()),
+                        None => return true,
+                    }
+                }
+                _ => return true,
+            };
+
+            result = check_constant_type(state, &value, typ, target, pos);
+            result.is_ok()
+        })
+    });
+
+    result?;
+
+    // The last expression is the return value
+    match body.iter().last() {
+        Some(Stmt::Expr(expr)) => match expr.get_literal_value() {
+            Some(value) => check_constant_type(state, &value, typ, target, expr.position()),
+            None => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Reject constant arguments to calls of script-defined functions that obviously do not match the
+/// types of their parameters.
+///
+/// Only calls that can only resolve to one single script-defined function are checked.
+#[cfg(not(feature = "no_function"))]
+fn check_fn_call_types(
+    state: &ParseState,
+    statements: &[Stmt],
+    lib: &FunctionsLib,
+) -> Result<(), ParseError> {
+    if lib.values().all(|f| f.param_types.is_empty()) {
+        return Ok(());
+    }
+
+    let mut result = Ok(());
+
+    let check_call = |x: &FnCallExpr| -> Result<(), ParseError> {
+        if x.is_qualified() || x.args.iter().any(|a| matches!(a, Expr::Spread(_, _))) {
+            return Ok(());
+        }
+
+        let num_args = x.args.len();
+
+        let mut candidates = lib.values().filter(|f| {
+            let max = f.params.len();
+            let min = max - f.defaults.len() - if f.variadic { 1 } else { 0 };
+            f.name == x.name && num_args >= min && (f.variadic || num_args <= max)
+        });
+
+        let fn_def = match (candidates.next(), candidates.next()) {
+            (Some(fn_def), None) if !fn_def.param_types.is_empty() => fn_def,
+            _ => return Ok(()),
+        };
+
+        let num_positional = num_args - x.named_args.len();
+
+        let named = x.named_args.iter().map(|name| {
+            fn_def
+                .params
+                .iter()
+                .position(|p| p == &name.name)
+                .unwrap_or(usize::MAX)
+        });
+
+        (0..num_positional)
+            .chain(named)
+            .zip(x.args.iter())
+            .try_for_each(|(index, arg)| {
+                if fn_def.variadic && index >= fn_def.params.len() - 1 {
+                    return Ok(());
+                }
+                match (fn_def.param_types.get(index), arg.get_literal_value()) {
+                    (Some(Some(typ)), Some(value)) => check_constant_type(
+                        state,
+                        &value,
+                        typ,
+                        || format!("for parameter '{}'", fn_def.params[index]),
+                        arg.position(),
+                    ),
+                    _ => Ok(()),
+                }
+            })
+    };
+
+    let path = &mut Default::default();
+
+    statements
+        .iter()
+        .chain(lib.values().flat_map(|f| f.body.iter()))
+        .all(|stmt| {
+            stmt.walk(path, &mut |path| match path.last() {
+                Some(ASTNode::Expr(Expr::FnCall(x, _))) => {
+                    result = check_call(x);
+                    result.is_ok()
+                }
+                _ => true,
+            })
+        });
+
+    result
+}
+
 /// Creates a curried expression from a list of external variables
 #[cfg(not(feature = "no_function"))]
 #[cfg(not(feature = "no_closure"))]
@@ -3032,27 +4545,43 @@ fn parse_anon_fn(
     settings.ensure_level_within_max_limit(state.max_expr_depth)?;
 
     let mut params_list: StaticVec<_> = Default::default();
+    let mut patterns = StaticVec::<(Identifier, Pattern)>::new();
 
     if input.next().expect(NEVER_ENDS).0 != Token::Or {
         if !match_token(input, Token::Pipe).0 {
             loop {
-                match input.next().expect(NEVER_ENDS) {
-                    (Token::Pipe, _) => break,
-                    (Token::Identifier(s), pos) => {
-                        if params_list.iter().any(|p| p == &s) {
-                            return Err(PERR::FnDuplicatedParam("".to_string(), s).into_err(pos));
+                // [pattern] or #{pattern}
+                if is_pattern_start(input) {
+                    let pattern = parse_pattern(input, state, settings.level_up())?;
+                    let s = pattern.to_string();
+                    check_param_pattern("", &pattern, &s, &params_list, &patterns)?;
+                    let s = state.get_identifier(s);
+                    state.stack.push((s.clone(), AccessMode::ReadWrite, None));
+                    params_list.push((s.clone(), pattern.position()));
+                    patterns.push((s, pattern));
+                } else {
+                    match input.next().expect(NEVER_ENDS) {
+                        (Token::Pipe, _) => break,
+                        (Token::Identifier(s), pos) => {
+                            if params_list.iter().any(|(p, _)| p == &s)
+                                || patterns.iter().any(|(_, p)| pattern_binds(p, &s))
+                            {
+                                return Err(
+                                    PERR::FnDuplicatedParam("".to_string(), s).into_err(pos)
+                                );
+                            }
+                            let s = state.get_identifier(s);
+                            state.stack.push((s.clone(), AccessMode::ReadWrite, None));
+                            params_list.push((s, pos))
+                        }
+                        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                        (_, pos) => {
+                            return Err(PERR::MissingToken(
+                                Token::Pipe.into(),
+                                "to close the parameters list of anonymous function".into(),
+                            )
+                            .into_err(pos))
                         }
-                        let s = state.get_identifier(s);
-                        state.stack.push((s.clone(), AccessMode::ReadWrite));
-                        params_list.push(s)
-                    }
-                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
-                    (_, pos) => {
-                        return Err(PERR::MissingToken(
-                            Token::Pipe.into(),
-                            "to close the parameters list of anonymous function".into(),
-                        )
-                        .into_err(pos))
                     }
                 }
 
@@ -3072,10 +4601,31 @@ fn parse_anon_fn(
         }
     }
 
+    // |[pattern]| body -> |hidden| { let [pattern] = hidden; body }
+    let destructures: StaticVec<_> = patterns
+        .into_iter()
+        .map(|(s, pattern)| make_destructure_stmt(state, s, pattern, AccessMode::ReadWrite))
+        .collect();
+
     // Parse function body
     settings.is_breakable = false;
     let body = parse_stmt(input, state, lib, settings.level_up())?;
 
+    let mut body = if destructures.is_empty() {
+        body
+    } else {
+        let pos = body.position();
+        let mut statements = destructures.into_vec();
+        statements.push(body);
+        Stmt::Block(statements.into_boxed_slice(), pos)
+    };
+
+    let is_generator = body.contains_yield();
+
+    if !is_generator {
+        mark_tail_calls(std::slice::from_mut(&mut body));
+    }
+
     // External variables may need to be processed in a consistent order,
     // so extract them into a list.
     #[cfg(not(feature = "no_closure"))]
@@ -3093,7 +4643,7 @@ fn parse_anon_fn(
     #[cfg(not(feature = "no_closure"))]
     params.extend(externals.iter().cloned());
 
-    params.append(&mut params_list);
+    params.extend(params_list.into_iter().map(|(p, _)| p));
 
     // Create unique function name by hashing the script body plus the parameters.
     let hasher = &mut get_hasher();
@@ -3108,6 +4658,11 @@ fn parse_anon_fn(
         name: fn_name.clone(),
         access: FnAccess::Public,
         params,
+        param_types: Default::default(),
+        return_type: None,
+        defaults: Default::default(),
+        variadic: false,
+        is_generator,
         #[cfg(not(feature = "no_closure"))]
         externals: Default::default(),
         body: body.into(),
@@ -3144,7 +4699,10 @@ impl Engine {
             allow_if_expr: false,
             allow_switch_expr: false,
             allow_stmt_expr: false,
+            allow_format_spec: false,
             allow_anonymous_fn: false,
+            #[cfg(not(feature = "no_function"))]
+            allow_yield: false,
             is_global: true,
             is_function_scope: false,
             is_breakable: false,
@@ -3186,7 +4744,10 @@ impl Engine {
                 allow_if_expr: true,
                 allow_switch_expr: true,
                 allow_stmt_expr: true,
+                allow_format_spec: false,
                 allow_anonymous_fn: true,
+                #[cfg(not(feature = "no_function"))]
+                allow_yield: false,
                 is_global: true,
                 is_function_scope: false,
                 is_breakable: false,
@@ -3229,6 +4790,9 @@ impl Engine {
             }
         }
 
+        #[cfg(not(feature = "no_function"))]
+        check_fn_call_types(state, &statements, &functions)?;
+
         Ok((statements, functions.into_iter().map(|(_, v)| v).collect()))
     }
 
@@ -3262,6 +4826,11 @@ impl From<Dynamic> for Expr {
                 Self::DynamicConstant(Box::new((*value).into()), Position::NONE)
             }
 
+            #[cfg(feature = "bigint")]
+            Union::BigInt(value, _, _) => {
+                Self::DynamicConstant(Box::new((*value).into()), Position::NONE)
+            }
+
             Union::Unit(_, _, _) => Self::Unit(Position::NONE),
             Union::Int(value, _, _) => Self::IntegerConstant(value, Position::NONE),
             Union::Char(value, _, _) => Self::CharConstant(value, Position::NONE),
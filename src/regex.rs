@@ -0,0 +1,928 @@
+//! Module implementing a simple regular expression engine, used by the
+//! [`RegexPackage`][crate::packages::RegexPackage].
+//!
+//! Patterns are compiled into a program for a Pike VM, which runs in time proportional to the
+//! length of the text multiplied by the size of the program, so hostile patterns cannot cause
+//! catastrophic backtracking. The number of steps taken can be bounded further via [`Steps`].
+//!
+//! # Syntax
+//!
+//! * Literals and escapes: `\n`, `\t`, `\r`, `\f`, `\v`, `\0`, `\xHH`, `\x{H...}`, `\u{H...}`
+//!   and `\` followed by any punctuation character.
+//! * `.` (any character except new-line), character classes such as `[a-z_]` and `[^0-9]`,
+//!   and the classes `\d`, `\w`, `\s` (digits, word characters, white-space) and their negations
+//!   `\D`, `\W`, `\S`, also inside character classes.
+//! * Anchors: `^`, `$`, `\A`, `\z`, `\b` and `\B`.
+//! * Groups: `(...)`, `(?:...)`, and named groups `(?P<name>...)` or `(?<name>...)`.
+//! * Alternation `|` and the quantifiers `*`, `+`, `?`, `{n}`, `{n,}` and `{n,m}`, which can be
+//!   followed by `?` to match as few repetitions as possible.
+//! * Flags: `(?i)` (case-insensitive), `(?m)` (`^` and `$` match at line breaks) and `(?s)`
+//!   (`.` matches new-lines), which can be turned off with `-` (e.g. `(?-i)`) or scoped to a group
+//!   (e.g. `(?i:...)`).
+
+use crate::Identifier;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Maximum number of repetitions in a counted quantifier, e.g. `a{1000}`.
+const MAX_REPETITIONS: u32 = 1000;
+/// Maximum number of instructions in a compiled program.
+const MAX_PROGRAM_SIZE: usize = 100_000;
+
+/// Is a character a word character, as matched by `\w`?
+#[inline(always)]
+#[must_use]
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Fold the case of a character for case-insensitive comparisons.
+#[inline]
+#[must_use]
+fn fold_case(ch: char) -> char {
+    if ch.is_ascii() {
+        ch.to_ascii_lowercase()
+    } else {
+        ch.to_lowercase().next().unwrap_or(ch)
+    }
+}
+
+/// A class of characters matched by `\d`, `\w` or `\s`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Perl {
+    Digit,
+    Word,
+    Space,
+}
+
+impl Perl {
+    /// Does this class contain a character?
+    #[inline(always)]
+    #[must_use]
+    fn contains(self, ch: char) -> bool {
+        match self {
+            Self::Digit => ch.is_ascii_digit(),
+            Self::Word => is_word_char(ch),
+            Self::Space => ch.is_whitespace(),
+        }
+    }
+}
+
+/// An item in a character class.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum ClassItem {
+    /// A range of characters (inclusive).
+    Range(char, char),
+    /// `\d`, `\w` or `\s`, negated if `true`.
+    Perl(Perl, bool),
+}
+
+/// A character class, e.g. `[a-z_]`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Class {
+    items: Vec<ClassItem>,
+    negated: bool,
+    case_insensitive: bool,
+}
+
+impl Class {
+    /// Make a class from a single item.
+    #[inline(always)]
+    #[must_use]
+    fn new(item: ClassItem, negated: bool) -> Self {
+        Self {
+            items: vec![item],
+            negated,
+            case_insensitive: false,
+        }
+    }
+    /// Does any item in this class match a character, ignoring negation?
+    #[must_use]
+    fn contains_raw(&self, ch: char) -> bool {
+        self.items.iter().any(|item| match *item {
+            ClassItem::Range(from, to) => from <= ch && ch <= to,
+            ClassItem::Perl(perl, negated) => perl.contains(ch) != negated,
+        })
+    }
+    /// Does this class match a character?
+    #[must_use]
+    fn contains(&self, ch: char) -> bool {
+        let found = self.contains_raw(ch)
+            || (self.case_insensitive
+                && (ch.to_lowercase().any(|c| self.contains_raw(c))
+                    || ch.to_uppercase().any(|c| self.contains_raw(c))));
+
+        found != self.negated
+    }
+}
+
+/// A zero-width assertion.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Assertion {
+    StartText,
+    EndText,
+    StartLine,
+    EndLine,
+    WordBoundary,
+    NotWordBoundary,
+}
+
+/// Flags controlling how a pattern is matched.
+#[derive(Debug, Clone, Copy, Default)]
+struct Flags {
+    /// `i`: match letters case-insensitively.
+    case_insensitive: bool,
+    /// `m`: `^` and `$` match at the start and end of lines.
+    multi_line: bool,
+    /// `s`: `.` matches new-lines.
+    dot_all: bool,
+}
+
+/// A node in the syntax tree of a pattern.
+#[derive(Debug, Clone)]
+enum Node {
+    Empty,
+    /// A character; already case-folded if case-insensitive.
+    Char(char, bool),
+    /// `.`, matching new-lines if `true`.
+    Any(bool),
+    Class(Box<Class>),
+    Assert(Assertion),
+    /// A group, capturing into the group number if any.
+    Group(Box<Node>, Option<usize>),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+        greedy: bool,
+    },
+}
+
+/// Parser for patterns.
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    /// Names of capture groups, by group number.
+    names: Vec<Option<Identifier>>,
+}
+
+impl Parser<'_> {
+    /// Consume the next character if it is a particular character.
+    #[inline]
+    fn eat(&mut self, ch: char) -> bool {
+        if self.chars.peek() == Some(&ch) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+    /// Parse alternatives, up to the end of the current group.
+    fn parse_alternate(&mut self, flags: &mut Flags) -> Result<Node, String> {
+        let mut alternatives = vec![self.parse_concat(flags)?];
+
+        while self.eat('|') {
+            alternatives.push(self.parse_concat(flags)?);
+        }
+
+        Ok(if alternatives.len() == 1 {
+            alternatives
+                .pop()
+                .expect("`alternatives` contains one item")
+        } else {
+            Node::Alternate(alternatives)
+        })
+    }
+    /// Parse a sequence of items, up to the next `|` or `)`.
+    fn parse_concat(&mut self, flags: &mut Flags) -> Result<Node, String> {
+        let mut nodes = Vec::new();
+
+        loop {
+            let node = match self.chars.peek() {
+                None | Some('|') | Some(')') => break,
+                Some('*') | Some('+') | Some('?') => {
+                    return Err("quantifier without anything to repeat".into())
+                }
+                Some(_) => match self.parse_atom(flags)? {
+                    Some(node) => node,
+                    None => continue,
+                },
+            };
+            nodes.push(self.parse_quantifier(node)?);
+        }
+
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().expect("`nodes` contains one item"),
+            _ => Node::Concat(nodes),
+        })
+    }
+    /// Parse an optional quantifier after a node.
+    fn parse_quantifier(&mut self, node: Node) -> Result<Node, String> {
+        let (min, max) = match self.chars.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.chars.next();
+                let min = self.parse_number()?;
+                let max = if self.eat(',') {
+                    match self.chars.peek() {
+                        Some('}') => None,
+                        _ => Some(self.parse_number()?),
+                    }
+                } else {
+                    Some(min)
+                };
+                if !self.eat('}') {
+                    return Err("unterminated repetition".into());
+                }
+                if max.map_or(false, |max| max < min) {
+                    return Err(format!("invalid repetition {{{},{:?}}}", min, max));
+                }
+                if min > MAX_REPETITIONS || max.map_or(false, |max| max > MAX_REPETITIONS) {
+                    return Err(format!(
+                        "repetition count exceeds the maximum of {}",
+                        MAX_REPETITIONS
+                    ));
+                }
+                let greedy = !self.eat('?');
+                return self.check_repeat(Node::Repeat {
+                    node: Box::new(node),
+                    min,
+                    max,
+                    greedy,
+                });
+            }
+            _ => return Ok(node),
+        };
+
+        self.chars.next();
+        let greedy = !self.eat('?');
+
+        self.check_repeat(Node::Repeat {
+            node: Box::new(node),
+            min,
+            max,
+            greedy,
+        })
+    }
+    /// Make sure a repetition is not itself followed by another quantifier.
+    fn check_repeat(&mut self, node: Node) -> Result<Node, String> {
+        match self.chars.peek() {
+            Some('*') | Some('+') | Some('?') => Err("nested quantifier".into()),
+            _ => Ok(node),
+        }
+    }
+    /// Parse a decimal number.
+    fn parse_number(&mut self) -> Result<u32, String> {
+        let mut value: Option<u32> = None;
+
+        while let Some(digit) = self.chars.peek().and_then(|c| c.to_digit(10)) {
+            self.chars.next();
+            value = Some(
+                value
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add(digit))
+                    .ok_or("repetition count too large")?,
+            );
+        }
+
+        value.ok_or_else(|| "missing repetition count".into())
+    }
+    /// Parse an atom, returning `None` for a flag group such as `(?i)`, which sets the flags for
+    /// the rest of the enclosing group.
+    fn parse_atom(&mut self, flags: &mut Flags) -> Result<Option<Node>, String> {
+        let ch = self.chars.next().expect("not at the end of the pattern");
+
+        Ok(Some(match ch {
+            '(' => return self.parse_group(flags),
+            '[' => Node::Class(Box::new(self.parse_class(flags)?)),
+            '.' => Node::Any(flags.dot_all),
+            '^' if flags.multi_line => Node::Assert(Assertion::StartLine),
+            '^' => Node::Assert(Assertion::StartText),
+            '$' if flags.multi_line => Node::Assert(Assertion::EndLine),
+            '$' => Node::Assert(Assertion::EndText),
+            '\\' => match self.parse_escape(false)? {
+                Escape::Char(ch) => self.make_char(ch, flags),
+                Escape::Class(class) => Node::Class(Box::new(class)),
+                Escape::Assert(assertion) => Node::Assert(assertion),
+            },
+            ch => self.make_char(ch, flags),
+        }))
+    }
+    /// Make a node matching a character.
+    #[must_use]
+    fn make_char(&self, ch: char, flags: &Flags) -> Node {
+        if flags.case_insensitive && fold_case(ch) != ch.to_uppercase().next().unwrap_or(ch) {
+            Node::Char(fold_case(ch), true)
+        } else {
+            Node::Char(ch, false)
+        }
+    }
+    /// Parse a group after the opening `(`.
+    fn parse_group(&mut self, flags: &mut Flags) -> Result<Option<Node>, String> {
+        let mut inner_flags = *flags;
+        let mut name = None;
+        let mut capture = true;
+
+        if self.eat('?') {
+            if self.eat('P') && !matches!(self.chars.peek(), Some('<')) {
+                return Err("invalid group syntax after '(?P'".into());
+            }
+            if self.eat('<') {
+                let mut text = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some('>') => break,
+                        Some(c) if is_word_char(c) => text.push(c),
+                        Some(c) if c == '=' || c == '!' => {
+                            return Err("look-behind assertions are not supported".into())
+                        }
+                        _ => return Err("invalid capture group name".into()),
+                    }
+                }
+                if text.is_empty() || text.starts_with(|c: char| c.is_ascii_digit()) {
+                    return Err(format!("invalid capture group name '{}'", text));
+                }
+                if self.names.iter().any(|n| n.as_deref() == Some(&text)) {
+                    return Err(format!("duplicate capture group name '{}'", text));
+                }
+                name = Some(text.into());
+            } else {
+                capture = false;
+                let mut negate = false;
+
+                loop {
+                    match self.chars.next() {
+                        Some('i') => inner_flags.case_insensitive = !negate,
+                        Some('m') => inner_flags.multi_line = !negate,
+                        Some('s') => inner_flags.dot_all = !negate,
+                        Some('-') if !negate => negate = true,
+                        Some(':') => break,
+                        Some(')') => {
+                            // Flags apply to the rest of the enclosing group
+                            *flags = inner_flags;
+                            return Ok(None);
+                        }
+                        Some('=') | Some('!') => {
+                            return Err("look-ahead assertions are not supported".into())
+                        }
+                        Some(c) => return Err(format!("unknown flag '{}'", c)),
+                        None => return Err("unterminated group".into()),
+                    }
+                }
+            }
+        }
+
+        let index = if capture {
+            self.names.push(name);
+            Some(self.names.len() - 1)
+        } else {
+            None
+        };
+
+        let node = self.parse_alternate(&mut inner_flags)?;
+
+        if !self.eat(')') {
+            return Err("unterminated group".into());
+        }
+
+        Ok(Some(Node::Group(Box::new(node), index)))
+    }
+    /// Parse a character class after the opening `[`.
+    fn parse_class(&mut self, flags: &Flags) -> Result<Class, String> {
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        let mut first = true;
+
+        loop {
+            let start = match self.chars.next() {
+                None => return Err("unterminated character class".into()),
+                Some(']') if !first => break,
+                Some('[') if self.eat(':') => {
+                    return Err("POSIX character classes are not supported".into())
+                }
+                Some('\\') => match self.parse_escape(true)? {
+                    Escape::Char(ch) => ch,
+                    Escape::Class(class) => {
+                        items.extend(class.items);
+                        first = false;
+                        continue;
+                    }
+                    Escape::Assert(_) => unreachable!("no assertions inside a character class"),
+                },
+                Some(ch) => ch,
+            };
+            first = false;
+
+            // Range, unless the `-` is the last character in the class
+            let mut lookahead = self.chars.clone();
+            if lookahead.next() == Some('-') && !matches!(lookahead.next(), Some(']') | None) {
+                self.chars.next();
+                let end = match self.chars.next() {
+                    Some('\\') => match self.parse_escape(true)? {
+                        Escape::Char(ch) => ch,
+                        _ => return Err("invalid range in character class".into()),
+                    },
+                    Some(ch) => ch,
+                    None => return Err("unterminated character class".into()),
+                };
+                if end < start {
+                    return Err(format!(
+                        "invalid range '{}-{}' in character class",
+                        start, end
+                    ));
+                }
+                items.push(ClassItem::Range(start, end));
+            } else {
+                items.push(ClassItem::Range(start, start));
+            }
+        }
+
+        Ok(Class {
+            items,
+            negated,
+            case_insensitive: flags.case_insensitive,
+        })
+    }
+    /// Parse an escape sequence after the `\`.
+    fn parse_escape(&mut self, in_class: bool) -> Result<Escape, String> {
+        let ch = self.chars.next().ok_or("incomplete escape sequence")?;
+
+        Ok(match ch {
+            'n' => Escape::Char('\n'),
+            't' => Escape::Char('\t'),
+            'r' => Escape::Char('\r'),
+            'f' => Escape::Char('\x0C'),
+            'v' => Escape::Char('\x0B'),
+            '0' => Escape::Char('\0'),
+            'x' | 'u' => Escape::Char(self.parse_hex(ch)?),
+            'd' => Escape::Class(Class::new(ClassItem::Perl(Perl::Digit, false), false)),
+            'D' => Escape::Class(Class::new(ClassItem::Perl(Perl::Digit, true), false)),
+            'w' => Escape::Class(Class::new(ClassItem::Perl(Perl::Word, false), false)),
+            'W' => Escape::Class(Class::new(ClassItem::Perl(Perl::Word, true), false)),
+            's' => Escape::Class(Class::new(ClassItem::Perl(Perl::Space, false), false)),
+            'S' => Escape::Class(Class::new(ClassItem::Perl(Perl::Space, true), false)),
+            'b' if !in_class => Escape::Assert(Assertion::WordBoundary),
+            'B' if !in_class => Escape::Assert(Assertion::NotWordBoundary),
+            'A' if !in_class => Escape::Assert(Assertion::StartText),
+            'z' if !in_class => Escape::Assert(Assertion::EndText),
+            ch if ch.is_ascii_punctuation() || ch == ' ' => Escape::Char(ch),
+            ch => return Err(format!("unknown escape sequence '\\{}'", ch)),
+        })
+    }
+    /// Parse a hex character code after `\x` or `\u`.
+    fn parse_hex(&mut self, kind: char) -> Result<char, String> {
+        let mut code = String::new();
+
+        if self.eat('{') {
+            loop {
+                match self.chars.next() {
+                    Some('}') => break,
+                    Some(c) => code.push(c),
+                    None => return Err(format!("unterminated escape sequence '\\{}'", kind)),
+                }
+            }
+        } else {
+            let len = if kind == 'x' { 2 } else { 4 };
+            code.extend(self.chars.by_ref().take(len));
+        }
+
+        u32::from_str_radix(&code, 16)
+            .ok()
+            .filter(|_| !code.is_empty() && !code.starts_with('+'))
+            .and_then(std::char::from_u32)
+            .ok_or_else(|| format!("invalid character code '\\{}{}'", kind, code))
+    }
+}
+
+/// Result of parsing an escape sequence.
+enum Escape {
+    Char(char),
+    Class(Class),
+    Assert(Assertion),
+}
+
+/// An instruction in a compiled program.
+#[derive(Debug, Clone)]
+enum Inst {
+    /// Match a character; already case-folded if case-insensitive.
+    Char(char, bool),
+    /// Match any character, including new-lines if `true`.
+    Any(bool),
+    Class(Box<Class>),
+    Assert(Assertion),
+    /// Continue at both targets, preferring the first.
+    Split(usize, usize),
+    Jump(usize),
+    /// Save the current position into a capture slot.
+    Save(usize),
+    Match,
+}
+
+/// Compiler from a syntax tree into a program.
+struct Compiler {
+    program: Vec<Inst>,
+}
+
+impl Compiler {
+    /// Add an instruction, returning its index.
+    fn push(&mut self, inst: Inst) -> Result<usize, String> {
+        if self.program.len() >= MAX_PROGRAM_SIZE {
+            return Err("regular expression is too large".into());
+        }
+        self.program.push(inst);
+        Ok(self.program.len() - 1)
+    }
+    /// Compile a node.
+    fn compile(&mut self, node: &Node) -> Result<(), String> {
+        match node {
+            Node::Empty => (),
+            Node::Char(ch, fold) => {
+                self.push(Inst::Char(*ch, *fold))?;
+            }
+            Node::Any(new_line) => {
+                self.push(Inst::Any(*new_line))?;
+            }
+            Node::Class(class) => {
+                self.push(Inst::Class(class.clone()))?;
+            }
+            Node::Assert(assertion) => {
+                self.push(Inst::Assert(*assertion))?;
+            }
+            Node::Group(node, None) => self.compile(node)?,
+            Node::Group(node, Some(index)) => {
+                self.push(Inst::Save(index * 2))?;
+                self.compile(node)?;
+                self.push(Inst::Save(index * 2 + 1))?;
+            }
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.compile(node)?;
+                }
+            }
+            Node::Alternate(nodes) => {
+                let mut jumps = Vec::with_capacity(nodes.len());
+
+                for (i, node) in nodes.iter().enumerate() {
+                    if i < nodes.len() - 1 {
+                        let split = self.push(Inst::Split(0, 0))?;
+                        self.compile(node)?;
+                        jumps.push(self.push(Inst::Jump(0))?);
+                        self.program[split] = Inst::Split(split + 1, self.program.len());
+                    } else {
+                        self.compile(node)?;
+                    }
+                }
+
+                let end = self.program.len();
+                for jump in jumps {
+                    self.program[jump] = Inst::Jump(end);
+                }
+            }
+            Node::Repeat {
+                node,
+                min,
+                max,
+                greedy,
+            } => {
+                for _ in 0..*min {
+                    self.compile(node)?;
+                }
+
+                match max {
+                    // x* => L1: split L2, L3; L2: x; jump L1; L3:
+                    None => {
+                        let split = self.push(Inst::Split(0, 0))?;
+                        self.compile(node)?;
+                        self.push(Inst::Jump(split))?;
+                        self.program[split] =
+                            self.make_split(split + 1, self.program.len(), *greedy);
+                    }
+                    // x{0,n} => split L1, end; L1: x; split L2, end; L2: x; ... end:
+                    Some(max) => {
+                        let mut splits = Vec::new();
+
+                        for _ in *min..*max {
+                            splits.push(self.push(Inst::Split(0, 0))?);
+                            self.compile(node)?;
+                        }
+
+                        let end = self.program.len();
+                        for split in splits {
+                            self.program[split] = self.make_split(split + 1, end, *greedy);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+    /// Make a split instruction, preferring to repeat if greedy.
+    #[inline(always)]
+    #[must_use]
+    fn make_split(&self, repeat: usize, skip: usize, greedy: bool) -> Inst {
+        if greedy {
+            Inst::Split(repeat, skip)
+        } else {
+            Inst::Split(skip, repeat)
+        }
+    }
+}
+
+/// Error raised when a match runs out of [`Steps`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) struct StepsExhausted;
+
+/// Budget of steps that a match can take.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Steps {
+    /// Maximum number of steps, or unlimited if `None`.
+    max: Option<u64>,
+    /// Number of steps taken so far.
+    taken: u64,
+}
+
+impl Steps {
+    /// A budget with a maximum number of steps, or unlimited if `None`.
+    #[inline(always)]
+    #[must_use]
+    pub fn new(max: Option<u64>) -> Self {
+        Self { max, taken: 0 }
+    }
+    /// Number of steps taken so far.
+    #[cfg(not(feature = "unchecked"))]
+    #[inline(always)]
+    #[must_use]
+    pub fn taken(&self) -> u64 {
+        self.taken
+    }
+    /// Take a step.
+    #[inline(always)]
+    fn step(&mut self) -> Result<(), StepsExhausted> {
+        if self.max.map_or(false, |max| self.taken >= max) {
+            return Err(StepsExhausted);
+        }
+        self.taken += 1;
+        Ok(())
+    }
+}
+
+/// Positions of capture groups in a match, as pairs of start and end byte offsets.
+/// Group 0 is the entire match.
+pub(crate) type Captures = Vec<Option<usize>>;
+
+/// A thread in the Pike VM.
+struct Thread {
+    pc: usize,
+    captures: Captures,
+}
+
+/// A compiled regular expression.
+#[derive(Debug, Clone)]
+pub(crate) struct Regex {
+    program: Vec<Inst>,
+    /// Names of capture groups, by group number.
+    names: Vec<Option<Identifier>>,
+}
+
+impl Regex {
+    /// Compile a pattern.
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            chars: pattern.chars().peekable(),
+            names: vec![None],
+        };
+
+        let node = parser.parse_alternate(&mut Flags::default())?;
+
+        if parser.chars.next().is_some() {
+            return Err("unmatched ')'".into());
+        }
+
+        let mut compiler = Compiler {
+            program: Vec::new(),
+        };
+        compiler.compile(&Node::Group(Box::new(node), Some(0)))?;
+        compiler.push(Inst::Match)?;
+
+        Ok(Self {
+            program: compiler.program,
+            names: parser.names,
+        })
+    }
+    /// Get the names of the capture groups, by group number.
+    /// Group 0 (the entire match) and unnamed groups have no names.
+    #[inline(always)]
+    #[must_use]
+    pub fn names(&self) -> &[Option<Identifier>] {
+        &self.names
+    }
+    /// Does an assertion hold at a position in the text?
+    #[must_use]
+    fn check(assertion: Assertion, text: &str, pos: usize) -> bool {
+        let prev = text[..pos].chars().next_back();
+        let next = text[pos..].chars().next();
+
+        match assertion {
+            Assertion::StartText => pos == 0,
+            Assertion::EndText => pos == text.len(),
+            Assertion::StartLine => prev.map_or(true, |c| c == '\n'),
+            Assertion::EndLine => next.map_or(true, |c| c == '\n'),
+            Assertion::WordBoundary | Assertion::NotWordBoundary => {
+                let boundary = prev.map_or(false, is_word_char) != next.map_or(false, is_word_char);
+                boundary == (assertion == Assertion::WordBoundary)
+            }
+        }
+    }
+    /// Add a thread, following all instructions that do not consume a character.
+    #[allow(clippy::too_many_arguments)]
+    fn add_thread(
+        &self,
+        list: &mut Vec<Thread>,
+        visited: &mut [usize],
+        generation: usize,
+        pc: usize,
+        captures: &mut Captures,
+        text: &str,
+        pos: usize,
+        steps: &mut Steps,
+    ) -> Result<(), StepsExhausted> {
+        enum Frame {
+            Explore(usize),
+            Restore(usize, Option<usize>),
+        }
+
+        let mut stack = vec![Frame::Explore(pc)];
+
+        while let Some(frame) = stack.pop() {
+            let mut pc = match frame {
+                Frame::Explore(pc) => pc,
+                Frame::Restore(slot, value) => {
+                    captures[slot] = value;
+                    continue;
+                }
+            };
+
+            loop {
+                if visited[pc] == generation {
+                    break;
+                }
+                visited[pc] = generation;
+                steps.step()?;
+
+                match self.program[pc] {
+                    Inst::Jump(target) => pc = target,
+                    Inst::Split(first, second) => {
+                        stack.push(Frame::Explore(second));
+                        pc = first;
+                    }
+                    Inst::Save(slot) => {
+                        stack.push(Frame::Restore(slot, captures[slot]));
+                        captures[slot] = Some(pos);
+                        pc += 1;
+                    }
+                    Inst::Assert(assertion) if Self::check(assertion, text, pos) => pc += 1,
+                    Inst::Assert(_) => break,
+                    _ => {
+                        list.push(Thread {
+                            pc,
+                            captures: captures.clone(),
+                        });
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+    /// Find the leftmost match in the text, starting from a byte offset.
+    pub fn captures_at(
+        &self,
+        text: &str,
+        start: usize,
+        steps: &mut Steps,
+    ) -> Result<Option<Captures>, StepsExhausted> {
+        let mut current = Vec::new();
+        let mut next = Vec::new();
+        let mut visited = vec![usize::MAX; self.program.len()];
+        let mut captures = vec![None; self.names.len() * 2];
+        let mut matched = None;
+        let mut pos = start;
+        let mut generation = 0;
+
+        loop {
+            // Start a new thread at this position, with the lowest priority
+            if matched.is_none() {
+                captures.iter_mut().for_each(|c| *c = None);
+                self.add_thread(
+                    &mut current,
+                    &mut visited,
+                    generation,
+                    0,
+                    &mut captures,
+                    text,
+                    pos,
+                    steps,
+                )?;
+            }
+
+            if current.is_empty() && (matched.is_some() || pos >= text.len()) {
+                break;
+            }
+
+            let ch = text[pos..].chars().next();
+            let next_pos = pos + ch.map_or(0, char::len_utf8);
+            generation += 1;
+
+            for mut thread in current.drain(..) {
+                steps.step()?;
+
+                let consumed = match (&self.program[thread.pc], ch) {
+                    (Inst::Match, _) => {
+                        // Lower-priority threads are cut off
+                        matched = Some(thread.captures);
+                        break;
+                    }
+                    (_, None) => false,
+                    (Inst::Char(c, false), Some(ch)) => *c == ch,
+                    (Inst::Char(c, true), Some(ch)) => *c == fold_case(ch),
+                    (Inst::Any(new_line), Some(ch)) => *new_line || ch != '\n',
+                    (Inst::Class(class), Some(ch)) => class.contains(ch),
+                    _ => unreachable!("only consuming instructions are queued"),
+                };
+
+                if consumed {
+                    self.add_thread(
+                        &mut next,
+                        &mut visited,
+                        generation,
+                        thread.pc + 1,
+                        &mut thread.captures,
+                        text,
+                        next_pos,
+                        steps,
+                    )?;
+                }
+            }
+
+            if ch.is_none() {
+                break;
+            }
+
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+            pos = next_pos;
+        }
+
+        Ok(matched)
+    }
+    /// Iterate through all non-overlapping matches in the text, stopping when the callback
+    /// returns `false`.
+    ///
+    /// An empty match immediately after the previous match is skipped.
+    pub fn for_each_match(
+        &self,
+        text: &str,
+        steps: &mut Steps,
+        mut callback: impl FnMut(&Captures) -> bool,
+    ) -> Result<(), StepsExhausted> {
+        let mut start = 0;
+        let mut last_end = None;
+
+        while start <= text.len() {
+            let captures = match self.captures_at(text, start, steps)? {
+                Some(captures) => captures,
+                None => break,
+            };
+            let (from, to) = (
+                captures[0].expect("group 0 always matches"),
+                captures[1].expect("group 0 always matches"),
+            );
+
+            if from == to {
+                // Advance past the empty match
+                start = to + text[to..].chars().next().map_or(1, char::len_utf8);
+
+                if last_end == Some(to) {
+                    continue;
+                }
+            } else {
+                start = to;
+            }
+
+            last_end = Some(to);
+
+            if !callback(&captures) {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
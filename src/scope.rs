@@ -53,10 +53,17 @@ const SCOPE_ENTRIES_INLINED: usize = 8;
 pub struct Scope<'a> {
     /// Current value of the entry.
     values: smallvec::SmallVec<[Dynamic; SCOPE_ENTRIES_INLINED]>,
-    /// (Name, aliases) of the entry.
-    names: smallvec::SmallVec<
-        [(Cow<'a, str>, Option<Box<StaticVec<Identifier>>>); SCOPE_ENTRIES_INLINED],
-    >,
+    /// (Name, aliases and declared type) of the entry.
+    names: smallvec::SmallVec<[(Cow<'a, str>, Option<Box<EntryInfo>>); SCOPE_ENTRIES_INLINED]>,
+}
+
+/// Rarely-used information of an entry in a [`Scope`], allocated only when needed.
+#[derive(Debug, Clone, Hash, Default)]
+struct EntryInfo {
+    /// Aliases of the entry.
+    aliases: StaticVec<Identifier>,
+    /// Declared type of the entry, if any.
+    typ: Option<Identifier>,
 }
 
 impl Default for Scope<'_> {
@@ -436,21 +443,36 @@ impl<'a> Scope<'a> {
     #[cfg(not(feature = "no_module"))]
     #[inline(always)]
     pub(crate) fn add_entry_alias(&mut self, index: usize, alias: Identifier) -> &mut Self {
-        let (_, aliases) = self
+        let (_, info) = self
             .names
             .get_mut(index)
             .expect("never fails unless the index is out of bounds");
-        match aliases {
-            None => {
-                let mut list = StaticVec::new();
-                list.push(alias);
-                *aliases = Some(list.into());
-            }
-            Some(aliases) if !aliases.iter().any(|a| a == &alias) => aliases.push(alias),
-            Some(_) => (),
+        let aliases = &mut info.get_or_insert_with(Default::default).aliases;
+        if !aliases.iter().any(|a| a == &alias) {
+            aliases.push(alias);
         }
         self
     }
+    /// Set the declared type of an entry in the [`Scope`].
+    #[inline(always)]
+    pub(crate) fn set_entry_type(&mut self, index: usize, typ: Identifier) -> &mut Self {
+        let (_, info) = self
+            .names
+            .get_mut(index)
+            .expect("never fails unless the index is out of bounds");
+        info.get_or_insert_with(Default::default).typ = Some(typ);
+        self
+    }
+    /// Get the declared type of an entry in the [`Scope`], if any.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn get_entry_type(&self, index: usize) -> Option<&str> {
+        self.names
+            .get(index)
+            .and_then(|(_, info)| info.as_ref())
+            .and_then(|info| info.typ.as_ref())
+            .map(|typ| typ.as_str())
+    }
     /// Clone the [`Scope`], keeping only the last instances of each variable name.
     /// Shadowed variables are omitted in the copy.
     #[inline(always)]
@@ -462,9 +484,9 @@ impl<'a> Scope<'a> {
             .iter()
             .enumerate()
             .rev()
-            .for_each(|(i, (name, alias))| {
+            .for_each(|(i, (name, info))| {
                 if !entries.names.iter().any(|(key, _)| key == name) {
-                    entries.names.push((name.clone(), alias.clone()));
+                    entries.names.push((name.clone(), info.clone()));
                     entries.values.push(self.values[i].clone());
                 }
             });
@@ -481,8 +503,12 @@ impl<'a> Scope<'a> {
         self.names
             .into_iter()
             .zip(self.values.into_iter())
-            .map(|((name, alias), value)| {
-                (name, value, alias.map(|a| a.to_vec()).unwrap_or_default())
+            .map(|((name, info), value)| {
+                (
+                    name,
+                    value,
+                    info.map(|info| info.aliases.to_vec()).unwrap_or_default(),
+                )
             })
     }
     /// Get an iterator to entries in the [`Scope`].
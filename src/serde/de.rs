@@ -10,10 +10,12 @@ use std::prelude::v1::*;
 use std::{any::type_name, fmt};
 
 #[cfg(not(feature = "no_index"))]
-use crate::Array;
+use crate::{Array, Blob, Set};
+#[cfg(not(feature = "no_index"))]
+use serde::de::value::SeqDeserializer;
 
 #[cfg(not(feature = "no_object"))]
-use crate::Map;
+use crate::{Dict, Map};
 
 /// Deserializer for [`Dynamic`][crate::Dynamic] which is kept as a reference.
 ///
@@ -47,6 +49,14 @@ impl<'de> DynamicDeserializer<'de> {
         )
         .into()
     }
+    /// Convert a [`BigInt`][num_bigint::BigInt] value into a primitive integer type, if it fits.
+    #[cfg(feature = "bigint")]
+    #[must_use]
+    fn bigint_to<T: std::convert::TryFrom<&'de num_bigint::BigInt>>(&self) -> Option<T> {
+        self.value
+            .downcast_ref::<num_bigint::BigInt>()
+            .and_then(|v| T::try_from(v).ok())
+    }
     #[must_use]
     fn deserialize_int<V: Visitor<'de>>(
         &mut self,
@@ -155,10 +165,33 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
             #[cfg(feature = "f32_float")]
             Union::Decimal(_, _, _) => self.deserialize_f32(visitor),
 
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref x, _, _) => {
+                use num_traits::ToPrimitive;
+
+                if let Some(v) = x.to_i64() {
+                    visitor.visit_i64(v)
+                } else if let Some(v) = x.to_u64() {
+                    visitor.visit_u64(v)
+                } else if let Some(v) = x.to_i128() {
+                    visitor.visit_i128(v)
+                } else if let Some(v) = x.to_u128() {
+                    visitor.visit_u128(v)
+                } else {
+                    visitor.visit_string(x.to_string())
+                }
+            }
+
             #[cfg(not(feature = "no_index"))]
             Union::Array(_, _, _) => self.deserialize_seq(visitor),
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(_, _, _) => self.deserialize_bytes(visitor),
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(_, _, _) => self.deserialize_seq(visitor),
             #[cfg(not(feature = "no_object"))]
             Union::Map(_, _, _) => self.deserialize_map(visitor),
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(_, _, _) => self.deserialize_map(visitor),
             Union::FnPtr(_, _, _) => self.type_error(),
             #[cfg(not(feature = "no_std"))]
             Union::TimeStamp(_, _, _) => self.type_error(),
@@ -186,6 +219,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(feature = "bigint")]
+        if let Some(x) = self.bigint_to::<i8>() {
+            return visitor.visit_i8(x);
+        }
         if let Ok(v) = self.value.as_int() {
             self.deserialize_int(v, visitor)
         } else {
@@ -196,6 +233,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(feature = "bigint")]
+        if let Some(x) = self.bigint_to::<i16>() {
+            return visitor.visit_i16(x);
+        }
         if let Ok(v) = self.value.as_int() {
             self.deserialize_int(v, visitor)
         } else {
@@ -206,6 +247,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(feature = "bigint")]
+        if let Some(x) = self.bigint_to::<i32>() {
+            return visitor.visit_i32(x);
+        }
         if let Ok(v) = self.value.as_int() {
             self.deserialize_int(v, visitor)
         } else if cfg!(feature = "only_i32") {
@@ -218,6 +263,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(feature = "bigint")]
+        if let Some(x) = self.bigint_to::<i64>() {
+            return visitor.visit_i64(x);
+        }
         if let Ok(v) = self.value.as_int() {
             self.deserialize_int(v, visitor)
         } else if cfg!(not(feature = "only_i32")) {
@@ -230,6 +279,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(feature = "bigint")]
+        if let Some(x) = self.bigint_to::<i128>() {
+            return visitor.visit_i128(x);
+        }
         if let Ok(v) = self.value.as_int() {
             self.deserialize_int(v, visitor)
         } else if cfg!(not(feature = "only_i32")) {
@@ -242,6 +295,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(feature = "bigint")]
+        if let Some(x) = self.bigint_to::<u8>() {
+            return visitor.visit_u8(x);
+        }
         if let Ok(v) = self.value.as_int() {
             self.deserialize_int(v, visitor)
         } else {
@@ -252,6 +309,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(feature = "bigint")]
+        if let Some(x) = self.bigint_to::<u16>() {
+            return visitor.visit_u16(x);
+        }
         if let Ok(v) = self.value.as_int() {
             self.deserialize_int(v, visitor)
         } else {
@@ -262,6 +323,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(feature = "bigint")]
+        if let Some(x) = self.bigint_to::<u32>() {
+            return visitor.visit_u32(x);
+        }
         if let Ok(v) = self.value.as_int() {
             self.deserialize_int(v, visitor)
         } else {
@@ -272,6 +337,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(feature = "bigint")]
+        if let Some(x) = self.bigint_to::<u64>() {
+            return visitor.visit_u64(x);
+        }
         if let Ok(v) = self.value.as_int() {
             self.deserialize_int(v, visitor)
         } else {
@@ -282,6 +351,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(feature = "bigint")]
+        if let Some(x) = self.bigint_to::<u128>() {
+            return visitor.visit_u128(x);
+        }
         if let Ok(v) = self.value.as_int() {
             self.deserialize_int(v, visitor)
         } else {
@@ -359,12 +432,25 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
         self.deserialize_str(visitor)
     }
 
-    fn deserialize_bytes<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Box<EvalAltResult>> {
-        self.type_error()
+    fn deserialize_bytes<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(not(feature = "no_index"))]
+        return self
+            .value
+            .downcast_ref::<Blob>()
+            .map_or_else(|| self.type_error(), |x| _visitor.visit_bytes(x));
+
+        #[cfg(feature = "no_index")]
+        return self.type_error();
     }
 
-    fn deserialize_byte_buf<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Box<EvalAltResult>> {
-        self.type_error()
+    fn deserialize_byte_buf<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Box<EvalAltResult>> {
+        self.deserialize_bytes(visitor)
     }
 
     fn deserialize_option<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Box<EvalAltResult>> {
@@ -394,6 +480,15 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_seq<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(not(feature = "no_index"))]
+        if let Some(blob) = self.value.downcast_ref::<Blob>() {
+            return _visitor.visit_seq(SeqDeserializer::new(blob.iter().copied()));
+        }
+        #[cfg(not(feature = "no_index"))]
+        if let Some(set) = self.value.downcast_ref::<Set>() {
+            return _visitor.visit_seq(IterateArray::new(set.iter()));
+        }
+
         #[cfg(not(feature = "no_index"))]
         return self.value.downcast_ref::<Array>().map_or_else(
             || self.type_error(),
@@ -422,6 +517,11 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
     }
 
     fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        #[cfg(not(feature = "no_object"))]
+        if let Some(dict) = self.value.downcast_ref::<Dict>() {
+            return _visitor.visit_map(IterateDict::new(dict.keys(), dict.values()));
+        }
+
         #[cfg(not(feature = "no_object"))]
         return self.value.downcast_ref::<Map>().map_or_else(
             || self.type_error(),
@@ -577,6 +677,63 @@ where
     }
 }
 
+/// `MapAccess` implementation for dictionaries.
+#[cfg(not(feature = "no_object"))]
+struct IterateDict<'a, KEYS, VALUES>
+where
+    KEYS: Iterator<Item = &'a Dynamic>,
+    VALUES: Iterator<Item = &'a Dynamic>,
+{
+    // Iterator for a stream of [`Dynamic`][crate::Dynamic] keys.
+    keys: KEYS,
+    // Iterator for a stream of [`Dynamic`][crate::Dynamic] values.
+    values: VALUES,
+}
+
+#[cfg(not(feature = "no_object"))]
+impl<'a, KEYS, VALUES> IterateDict<'a, KEYS, VALUES>
+where
+    KEYS: Iterator<Item = &'a Dynamic>,
+    VALUES: Iterator<Item = &'a Dynamic>,
+{
+    #[must_use]
+    pub fn new(keys: KEYS, values: VALUES) -> Self {
+        Self { keys, values }
+    }
+}
+
+#[cfg(not(feature = "no_object"))]
+impl<'a: 'de, 'de, KEYS, VALUES> MapAccess<'de> for IterateDict<'a, KEYS, VALUES>
+where
+    KEYS: Iterator<Item = &'a Dynamic>,
+    VALUES: Iterator<Item = &'a Dynamic>,
+{
+    type Error = Box<EvalAltResult>;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Box<EvalAltResult>> {
+        // Deserialize each key item coming out of the keys iterator.
+        match self.keys.next() {
+            None => Ok(None),
+            Some(item) => seed
+                .deserialize(&mut DynamicDeserializer::from_dynamic(item))
+                .map(Some),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Box<EvalAltResult>> {
+        // Deserialize each value item coming out of the iterator.
+        seed.deserialize(&mut DynamicDeserializer::from_dynamic(
+            self.values.next().unwrap(),
+        ))
+    }
+}
+
 #[cfg(not(feature = "no_object"))]
 struct EnumDeserializer<'t, 'de: 't> {
     tag: &'t str,
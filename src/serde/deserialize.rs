@@ -13,10 +13,10 @@ use crate::Array;
 use serde::de::SeqAccess;
 
 #[cfg(not(feature = "no_object"))]
-use crate::Map;
+use crate::{Dict, Map};
 
 #[cfg(not(feature = "no_object"))]
-use serde::de::MapAccess;
+use serde::de::{MapAccess, Unexpected};
 
 struct DynamicVisitor;
 
@@ -42,8 +42,8 @@ impl<'d> Visitor<'d> for DynamicVisitor {
         #[cfg(not(feature = "only_i32"))]
         return Ok(v.into());
         #[cfg(feature = "only_i32")]
-        if v > i32::MAX as i64 {
-            return Ok(Dynamic::from(v));
+        if v > i32::MAX as i64 || v < i32::MIN as i64 {
+            return Ok(super::wide_int(v));
         } else {
             return self.visit_i32(v as i32);
         }
@@ -59,7 +59,7 @@ impl<'d> Visitor<'d> for DynamicVisitor {
         return Ok(INT::from(v).into());
         #[cfg(feature = "only_i32")]
         if v > i32::MAX as u32 {
-            return Ok(Dynamic::from(v));
+            return Ok(super::wide_int(v));
         } else {
             return self.visit_i32(v as i32);
         }
@@ -67,13 +67,42 @@ impl<'d> Visitor<'d> for DynamicVisitor {
     fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
         #[cfg(not(feature = "only_i32"))]
         if v > i64::MAX as u64 {
-            return Ok(Dynamic::from(v));
+            return Ok(super::wide_int(v));
         } else {
             return self.visit_i64(v as i64);
         }
         #[cfg(feature = "only_i32")]
         if v > i32::MAX as u64 {
-            return Ok(Dynamic::from(v));
+            return Ok(super::wide_int(v));
+        } else {
+            return self.visit_i32(v as i32);
+        }
+    }
+
+    fn visit_i128<E: Error>(self, v: i128) -> Result<Self::Value, E> {
+        #[cfg(not(feature = "only_i32"))]
+        if v > i64::MAX as i128 || v < i64::MIN as i128 {
+            return Ok(super::wide_int(v));
+        } else {
+            return self.visit_i64(v as i64);
+        }
+        #[cfg(feature = "only_i32")]
+        if v > i32::MAX as i128 || v < i32::MIN as i128 {
+            return Ok(super::wide_int(v));
+        } else {
+            return self.visit_i32(v as i32);
+        }
+    }
+    fn visit_u128<E: Error>(self, v: u128) -> Result<Self::Value, E> {
+        #[cfg(not(feature = "only_i32"))]
+        if v > i64::MAX as u128 {
+            return Ok(super::wide_int(v));
+        } else {
+            return self.visit_i64(v as i64);
+        }
+        #[cfg(feature = "only_i32")]
+        if v > i32::MAX as u128 {
+            return Ok(super::wide_int(v));
         } else {
             return self.visit_i32(v as i32);
         }
@@ -136,6 +165,11 @@ impl<'d> Visitor<'d> for DynamicVisitor {
         Deserialize::deserialize(de)
     }
 
+    #[cfg(not(feature = "no_index"))]
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Dynamic::from_blob(v.to_vec()))
+    }
+
     #[cfg(not(feature = "no_index"))]
     fn visit_seq<A: SeqAccess<'d>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
         let mut arr: Array = Default::default();
@@ -151,8 +185,24 @@ impl<'d> Visitor<'d> for DynamicVisitor {
     fn visit_map<M: MapAccess<'d>>(self, mut map: M) -> Result<Self::Value, M::Error> {
         let mut m: Map = Default::default();
 
-        while let Some((k, v)) = map.next_entry::<&str, _>()? {
-            m.insert(k.into(), v);
+        while let Some((k, v)) = map.next_entry::<Dynamic, Dynamic>()? {
+            if k.is::<ImmutableString>() {
+                m.insert(k.cast::<ImmutableString>().as_str().into(), v);
+                continue;
+            }
+
+            // Not all keys are strings, so switch to a [`Dict`]
+            let mut d: Dict = m.into_iter().map(|(k, v)| (k.as_str().into(), v)).collect();
+            let mut entry = Some((k, v));
+
+            while let Some((k, v)) = entry {
+                d.insert(k, v).map_err(|typ| {
+                    M::Error::invalid_type(Unexpected::Other(typ), &"a hashable key")
+                })?;
+                entry = map.next_entry::<Dynamic, Dynamic>()?;
+            }
+
+            return Ok(d.into());
         }
 
         Ok(m.into())
@@ -12,3 +12,25 @@ mod metadata;
 
 pub use de::from_dynamic;
 pub use ser::to_dynamic;
+
+use crate::Dynamic;
+
+/// Convert an integer that does not fit into an [`INT`][crate::INT] into a [`Dynamic`].
+///
+/// Under `bigint`, the value becomes a [`BigInt`][num_bigint::BigInt].
+#[cfg(feature = "bigint")]
+#[inline(always)]
+#[must_use]
+fn wide_int(v: impl Into<num_bigint::BigInt>) -> Dynamic {
+    v.into().into()
+}
+
+/// Convert an integer that does not fit into an [`INT`][crate::INT] into a [`Dynamic`].
+///
+/// The value keeps its original type as a custom type.
+#[cfg(not(feature = "bigint"))]
+#[inline(always)]
+#[must_use]
+fn wide_int(v: impl crate::dynamic::Variant + Clone) -> Dynamic {
+    Dynamic::from(v)
+}
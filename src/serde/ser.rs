@@ -13,7 +13,7 @@ use std::prelude::v1::*;
 use crate::Array;
 
 #[cfg(not(feature = "no_object"))]
-use crate::Map;
+use crate::{Dict, ImmutableString, Map};
 
 /// Serializer for [`Dynamic`][crate::Dynamic] which is kept as a reference.
 struct DynamicSerializer {
@@ -32,6 +32,40 @@ impl DynamicSerializer {
             _value,
         }
     }
+    /// Add an entry into the object map being built.
+    ///
+    /// The object map is turned into a [`Dict`] when a key is not a string.
+    #[cfg(not(feature = "no_object"))]
+    fn insert_entry(&mut self, key: Dynamic, value: Dynamic) -> Result<(), Box<EvalAltResult>> {
+        if key.is::<ImmutableString>() {
+            if let Some(map) = self._value.downcast_mut::<Map>() {
+                map.insert(key.cast::<ImmutableString>().into(), value);
+                return Ok(());
+            }
+        }
+
+        if let Some(map) = self._value.downcast_mut::<Map>() {
+            let dict: Dict = std::mem::take(map)
+                .into_iter()
+                .map(|(k, v)| (k.as_str().into(), v))
+                .collect();
+            self._value = dict.into();
+        }
+
+        self._value
+            .downcast_mut::<Dict>()
+            .unwrap()
+            .insert(key, value)
+            .map(|_| ())
+            .map_err(|typ| {
+                EvalAltResult::ErrorMismatchDataType(
+                    "a hashable value".into(),
+                    typ.into(),
+                    Position::NONE,
+                )
+                .into()
+            })
+    }
 }
 
 /// Serialize a Rust type that implements [`serde::Serialize`] into a [`Dynamic`][crate::Dynamic].
@@ -140,8 +174,8 @@ impl Serializer for &mut DynamicSerializer {
         #[cfg(not(feature = "only_i32"))]
         return Ok(v.into());
         #[cfg(feature = "only_i32")]
-        if v > i32::MAX as i64 {
-            return Ok(Dynamic::from(v));
+        if v > i32::MAX as i64 || v < i32::MIN as i64 {
+            return Ok(super::wide_int(v));
         } else {
             return self.serialize_i32(v as i32);
         }
@@ -149,14 +183,14 @@ impl Serializer for &mut DynamicSerializer {
 
     fn serialize_i128(self, v: i128) -> Result<Self::Ok, Box<EvalAltResult>> {
         #[cfg(not(feature = "only_i32"))]
-        if v > i64::MAX as i128 {
-            return Ok(Dynamic::from(v));
+        if v > i64::MAX as i128 || v < i64::MIN as i128 {
+            return Ok(super::wide_int(v));
         } else {
             return self.serialize_i64(v as i64);
         }
         #[cfg(feature = "only_i32")]
-        if v > i32::MAX as i128 {
-            return Ok(Dynamic::from(v));
+        if v > i32::MAX as i128 || v < i32::MIN as i128 {
+            return Ok(super::wide_int(v));
         } else {
             return self.serialize_i32(v as i32);
         }
@@ -181,7 +215,7 @@ impl Serializer for &mut DynamicSerializer {
         return self.serialize_i64(i64::from(v));
         #[cfg(feature = "only_i32")]
         if v > i32::MAX as u32 {
-            return Ok(Dynamic::from(v));
+            return Ok(super::wide_int(v));
         } else {
             return self.serialize_i32(v as i32);
         }
@@ -190,13 +224,13 @@ impl Serializer for &mut DynamicSerializer {
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Box<EvalAltResult>> {
         #[cfg(not(feature = "only_i32"))]
         if v > i64::MAX as u64 {
-            return Ok(Dynamic::from(v));
+            return Ok(super::wide_int(v));
         } else {
             return self.serialize_i64(v as i64);
         }
         #[cfg(feature = "only_i32")]
         if v > i32::MAX as u64 {
-            return Ok(Dynamic::from(v));
+            return Ok(super::wide_int(v));
         } else {
             return self.serialize_i32(v as i32);
         }
@@ -205,13 +239,13 @@ impl Serializer for &mut DynamicSerializer {
     fn serialize_u128(self, v: u128) -> Result<Self::Ok, Box<EvalAltResult>> {
         #[cfg(not(feature = "only_i32"))]
         if v > i64::MAX as u128 {
-            return Ok(Dynamic::from(v));
+            return Ok(super::wide_int(v));
         } else {
             return self.serialize_i64(v as i64);
         }
         #[cfg(feature = "only_i32")]
         if v > i32::MAX as u128 {
-            return Ok(Dynamic::from(v));
+            return Ok(super::wide_int(v));
         } else {
             return self.serialize_i32(v as i32);
         }
@@ -543,19 +577,9 @@ impl SerializeMap for DynamicSerializer {
     ) -> Result<(), Box<EvalAltResult>> {
         #[cfg(not(feature = "no_object"))]
         {
-            let key = std::mem::take(&mut self._key)
-                .as_immutable_string()
-                .map_err(|typ| {
-                    EvalAltResult::ErrorMismatchDataType(
-                        "string".into(),
-                        typ.into(),
-                        Position::NONE,
-                    )
-                })?;
+            let key = std::mem::take(&mut self._key);
             let _value = _value.serialize(&mut *self)?;
-            let map = self._value.downcast_mut::<Map>().unwrap();
-            map.insert(key.into(), _value);
-            Ok(())
+            self.insert_entry(key, _value)
         }
         #[cfg(feature = "no_object")]
         return EvalAltResult::ErrorMismatchDataType(
@@ -574,13 +598,8 @@ impl SerializeMap for DynamicSerializer {
         #[cfg(not(feature = "no_object"))]
         {
             let _key: Dynamic = _key.serialize(&mut *self)?;
-            let _key = _key.as_immutable_string().map_err(|typ| {
-                EvalAltResult::ErrorMismatchDataType("string".into(), typ.into(), Position::NONE)
-            })?;
             let _value = _value.serialize(&mut *self)?;
-            let map = self._value.downcast_mut::<Map>().unwrap();
-            map.insert(_key.into(), _value);
-            Ok(())
+            self.insert_entry(_key, _value)
         }
         #[cfg(feature = "no_object")]
         return EvalAltResult::ErrorMismatchDataType(
@@ -52,8 +52,25 @@ impl Serialize for Dynamic {
                 }
             }
 
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref x, _, _) => {
+                use num_traits::ToPrimitive;
+
+                if let Some(v) = x.to_i64() {
+                    ser.serialize_i64(v)
+                } else if let Some(v) = x.to_u64() {
+                    ser.serialize_u64(v)
+                } else {
+                    ser.serialize_str(&x.to_string())
+                }
+            }
+
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref a, _, _) => (**a).serialize(ser),
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(ref a, _, _) => ser.serialize_bytes(a),
+            #[cfg(not(feature = "no_index"))]
+            Union::Set(ref x, _, _) => ser.collect_seq(x.iter()),
             #[cfg(not(feature = "no_object"))]
             Union::Map(ref m, _, _) => {
                 let mut map = ser.serialize_map(Some(m.len()))?;
@@ -62,6 +79,8 @@ impl Serialize for Dynamic {
                 }
                 map.end()
             }
+            #[cfg(not(feature = "no_object"))]
+            Union::Dict(ref d, _, _) => ser.collect_map(d.iter()),
             Union::FnPtr(ref f, _, _) => ser.serialize_str(f.fn_name()),
             #[cfg(not(feature = "no_std"))]
             Union::TimeStamp(ref x, _, _) => ser.serialize_str(x.as_ref().type_name()),
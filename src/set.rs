@@ -0,0 +1,145 @@
+//! Module implementing the [`Set`] collection type.
+#![cfg(not(feature = "no_index"))]
+
+use crate::dict::{self, Dict};
+use crate::Dynamic;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+/// Set of unique [`Dynamic`] values.
+/// Not available under `no_index`.
+///
+/// Only values that can be hashed (i.e. `()`, `bool`,
+/// characters, strings, numbers, ranges, and arrays, BLOBs, object maps, dictionaries and sets
+/// containing them) can be put into a [`Set`].
+///
+/// Iteration order is unspecified.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{Engine, Set, INT};
+///
+/// let engine = Engine::new();
+///
+/// let set: Set = engine.eval("let s = set([1, 2, 3, 2, 1]); s.insert(4); s")?;
+///
+/// assert_eq!(set.len(), 4);
+/// assert!(set.contains(&(4 as INT).into()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Set(Dict);
+
+impl fmt::Debug for Set {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl Hash for Set {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.len().hash(state);
+        self.iter().for_each(|v| dict::calc_key_hash(v).hash(state));
+    }
+}
+
+impl Set {
+    /// Create a new empty [`Set`].
+    #[inline(always)]
+    #[must_use]
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Get the number of values in the [`Set`].
+    #[inline(always)]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    /// Is the [`Set`] empty?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Does the [`Set`] contain a value?
+    #[inline(always)]
+    #[must_use]
+    pub fn contains(&self, value: &Dynamic) -> bool {
+        self.0.contains_key(value)
+    }
+    /// Add a value into the [`Set`].
+    ///
+    /// Returns `true` if the value was not already in the [`Set`].
+    /// Returns the name of the actual type if the value cannot be hashed.
+    #[inline]
+    pub fn insert(&mut self, value: Dynamic) -> Result<bool, &'static str> {
+        if self.0.contains_key(&value) {
+            Ok(false)
+        } else {
+            self.0.insert(value, Dynamic::UNIT).map(|_| true)
+        }
+    }
+    /// Remove a value from the [`Set`], returning it if it was in the [`Set`].
+    #[inline(always)]
+    pub fn remove(&mut self, value: &Dynamic) -> Option<Dynamic> {
+        self.0.remove_entry(value).map(|(k, _)| k)
+    }
+    /// Remove all values from the [`Set`].
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+    /// Add all values of another [`Set`] into this [`Set`].
+    #[inline]
+    pub fn extend_from_set(&mut self, other: Self) {
+        if self.0.is_empty() {
+            *self = other;
+        } else {
+            self.0.extend(other.0);
+        }
+    }
+    /// Keep only the values that are also in another [`Set`].
+    #[inline]
+    pub fn retain_in(&mut self, other: &Self) {
+        self.0.retain(|k, _| other.0.contains_key(k));
+    }
+    /// Remove all the values that are in another [`Set`].
+    #[inline]
+    pub fn remove_all_in(&mut self, other: &Self) {
+        if !other.0.is_empty() {
+            self.0.retain(|k, _| !other.0.contains_key(k));
+        }
+    }
+    /// Are all the values of this [`Set`] also in another [`Set`]?
+    #[inline]
+    #[must_use]
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.0.len() <= other.0.len() && self.iter().all(|k| other.0.contains_key(k))
+    }
+    /// Iterate through all the values in the [`Set`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &Dynamic> {
+        self.0.keys()
+    }
+}
+
+impl IntoIterator for Set {
+    type Item = Dynamic;
+    type IntoIter =
+        std::iter::Map<<Dict as IntoIterator>::IntoIter, fn((Dynamic, Dynamic)) -> Dynamic>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter().map(|(k, _)| k)
+    }
+}
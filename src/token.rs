@@ -4,6 +4,7 @@ use crate::engine::{
     Precedence, KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_FN_PTR_CALL,
     KEYWORD_FN_PTR_CURRY, KEYWORD_IS_DEF_VAR, KEYWORD_PRINT, KEYWORD_THIS, KEYWORD_TYPE_OF,
 };
+use crate::format::FormatSpec;
 use crate::{Engine, LexError, StaticVec, INT};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -24,6 +25,9 @@ use crate::{ast::FloatWrapper, FLOAT};
 #[cfg(feature = "decimal")]
 use rust_decimal::Decimal;
 
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+
 #[cfg(not(feature = "no_function"))]
 use crate::engine::KEYWORD_IS_DEF_FN;
 
@@ -33,6 +37,9 @@ pub struct TokenizerControlBlock {
     /// Is the current tokenizer position within an interpolated text string?
     /// This flag allows switching the tokenizer back to _text_ parsing after an interpolation stream.
     pub is_within_text: bool,
+    /// Is the current tokenizer position at the format specifier of an interpolation (e.g. `.2` in `${x:.2}`)?
+    /// This flag allows reading the format specifier as raw text up to the closing `}`.
+    pub is_within_format_spec: bool,
 }
 
 /// _(INTERNALS)_ A shared object that allows control of the tokenizer from outside.
@@ -286,6 +293,11 @@ pub enum Token {
     /// Requires the `decimal` feature.
     #[cfg(feature = "decimal")]
     DecimalConstant(Decimal),
+    /// A [`BigInt`] constant, i.e. an integer literal that does not fit into an [`INT`].
+    ///
+    /// Requires the `bigint` feature.
+    #[cfg(feature = "bigint")]
+    BigIntConstant(BigInt),
     /// An identifier.
     Identifier(String),
     /// A character constant.
@@ -334,12 +346,26 @@ pub enum Token {
     DoubleColon,
     /// `=>`
     DoubleArrow,
+    /// `->`
+    SingleArrow,
     /// `_`
     Underscore,
     /// `,`
     Comma,
     /// `.`
     Period,
+    /// `?.`
+    Elvis,
+    /// `??`
+    DoubleQuestion,
+    /// `?[`
+    QuestionBracket,
+    /// `..`
+    ExclusiveRange,
+    /// `..=`
+    InclusiveRange,
+    /// `...`
+    Spread,
     /// `#{`
     MapStart,
     /// `=`
@@ -411,6 +437,8 @@ pub enum Token {
     Try,
     /// `catch`
     Catch,
+    /// `finally`
+    Finally,
     /// `+=`
     PlusAssign,
     /// `-=`
@@ -438,6 +466,16 @@ pub enum Token {
     /// Reserved under the `no_function` feature.
     #[cfg(not(feature = "no_function"))]
     Private,
+    /// `yield`
+    ///
+    /// Reserved under the `no_function` feature.
+    #[cfg(not(feature = "no_function"))]
+    Yield,
+    /// `enum`
+    ///
+    /// Reserved under the `no_function` feature.
+    #[cfg(not(feature = "no_function"))]
+    Enum,
     /// `import`
     ///
     /// Reserved under the `no_module` feature.
@@ -488,9 +526,16 @@ impl Token {
             Colon => ":",
             DoubleColon => "::",
             DoubleArrow => "=>",
+            SingleArrow => "->",
             Underscore => "_",
             Comma => ",",
             Period => ".",
+            Elvis => "?.",
+            DoubleQuestion => "??",
+            QuestionBracket => "?[",
+            ExclusiveRange => "..",
+            InclusiveRange => "..=",
+            Spread => "...",
             MapStart => "#{",
             Equals => "=",
             True => "true",
@@ -523,6 +568,7 @@ impl Token {
             Throw => "throw",
             Try => "try",
             Catch => "catch",
+            Finally => "finally",
             PlusAssign => "+=",
             MinusAssign => "-=",
             MultiplyAssign => "*=",
@@ -544,6 +590,10 @@ impl Token {
             Fn => "fn",
             #[cfg(not(feature = "no_function"))]
             Private => "private",
+            #[cfg(not(feature = "no_function"))]
+            Yield => "yield",
+            #[cfg(not(feature = "no_function"))]
+            Enum => "enum",
 
             #[cfg(not(feature = "no_module"))]
             Import => "import",
@@ -567,6 +617,8 @@ impl Token {
             FloatConstant(f) => f.to_string().into(),
             #[cfg(feature = "decimal")]
             DecimalConstant(d) => d.to_string().into(),
+            #[cfg(feature = "bigint")]
+            BigIntConstant(n) => n.to_string().into(),
             StringConstant(_) => "string".into(),
             InterpolatedString(_) => "string".into(),
             CharConstant(c) => c.to_string().into(),
@@ -680,9 +732,16 @@ impl Token {
             ":" => Colon,
             "::" => DoubleColon,
             "=>" => DoubleArrow,
+            "->" => SingleArrow,
             "_" => Underscore,
             "," => Comma,
             "." => Period,
+            "?." => Elvis,
+            "??" => DoubleQuestion,
+            "?[" => QuestionBracket,
+            ".." => ExclusiveRange,
+            "..=" => InclusiveRange,
+            "..." => Spread,
             "#{" => MapStart,
             "=" => Equals,
             "true" => True,
@@ -715,6 +774,7 @@ impl Token {
             "throw" => Throw,
             "try" => Try,
             "catch" => Catch,
+            "finally" => Finally,
             "+=" => PlusAssign,
             "-=" => MinusAssign,
             "*=" => MultiplyAssign,
@@ -736,6 +796,10 @@ impl Token {
             "fn" => Fn,
             #[cfg(not(feature = "no_function"))]
             "private" => Private,
+            #[cfg(not(feature = "no_function"))]
+            "yield" => Yield,
+            #[cfg(not(feature = "no_function"))]
+            "enum" => Enum,
 
             #[cfg(not(feature = "no_module"))]
             "import" => Import,
@@ -745,16 +809,17 @@ impl Token {
             "as" => As,
 
             #[cfg(feature = "no_function")]
-            "fn" | "private" => Reserved(syntax.into()),
+            "fn" | "private" | "yield" | "enum" => Reserved(syntax.into()),
 
             #[cfg(feature = "no_module")]
             "import" | "export" | "as" => Reserved(syntax.into()),
 
-            "===" | "!==" | "->" | "<-" | ":=" | "~" | "::<" | "(*" | "*)" | "#" | "#!"
-            | "public" | "protected" | "super" | "new" | "use" | "module" | "package" | "var"
-            | "static" | "shared" | "with" | "goto" | "exit" | "match" | "case" | "default"
-            | "void" | "null" | "nil" | "spawn" | "thread" | "go" | "sync" | "async" | "await"
-            | "yield" => Reserved(syntax.into()),
+            "===" | "!==" | "<-" | ":=" | "~" | "::<" | "(*" | "*)" | "#" | "#!" | "public"
+            | "protected" | "super" | "new" | "use" | "module" | "package" | "var" | "static"
+            | "shared" | "with" | "goto" | "exit" | "match" | "case" | "default" | "void"
+            | "null" | "nil" | "spawn" | "thread" | "go" | "sync" | "async" | "await" => {
+                Reserved(syntax.into())
+            }
 
             KEYWORD_PRINT | KEYWORD_DEBUG | KEYWORD_TYPE_OF | KEYWORD_EVAL | KEYWORD_FN_PTR
             | KEYWORD_FN_PTR_CALL | KEYWORD_FN_PTR_CURRY | KEYWORD_THIS | KEYWORD_IS_DEF_VAR => {
@@ -802,6 +867,12 @@ impl Token {
             Divide           |
             Comma            |
             Period           |
+            Elvis            |
+            DoubleQuestion   |
+            QuestionBracket  |
+            ExclusiveRange   |
+            InclusiveRange   |
+            Spread           |
             Equals           |
             LessThan         |
             GreaterThan      |
@@ -838,6 +909,9 @@ impl Token {
             Throw            |
             In               => true,
 
+            #[cfg(not(feature = "no_function"))]
+            Yield => true,
+
             _ => false,
         }
     }
@@ -853,6 +927,8 @@ impl Token {
             | LeftShiftAssign | RightShiftAssign | AndAssign | OrAssign | XOrAssign
             | ModuloAssign => 0,
 
+            DoubleQuestion => 20,
+
             Or | XOr | Pipe => 30,
 
             And | Ampersand => 60,
@@ -863,6 +939,8 @@ impl Token {
 
             LessThan | LessThanEqualsTo | GreaterThan | GreaterThanEqualsTo => 130,
 
+            ExclusiveRange | InclusiveRange => 140,
+
             Plus | Minus => 150,
 
             Divide | Multiply | Modulo => 180,
@@ -906,11 +984,13 @@ impl Token {
         match self {
             LeftBrace | RightBrace | LeftParen | RightParen | LeftBracket | RightBracket | Plus
             | UnaryPlus | Minus | UnaryMinus | Multiply | Divide | Modulo | PowerOf | LeftShift
-            | RightShift | SemiColon | Colon | DoubleColon | Comma | Period | MapStart | Equals
-            | LessThan | GreaterThan | LessThanEqualsTo | GreaterThanEqualsTo | EqualsTo
-            | NotEqualsTo | Bang | Pipe | Or | XOr | Ampersand | And | PlusAssign | MinusAssign
-            | MultiplyAssign | DivideAssign | LeftShiftAssign | RightShiftAssign | AndAssign
-            | OrAssign | XOrAssign | ModuloAssign | PowerOfAssign => true,
+            | RightShift | SemiColon | Colon | DoubleColon | Comma | Period | Elvis
+            | DoubleQuestion | QuestionBracket | ExclusiveRange | InclusiveRange | Spread
+            | MapStart | Equals | LessThan | GreaterThan | LessThanEqualsTo
+            | GreaterThanEqualsTo | EqualsTo | NotEqualsTo | Bang | Pipe | Or | XOr | Ampersand
+            | And | PlusAssign | MinusAssign | MultiplyAssign | DivideAssign | LeftShiftAssign
+            | RightShiftAssign | AndAssign | OrAssign | XOrAssign | ModuloAssign
+            | PowerOfAssign => true,
 
             _ => false,
         }
@@ -923,13 +1003,13 @@ impl Token {
 
         match self {
             #[cfg(not(feature = "no_function"))]
-            Fn | Private => true,
+            Fn | Private | Yield | Enum => true,
 
             #[cfg(not(feature = "no_module"))]
             Import | Export | As => true,
 
             True | False | Let | Const | If | Else | Do | While | Until | Loop | For | In
-            | Continue | Break | Return | Throw | Try | Catch => true,
+            | Continue | Break | Return | Throw | Try | Catch | Finally => true,
 
             _ => false,
         }
@@ -1449,7 +1529,7 @@ fn get_next_token_inner(
                                     stream.unget(next_char);
                                     break;
                                 }
-                                // .. - reserved symbol, not a floating-point number
+                                // .. - range operator, not a floating-point number
                                 '.' => {
                                     stream.unget(next_char);
                                     break;
@@ -1536,16 +1616,32 @@ fn get_next_token_inner(
                             .filter(|&&c| c != NUMBER_SEPARATOR)
                             .collect();
 
-                        INT::from_str_radix(&out, radix)
-                            .map(Token::IntegerConstant)
-                            .unwrap_or_else(|_| {
-                                Token::LexError(LERR::MalformedNumber(result.into_iter().collect()))
-                            })
+                        let num = INT::from_str_radix(&out, radix).map(Token::IntegerConstant);
+
+                        // If the integer is too large, try big integer instead
+                        #[cfg(feature = "bigint")]
+                        let num = num.or_else(|err| {
+                            BigInt::parse_bytes(out.as_bytes(), radix)
+                                .map(Token::BigIntConstant)
+                                .ok_or(err)
+                        });
+
+                        num.unwrap_or_else(|_| {
+                            Token::LexError(LERR::MalformedNumber(result.into_iter().collect()))
+                        })
                     } else {
                         let out: String =
                             result.iter().filter(|&&c| c != NUMBER_SEPARATOR).collect();
                         let num = INT::from_str(&out).map(Token::IntegerConstant);
 
+                        // If the integer is too large, try big integer instead
+                        #[cfg(feature = "bigint")]
+                        let num = num.or_else(|err| {
+                            BigInt::from_str(&out)
+                                .map(Token::BigIntConstant)
+                                .map_err(|_| err)
+                        });
+
                         // If integer parsing is unnecessary, try float instead
                         #[cfg(not(feature = "no_float"))]
                         let num =
@@ -1693,7 +1789,7 @@ fn get_next_token_inner(
             }
             ('-', '>') => {
                 eat_next(stream, pos);
-                return Some((Token::Reserved("->".into()), start_pos));
+                return Some((Token::SingleArrow, start_pos));
             }
             ('-', '-') => {
                 eat_next(stream, pos);
@@ -1798,11 +1894,16 @@ fn get_next_token_inner(
             ('.', '.') => {
                 eat_next(stream, pos);
 
-                if stream.peek_next() == Some('.') {
-                    eat_next(stream, pos);
-                    return Some((Token::Reserved("...".into()), start_pos));
-                } else {
-                    return Some((Token::Reserved("..".into()), start_pos));
+                match stream.peek_next() {
+                    Some('.') => {
+                        eat_next(stream, pos);
+                        return Some((Token::Spread, start_pos));
+                    }
+                    Some('=') => {
+                        eat_next(stream, pos);
+                        return Some((Token::InclusiveRange, start_pos));
+                    }
+                    _ => return Some((Token::ExclusiveRange, start_pos)),
                 }
             }
             ('.', _) => return Some((Token::Period, start_pos)),
@@ -1927,6 +2028,20 @@ fn get_next_token_inner(
             }
             ('%', _) => return Some((Token::Modulo, start_pos)),
 
+            ('?', '.') => {
+                eat_next(stream, pos);
+                return Some((Token::Elvis, start_pos));
+            }
+            ('?', '?') => {
+                eat_next(stream, pos);
+                return Some((Token::DoubleQuestion, start_pos));
+            }
+            ('?', '[') => {
+                eat_next(stream, pos);
+                return Some((Token::QuestionBracket, start_pos));
+            }
+            ('?', _) => return Some((Token::Reserved("?".into()), start_pos)),
+
             ('@', _) => return Some((Token::Reserved("@".into()), start_pos)),
 
             ('$', _) => return Some((Token::Reserved("$".into()), start_pos)),
@@ -2000,6 +2115,29 @@ pub fn is_keyword_function(name: &str) -> bool {
     }
 }
 
+/// Can this binary operator be overloaded by a script-defined function?
+#[cfg(not(feature = "no_function"))]
+#[inline(always)]
+#[must_use]
+pub fn is_overloadable_operator(op: &str) -> bool {
+    match op {
+        "+" | "-" | "*" | "/" | "%" | "**" | "<<" | ">>" | "&" | "|" | "^" | "==" | "!=" | "<"
+        | "<=" | ">" | ">=" => true,
+        _ => false,
+    }
+}
+
+/// Can this unary operator be overloaded by a script-defined function?
+#[cfg(not(feature = "no_function"))]
+#[inline(always)]
+#[must_use]
+pub fn is_overloadable_unary_operator(op: &str) -> bool {
+    match op {
+        "-" | "!" => true,
+        _ => false,
+    }
+}
+
 /// Is a text string a valid identifier?
 #[must_use]
 pub fn is_valid_identifier(name: impl Iterator<Item = char>) -> bool {
@@ -2138,6 +2276,34 @@ impl<'a> Iterator for TokenIterator<'a> {
             self.tokenizer_control.set(control);
         }
 
+        if control.is_within_format_spec {
+            // Reset it
+            control.is_within_format_spec = false;
+            self.tokenizer_control.set(control);
+
+            // Read the format specifier up to the closing `}`
+            let start_pos = self.pos;
+            let mut spec = String::new();
+
+            while let Some(ch) = self.stream.peek_next() {
+                if ch == '}' || ch == '\n' {
+                    break;
+                }
+                self.stream.get_next();
+                self.pos.advance();
+                spec.push(ch);
+            }
+
+            // Surrounding whitespace is not part of the format specifier,
+            // and a fill character that is a space is the default anyway
+            let spec = spec.trim().to_string();
+
+            return Some(match FormatSpec::parse(&spec) {
+                Ok(_) => (Token::StringConstant(spec), start_pos),
+                Err(_) => (Token::LexError(LERR::MalformedFormatSpec(spec)), start_pos),
+            });
+        }
+
         let (token, pos) = match get_next_token(&mut self.stream, &mut self.state, &mut self.pos) {
             // {EOF}
             None => return None,
@@ -2160,8 +2326,6 @@ impl<'a> Iterator for TokenIterator<'a> {
                 ("!==", false) => Token::LexError(LERR::ImproperSymbol(s,
                     "'!==' is not a valid operator. This is not JavaScript! Should it be '!='?".to_string(),
                 )),
-                ("->", false) => Token::LexError(LERR::ImproperSymbol(s,
-                    "'->' is not a valid symbol. This is not C or C++!".to_string())),
                 ("<-", false) => Token::LexError(LERR::ImproperSymbol(s,
                     "'<-' is not a valid symbol. This is not Go! Should it be '<='?".to_string(),
                 )),
@@ -0,0 +1,250 @@
+#![cfg(feature = "async")]
+use rhai::{Engine, EvalAltResult, Shared, INT};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+struct Unpark(thread::Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut future = Box::pin(future);
+
+    loop {
+        match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(r) => return r,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// Future that is pending until released.
+struct Gate {
+    started: Arc<AtomicBool>,
+    released: Arc<AtomicBool>,
+}
+
+impl Future for Gate {
+    type Output = INT;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<INT> {
+        self.started.store(true, Ordering::SeqCst);
+
+        if self.released.load(Ordering::SeqCst) {
+            Poll::Ready(42)
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn test_async_eval() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_async_fn("double", |x: INT| async move { x * 2 });
+    engine.register_async_result_fn("check", |x: INT| async move {
+        if x < 0 {
+            Err("negative".into())
+        } else {
+            Ok(x)
+        }
+    });
+
+    let engine = Shared::new(engine);
+
+    assert_eq!(
+        block_on(engine.eval_async::<INT>("let x = 0; for i in 0..5 { x += double(i); } x"))?,
+        20
+    );
+    assert_eq!(block_on(engine.eval_async::<INT>("check(42)"))?, 42);
+    assert!(matches!(
+        *block_on(engine.eval_async::<INT>("check(-1)")).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+    assert!(matches!(
+        *block_on(engine.eval_async::<INT>("let x = ;")).expect_err("should error"),
+        EvalAltResult::ErrorParsing(_, _)
+    ));
+
+    let ast = engine.compile("40 + double(1)")?;
+    assert_eq!(block_on(engine.eval_ast_async::<INT>(&ast))?, 42);
+
+    // Asynchronous functions do not block synchronous evaluation
+    assert!(matches!(
+        *engine.eval::<INT>("double(21)").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_async_eval_yields() -> Result<(), Box<EvalAltResult>> {
+    let started = Arc::new(AtomicBool::new(false));
+    let released = Arc::new(AtomicBool::new(false));
+
+    let mut engine = Engine::new();
+
+    let (s, r) = (started.clone(), released.clone());
+    engine.register_async_fn("wait", move || Gate {
+        started: s.clone(),
+        released: r.clone(),
+    });
+
+    let engine = Shared::new(engine);
+
+    let mut future = engine.eval_async::<INT>("wait() / 2");
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    // The evaluation does not complete while `wait` is pending
+    while !started.load(Ordering::SeqCst) {
+        assert!(Pin::new(&mut future).poll(&mut cx).is_pending());
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert!(Pin::new(&mut future).poll(&mut cx).is_pending());
+
+    released.store(true, Ordering::SeqCst);
+
+    assert_eq!(block_on(future)?, 21);
+
+    Ok(())
+}
+
+#[test]
+fn test_async_eval_pool() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_async_fn("double", |x: INT| async move { x * 2 });
+    engine.register_fn("thread_id", || format!("{:?}", thread::current().id()));
+
+    let engine = Shared::new(engine);
+
+    // Threads are reused by later evaluations
+    let mut ids = Vec::new();
+
+    for _ in 0..50 {
+        let id = block_on(engine.eval_async::<String>("thread_id()"))?;
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    assert!(ids.len() < 50);
+
+    // Many evaluations at once all complete
+    let mut futures: Vec<_> = (0..100 as INT)
+        .map(|i| engine.eval_async::<INT>(&format!("double({})", i)))
+        .collect();
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut results = vec![None; futures.len()];
+
+    while results.iter().any(Option::is_none) {
+        for (future, result) in futures.iter_mut().zip(results.iter_mut()) {
+            if result.is_none() {
+                if let Poll::Ready(r) = Pin::new(future).poll(&mut cx) {
+                    *result = Some(r?);
+                }
+            }
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert!(results
+        .into_iter()
+        .enumerate()
+        .all(|(i, r)| r == Some(i as INT * 2)));
+
+    Ok(())
+}
+
+#[test]
+fn test_async_eval_nested() -> Result<(), Box<EvalAltResult>> {
+    let slot = Arc::new(OnceLock::<Shared<Engine>>::new());
+
+    let mut engine = Engine::new();
+
+    engine.register_async_fn("double", |x: INT| async move { x * 2 });
+
+    let outer = slot.clone();
+    engine.register_async_result_fn("nested", move |x: INT| {
+        let engine = outer.get().expect("engine is set").clone();
+        async move {
+            engine
+                .eval_async::<INT>(&format!("double({})", x))
+                .await
+                .map(|r| r + 1)
+        }
+    });
+
+    let engine = Shared::new(engine);
+    let _ = slot.set(engine.clone());
+
+    // More outer evaluations than idle threads kept by the pool, each waiting on a nested one
+    let count = thread::available_parallelism()
+        .map_or(4, |n| n.get())
+        .max(4)
+        * 2;
+
+    let mut futures: Vec<_> = (0..count as INT)
+        .map(|i| engine.eval_async::<INT>(&format!("nested({})", i)))
+        .collect();
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut results = vec![None; futures.len()];
+
+    while results.iter().any(Option::is_none) {
+        for (future, result) in futures.iter_mut().zip(results.iter_mut()) {
+            if result.is_none() {
+                if let Poll::Ready(r) = Pin::new(future).poll(&mut cx) {
+                    *result = Some(r?);
+                }
+            }
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert!(results
+        .into_iter()
+        .enumerate()
+        .all(|(i, r)| r == Some(i as INT * 2 + 1)));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_async_call_fn() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_async_fn("double", |x: INT| async move { x * 2 });
+
+    let ast = engine.compile("fn calc(x, y) { double(x) + y }")?;
+
+    let engine = Shared::new(engine);
+
+    let result: INT = block_on(engine.call_fn_async(&ast, "calc", (20 as INT, 2 as INT)))?;
+    assert_eq!(result, 42);
+
+    assert!(matches!(
+        *block_on(engine.call_fn_async::<INT>(&ast, "nope", ())).expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    Ok(())
+}
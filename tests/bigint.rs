@@ -0,0 +1,231 @@
+#![cfg(feature = "bigint")]
+
+use num_bigint::BigInt;
+use rhai::{Engine, EvalAltResult, Scope, INT};
+
+#[test]
+fn test_bigint() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>("type_of(to_bigint(42))")?, "bigint");
+    assert_eq!(
+        engine.eval::<BigInt>("123456789012345678901234567890")?,
+        "123456789012345678901234567890".parse::<BigInt>().unwrap()
+    );
+    assert_eq!(
+        engine.eval::<BigInt>("0xffff_ffff_ffff_ffff_ffff")?,
+        BigInt::from(u128::pow(2, 80) - 1)
+    );
+    assert_eq!(
+        engine.eval::<BigInt>("to_bigint(2) ** 100")?,
+        BigInt::from(2).pow(100)
+    );
+    assert_eq!(
+        engine.eval::<BigInt>("-99999999999999999999 + 1")?,
+        "-99999999999999999998".parse::<BigInt>().unwrap()
+    );
+    assert_eq!(engine.eval::<BigInt>("to_bigint(7) % 4")?, BigInt::from(3));
+    assert_eq!(
+        engine.eval::<BigInt>("100 / to_bigint(7)")?,
+        BigInt::from(14)
+    );
+    assert_eq!(
+        engine.eval::<BigInt>("abs(to_bigint(-5))")?,
+        BigInt::from(5)
+    );
+    assert_eq!(engine.eval::<INT>("sign(to_bigint(-5))")?, -1);
+    assert!(engine.eval::<bool>("is_odd(to_bigint(-3))")?);
+    assert!(engine.eval::<bool>("is_zero(to_bigint(0))")?);
+
+    assert_eq!(
+        engine.eval::<BigInt>("let x = to_bigint(10); x *= 10; x -= 1; x")?,
+        BigInt::from(99)
+    );
+
+    assert!(engine.eval::<bool>("99999999999999999999 > 42")?);
+    assert!(engine.eval::<bool>("42 < 99999999999999999999")?);
+    assert!(engine.eval::<bool>("to_bigint(42) == 42")?);
+    assert!(!engine.eval::<bool>("to_bigint(42) != to_bigint(42)")?);
+
+    assert!(matches!(
+        *engine
+            .eval::<BigInt>("to_bigint(42) / 0")
+            .expect_err("expects division by zero"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<BigInt>("to_bigint(42) ** -1")
+            .expect_err("expects negative index"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_bigint_conversions() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    scope.push("big", BigInt::from(10).pow(22));
+
+    assert_eq!(
+        engine.eval::<BigInt>(r#"parse_bigint("-123456789012345678901234567890")"#)?,
+        "-123456789012345678901234567890".parse::<BigInt>().unwrap()
+    );
+    assert_eq!(
+        engine.eval::<BigInt>(r#"parse_bigint("ff", 16)"#)?,
+        BigInt::from(255)
+    );
+    assert!(engine.eval::<BigInt>(r#"parse_bigint("12x")"#).is_err());
+
+    assert_eq!(engine.eval::<INT>("to_int(to_bigint(42))")?, 42);
+    assert!(matches!(
+        *engine
+            .eval_with_scope::<INT>(&mut scope, "to_int(big)")
+            .expect_err("expects overflow"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    assert_eq!(
+        engine.eval_with_scope::<String>(&mut scope, "big.to_string()")?,
+        format!("1{}", "0".repeat(22))
+    );
+    assert_eq!(engine.eval::<String>("to_bigint(-255).to_hex()")?, "-ff");
+    assert_eq!(
+        engine.eval::<String>(r#"format("{:#x}", to_bigint(255))"#)?,
+        "0xff"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{:+06}", to_bigint(42))"#)?,
+        "+00042"
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(engine.eval::<rhai::FLOAT>("to_float(to_bigint(42))")?, 42.0);
+        assert_eq!(engine.eval::<BigInt>("to_bigint(42.9)")?, BigInt::from(42));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bigint_shift() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<BigInt>("to_bigint(1) << 100")?,
+        BigInt::from(2).pow(100)
+    );
+    assert_eq!(
+        engine.eval::<BigInt>("(to_bigint(1) << 100) >> 98")?,
+        BigInt::from(4)
+    );
+    assert_eq!(
+        engine.eval::<BigInt>("to_bigint(-8) >> 1000")?,
+        BigInt::from(-1)
+    );
+    assert_eq!(
+        engine.eval::<BigInt>("let x = to_bigint(3); x <<= 70; x >>= 69; x")?,
+        BigInt::from(6)
+    );
+    assert!(matches!(
+        *engine
+            .eval::<BigInt>("to_bigint(1) << -1")
+            .expect_err("expects negative shift"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_bigint_switch() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let script = r#"
+        let x = to_bigint(0) + X;
+        switch x {
+            0 => "zero",
+            1 | 2 => "small",
+            3..10 => "range",
+            99999999999999999999 => "big",
+            _ => "other"
+        }
+    "#;
+
+    for (value, expected) in [
+        ("0", "zero"),
+        ("2", "small"),
+        ("7", "range"),
+        ("99999999999999999999", "big"),
+        ("99999999999999999998", "other"),
+    ] {
+        assert_eq!(
+            engine.eval::<String>(&script.replace("X", value))?,
+            expected
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_float"))]
+#[test]
+fn test_bigint_float() -> Result<(), Box<EvalAltResult>> {
+    use rhai::FLOAT;
+
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<FLOAT>("to_bigint(3) * 0.5")?, 1.5);
+    assert_eq!(engine.eval::<FLOAT>("1.5 + to_bigint(2)")?, 3.5);
+    assert_eq!(
+        engine.eval::<FLOAT>("to_bigint(2) ** 0.5")?,
+        (2.0 as FLOAT).sqrt()
+    );
+    assert!(engine.eval::<bool>("to_bigint(2) > 1.5")?);
+    assert!(engine.eval::<bool>("2.0 == to_bigint(2)")?);
+    assert!(engine.eval::<bool>("99999999999999999999 > 1.0e19")?);
+    assert_eq!(
+        engine.eval::<FLOAT>("let x = to_bigint(3); x += 0.5; x")?,
+        3.5
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "decimal")]
+#[test]
+fn test_bigint_decimal() -> Result<(), Box<EvalAltResult>> {
+    use rust_decimal::Decimal;
+
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<Decimal>("to_bigint(1) + to_decimal(0) / to_decimal(4) + to_decimal(2)")?,
+        Decimal::from(3)
+    );
+    assert_eq!(
+        engine.eval::<Decimal>("to_bigint(3) * parse_decimal(\"0.5\")")?,
+        "1.5".parse::<Decimal>().unwrap()
+    );
+    assert!(engine.eval::<bool>("to_bigint(2) > parse_decimal(\"1.5\")")?);
+    assert_eq!(
+        engine.eval::<BigInt>("to_bigint(parse_decimal(\"-2.7\"))")?,
+        BigInt::from(-2)
+    );
+    assert_eq!(
+        engine.eval::<Decimal>("to_decimal(to_bigint(42))")?,
+        Decimal::from(42)
+    );
+    assert!(matches!(
+        *engine
+            .eval::<Decimal>("to_bigint(10) ** 40 + to_decimal(1)")
+            .expect_err("expects conversion error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+#![cfg(not(feature = "no_index"))]
+use rhai::{Blob, Engine, EvalAltResult, Scope, INT};
+
+#[test]
+fn test_blobs() -> Result<(), Box<EvalAltResult>> {
+    let mut a = Blob::new();
+    a.push(1);
+    a.push(2);
+    a.push(3);
+
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+    scope.push("x", a);
+
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "x[1]")?, 2);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "x[0]")?, 1);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "x[-1]")?, 3);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "x[-3]")?, 1);
+    assert!(engine.eval_with_scope::<bool>(&mut scope, "2 in x")?);
+    assert!(!engine.eval_with_scope::<bool>(&mut scope, "42 in x")?);
+    assert_eq!(
+        engine.eval_with_scope::<String>(&mut scope, "type_of(x)")?,
+        "blob"
+    );
+    assert_eq!(
+        engine.eval_with_scope::<Blob>(&mut scope, "x += 4; x")?,
+        [1, 2, 3, 4]
+    );
+    assert_eq!(
+        engine.eval_with_scope::<Blob>(&mut scope, "let y = x; y[1] = 0x142; y")?,
+        [1, 0x42, 3, 4]
+    );
+    assert_eq!(
+        engine.eval_with_scope::<Blob>(&mut scope, "x[1..3]")?,
+        [2, 3]
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "let s = 0; for b in x { s += b } s")?,
+        10
+    );
+    assert_eq!(
+        engine.eval_with_scope::<Blob>(&mut scope, "x + x")?,
+        [1, 2, 3, 4, 1, 2, 3, 4]
+    );
+    assert!(engine.eval_with_scope::<bool>(&mut scope, "x == x + blob()")?);
+    assert!(engine.eval_with_scope::<bool>(&mut scope, "x != blob(4)")?);
+    assert_eq!(
+        engine.eval_with_scope::<String>(&mut scope, "to_string(x)")?,
+        "[01 02 03 04]"
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "x.len")?, 4);
+        assert_eq!(
+            engine.eval::<Blob>("let x = blob(3, 0x99); x.push(0x1ab); x.insert(0, 7); x")?,
+            [7, 0x99, 0x99, 0x99, 0xab]
+        );
+        assert_eq!(
+            engine.eval::<Blob>("let x = blob(2); x.pad(4, 42); x.reverse(); x")?,
+            [42, 42, 0, 0]
+        );
+        assert_eq!(
+            engine.eval::<INT>("let x = blob(4, 5); x.pop() + x.shift() + x.len")?,
+            12
+        );
+        assert_eq!(
+            engine.eval::<Blob>("let x = blob(5, 1); x.splice(1, 2, blob(1, 9)); x.extract(1)")?,
+            [9, 1, 1]
+        );
+        assert_eq!(
+            engine.eval::<Blob>("let x = blob(5, 1); x.write_le(1, 2, 0x1234); x.split(3)")?,
+            [1, 1]
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_blobs_conversions() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "hello".to_blob(); x.to_hex()"#)?,
+        "68656c6c6f"
+    );
+    assert_eq!(
+        engine.eval::<Blob>(r#"from_hex("00ff7F")"#)?,
+        [0, 0xff, 0x7f]
+    );
+    assert!(engine.eval::<Blob>(r#"from_hex("0g")"#).is_err());
+    assert!(engine.eval::<Blob>(r#"from_hex("abc")"#).is_err());
+
+    assert_eq!(
+        engine.eval::<String>(r#""hello".to_blob().to_base64()"#)?,
+        "aGVsbG8="
+    );
+    assert_eq!(
+        engine.eval::<String>(r#""hi!".to_blob().to_base64()"#)?,
+        "aGkh"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"from_base64("aGVsbG8=").as_string()"#)?,
+        "hello"
+    );
+    assert!(engine.eval::<Blob>(r#"from_base64("a*==")"#).is_err());
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"let x = blob(5, 0x2d); x.write_utf8(1, 3, "abcdef"); x.as_string()"#
+        )?,
+        "-abc-"
+    );
+    assert_eq!(
+        engine.eval::<rhai::Array>("blob(2, 3).to_array()")?.len(),
+        2
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_blobs_parse() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let x = blob(4); x.write_le(0, 2, 0x1234); x.parse_le_int(0, 2)")?,
+        0x1234
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = blob(4); x.write_be(0, 2, 0x1234); x.parse_be_int(0, 2)")?,
+        0x1234
+    );
+    assert_eq!(
+        engine.eval::<Blob>("let x = blob(4); x.write_be(1, 2, 0x1234); x")?,
+        [0, 0x12, 0x34, 0]
+    );
+    assert_eq!(
+        engine.eval::<Blob>("let x = blob(4); x.write_le(-2, 8, 0x1234); x")?,
+        [0, 0, 0x34, 0x12]
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = blob(2, 0xff); x.parse_le_int(0, 100)")?,
+        0xffff
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = blob(2, 0xff); x.parse_le_int(10, 2)")?,
+        0
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = blob(16); x.write_le(3, 8, -42); x.parse_le_int(3, 8)")?,
+        -42
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(
+            engine.eval::<rhai::FLOAT>(
+                "let x = blob(16); x.write_le(2, 16, 1.5); x.parse_le_float(2, 16)"
+            )?,
+            1.5
+        );
+        assert_eq!(
+            engine.eval::<rhai::FLOAT>(
+                "let x = blob(16); x.write_be(0, 16, -0.25); x.parse_be_float(0, 16)"
+            )?,
+            -0.25
+        );
+    }
+
+    Ok(())
+}
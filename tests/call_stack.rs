@@ -0,0 +1,182 @@
+#![cfg(not(feature = "no_function"))]
+use rhai::{Engine, EvalAltResult, Position, INT};
+
+#[test]
+fn test_call_stack() {
+    let engine = Engine::new();
+
+    let err = engine
+        .eval::<INT>(
+            "
+                fn inner(x) { x / 0 }
+                fn middle(x) { inner(x) + 1 }
+                fn outer(x) { middle(x) * 2 }
+
+                outer(42)
+            ",
+        )
+        .expect_err("should error");
+
+    let stack = err.call_stack();
+
+    assert_eq!(
+        stack
+            .iter()
+            .map(|frame| frame.fn_name.as_str())
+            .collect::<Vec<_>>(),
+        ["inner", "middle", "outer"]
+    );
+    assert_eq!(stack[0].position, Position::new(3, 32));
+    assert_eq!(stack[1].position, Position::new(4, 31));
+    assert_eq!(stack[2].position, Position::new(6, 17));
+    assert!(stack.iter().all(|frame| frame.source.is_empty()));
+
+    #[cfg(not(feature = "unchecked"))]
+    assert!(matches!(
+        err.root_error(),
+        EvalAltResult::ErrorArithmetic(_, pos) if *pos == Position::new(2, 33)
+    ));
+
+    // Each nested call wraps the error of the call it made
+    assert!(matches!(
+        *err,
+        EvalAltResult::ErrorInFunctionCall(ref fn_name, _, ref inner, _)
+            if fn_name == "outer"
+                && matches!(**inner, EvalAltResult::ErrorInFunctionCall(ref fn_name, _, _, _) if fn_name == "middle")
+    ));
+
+    assert!(engine
+        .eval::<INT>("let x = 1; x.foo()")
+        .expect_err("should error")
+        .call_stack()
+        .is_empty());
+}
+
+#[test]
+fn test_call_stack_message() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let mut ast = engine.compile(
+        "
+            fn inner() { throw 42; }
+            fn outer() { let x = inner(); x }
+            outer()
+        ",
+    )?;
+
+    assert_eq!(
+        engine.eval_ast::<()>(&ast).expect_err("should error").to_string(),
+        "Runtime error: 42 (line 2, position 26) in call to function inner (line 3, position 34) in call to function outer (line 4, position 13)"
+    );
+
+    ast.set_source("test");
+
+    assert_eq!(
+        engine.eval_ast::<()>(&ast).expect_err("should error").to_string(),
+        "Runtime error: 42 (line 2, position 26) in call to function inner @ 'test' (line 3, position 34) in call to function outer @ 'test' (line 4, position 13)"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_call_stack_source() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let mut ast = engine.compile("fn foo() { throw 42; } foo()")?;
+    ast.set_source("test");
+
+    let err = engine.eval_ast::<()>(&ast).expect_err("should error");
+    let stack = err.call_stack();
+
+    assert_eq!(stack.len(), 1);
+    assert_eq!(stack[0].fn_name, "foo");
+    assert_eq!(stack[0].source, "test");
+    assert_eq!(stack[0].to_string(), "foo @ 'test' (line 1, position 24)");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+fn test_call_stack_catch() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                fn inner() { let x = [1]; x[9] }
+                fn outer() { let x = inner(); x }
+
+                try {
+                    outer();
+                } catch (err) {
+                    let s = "";
+                    for frame in err.stack {
+                        s += ` ${frame.function}@${frame.line}:${frame.position}`;
+                    }
+                    return s;
+                }
+            "#
+        )?,
+        " inner@3:38 outer@6:21"
+    );
+
+    assert_eq!(
+        engine.eval::<INT>("try { let x = [1]; x[9] } catch (err) { return err.stack.len(); }")?,
+        0
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_call_stack_native() {
+    let engine = Engine::new();
+
+    let err = engine
+        .eval::<()>(
+            "
+                fn f(x) { map(x, |v| foo(v)) }
+                f([1])
+            ",
+        )
+        .expect_err("should error");
+
+    let stack = err.call_stack();
+
+    assert_eq!(stack.len(), 3);
+    assert!(stack[0].fn_name.starts_with("anon$"));
+    assert_eq!(stack[0].to_string(), "closure");
+    assert_eq!(stack[1].fn_name, "map");
+    assert_eq!(stack[1].position, Position::new(2, 27));
+    assert_eq!(stack[2].fn_name, "f");
+    assert_eq!(stack[2].position, Position::new(3, 17));
+}
+
+#[test]
+fn test_call_stack_tail_calls() {
+    let engine = Engine::new();
+
+    // Functions making tail calls are replaced by the functions they call
+    let err = engine
+        .eval::<()>(
+            "
+                fn inner() { throw 42; }
+                fn middle() { inner() }
+                fn outer() { let x = middle(); x }
+                outer()
+            ",
+        )
+        .expect_err("should error");
+
+    assert_eq!(
+        err.call_stack()
+            .iter()
+            .map(|frame| frame.fn_name.as_str())
+            .collect::<Vec<_>>(),
+        ["inner", "outer"]
+    );
+}
@@ -5,6 +5,8 @@ fn test_constant() -> Result<(), Box<EvalAltResult>> {
     let engine = Engine::new();
 
     assert_eq!(engine.eval::<INT>("const x = 123; x")?, 123);
+    assert_eq!(engine.eval::<INT>("const x = 123; let x = 42; x")?, 42);
+    assert_eq!(engine.eval::<INT>("let x = 42; { const x = 123; } x")?, 42);
 
     assert!(matches!(
         *engine
@@ -2,7 +2,7 @@
 use rhai::{Engine, EvalAltResult, ParseErrorType};
 
 #[cfg(not(feature = "no_index"))]
-use rhai::Array;
+use rhai::{Array, Blob, Set};
 
 #[cfg(not(feature = "no_object"))]
 use rhai::Map;
@@ -115,6 +115,31 @@ fn test_max_array_size() -> Result<(), Box<EvalAltResult>> {
         EvalAltResult::ErrorDataTooLarge(_, _)
     ));
 
+    assert!(matches!(
+        *engine.eval::<Blob>("blob(100)").expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, _)
+    ));
+
+    assert!(matches!(
+        *engine
+            .eval::<Set>("let s = set(); for i in 0..20 { s += i } s")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, _)
+    ));
+
+    assert!(matches!(
+        *engine
+            .eval::<Blob>(
+                "
+                    let x = blob(6);
+                    let y = blob(6);
+                    x + y
+                "
+            )
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, _)
+    ));
+
     assert!(matches!(
         *engine
             .eval::<Array>(
@@ -0,0 +1,281 @@
+#![cfg(not(feature = "no_function"))]
+use rhai::{Dynamic, Engine, EvalAltResult, ParseErrorType, Scope, INT};
+
+#[test]
+fn test_default_params() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("fn f(a, b = 10, c = 100) { a + b + c } f(1)")?,
+        111
+    );
+    assert_eq!(
+        engine.eval::<INT>("fn f(a, b = 10, c = 100) { a + b + c } f(1, 2)")?,
+        103
+    );
+    assert_eq!(
+        engine.eval::<INT>("fn f(a, b = 10, c = 100) { a + b + c } f(1, 2, 3)")?,
+        6
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"fn f(a = "x", b = -1) { a + b } f()"#)?,
+        "x-1"
+    );
+    assert_eq!(
+        engine.eval::<INT>("fn f(a, b = 10) { a + b } fn f() { 42 } f() + f(1)")?,
+        53
+    );
+    assert!(engine.eval::<bool>("fn f(a, b = 10) { a + b } is_def_fn(\"f\", 1)")?);
+    assert!(!engine.eval::<bool>("fn f(a, b = 10) { a + b } is_def_fn(\"f\", 3)")?);
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<INT>("fn f(x = [1, 2, 3]) { x.len() } f()")?,
+        3
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>("fn f(x, y = 1) { this + x + y } let z = 40; z.f(1)")?,
+        42
+    );
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>("fn f(x = 1) { this += x; } let z = 40; z.f(); z.f(); z")?,
+        42
+    );
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>("fn f(x = 10, y = 2) { this + x + y } 5.f() + 5.f(1)")?,
+        25
+    );
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"fn f(x = 10, y = 2) { this + x + y } let z = 5; z.call(Fn("f")) + z.call(Fn("f"), 1)"#
+        )?,
+        25
+    );
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<INT>("fn f(x, ...y) { this + x + y.len() } 5.f(1) + 5.f(1, 2, 3)")?,
+        14
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("fn f(a, b = 10) { a + b } f()")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    assert!(matches!(
+        *engine
+            .compile("fn f(a, b = 10, c) { a + b + c }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MissingToken(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .compile("let x = 1; fn f(a, b = x) { a + b }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::ExprExpected(_)
+    ));
+    assert!(matches!(
+        *engine
+            .compile("fn f(a, b = 10) { a + b } fn f(a) { a }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnDuplicatedDefinition(_, 1)
+    ));
+    assert!(matches!(
+        *engine
+            .compile("fn f(a, b = 10) { a + b } fn f(a, b, c = 1) { a }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnDuplicatedDefinition(_, 2)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_named_args() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let script = r#"
+        fn f(a, b = 10, c = "x") { `${a}-${b}-${c}` }
+    "#;
+
+    assert_eq!(
+        engine.eval::<String>(&format!(r#"{} f(1, c: "y")"#, script))?,
+        "1-10-y"
+    );
+    assert_eq!(
+        engine.eval::<String>(&format!(r#"{} f(c: "y", b: 2, a: 1)"#, script))?,
+        "1-2-y"
+    );
+    assert_eq!(
+        engine.eval::<String>(&format!(r#"{} let c = 42; f(1, c: c)"#, script))?,
+        "1-10-42"
+    );
+    assert_eq!(
+        engine.eval::<String>(&format!(r#"{} let x = f(a: 0, b: 1 + 1); x"#, script))?,
+        "0-2-x"
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<String>(&format!(r#"{} f(b: 2)"#, script))
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(f, _) if f == "f (b: i64)"
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<String>(&format!(r#"{} f(1, a: 2)"#, script))
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<String>(&format!(r#"{} f(1, d: 2)"#, script))
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    assert!(matches!(
+        *engine
+            .compile(&format!(r#"{} f(b: 2, 1)"#, script))
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MalformedCallExpr(_)
+    ));
+    assert!(matches!(
+        *engine
+            .compile(&format!(r#"{} f(1, b: 2, b: 3)"#, script))
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MalformedCallExpr(_)
+    ));
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .compile(&format!(r#"{} let x = 1; x.f(b: 2)"#, script))
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MalformedCallExpr(_)
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_module"))]
+fn test_named_args_module() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let ast = engine.compile(
+        r#"
+            fn area(w, h = 1) { w * h }
+            private fn hidden(x = 1) { x }
+        "#,
+    )?;
+    let module = rhai::Module::eval_ast_as_new(Default::default(), &ast, &engine)?;
+    engine.register_static_module("shape", module.into());
+
+    assert_eq!(engine.eval::<INT>("shape::area(3)")?, 3);
+    assert_eq!(engine.eval::<INT>("shape::area(3, 2)")?, 6);
+    assert_eq!(engine.eval::<INT>("shape::area(3, h: 4)")?, 12);
+    assert_eq!(engine.eval::<INT>("shape::area(h: 5, w: 2)")?, 10);
+
+    assert!(engine.eval::<INT>("shape::hidden()").is_err());
+    assert!(engine.eval::<INT>("shape::hidden(x: 2)").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_default_params_call_fn() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    let ast = engine.compile(
+        r#"
+            fn f(a, b = 10, c = "x") { `${a}-${b}-${c}` }
+        "#,
+    )?;
+
+    let r: String = engine.call_fn(&mut scope, &ast, "f", (1 as INT,))?;
+    assert_eq!(r, "1-10-x");
+
+    let r: String = engine.call_fn(&mut scope, &ast, "f", (1 as INT, 2 as INT))?;
+    assert_eq!(r, "1-2-x");
+
+    let r = engine.call_fn_dynamic(&mut scope, &ast, true, "f", None, [Dynamic::from(1 as INT)])?;
+    assert_eq!(r.cast::<String>(), "1-10-x");
+
+    let r: String = engine.call_fn_with_named_args(
+        &mut scope,
+        &ast,
+        "f",
+        (1 as INT,),
+        [("c", Dynamic::from("y"))],
+    )?;
+    assert_eq!(r, "1-10-y");
+
+    let r: String = engine.call_fn_with_named_args(
+        &mut scope,
+        &ast,
+        "f",
+        (),
+        [
+            ("b", Dynamic::from(2 as INT)),
+            ("a", Dynamic::from(0 as INT)),
+        ],
+    )?;
+    assert_eq!(r, "0-2-x");
+
+    assert!(matches!(
+        *engine
+            .call_fn::<String>(&mut scope, &ast, "f", ())
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .call_fn_with_named_args::<String, _>(
+                &mut scope,
+                &ast,
+                "f",
+                (1 as INT,),
+                [("a", Dynamic::from(2 as INT))]
+            )
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    let ast2 = engine.compile("fn g(x = 1) { x }")?;
+    let merged = ast.merge(&ast2);
+
+    let r: String = engine.call_fn(&mut scope, &merged, "f", (1 as INT,))?;
+    assert_eq!(r, "1-10-x");
+    let r: INT = engine.call_fn(&mut scope, &merged, "g", ())?;
+    assert_eq!(r, 1);
+
+    // Redefining a function drops the numbers of arguments only the old one accepted
+    let merged = merged.merge(&engine.compile("fn f(a, b, c) { a + b + c }")?);
+
+    let r: INT = engine.call_fn(&mut scope, &merged, "f", (1 as INT, 2 as INT, 3 as INT))?;
+    assert_eq!(r, 6);
+    assert!(matches!(
+        *engine
+            .call_fn::<String>(&mut scope, &merged, "f", (1 as INT,))
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    Ok(())
+}
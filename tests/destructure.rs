@@ -0,0 +1,208 @@
+#![cfg(not(feature = "no_index"))]
+use rhai::{Array, Engine, EvalAltResult, ParseErrorType, INT};
+
+#[test]
+fn test_destructure_array() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let [a, b, c] = [1, 2, 3]; a + b * c")?,
+        7
+    );
+    assert_eq!(
+        engine.eval::<INT>("let [a, b, ..rest] = [1, 2, 3, 4, 5]; a + b + rest.len()")?,
+        6
+    );
+    assert_eq!(
+        engine
+            .eval::<Array>("let [a, b, ..rest] = [1, 2]; rest")?
+            .len(),
+        0
+    );
+    assert_eq!(engine.eval::<INT>("let [_, x, _] = [1, 2, 3]; x")?, 2);
+    assert_eq!(engine.eval::<INT>("let [x, ..] = [1, 2, 3]; x")?, 1);
+    assert_eq!(
+        engine.eval::<INT>("let [a, [b, c]] = [1, [2, 3]]; a * 100 + b * 10 + c")?,
+        123
+    );
+    assert_eq!(engine.eval::<INT>("let a = 42; { let [a] = [1]; } a")?, 42);
+    assert_eq!(
+        engine.eval::<INT>("const x = 1; let [x, y] = [40, 2]; x + y")?,
+        42
+    );
+
+    assert!(matches!(
+        *engine
+            .compile("const [a, b] = [1, 2]; a = 3;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::AssignmentToConstant(_)
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_destructure_map() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let #{x, y: alias} = #{x: 40, y: 2}; x + alias")?,
+        42
+    );
+    assert_eq!(
+        engine
+            .eval::<INT>(r#"let #{"a b": c, ..rest} = #{"a b": 1, d: 2, e: 3}; c + rest.len()"#)?,
+        3
+    );
+    assert_eq!(
+        engine
+            .eval::<INT>("let #{p: [a, b], q: #{r}} = #{p: [1, 2], q: #{r: 3}, z: 0}; a + b + r")?,
+        6
+    );
+    assert_eq!(
+        engine.eval::<INT>("let [#{x}, #{x: y}] = [#{x: 1}, #{x: 2}]; x + y")?,
+        3
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_destructure_for() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                let sum = 0;
+                for [a, b] in [[1, 2], [3, 4], [5, 6]] { sum += a * b; }
+                sum
+            "
+        )?,
+        44
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                let sum = 0;
+                for ([a, ..rest], i) in [[1, 2], [3, 4, 5]] { sum += a * i + rest.len(); }
+                sum
+            "
+        )?,
+        6
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                let sum = 0;
+                for #{x, y} in [#{x: 1, y: 2}, #{x: 3, y: 4}] { sum += x * y; }
+                sum
+            "
+        )?,
+        14
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_destructure_fn_params() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn dot([a, b], [c, d]) { a * c + b * d }
+                dot([1, 2], [3, 4])
+            "
+        )?,
+        11
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn area(#{w, h}, scale) { w * h * scale }
+                area(#{w: 2, h: 3}, 7)
+            "
+        )?,
+        42
+    );
+
+    #[cfg(not(feature = "no_closure"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                let k = 10;
+                let f = |[a, b], c| (a + b) * c + k;
+                f.call([1, 2], 3)
+            "
+        )?,
+        19
+    );
+
+    assert!(matches!(
+        *engine
+            .compile("fn f([a, b], a) { a }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnDuplicatedParam(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_destructure_errors() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let [a, b] = [1, 2, 3]; a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDestructure(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let [a, b, ..rest] = [1]; a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDestructure(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let [a, b] = 42; a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(_, _, _)
+    ));
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let #{x, y} = #{x: 1}; x")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDestructure(p, _) if p.contains("'y'")
+    ));
+
+    assert!(matches!(
+        *engine
+            .compile("let [a, a] = [1, 2];")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::DuplicatedVariable(_)
+    ));
+    assert!(matches!(
+        *engine
+            .compile("let [a, ..b, c] = [1, 2];")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MissingToken(_, _)
+    ));
+
+    Ok(())
+}
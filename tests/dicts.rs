@@ -0,0 +1,129 @@
+#![cfg(not(feature = "no_object"))]
+use rhai::{Dict, Dynamic, Engine, EvalAltResult, INT};
+
+#[test]
+fn test_dicts() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<Dict>("dict()")?.len(), 0);
+    assert_eq!(engine.eval::<String>("type_of(dict())")?, "dict");
+    assert_eq!(
+        engine.eval::<INT>("let d = dict(); d[1] = 2; d['x'] = 3; d[1] + d['x']")?,
+        5
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"let d = dict(); d[1] = 2; d["1"] = 3; d.len"#)?,
+        2
+    );
+    assert_eq!(
+        engine.eval::<INT>("let d = dict(); d[[1, 2]] = 42; d[true] = 1; d[[1, 2]]")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>("let d = dict(); d[()] = 1; d[()] += 41; d[()]")?,
+        42
+    );
+    assert_eq!(engine.eval::<()>("let d = dict(); d[1]")?, ());
+    assert!(engine.eval::<bool>("let d = dict(); d[42] = 0; 42 in d")?);
+    assert!(!engine.eval::<bool>(r#"let d = dict(); d[42] = 0; "42" in d"#)?);
+    assert_eq!(
+        engine.eval::<INT>("let d = dict(#{a: 1, b: 2}); d.remove(\"a\") + d.len")?,
+        2
+    );
+    assert!(engine.eval::<bool>(r#"dict(#{a: 1}) + dict([[1, 2]]) == dict([["a", 1], [1, 2]])"#)?);
+    assert!(engine.eval::<bool>("dict([[1, 2]]) != dict([[1, 3]])")?);
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine
+            .eval::<rhai::Array>("let d = dict(); d['x'] = 1; d['y'] = 2; d.values()")?
+            .into_iter()
+            .map(|v| v.as_int().unwrap())
+            .sum::<INT>(),
+        3
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<Dynamic>(r#"let d = dict(); d[Fn("foo")] = 1;"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(_, _, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_dicts_rust() -> Result<(), Box<EvalAltResult>> {
+    let mut dict = Dict::new();
+
+    assert!(matches!(
+        dict.insert((1 as INT).into(), "one".into()),
+        Ok(None)
+    ));
+    assert!(matches!(
+        dict.insert('x'.into(), (42 as INT).into()),
+        Ok(None)
+    ));
+    assert!(dict
+        .insert(rhai::FnPtr::new("foo")?.into(), ().into())
+        .is_err());
+    assert!(dict.contains_key(&(1 as INT).into()));
+    assert!(!dict.contains_key(&"1".into()));
+    assert_eq!(dict.len(), 2);
+
+    assert_eq!(
+        dict.insert((1 as INT).into(), "uno".into())
+            .map(|v| v.unwrap().to_string()),
+        Ok("one".to_string())
+    );
+
+    let engine = Engine::new();
+    let mut scope = rhai::Scope::new();
+    scope.push("d", dict);
+
+    assert_eq!(engine.eval_with_scope::<String>(&mut scope, "d[1]")?, "uno");
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "d['x']")?, 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_dicts_key_equality() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut dict = Dict::new();
+
+    dict.insert((42 as INT).into(), "int".into()).unwrap();
+    dict.insert("42".into(), "string".into()).unwrap();
+    assert_eq!(dict.len(), 2);
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        dict.insert((1.0 as rhai::FLOAT).into(), "float".into())
+            .unwrap();
+        assert!(!dict.contains_key(&(1 as INT).into()));
+        assert!(dict.contains_key(&(1.0 as rhai::FLOAT).into()));
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let key = || Dynamic::from(vec![Dynamic::from(1 as INT), Dynamic::from('x')]);
+        dict.insert(key(), "array".into()).unwrap();
+        assert_eq!(dict.get(&key()).unwrap().to_string(), "array");
+    }
+
+    // Values that cannot be compared are hashed by type instead of panicking
+    #[derive(Clone)]
+    struct Foo;
+
+    let hash = |value: Dynamic| {
+        let hasher = &mut DefaultHasher::new();
+        value.hash(hasher);
+        hasher.finish()
+    };
+
+    assert_eq!(hash(Dynamic::from(Foo)), hash(Dynamic::from(Foo)));
+    assert!(dict.insert(Dynamic::from(Foo), ().into()).is_err());
+}
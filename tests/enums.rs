@@ -0,0 +1,307 @@
+#![cfg(not(feature = "no_function"))]
+
+use rhai::{Engine, EnumValue, EvalAltResult, ParseErrorType, INT};
+
+const SHAPES: &str = "
+    enum Shape {
+        Circle(r),
+        Rect(w, h),
+        Empty,
+    }
+
+    fn area(shape) {
+        switch shape {
+            Circle(r) => 3 * r * r,
+            Shape::Rect(w, h) if w == h => w * w,
+            Rect(w, h) => w * h,
+            Empty => 0,
+        }
+    }
+";
+
+#[test]
+fn test_enums() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(&format!("{} area(Shape::Circle(2))", SHAPES))?,
+        12
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!("{} area(Shape::Rect(2, 3))", SHAPES))?,
+        6
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!("{} area(Shape::Rect(4, 4))", SHAPES))?,
+        16
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!("{} area(Shape::Empty)", SHAPES))?,
+        0
+    );
+
+    // Variables bound in a case do not leak out of it
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let r = 1; let a = switch Shape::Circle(5) {{ Circle(r) => r, _ => 0 }}; r * 10 + a",
+            SHAPES
+        ))?,
+        15
+    );
+
+    // Cases can list multiple variants, without binding any fields
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} switch Shape::Rect(1, 2) {{ Circle(_) | Shape::Empty => 1, Rect(_, _) => 2 }}",
+            SHAPES
+        ))?,
+        2
+    );
+
+    // Non-enum values never match variants
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} switch 42 {{ Circle(r) => r, _ => 0 }}",
+            SHAPES
+        ))?,
+        0
+    );
+
+    assert_eq!(
+        engine.eval::<String>(&format!("{} Shape::Rect(1, 2).to_string()", SHAPES))?,
+        "Rect(1, 2)"
+    );
+    assert_eq!(
+        engine.eval::<String>(&format!("{} type_of(Shape::Empty())", SHAPES))?,
+        "enum"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_enums_shared_variants() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let script = "
+        enum Shape { Circle(r), Empty }
+        enum Cell { Full(value), Empty }
+
+        fn describe(x) {
+            switch x {
+                Circle(r) => r,
+                Shape::Empty => 2,
+                Full(v) => v,
+                Cell::Empty => 4,
+                _ => 0
+            }
+        }
+    ";
+
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{}
+                describe(Shape::Circle(1)) * 10000 + describe(Shape::Empty) * 1000
+                    + describe(Cell::Full(3)) * 100 + describe(Cell::Empty) * 10 + describe(42)
+            ",
+            script
+        ))?,
+        12340
+    );
+
+    // Variants of other enums simply do not match
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} switch Cell::Full(1) {{ Circle(r) => r, Shape::Empty => 2, _ => 0 }}",
+            script
+        ))?,
+        0
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_enums_equality() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let script = "
+        enum Shape { Circle(r), Rect(w, h), Empty }
+        enum Cell { Full(value), Empty }
+    ";
+
+    assert!(engine.eval::<bool>(&format!(
+        "{} Shape::Rect(1, 2) == Shape::Rect(1, 2)",
+        script
+    ))?);
+    assert!(engine.eval::<bool>(&format!("{} Shape::Empty == Shape::Empty", script))?);
+    assert!(!engine.eval::<bool>(&format!(
+        "{} Shape::Rect(1, 2) == Shape::Rect(2, 1)",
+        script
+    ))?);
+    assert!(!engine.eval::<bool>(&format!("{} Shape::Circle(1) == Shape::Rect(1, 1)", script))?);
+    assert!(!engine.eval::<bool>(&format!("{} Shape::Empty == Cell::Empty", script))?);
+    assert!(engine.eval::<bool>(&format!("{} Shape::Empty != Cell::Empty", script))?);
+    assert!(engine.eval::<bool>(&format!("{} Shape::Circle(1) != Shape::Circle(2)", script))?);
+    assert!(!engine.eval::<bool>(&format!("{} Shape::Circle(1) != Shape::Circle(1)", script))?);
+
+    // Fields are compared with `==`
+    assert!(engine.eval::<bool>(&format!(
+        r#"{} Cell::Full(Shape::Circle("x")) == Cell::Full(Shape::Circle("x"))"#,
+        script
+    ))?);
+    assert!(!engine.eval::<bool>(&format!(
+        "{} Cell::Full(Shape::Circle(1)) == Cell::Full(Shape::Circle(2))",
+        script
+    ))?);
+
+    // In `switch` cases
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "
+                {}
+                let target = Shape::Rect(2, 3);
+                let found = [];
+                for shape in [Shape::Circle(1), Shape::Rect(2, 3), Shape::Rect(3, 2), Shape::Empty] {{
+                    found.push(switch shape {{
+                        Rect(w, h) if shape == target => 1,
+                        Rect(w, h) => 2,
+                        Circle(r) if shape != Shape::Circle(2) => 3,
+                        _ => 4,
+                    }});
+                }}
+                found[0] * 1000 + found[1] * 100 + found[2] * 10 + found[3]
+            ",
+            script
+        ))?,
+        3124
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_enums_from_rust() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let value = engine.eval::<EnumValue>(&format!("{} Shape::Rect(3, 4)", SHAPES))?;
+
+    assert_eq!(value.enum_name(), "Shape");
+    assert_eq!(value.variant_name(), "Rect");
+    assert_eq!(value.field_names().collect::<Vec<_>>(), ["w", "h"]);
+    assert_eq!(value.field("w").unwrap().as_int().unwrap(), 3);
+    assert!(value.field("r").is_none());
+    assert_eq!(
+        value
+            .into_fields()
+            .into_iter()
+            .map(|v| v.as_int().unwrap())
+            .collect::<Vec<_>>(),
+        [3, 4]
+    );
+
+    let value = engine.eval::<EnumValue>(&format!("{} Shape::Empty", SHAPES))?;
+
+    assert_eq!(value.variant_name(), "Empty");
+    assert!(value.fields().is_empty());
+    assert_eq!(format!("{:?}", value), "Shape::Empty");
+
+    Ok(())
+}
+
+#[test]
+fn test_enums_errors() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        *engine
+            .compile(&format!("{} switch 1 {{ Circel(r) => r, _ => 0 }}", SHAPES))
+            .expect_err("should error")
+            .0,
+        ParseErrorType::UnknownVariant("Circel".to_string())
+    );
+    assert_eq!(
+        *engine
+            .compile(&format!(
+                "{} switch 1 {{ Shape::Full => 1, _ => 0 }}",
+                SHAPES
+            ))
+            .expect_err("should error")
+            .0,
+        ParseErrorType::UnknownVariant("Shape::Full".to_string())
+    );
+    assert_eq!(
+        *engine
+            .compile(&format!("{} switch 1 {{ Rect(w) => w, _ => 0 }}", SHAPES))
+            .expect_err("should error")
+            .0,
+        ParseErrorType::WrongVariantFields("Shape::Rect".to_string(), 2)
+    );
+    assert_eq!(
+        *engine
+            .compile(&format!("{} Shape::Rect(1)", SHAPES))
+            .expect_err("should error")
+            .0,
+        ParseErrorType::WrongVariantFields("Shape::Rect".to_string(), 2)
+    );
+    assert_eq!(
+        *engine
+            .compile(&format!("{} Shape::Square(1)", SHAPES))
+            .expect_err("should error")
+            .0,
+        ParseErrorType::UnknownVariant("Shape::Square".to_string())
+    );
+    assert_eq!(
+        *engine
+            .compile("enum A { X } enum B { X } switch 1 { X => 1 }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::AmbiguousVariant("X".to_string())
+    );
+    assert_eq!(
+        *engine
+            .compile("enum E { A } enum E { B }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::EnumDuplicatedDefinition("E".to_string())
+    );
+
+    // Variants are not functions
+    assert!(matches!(
+        *engine
+            .eval::<INT>(&format!("{} Rect(1, 2)", SHAPES))
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(f, _) if f.starts_with("Rect (")
+    ));
+
+    assert_eq!(
+        *engine
+            .compile("fn f() { enum E { A } }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::WrongEnumDefinition
+    );
+    assert_eq!(
+        *engine
+            .compile("enum E { A(x), B(x) } switch E::A(1) { A(x) | B(_) => x }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::WrongVariantBindings
+    );
+    assert_eq!(
+        *engine
+            .compile("enum E { A(x), A }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::EnumDuplicatedVariant("E".to_string(), "A".to_string())
+    );
+    assert_eq!(
+        *engine
+            .compile("enum E { A(x, x) }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnDuplicatedParam("A".to_string(), "x".to_string())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,386 @@
+#![cfg(not(feature = "no_function"))]
+use rhai::{Engine, EvalAltResult, Generator, ParseErrorType, Scope, INT};
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_generators() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn count(n) {
+                    let i = 0;
+                    while i < n { yield i; i += 1; }
+                }
+                let sum = 0;
+                for x in count(5) { sum += x; }
+                sum
+            "
+        )?,
+        10
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            "
+                fn fib() {
+                    let a = 0; let b = 1;
+                    loop { yield a; let c = a + b; a = b; b = c; }
+                }
+                let s = ``;
+                for x in fib() { if x > 20 { break; } s += `${x} `; }
+                s
+            "
+        )?,
+        "0 1 1 2 3 5 8 13 "
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn gen() { yield 1; yield 2; }
+                let g = gen();
+                let a = g.next();
+                let b = g.next();
+                let done = g.is_done;
+                let c = g.next();
+                a * 100 + b * 10 + if done { 1 } else if c == () && g.is_done { 0 } else { 9 }
+            "
+        )?,
+        120
+    );
+    assert_eq!(
+        engine.eval::<String>("fn gen() { yield; } type_of(gen())")?,
+        "generator"
+    );
+    assert_eq!(
+        engine.eval::<String>("fn gen() { yield; } `${gen()}`")?,
+        "<generator>"
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_generators_control_flow() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                fn gen(items) {
+                    for (x, i) in items {
+                        if x == "skip" { continue; }
+                        if x == "stop" { break; }
+                        if i % 2 == 0 { yield x; } else { yield x + "!"; }
+                    }
+                    yield "end";
+                }
+                let s = "";
+                for x in gen(["a", "b", "skip", "c", "stop", "d"]) { s += x; }
+                s
+            "#
+        )?,
+        "ab!c!end"
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            "
+                fn gen() {
+                    let i = 0;
+                    do {
+                        i += 1;
+                        if i == 2 { continue; }
+                        yield i;
+                        if i == 4 { return; }
+                    } while i < 10;
+                    yield 99;
+                }
+                let s = ``;
+                for x in gen() { s += x; }
+                s
+            "
+        )?,
+        "134"
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            "
+                fn inner(n) { for i in range(0, n) { yield i; } }
+                fn outer() { for i in range(1, 4) { for j in inner(i) { yield i * 10 + j; } } }
+                let s = ``;
+                for x in outer() { s += `${x} `; }
+                s
+            "
+        )?,
+        "10 20 21 30 31 32 "
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn gen() {
+                    let x = 1;
+                    { let y = 10; yield x + y; x = 2; }
+                    yield x;
+                }
+                let sum = 0;
+                for x in gen() { sum += x; }
+                sum
+            "
+        )?,
+        13
+    );
+    assert_eq!(
+        engine.eval::<INT>("fn gen(x) { if x > 0 { return; } yield 1; } gen(1).next() ?? 42")?,
+        42
+    );
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<String>(
+            "
+                fn gen(items) {
+                    for x in items {
+                        switch x {
+                            0 => continue,
+                            1 | 2 => { yield x; yield -x; }
+                            if x > 10 => { let y = x * 2; yield y; }
+                            _ => yield `${x}?`
+                        }
+                    }
+                }
+                let s = ``;
+                for x in gen([1, 0, 5, 20]) { s += `${x} `; }
+                s
+            "
+        )?,
+        "1 -1 5? 40 "
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn gen() { for x in range(0, 5) { yield x; } }
+                let g = gen();
+                g.next();
+                g.next();
+                let h = g;
+                h.next() + g.next() * 10
+            "
+        )?,
+        22
+    );
+
+    #[cfg(not(feature = "no_closure"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                let base = 40;
+                let gen = || { yield base; yield base + 2; };
+                let g = gen.call();
+                g.next();
+                g.next()
+            "
+        )?,
+        42
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_generators_errors() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<INT>("fn gen() { yield 1; throw 42; } let g = gen(); g.next(); g.next()")
+            .expect_err("should error"),
+        EvalAltResult::ErrorInFunctionCall(fn_name, _, err, _)
+            if fn_name == "gen" && matches!(*err, EvalAltResult::ErrorRuntime(_, _))
+    ));
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"fn gen() { yield no_such_fn(); } gen().next()"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorInFunctionCall(_, _, _, _)
+    ));
+    #[cfg(not(feature = "no_object"))]
+    assert!(engine.eval::<bool>(
+        r#"fn gen() { yield 1; throw 42; } let g = gen(); g.next(); try { g.next() } catch { } g.is_done"#
+    )?);
+
+    for script in [
+        "yield 1;",
+        "fn f() { let x = { yield 1; }; }",
+        "fn f(x) { let y = switch x { 1 => { yield 1; } }; }",
+        "fn f() { try { yield 1; } catch { } }",
+    ] {
+        assert!(matches!(
+            *engine.compile(script).expect_err("should error").0,
+            ParseErrorType::WrongYield
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_generators_from_rust() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let ast = engine.compile(
+        "
+            fn double(x) { x * 2 }
+            fn gen(n) { for i in range(0, n) { yield double(i); } }
+        ",
+    )?;
+
+    let mut gen: Generator = engine.call_fn(&mut Scope::new(), &ast, "gen", (3 as INT,))?;
+
+    assert!(!gen.is_done());
+    assert_eq!(gen.resume(&engine, &ast)?.map(|v| v.cast::<INT>()), Some(0));
+
+    let rest = gen
+        .iter(&engine, &ast)
+        .map(|v| v.map(|v| v.cast::<INT>()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(rest, [2, 4]);
+    assert!(gen.is_done());
+    assert!(gen.resume(&engine, &ast)?.is_none());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "sync"))]
+fn test_generators_iterators() -> Result<(), Box<EvalAltResult>> {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[derive(Clone)]
+    struct Numbers(Rc<Cell<usize>>, INT);
+
+    impl IntoIterator for Numbers {
+        type Item = INT;
+        type IntoIter = std::ops::Range<INT>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.0.set(self.0.get() + 1);
+            0..self.1
+        }
+    }
+
+    let created = Rc::new(Cell::new(0));
+    let mut engine = Engine::new();
+    let mut scope = Scope::new();
+
+    engine.register_iterator::<Numbers>();
+    scope.push("numbers", Numbers(created.clone(), 100));
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "
+                fn gen(items) { for x in items { yield x; } }
+                let sum = 0;
+                for x in gen(numbers) { sum += x; }
+                sum
+            "
+        )?,
+        4950
+    );
+
+    // The iterator is kept while the generator is suspended
+    assert_eq!(created.get(), 1);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_generators_iterators_linear() -> Result<(), Box<EvalAltResult>> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Clone)]
+    struct Numbers(Arc<AtomicUsize>, INT);
+
+    struct Counted(Arc<AtomicUsize>, std::ops::Range<INT>);
+
+    impl Iterator for Counted {
+        type Item = INT;
+
+        fn next(&mut self) -> Option<INT> {
+            let item = self.1.next();
+            if item.is_some() {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+            item
+        }
+    }
+
+    impl IntoIterator for Numbers {
+        type Item = INT;
+        type IntoIter = Counted;
+
+        fn into_iter(self) -> Self::IntoIter {
+            Counted(self.0, 0..self.1)
+        }
+    }
+
+    let produced = Arc::new(AtomicUsize::new(0));
+    let mut engine = Engine::new();
+    let mut scope = Scope::new();
+
+    engine.register_iterator::<Numbers>();
+    scope.push("numbers", Numbers(produced.clone(), 1000));
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "
+                fn gen(items) { for x in items { yield x; } }
+                let sum = 0;
+                for x in gen(numbers) { sum += x; }
+                sum
+            "
+        )?,
+        499500
+    );
+
+    // Items are not taken again for every value yielded
+    assert!(produced.load(Ordering::SeqCst) < 4000);
+
+    // Ranges resume from where they were suspended, also in cloned generators
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn gen(items) { for x in items { yield x; } }
+                let sum = 0;
+                for x in gen(0..100000) { sum += x; }
+                sum
+            "
+        )?,
+        4999950000
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn gen(items) { for x in items { yield x; } }
+                let g = gen(1..=4);
+                g.next();
+                let h = g;
+                let a = g.next();
+                let b = h.next();
+                let c = h.next();
+                a * 100 + b * 10 + c
+            "
+        )?,
+        223
+    );
+
+    Ok(())
+}
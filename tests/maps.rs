@@ -212,6 +212,44 @@ fn test_map_json() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+#[cfg(feature = "preserve_order")]
+fn test_map_preserve_order() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let map = engine.parse_json(r#"{"z":1, "a":2, "m":3}"#, false)?;
+    assert_eq!(
+        map.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+        ["z", "a", "m"]
+    );
+
+    assert_eq!(
+        engine.eval::<String>("let x = #{b:1, a:2}; x.c = 3; x.to_string()")?,
+        "#{\"b\": 1, \"a\": 2, \"c\": 3}"
+    );
+    assert_eq!(
+        engine.eval::<String>("let x = #{c:1, b:2, a:3}; x.remove(\"b\"); x.to_string()")?,
+        "#{\"c\": 1, \"a\": 3}"
+    );
+    assert_eq!(
+        engine.eval::<String>("let x = #{b:1}; #{...x, a:2}.to_string()")?,
+        "#{\"b\": 1, \"a\": 2}"
+    );
+    assert!(engine.eval::<bool>("#{b:1, a:2} == #{a:2, b:1}")?);
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine
+            .eval::<rhai::Array>("let x = #{y:1, x:2}; x.keys()")?
+            .into_iter()
+            .map(|k| k.as_string().unwrap())
+            .collect::<Vec<_>>(),
+        ["y", "x"]
+    );
+
+    Ok(())
+}
+
 #[test]
 #[cfg(not(feature = "no_function"))]
 fn test_map_oop() -> Result<(), Box<EvalAltResult>> {
@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, OverflowMode, Scope, INT};
 
 #[cfg(not(feature = "no_float"))]
 use rhai::FLOAT;
@@ -119,3 +119,208 @@ fn test_math_parse() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_math_overflow_mode() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let mut scope = Scope::new();
+
+    scope.push_constant("MAX", INT::MAX);
+    scope.push_constant("MIN", INT::MIN);
+
+    #[cfg(not(feature = "unchecked"))]
+    assert!(matches!(
+        *engine
+            .eval_with_scope::<INT>(&mut scope, "MAX + 1")
+            .expect_err("expects overflow"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    engine.set_overflow_mode(OverflowMode::Wrapping);
+    assert_eq!(engine.overflow_mode(), OverflowMode::Wrapping);
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MAX + 1")?,
+        INT::MIN
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MIN - 1")?,
+        INT::MAX
+    );
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "MAX * 2")?, -2);
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MIN / -1")?,
+        INT::MIN
+    );
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "MIN % -1")?, 0);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "-MIN")?, INT::MIN);
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "abs(MIN)")?,
+        INT::MIN
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "let x = MAX; x += 1; x")?,
+        INT::MIN
+    );
+    assert_eq!(engine.eval::<INT>("2 ** 100")?, 0);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "MAX << 1")?, -2);
+    assert_eq!(engine.eval::<INT>("1 << 70")?, 64);
+    assert_eq!(engine.eval::<INT>("256 >> 70")?, 4);
+    assert_eq!(engine.eval::<INT>("let x = 1; x <<= 70; x")?, 64);
+    #[cfg(not(feature = "only_i32"))]
+    {
+        assert_eq!(engine.eval::<INT>("2 ** 5000000000")?, 0);
+        assert_eq!(engine.eval::<INT>("3 ** 4611686018427387904")?, 1);
+        assert_eq!(engine.eval::<INT>("-1 ** 5000000001")?, -1);
+    }
+
+    engine.set_overflow_mode(OverflowMode::Saturating);
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MAX + 1")?,
+        INT::MAX
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MIN - 1")?,
+        INT::MIN
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MIN * 2")?,
+        INT::MIN
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MIN / -1")?,
+        INT::MAX
+    );
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "-MIN")?, INT::MAX);
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "let x = MIN; x -= 1; x")?,
+        INT::MIN
+    );
+    assert_eq!(engine.eval::<INT>("-2 ** 101")?, INT::MIN);
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MAX << 1")?,
+        INT::MAX
+    );
+    assert_eq!(engine.eval::<INT>("-3 << 100")?, INT::MIN);
+    assert_eq!(engine.eval::<INT>("0 << 100")?, 0);
+    assert_eq!(engine.eval::<INT>("-3 << 2")?, -12);
+    assert_eq!(engine.eval::<INT>("-3 >> 100")?, -1);
+    assert_eq!(engine.eval::<INT>("3 >> 100")?, 0);
+    assert_eq!(engine.eval::<INT>("let x = 1; x <<= 100; x")?, INT::MAX);
+    #[cfg(not(feature = "only_i32"))]
+    {
+        assert_eq!(engine.eval::<INT>("2 ** 5000000000")?, INT::MAX);
+        assert_eq!(engine.eval::<INT>("-2 ** 5000000001")?, INT::MIN);
+        assert_eq!(engine.eval::<INT>("-1 ** 5000000000")?, 1);
+    }
+
+    #[cfg(not(feature = "unchecked"))]
+    assert!(matches!(
+        *engine
+            .eval::<INT>("1 << -1")
+            .expect_err("expects negative shift"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    #[cfg(not(feature = "unchecked"))]
+    assert!(matches!(
+        *engine
+            .eval::<INT>("42 / 0")
+            .expect_err("expects division by zero"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_math_overflow_functions() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    scope.push_constant("MAX", INT::MAX);
+    scope.push_constant("MIN", INT::MIN);
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "wrapping_add(MAX, 1)")?,
+        INT::MIN
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "wrapping_sub(MIN, 1)")?,
+        INT::MAX
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MAX.wrapping_mul(2)")?,
+        -2
+    );
+    assert_eq!(engine.eval::<INT>("wrapping_pow(2, 100)")?, 0);
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "saturating_add(MAX, 1)")?,
+        INT::MAX
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "saturating_sub(MIN, 1)")?,
+        INT::MIN
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MIN.saturating_mul(2)")?,
+        INT::MIN
+    );
+    assert_eq!(engine.eval::<INT>("saturating_pow(2, 100)")?, INT::MAX);
+    assert_eq!(engine.eval::<INT>("checked_add(40, 2)")?, 42);
+    assert!(engine
+        .eval_with_scope::<()>(&mut scope, "checked_add(MAX, 1)")
+        .is_ok());
+    assert!(engine
+        .eval_with_scope::<()>(&mut scope, "checked_sub(MIN, 1)")
+        .is_ok());
+    assert!(engine
+        .eval_with_scope::<()>(&mut scope, "checked_mul(MAX, 2)")
+        .is_ok());
+    assert_eq!(engine.eval::<INT>("checked_pow(2, 100) ?? -1")?, -1);
+    assert_eq!(engine.eval::<INT>("checked_div(42, 0) ?? -1")?, -1);
+    assert_eq!(engine.eval::<INT>("checked_rem(42, 5)")?, 2);
+    assert_eq!(engine.eval::<INT>("checked_shl(1, 100) ?? -1")?, -1);
+    assert_eq!(engine.eval::<INT>("checked_shr(256, 4)")?, 16);
+    assert!(engine
+        .eval_with_scope::<()>(&mut scope, "checked_neg(MIN)")
+        .is_ok());
+    assert!(engine
+        .eval_with_scope::<()>(&mut scope, "checked_abs(MIN)")
+        .is_ok());
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "wrapping_div(MIN, -1)")?,
+        INT::MIN
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "wrapping_neg(MIN)")?,
+        INT::MIN
+    );
+    assert_eq!(engine.eval::<INT>("wrapping_shl(1, 70)")?, 64);
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "saturating_div(MIN, -1)")?,
+        INT::MAX
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "saturating_abs(MIN)")?,
+        INT::MAX
+    );
+    assert_eq!(engine.eval::<INT>("saturating_shl(-1, 100)")?, INT::MIN);
+    assert_eq!(engine.eval::<INT>("saturating_shr(-8, 100)")?, -1);
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("wrapping_pow(2, -1)")
+            .expect_err("expects negative index"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>("wrapping_div(42, 0)")
+            .expect_err("expects division by zero"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    Ok(())
+}
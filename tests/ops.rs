@@ -1,5 +1,8 @@
 use rhai::{Engine, EvalAltResult, Scope, INT};
 
+#[cfg(not(feature = "no_function"))]
+use rhai::ParseErrorType;
+
 #[test]
 fn test_ops() -> Result<(), Box<EvalAltResult>> {
     let engine = Engine::new();
@@ -56,3 +59,168 @@ fn test_ops_precedence() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_ops_overload_maps() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let script = r#"
+        fn op "-"(a, b) { #{ x: a.x - b.x, y: a.y - b.y } }
+        fn op "*"(a, k) { #{ x: a.x * k, y: a.y * k } }
+        fn op "+"(a, b) { #{ x: 0, y: 0 } }
+    "#;
+
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let p = #{{ x: 11, y: 22 }} - #{{ x: 10, y: 20 }}; p.x * 100 + p.y",
+            script
+        ))?,
+        102
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let p = #{{ x: 1, y: 2 }}; p *= 3; p -= #{{ x: 1, y: 1 }}; p.x * 100 + p.y",
+            script
+        ))?,
+        205
+    );
+
+    // Native functions on object maps take precedence over overloads
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let p = #{{ x: 1 }} + #{{ y: 2 }}; p.x * 100 + p.y",
+            script
+        ))?,
+        102
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let p = #{{ x: 1 }}; p += #{{ y: 2 }}; p.x * 100 + p.y",
+            script
+        ))?,
+        102
+    );
+
+    // Operators on other types are not affected
+    assert_eq!(engine.eval::<INT>(&format!("{} 40 + 2", script))?, 42);
+
+    let script = r#"
+        fn op "-"(a) { #{ x: 0 - a.x, y: 0 - a.y } }
+        fn op "!"(a) { a.x == 0 && a.y == 0 }
+    "#;
+
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let p = #{{ x: 1, y: 2 }}; let q = -p; q.x * 100 + q.y",
+            script
+        ))?,
+        -102
+    );
+    assert!(engine.eval::<bool>(&format!("{} !#{{ x: 0, y: 0 }}", script))?);
+    assert!(!engine.eval::<bool>(&format!("{} !#{{ x: 1, y: 0 }}", script))?);
+
+    // Unary operators on other types are not affected
+    assert_eq!(
+        engine.eval::<INT>(&format!("{} let x = 42; let y = -x; y", script))?,
+        -42
+    );
+    assert!(engine.eval::<bool>(&format!("{} !false", script))?);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_ops_overload_custom_types() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    struct Money(INT);
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Money>("Money")
+        .register_fn("money", Money)
+        .register_fn("cents", |m: &mut Money| m.0);
+
+    assert_eq!(
+        engine.eval::<Money>(
+            r#"
+                fn op "+"(a, b) { money(cents(a) + cents(b)) }
+                fn op "-"(a, b) { money(cents(a) - cents(b)) }
+
+                let total = money(100) + money(250);
+                total -= money(50);
+                total
+            "#
+        )?,
+        Money(300)
+    );
+    assert_eq!(
+        engine.eval::<Money>(
+            r#"
+                fn op "-"(a) { money(0 - cents(a)) }
+
+                let m = money(100);
+                let n = -m;
+                n
+            "#
+        )?,
+        Money(-100)
+    );
+
+    // Native functions on custom types take precedence over overloads
+    engine.register_fn("+", |m: Money, cents: INT| Money(m.0 + cents));
+
+    assert_eq!(
+        engine.eval::<Money>(
+            r#"
+                fn op "+"(a, b) { money(cents(a) + cents(b)) }
+
+                let total = money(100) + money(250) + 7;
+                total += 3;
+                total
+            "#
+        )?,
+        Money(360)
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<Money>("money(1) + money(2)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(f, _) if f.starts_with("+ (")
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_ops_overload_invalid() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert!(matches!(
+        *engine.compile(r#"fn op "&&"(a, b) { true }"#).expect_err("should error").0,
+        ParseErrorType::FnInvalidOperator(op) if op == "&&"
+    ));
+    assert!(matches!(
+        *engine.compile(r#"fn op "+"(a) { a }"#).expect_err("should error").0,
+        ParseErrorType::FnInvalidOperator(op) if op == "+"
+    ));
+    assert!(matches!(
+        *engine.compile(r#"fn op "!"(a, b) { a }"#).expect_err("should error").0,
+        ParseErrorType::FnInvalidOperator(op) if op == "!"
+    ));
+    assert!(matches!(
+        *engine.compile(r#"fn op "-"(a, b, c) { a }"#).expect_err("should error").0,
+        ParseErrorType::FnInvalidOperator(op) if op == "-"
+    ));
+
+    // `op` is still a valid function name
+    assert_eq!(engine.eval::<INT>("fn op(x) { x + 1 } op(41)")?, 42);
+
+    Ok(())
+}
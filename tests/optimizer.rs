@@ -22,6 +22,37 @@ fn test_optimizer() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_optimizer_method_calls() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::Full);
+
+    // Method calls with constant arguments are not evaluated without their objects
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "{}-{}"; s.format(1, 2)"#)?,
+        "1-2"
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"let s = "{}-{}"; s.format(1, 2).len()"#)?,
+        3
+    );
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"let d = 1; d.format("x")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<String>(r#"let a = ["{}!"]; a[0].format("hi")"#)?,
+        "hi!"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_optimizer_run() -> Result<(), Box<EvalAltResult>> {
     fn run_test(engine: &mut Engine) -> Result<(), Box<EvalAltResult>> {
@@ -86,7 +117,7 @@ fn test_optimizer_parse() -> Result<(), Box<EvalAltResult>> {
 
     assert_eq!(
         format!("{:?}", ast),
-        r#"AST { source: None, body: Block[Const(false @ 1:18, "DECISION" @ 1:7, false, 1:1), Expr(123 @ 1:51)], functions: Module, resolver: None }"#
+        r#"AST { source: None, body: Block[Const(false @ 1:18, ("DECISION" @ 1:7, None), false, 1:1), Expr(123 @ 1:51)], functions: Module, resolver: None }"#
     );
 
     let ast = engine.compile("if 1 == 2 { 42 }")?;
@@ -0,0 +1,124 @@
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+
+#[test]
+fn test_optional_coalesce() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let x = (); x ?? 42")?, 42);
+    assert_eq!(engine.eval::<INT>("let x = 1; x ?? 42")?, 1);
+    assert_eq!(engine.eval::<bool>("let x = false; x ?? true")?, false);
+    assert_eq!(
+        engine.eval::<INT>("let x = (); let y = (); x ?? y ?? 3")?,
+        3
+    );
+    assert_eq!(engine.eval::<INT>("() ?? 1 + 2")?, 3);
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; let y = 1 ?? { x = 42; 2 }; x + y")?,
+        1
+    );
+
+    assert!(matches!(
+        *engine
+            .compile("let x = (); x ?? 1 = 2;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::BadInput(_)
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_optional_dot() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let x = #{ a: #{ b: 42 } }; x?.a?.b")?,
+        42
+    );
+    engine.eval::<()>("let x = (); x?.a.b.c")?;
+    engine.eval::<()>("let x = #{}; x.a?.b")?;
+    engine.eval::<()>("let x = (); x?.len()")?;
+    assert_eq!(engine.eval::<INT>("let x = #{}; x.a?.b ?? 42")?, 42);
+
+    assert!(matches!(
+        *engine
+            .eval::<()>("let x = #{}; x.a.b")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDotExpr(_, _)
+    ));
+
+    assert!(matches!(
+        *engine
+            .compile("let x = #{}; x?.a = 1;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::AssignmentToInvalidLHS(_)
+    ));
+    assert!(matches!(
+        *engine
+            .compile("let x = #{}; x.a?.b += 1;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::AssignmentToInvalidLHS(_)
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_optional_index() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let x = [1, [2, 3]]; x?[1]?[0]")?, 2);
+    engine.eval::<()>("let x = (); x?[0][1]")?;
+    engine.eval::<()>("let x = [()]; x[0]?[1]")?;
+    assert_eq!(engine.eval::<INT>("let x = [()]; x[0]?[1] ?? 42")?, 42);
+
+    assert!(matches!(
+        *engine
+            .eval::<()>("let x = [()]; x[0][1]")
+            .expect_err("should error"),
+        EvalAltResult::ErrorIndexingType(_, _)
+    ));
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>(r#"let x = #{ a: [#{ b: 42 }] }; x?.a?[0]?.b + (x?.c?[0]?.b ?? 0)"#)?,
+        42
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+fn test_optional_json() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = rhai::Scope::new();
+
+    let json = r#"{"user": {"name": "Bob", "tags": ["a", "b"], "address": null}}"#;
+    scope.push("data", engine.parse_json(json.replace("{", "#{"), true)?);
+
+    assert_eq!(
+        engine.eval_with_scope::<String>(&mut scope, "data?.user?.name ?? \"anonymous\"")?,
+        "Bob"
+    );
+    assert_eq!(
+        engine.eval_with_scope::<String>(&mut scope, "data?.user?.tags?[1] ?? \"none\"")?,
+        "b"
+    );
+    assert_eq!(
+        engine.eval_with_scope::<String>(&mut scope, "data?.user?.address?.city ?? \"unknown\"")?,
+        "unknown"
+    );
+    assert_eq!(
+        engine.eval_with_scope::<String>(&mut scope, "data?.owner?.tags?[0] ?? \"none\"")?,
+        "none"
+    );
+
+    Ok(())
+}
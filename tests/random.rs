@@ -0,0 +1,128 @@
+use rhai::packages::{Package, RandomPackage};
+use rhai::{Engine, EvalAltResult, Scope, INT};
+
+fn make_engine(seed: u64) -> Engine {
+    let mut engine = Engine::new();
+    engine.register_global_module(RandomPackage::new().as_shared_module());
+    engine.set_random_seed(seed);
+    engine
+}
+
+#[test]
+fn test_random() -> Result<(), Box<EvalAltResult>> {
+    let engine = make_engine(42);
+
+    assert!(engine.eval::<bool>(
+        "
+            let ok = true;
+            for i in 0..100 {
+                let x = rand(-3, 3);
+                let y = rand(1..=6);
+                let z = rand(0..2);
+                ok = ok && x >= -3 && x <= 3 && y >= 1 && y <= 6 && (z == 0 || z == 1);
+            }
+            ok
+        "
+    )?);
+    assert_eq!(engine.eval::<INT>("rand(7, 7)")?, 7);
+    assert_ne!(engine.eval::<INT>("rand()")?, engine.eval::<INT>("rand()")?);
+
+    #[cfg(not(feature = "no_float"))]
+    assert!(engine.eval::<bool>(
+        "
+            let ok = true;
+            for i in 0..100 {
+                let x = rand_float();
+                ok = ok && x >= 0.0 && x < 1.0;
+            }
+            ok
+        "
+    )?);
+
+    assert!(matches!(
+        *engine.eval::<INT>("rand(2, 1)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>("rand(1..1)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_random_seed() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = make_engine(42);
+    let first = engine.eval::<INT>("rand()")?;
+    assert_eq!(make_engine(42).eval::<INT>("rand()")?, first);
+    assert_ne!(make_engine(43).eval::<INT>("rand()")?, first);
+
+    engine.set_random_seed(42);
+    assert_eq!(engine.eval::<INT>("rand()")?, first);
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let run = |engine: &mut Engine| -> Result<String, Box<EvalAltResult>> {
+            engine.set_random_seed(123);
+            engine
+                .eval::<rhai::Array>("[rand(), rand(1, 100), rand(1, 100)]")
+                .map(|a| format!("{:?}", a))
+        };
+        assert_eq!(run(&mut engine)?, run(&mut engine)?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_random_seed_per_evaluation() -> Result<(), Box<EvalAltResult>> {
+    let engine = make_engine(42);
+    let ast = engine.compile("rand(1, 1000) * 10000 + rand(1, 1000)")?;
+
+    let first = engine.eval_ast_with_scope_and_seed::<INT>(&mut Scope::new(), &ast, 7)?;
+    assert_eq!(
+        engine.eval_ast_with_scope_and_seed::<INT>(&mut Scope::new(), &ast, 7)?,
+        first
+    );
+    assert_ne!(
+        engine.eval_ast_with_scope_and_seed::<INT>(&mut Scope::new(), &ast, 8)?,
+        first
+    );
+
+    // The random number generator of the engine is not touched
+    assert_eq!(
+        engine.eval::<INT>("rand()")?,
+        make_engine(42).eval::<INT>("rand()")?
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_random_array() -> Result<(), Box<EvalAltResult>> {
+    let engine = make_engine(42);
+
+    #[cfg(not(feature = "no_function"))]
+    assert!(engine.eval::<bool>(
+        "
+            let a = [1, 2, 3, 4, 5, 6, 7, 8];
+            a.shuffle();
+            a.len() == 8 && a.reduce(|sum, v| sum + v, 0) == 36 && a != [1, 2, 3, 4, 5, 6, 7, 8]
+        "
+    )?);
+    #[cfg(not(feature = "no_function"))]
+    assert!(engine.eval::<bool>(
+        "
+            let a = [1, 2, 3, 4, 5];
+            let s = a.sample(3);
+            s.len() == 3 && s.all(|v| v in a) && s[0] != s[1] && s[1] != s[2] && s[0] != s[2]
+        "
+    )?);
+    assert_eq!(engine.eval::<INT>("[1, 2, 3].sample(10).len()")?, 3);
+    assert_eq!(engine.eval::<INT>("[1, 2, 3].sample(-1).len()")?, 0);
+    assert_eq!(engine.eval::<INT>("let a = []; a.shuffle(); a.len()")?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use rhai::{Engine, EvalAltResult, ExclusiveRange, InclusiveRange, INT};
+
+#[cfg(not(feature = "no_index"))]
+use rhai::Array;
+
+#[test]
+fn test_ranges() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<ExclusiveRange>("1..10")?, 1..10);
+    assert_eq!(engine.eval::<InclusiveRange>("1..=10")?, 1..=10);
+    assert_eq!(
+        engine.eval::<ExclusiveRange>("let x = 2; x*2..x*5+1")?,
+        4..11
+    );
+    assert_eq!(engine.eval::<ExclusiveRange>("-5..-1")?, -5..-1);
+    assert_eq!(engine.eval::<String>("type_of(0..3)")?, "range");
+    assert_eq!(engine.eval::<String>("type_of(0..=3)")?, "range=");
+    assert_eq!(engine.eval::<String>("let r = 0..3; `${r}`")?, "0..3");
+    assert_eq!(engine.eval::<String>("(0..=3).to_string()")?, "0..=3");
+
+    assert_eq!(engine.eval::<INT>("let r = 3..8; r.start + r.end")?, 11);
+    assert_eq!(engine.eval::<INT>("let r = 3..=8; r.end - r.start")?, 5);
+    assert!(engine.eval::<bool>("(1..=2).is_inclusive")?);
+    assert!(engine.eval::<bool>("(1..2).is_exclusive")?);
+
+    assert!(engine.eval::<bool>("5 in 0..10")?);
+    assert!(!engine.eval::<bool>("10 in 0..10")?);
+    assert!(engine.eval::<bool>("10 in 0..=10")?);
+    assert!(!engine.eval::<bool>("-1 in 0..=10")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_ranges_for() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                let sum = 0;
+                for x in 1..10 { sum += x; }
+                sum
+            "
+        )?,
+        45
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                let sum = 0;
+                for x in 1..=10 { sum += x; }
+                sum
+            "
+        )?,
+        55
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                let sum = 0;
+                let n = 4;
+                for (x, i) in 0..n { sum += x * i; }
+                sum
+            "
+        )?,
+        14
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                let count = 0;
+                for x in 10..0 { count += 1; }
+                count
+            "
+        )?,
+        0
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_ranges_index() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let a = engine.eval::<Array>("let x = [1, 2, 3, 4, 5]; x[1..3]")?;
+    assert_eq!(a.len(), 2);
+    assert_eq!(a[0].as_int().unwrap(), 2);
+    assert_eq!(a[1].as_int().unwrap(), 3);
+
+    assert_eq!(
+        engine.eval::<INT>("let x = [1, 2, 3, 4, 5]; x[1..=3].len()")?,
+        3
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = [1, 2, 3, 4, 5]; x[-2..=-1][0]")?,
+        4
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = [1, 2, 3, 4, 5]; x[3..100].len()")?,
+        2
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = [1, 2, 3, 4, 5]; x[4..1].len()")?,
+        0
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = [1, 2, 3, 4, 5]; x[0..=-100].len()")?,
+        0
+    );
+
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "hello, world!"; s[0..5]"#)?,
+        "hello"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "hello, world!"; s[7..=11]"#)?,
+        "world"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "hello, world!"; s[-6..-1]"#)?,
+        "world"
+    );
+    assert_eq!(engine.eval::<String>(r#""日本語文字列"[2..4]"#)?, "語文");
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_ranges_index_assignment() {
+    let engine = Engine::new();
+
+    assert!(matches!(
+        *engine
+            .eval::<Array>("let a = [1, 2, 3, 4]; a[1..3] = 9; a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorIndexingType(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<Array>("let a = [1, 2, 3, 4]; a[1..3] += [9]; a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorIndexingType(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<Array>("let a = [1, 2, 3, 4]; a[1..3][0] = 9; a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorIndexingType(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"let s = "hello"; s[0..2] = "J"; s"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorIndexingType(_, _)
+    ));
+}
@@ -0,0 +1,168 @@
+use rhai::packages::{Package, RegexPackage};
+use rhai::{Engine, EvalAltResult};
+
+#[cfg(not(feature = "no_index"))]
+use rhai::INT;
+
+fn make_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.register_global_module(RegexPackage::new().as_shared_module());
+    engine
+}
+
+#[test]
+fn test_regex_matches() -> Result<(), Box<EvalAltResult>> {
+    let engine = make_engine();
+
+    assert!(engine.eval::<bool>(r#"matches("abc123", "\\d+")"#)?);
+    assert!(!engine.eval::<bool>(r#"matches("abc", "^\\d+$")"#)?);
+    assert!(engine.eval::<bool>(r#"matches("Hello", "(?i)^hello$")"#)?);
+    assert!(engine.eval::<bool>(r#"matches("a\nb", "(?m)^b$")"#)?);
+    assert!(!engine.eval::<bool>(r#"matches("a\nb", "a.b")"#)?);
+    assert!(engine.eval::<bool>(r#"matches("a\nb", "(?s)a.b")"#)?);
+    assert!(engine.eval::<bool>(r#"matches("foo bar", "\\bbar\\b")"#)?);
+    assert!(!engine.eval::<bool>(r#"matches("foobar", "\\bbar")"#)?);
+    assert!(engine.eval::<bool>(r#"matches("x-y_z", "^[\\w-]{5}$")"#)?);
+    assert!(engine.eval::<bool>(r#"matches("größe", "^\\w+$")"#)?);
+    assert!(engine.eval::<bool>(r#"matches("ab", "^(a|ab)(c|bcd|)$")"#)?);
+
+    assert!(matches!(
+        *engine
+            .eval::<bool>(r#"matches("x", "(a")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<bool>(r#"matches("x", "a**")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_regex_find() -> Result<(), Box<EvalAltResult>> {
+    let engine = make_engine();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                let m = find("GET /index.html 200", "(\\w+) (\\S+) (\\d+)");
+                `${len(m)}:${m[1]}:${m[3]}`
+            "#
+        )?,
+        "4:GET:200"
+    );
+    assert_eq!(engine.eval::<()>(r#"find("abc", "\\d")"#)?, ());
+    assert_eq!(
+        engine.eval::<String>(r#"find_all("a1b22c333", "\\d+")[2][0]"#)?,
+        "333"
+    );
+    assert_eq!(engine.eval::<INT>(r#"len(find_all("aaa", "a*?"))"#)?, 4);
+    assert_eq!(
+        engine.eval::<String>(r#"find("<<a>>", "<(.+?)>")[1]"#)?,
+        "<a"
+    );
+    assert_eq!(engine.eval::<()>(r#"find("ac", "a(b)?c")[1]"#)?, ());
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                let m = find("code=404 ms=12", "code=(?P<code>\\d+) (\\w+)=(?<time>\\d+)");
+                `${m.code}/${m["2"]}/${m.time}/${m["0"].len()}`
+            "#
+        )?,
+        "404/ms/12/14"
+    );
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"let a = split_regex("a, b;c ,d", "\\s*[,;]\\s*"); `${len(a)}${a[1]}${a[3]}`"#
+        )?,
+        "4bd"
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"len(split_regex("a1b2c3d", "\\d", 2))"#)?,
+        2
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"split_regex("a1b2c3d", "\\d", 2)[1]"#)?,
+        "b2c3d"
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_regex_replace() -> Result<(), Box<EvalAltResult>> {
+    let engine = make_engine();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"let s = "2021-08-05"; s.replace_regex("(\\d+)-(\\d+)-(\\d+)", "$3/$2/$1"); s"#
+        )?,
+        "05/08/2021"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "a1b2"; s.replace_regex("(?P<d>\\d)", "<${d}$$>"); s"#)?,
+        "a<1$>b<2$>"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "abc"; s.replace_regex("x*", "-"); s"#)?,
+        "-a-b-c-"
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+fn test_regex_limits() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = make_engine();
+    engine.set_max_operations(500);
+
+    assert!(matches!(
+        *engine
+            .eval::<bool>(r#"matches("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa!", "^(a+)+$")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorTooManyOperations(_)
+    ));
+
+    // Steps of all matches count towards the same maximum
+    engine.set_max_operations(1000);
+
+    let script = r#"
+        let text = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa!";
+        let n = 0;
+        for i in range(0, 20) { if matches(text, "a+!") { n += 1; } }
+        n == 20
+    "#;
+
+    assert!(engine.eval::<bool>(
+        r#"matches("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa!", "a+!")"#
+    )?);
+    assert!(matches!(
+        *engine.eval::<bool>(script).expect_err("should error"),
+        EvalAltResult::ErrorTooManyOperations(_)
+    ));
+
+    engine.set_max_operations(0);
+    assert!(engine.eval::<bool>(script)?);
+    assert!(!engine.eval::<bool>(
+        r#"matches("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa!", "^(a+)+$")"#
+    )?);
+
+    assert!(matches!(
+        *engine
+            .eval::<bool>(r#"matches("x", "(a{1000}){1000}")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+
+    Ok(())
+}
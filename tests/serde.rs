@@ -6,19 +6,24 @@ use rhai::{
 };
 use serde::{Deserialize, Serialize};
 
-#[cfg(not(feature = "no_index"))]
-use rhai::Array;
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
 #[cfg(not(feature = "no_object"))]
 use rhai::Map;
 #[cfg(not(feature = "no_float"))]
 use rhai::FLOAT;
+#[cfg(not(feature = "no_index"))]
+use rhai::{Array, Blob};
 #[cfg(feature = "decimal")]
 use rust_decimal::Decimal;
 
 #[test]
 fn test_serde_ser_primary_types() -> Result<(), Box<EvalAltResult>> {
     assert!(to_dynamic(42_u64)?.is::<INT>());
+    #[cfg(not(feature = "bigint"))]
     assert!(to_dynamic(u64::MAX)?.is::<u64>());
+    #[cfg(feature = "bigint")]
+    assert!(to_dynamic(u64::MAX)?.is::<BigInt>());
     assert!(to_dynamic(42 as INT)?.is::<INT>());
     assert!(to_dynamic(true)?.is::<bool>());
     assert!(to_dynamic(())?.is::<()>());
@@ -403,6 +408,106 @@ fn test_serde_de_array() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_serde_blob() -> Result<(), Box<EvalAltResult>> {
+    struct Bytes<'a>(&'a [u8]);
+
+    impl Serialize for Bytes<'_> {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            ser.serialize_bytes(self.0)
+        }
+    }
+
+    let d = to_dynamic(Bytes(&[1, 2, 3]))?;
+    assert_eq!(d.type_name(), "blob");
+    assert_eq!(d.clone_cast::<Blob>(), [1, 2, 3]);
+
+    assert_eq!(from_dynamic::<Vec<u8>>(&d)?, [1, 2, 3]);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_serde_set() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let d = engine.eval::<Dynamic>("set([1, 2, 3, 2])")?;
+    assert_eq!(d.type_name(), "set");
+
+    let mut values = from_dynamic::<Vec<INT>>(&d)?;
+    values.sort();
+    assert_eq!(values, [1, 2, 3]);
+
+    let set = from_dynamic::<std::collections::BTreeSet<INT>>(&d)?;
+    assert_eq!(set.len(), 3);
+    assert!(set.contains(&2));
+
+    let d = to_dynamic(d)?;
+    assert!(d.is::<Array>());
+    assert_eq!(d.cast::<Array>().len(), 3);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "preserve_order")]
+fn test_serde_preserve_order() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Debug, Serialize)]
+    struct Hello {
+        z: INT,
+        a: INT,
+        m: INT,
+    }
+
+    let d = to_dynamic(Hello { z: 1, a: 2, m: 3 })?;
+    let map = d.cast::<Map>();
+    assert_eq!(
+        map.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+        ["z", "a", "m"]
+    );
+
+    let engine = Engine::new();
+    let d = engine.eval::<Dynamic>("#{z: 1, a: 2, m: 3}")?;
+    let map = from_dynamic::<Dynamic>(&d)?.cast::<Map>();
+    assert_eq!(
+        map.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+        ["z", "a", "m"]
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_serde_dict() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let d = engine.eval::<Dynamic>("let d = dict(); d[1] = 'a'; d[2] = 'b'; d")?;
+    assert_eq!(d.type_name(), "dict");
+
+    let map = from_dynamic::<std::collections::BTreeMap<INT, char>>(&d)?;
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&2], 'b');
+
+    let d = to_dynamic(map)?;
+    assert_eq!(d.type_name(), "dict");
+    assert_eq!(
+        d.cast::<rhai::Dict>()
+            .get(&(1 as INT).into())
+            .unwrap()
+            .as_char(),
+        Ok('a')
+    );
+
+    let mut map = std::collections::BTreeMap::new();
+    map.insert("x".to_string(), 42 as INT);
+    assert!(to_dynamic(map)?.is::<Map>());
+
+    Ok(())
+}
+
 #[test]
 #[cfg(not(feature = "no_index"))]
 #[cfg(not(feature = "no_object"))]
@@ -746,3 +851,58 @@ fn test_serde_json() -> serde_json::Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[cfg(feature = "bigint")]
+fn test_serde_bigint() -> Result<(), Box<EvalAltResult>> {
+    let big = BigInt::from(u128::MAX) * 2_u32;
+
+    assert_eq!(
+        to_dynamic(u128::MAX)?.cast::<BigInt>(),
+        BigInt::from(u128::MAX)
+    );
+    assert_eq!(
+        to_dynamic(i128::MIN)?.cast::<BigInt>(),
+        BigInt::from(i128::MIN)
+    );
+    assert!(to_dynamic(42_i128)?.is::<INT>());
+
+    let d: Dynamic = BigInt::from(42).into();
+    assert_eq!(from_dynamic::<u8>(&d)?, 42);
+    assert_eq!(from_dynamic::<i64>(&d)?, 42);
+
+    let d: Dynamic = BigInt::from(u64::MAX).into();
+    assert_eq!(from_dynamic::<u64>(&d)?, u64::MAX);
+    assert!(from_dynamic::<i64>(&d).is_err());
+
+    let d: Dynamic = BigInt::from(i128::MIN).into();
+    assert_eq!(from_dynamic::<i128>(&d)?, i128::MIN);
+
+    let d: Dynamic = big.into();
+    assert!(from_dynamic::<u128>(&d).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "bigint")]
+#[cfg(feature = "metadata")]
+fn test_serde_json_bigint() -> serde_json::Result<()> {
+    let d: Dynamic = BigInt::from(u64::MAX).into();
+    assert_eq!(serde_json::to_string(&d)?, u64::MAX.to_string());
+
+    let d: Dynamic = (BigInt::from(u64::MAX) * 2_u32).into();
+    assert_eq!(serde_json::to_string(&d)?, r#""36893488147419103230""#);
+
+    let d: Dynamic = serde_json::from_str(&u64::MAX.to_string())?;
+    assert_eq!(d.cast::<BigInt>(), BigInt::from(u64::MAX));
+
+    let d: Dynamic = (BigInt::from(u128::MAX) * 2_u32).into();
+    let v: serde_json::Value = from_dynamic(&d).unwrap();
+    assert_eq!(
+        v,
+        serde_json::Value::String((BigInt::from(u128::MAX) * 2_u32).to_string())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+#![cfg(not(feature = "no_index"))]
+use rhai::{Array, Engine, EvalAltResult, Set, INT};
+
+#[test]
+fn test_sets() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<Set>("set([1, 2, 3, 2, 1])")?.len(), 3);
+    assert_eq!(engine.eval::<Set>("set()")?.len(), 0);
+    assert_eq!(engine.eval::<String>("type_of(set())")?, "set");
+    assert!(engine.eval::<bool>("let s = set([1, 2, 3]); 2 in s")?);
+    assert!(!engine.eval::<bool>(r#"let s = set([1, 2, 3]); "2" in s"#)?);
+    assert!(engine.eval::<bool>(r#"let s = set(["a", 'b', [1, 2]]); [1, 2] in s"#)?);
+    assert_eq!(
+        engine.eval::<INT>("let s = set([1, 2, 3, 1]); let t = 0; for x in s { t += x } t")?,
+        6
+    );
+    assert_eq!(
+        engine.eval::<Set>("let s = set([1, 2]); s += 3; s += 2; s")?,
+        engine.eval::<Set>("set([3, 2, 1])")?
+    );
+    assert!(engine.eval::<bool>("set([1, 2]) + set([2, 3]) == set([1, 2, 3])")?);
+    assert!(engine.eval::<bool>("set([1, 2, 3]) - set([2, 3]) == set([1])")?);
+    assert!(engine.eval::<bool>("set([1, 2]) != set([1, 2, 3])")?);
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<INT>("let s = set([1, 2, 2]); s.len")?, 2);
+        assert_eq!(
+            engine
+                .eval::<Array>(
+                    "
+                    let s = set([1, 2, 3]);
+                    let r = [];
+                    r.push(s.insert(4));
+                    r.push(s.insert(1));
+                    r.push(s.remove(2));
+                    r.push(s.remove(42));
+                    r.push(s.len);
+                    r
+                "
+                )?
+                .into_iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>(),
+            ["true", "false", "true", "false", "3"]
+        );
+        assert!(engine.eval::<bool>(
+            "let s = set([1, 2, 3]); s.intersection(set([2, 3, 4])); s == set([2, 3])"
+        )?);
+        assert!(engine
+            .eval::<bool>("let s = set([1, 2, 3]); s.difference(set([2, 3, 4])); s == set([1])")?);
+        assert!(engine
+            .eval::<bool>("let s = set([1, 2]); s.union(set([2, 3])); s == set([1, 2, 3])")?);
+        assert!(engine.eval::<bool>("set([1, 2]).is_subset(set([1, 2, 3]))")?);
+        assert!(!engine.eval::<bool>("set([1, 4]).is_subset(set([1, 2, 3]))")?);
+        assert!(engine.eval::<bool>("set([1, 2, 3]).is_superset(set([3]))")?);
+        assert_eq!(
+            engine
+                .eval::<Array>("let s = set([42, 42]); s.to_array()")?
+                .into_iter()
+                .map(|v| v.as_int().unwrap())
+                .collect::<Vec<_>>(),
+            [42]
+        );
+    }
+
+    assert!(matches!(
+        *engine
+            .eval::<Set>(r#"set([1, Fn("foo")])"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(_, _, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_sets_rust() -> Result<(), Box<EvalAltResult>> {
+    let mut set = Set::new();
+
+    assert_eq!(set.insert((1 as INT).into()), Ok(true));
+    assert_eq!(set.insert("hello".into()), Ok(true));
+    assert_eq!(set.insert((1 as INT).into()), Ok(false));
+    assert!(set.insert(rhai::FnPtr::new("foo")?.into()).is_err());
+    assert!(set.contains(&"hello".into()));
+    assert!(!set.contains(&(2 as INT).into()));
+    assert_eq!(set.len(), 2);
+
+    let engine = Engine::new();
+    let mut scope = rhai::Scope::new();
+    scope.push("s", set);
+
+    assert!(engine.eval_with_scope::<bool>(&mut scope, r#""hello" in s"#)?);
+
+    Ok(())
+}
@@ -0,0 +1,216 @@
+#![cfg(not(feature = "no_index"))]
+use rhai::{Array, Engine, EvalAltResult, ParseErrorType, Scope, INT};
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_rest_params() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("fn f(a, ...rest) { a + rest.len() } f(10, 1, 2, 3)")?,
+        13
+    );
+    assert_eq!(
+        engine.eval::<INT>("fn f(a, ...rest) { a + rest.len() } f(10)")?,
+        10
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                fn log(level, ...args) { `${level}: ${args}` }
+                log("info", 1, "x", true)
+            "#
+        )?,
+        r#"info: [1, "x", true]"#
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            "fn sum(...xs) { let t = 0; for x in xs { t += x; } t } sum() + sum(1, 2, 3)"
+        )?,
+        6
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"fn f(a, b = 2, ...rest) { `${a}-${b}-${rest}` } f(1)"#)?,
+        "1-2-[]"
+    );
+    assert_eq!(
+        engine
+            .eval::<String>(r#"fn f(a, b = 2, ...rest) { `${a}-${b}-${rest}` } f(1, 3, 4, 5)"#)?,
+        "1-3-[4, 5]"
+    );
+    assert_eq!(
+        engine.eval::<INT>("fn f(a) { 1 } fn f(a, b, ...rest) { 2 } f(0) * 10 + f(0, 0)")?,
+        12
+    );
+    assert!(
+        engine.eval::<bool>(r#"fn f(a, ...rest) { a } is_def_fn("f", 1) && is_def_fn("f", 10)"#)?
+    );
+    assert!(!engine.eval::<bool>(r#"fn f(a, ...rest) { a } is_def_fn("f", 0)"#)?);
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>("fn f(...xs) { this + xs.len() } let z = 40; z.f(1, 2)")?,
+        42
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("fn f(a, b, ...rest) { a } f(1)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    assert!(matches!(
+        *engine
+            .compile("fn f(...rest, a) { a }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MissingToken(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .compile("fn f(a, ...a) { a }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnDuplicatedParam(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .compile("fn f(a, ...rest) { a } fn f(a, b) { a }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnDuplicatedDefinition(_, 2)
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_rest_params_call_fn() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    let ast = engine.compile("fn f(a, ...rest) { a + rest.len() }")?;
+
+    let r: INT = engine.call_fn(&mut scope, &ast, "f", (1 as INT,))?;
+    assert_eq!(r, 1);
+
+    let r: INT = engine.call_fn(&mut scope, &ast, "f", (1 as INT, 2 as INT, 3 as INT))?;
+    assert_eq!(r, 3);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_module"))]
+fn test_rest_params_module() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let ast = engine.compile("fn count(...xs) { xs.len() }")?;
+    let module = rhai::Module::eval_ast_as_new(Default::default(), &ast, &engine)?;
+    engine.register_static_module("m", module.into());
+
+    assert_eq!(engine.eval::<INT>("m::count()")?, 0);
+    assert_eq!(engine.eval::<INT>("m::count(1, 2, 3)")?, 3);
+    assert_eq!(engine.eval::<INT>("let a = [1, 2]; m::count(...a, 3)")?, 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_spread_call() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"let a = ["hello", 1, 3]; sub_string(...a)"#)?,
+        "ell"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let a = [1]; let b = [3]; sub_string("hello", ...a, ...b)"#)?,
+        "ell"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let a = []; sub_string("hello", ...a, 1, 3)"#)?,
+        "ell"
+    );
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval::<INT>("fn f(a, b, c) { a * 100 + b * 10 + c } let x = [2, 3]; f(1, ...x)")?,
+        123
+    );
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval::<INT>("fn f(a, ...rest) { a + rest.len() } let x = [1, 2, 3]; f(...x)")?,
+        3
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"let a = 42; sub_string("hello", ...a)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(_, _, _)
+    ));
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .compile(r#"let x = [1]; let a = "hello"; a.sub_string(...x)"#)
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MalformedCallExpr(_)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_spread_array() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let a = engine.eval::<Array>("let a = [1, 2]; let b = [3]; [0, ...a, ...b, 4]")?;
+    assert_eq!(
+        a.into_iter().map(|v| v.cast::<INT>()).collect::<Vec<_>>(),
+        [0, 1, 2, 3, 4]
+    );
+    assert_eq!(engine.eval::<INT>("let a = []; [...a, ...a].len()")?, 0);
+
+    assert!(matches!(
+        *engine
+            .eval::<Array>(r#"let a = "hello"; [...a]"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(_, _, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_spread_map() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let base = #{a: 1, x: 0}; let m = #{...base, x: 41}; m.a + m.x")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>("let base = #{a: 1, x: 0}; let m = #{x: 41, ...base}; m.a + m.x")?,
+        1
+    );
+    assert_eq!(
+        engine.eval::<INT>("let a = #{x: 1}; let b = #{y: 2}; #{...a, ...b}.len()")?,
+        2
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let a = [1]; #{...a}.len()")
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(_, _, _)
+    ));
+
+    Ok(())
+}
@@ -35,6 +35,168 @@ fn test_stack_overflow_fn_calls() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_stack_tail_calls() -> Result<(), Box<EvalAltResult>> {
+    #[cfg(not(feature = "no_optimize"))]
+    for level in [
+        rhai::OptimizationLevel::None,
+        rhai::OptimizationLevel::Simple,
+        rhai::OptimizationLevel::Full,
+    ] {
+        let mut engine = Engine::new();
+        engine.set_optimization_level(level);
+        check_tail_calls(&engine)?;
+    }
+
+    #[cfg(feature = "no_optimize")]
+    check_tail_calls(&Engine::new())?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_function"))]
+fn check_tail_calls(engine: &Engine) -> Result<(), Box<EvalAltResult>> {
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn sum(n, acc) {
+                    if n == 0 { return acc; }
+                    return sum(n - 1, acc + n);
+                }
+                sum(1000, 0)
+            "
+        )?,
+        500500
+    );
+
+    assert!(engine.eval::<bool>(
+        "
+            fn is_even(n) { if n == 0 { return true; } return is_odd(n - 1); }
+            fn is_odd(n) { if n == 0 { return false; } return is_even(n - 1); }
+            is_even(1000)
+        "
+    )?);
+
+    // Tail calls to native functions and default parameters
+    assert_eq!(
+        engine.eval::<String>(
+            "
+                fn count(n, acc = 0) {
+                    if n == 0 { return to_string(acc); }
+                    return count(n - 1, acc + 1);
+                }
+                count(100)
+            "
+        )?,
+        "100"
+    );
+
+    // Calls to function pointers
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn sum(n, acc) {
+                    if n == 0 { return acc; }
+                    let f = Fn("sum");
+                    return f.call(n - 1, acc + n);
+                }
+                fn sum2(n, acc) {
+                    if n == 0 { return acc; }
+                    let f = Fn("sum2");
+                    return call(f, n - 1, acc + n);
+                }
+                sum(1000, 0) + sum2(1000, 0)
+            "#
+        )?,
+        1001000
+    );
+
+    // Method calls on `this` keep it bound
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn add(n) {
+                    if n == 0 { return this; }
+                    this += 1;
+                    return this.add(n - 1);
+                }
+                let x = 0;
+                x.add(1000);
+                x
+            "
+        )?,
+        1000
+    );
+
+    // Calls whose results are used, or that are followed by other statements, are not tail calls
+    assert!(matches!(
+        *engine
+            .eval::<INT>(
+                "
+                    fn foo(n) { if n == 0 { 0 } else { 1 + foo(n - 1) } }
+                    foo(100)
+                "
+            )
+            .expect_err("should error"),
+        EvalAltResult::ErrorStackOverflow(_)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>(
+                "
+                    fn foo(n) { if n > 0 { foo(n - 1) } 42 }
+                    foo(100)
+                "
+            )
+            .expect_err("should error"),
+        EvalAltResult::ErrorStackOverflow(_)
+    ));
+
+    // Calls inside try statements are not tail calls
+    assert!(matches!(
+        *engine
+            .eval::<INT>(
+                "
+                    fn foo(n) {
+                        if n == 0 { return 0; }
+                        try { return foo(n - 1); } catch (err) { throw err; }
+                    }
+                    foo(100)
+                "
+            )
+            .expect_err("should error"),
+        EvalAltResult::ErrorStackOverflow(_)
+    ));
+
+    // The last expression of a function body, through `if` and `switch` branches, is also
+    // in tail position
+    assert!(engine.eval::<bool>(
+        "
+            fn is_even(n) { if n == 0 { true } else { is_odd(n - 1) } }
+            fn is_odd(n) { if n == 0 { false } else { is_even(n - 1) } }
+            is_even(100000)
+        "
+    )?);
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn sum(n, acc) {
+                    switch n {
+                        0 => acc,
+                        _ => { let m = n - 1; sum(m, acc + n) }
+                    }
+                }
+                sum(1000, 0)
+            "
+        )?,
+        500500
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_stack_overflow_parsing() -> Result<(), Box<EvalAltResult>> {
     let mut engine = Engine::new();
@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, ImmutableString, Scope, INT};
+use rhai::{Engine, EvalAltResult, ImmutableString, LexError, ParseErrorType, Scope, INT};
 
 #[test]
 fn test_string() -> Result<(), Box<EvalAltResult>> {
@@ -317,6 +317,94 @@ fn test_string_split() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_string_affixes() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert!(engine.eval::<bool>(r#""hello".starts_with("he")"#)?);
+    assert!(!engine.eval::<bool>(r#""hello".starts_with("lo")"#)?);
+    assert!(engine.eval::<bool>(r#""hello".ends_with('o')"#)?);
+    assert!(engine.eval::<bool>(r#""hello".contains("ell")"#)?);
+    assert!(!engine.eval::<bool>(r#""hello".contains('x')"#)?);
+
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "  hi  "; s.trim_start(); s"#)?,
+        "hi  "
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "  hi  "; s.trim_end(); s"#)?,
+        "  hi"
+    );
+    assert_eq!(engine.eval::<String>(r#""ab".repeat(3)"#)?, "ababab");
+    assert_eq!(engine.eval::<String>(r#""ab".repeat(-1)"#)?, "");
+    #[cfg(not(feature = "only_i32"))]
+    assert!(matches!(
+        *engine
+            .eval::<String>(&format!(r#""abc".repeat({})"#, INT::MAX))
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, _)
+    ));
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "héllo"; s.reverse(); s"#)?,
+        "olléh"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = ""; for c in "abc".chars_rev() { s += c } s"#)?,
+        "cba"
+    );
+
+    assert!(engine.eval::<bool>(r#""ÉCOLE".eq_ignore_case("école")"#)?);
+    assert_eq!(
+        engine.eval::<INT>(r#""apple".compare_ignore_case("Banana")"#)?,
+        -1
+    );
+    assert_eq!(engine.eval::<INT>(r#""b".compare_ignore_case("A")"#)?, 1);
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        let mut engine = Engine::new();
+        engine.set_max_string_size(10);
+
+        assert!(matches!(
+            *engine
+                .eval::<String>(r#""abc".repeat(4)"#)
+                .expect_err("should error"),
+            EvalAltResult::ErrorDataTooLarge(_, _)
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "unicode")]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_string_unicode() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>(r#""e\u0301".len"#)?, 2);
+    assert_eq!(engine.eval::<INT>(r#""e\u0301".grapheme_len"#)?, 1);
+    assert_eq!(engine.eval::<INT>(r#""👩‍👩‍👧🇫🇷!".grapheme_len"#)?, 3);
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "a👍🏽b"; s.reverse_graphemes(); s"#)?,
+        "b👍🏽a"
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<String>(r#"let g = "ne\u0301e".graphemes(); g[1]"#)?,
+        "e\u{301}"
+    );
+
+    assert!(engine.eval::<bool>(r#""e\u0301".to_nfc() == "é""#)?);
+    assert!(engine.eval::<bool>(r#""é".to_nfd() == "e\u0301""#)?);
+    assert_eq!(engine.eval::<String>(r#""ﬁ²".to_nfkc()"#)?, "fi2");
+    assert_eq!(engine.eval::<INT>(r#""ﬁ".to_nfkd().len"#)?, 2);
+
+    Ok(())
+}
+
 #[test]
 fn test_string_interpolated() -> Result<(), Box<EvalAltResult>> {
     let engine = Engine::new();
@@ -401,3 +489,122 @@ Undeniable logic:
 
     Ok(())
 }
+
+#[test]
+fn test_string_format_fn() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"format("[{:>6}|{:<4}|{:^5}]", 42, "ab", 'x')"#)?,
+        "[    42|ab  |  x  ]"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{:08x} {:#X} {:o} {:#b}", 255, 255, 8, 5)"#)?,
+        "000000ff 0xFF 10 0b101"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{:+} {:+} {:05}", 42, -42, -42)"#)?,
+        "+42 -42 -0042"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{1}-{0} {{{}}} {:*>4}", "a", "b")"#)?,
+        "b-a {a} ***b"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{:?} {:.3}", "hi", "hello")"#)?,
+        r#""hi" hel"#
+    );
+    assert_eq!(engine.eval::<String>(r#"format("no args")"#)?, "no args");
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(
+            engine.eval::<String>(r#"format("[{:>8.2}] {:.1e}", 3.14159, 1234.5)"#)?,
+            "[    3.14] 1.2e3"
+        );
+        assert_eq!(
+            engine.eval::<String>(r#"format("{:+.3} {:07.2}", 2.0, -1.5)"#)?,
+            "+2.000 -001.50"
+        );
+    }
+
+    #[cfg(feature = "decimal")]
+    assert_eq!(
+        engine.eval::<String>(r#"format("[{:>7.2}]", parse_decimal("-1.5"))"#)?,
+        "[  -1.50]"
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"format("{} {}", 1)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+    assert!(engine.eval::<String>(r#"format("{:x}", "hello")"#).is_err());
+    assert!(engine.eval::<String>(r#"format("{:q}", 1)"#).is_err());
+    assert!(engine.eval::<String>(r#"format("{", 1)"#).is_err());
+    assert!(engine
+        .eval::<String>(r#"format("{:99999999999}", 1)"#)
+        .is_err());
+    assert!(engine
+        .eval::<String>(r#"format("{:.99999999}", 1)"#)
+        .is_err());
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        let mut engine = Engine::new();
+        engine.set_max_string_size(100);
+
+        assert!(matches!(
+            *engine
+                .eval::<String>(r#"format("{:1000}", 1)"#)
+                .expect_err("should error"),
+            EvalAltResult::ErrorDataTooLarge(_, _)
+        ));
+        assert_eq!(engine.eval::<String>(r#"format("{:.200}", "hi")"#)?, "hi");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_string_interpolated_format() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>("let x = 255; `x=${x:#06x}!`")?,
+        "x=0x00ff!"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "ab"; `[${s:>4}] ${s}`"#)?,
+        "[  ab] ab"
+    );
+    assert_eq!(
+        engine.eval::<String>("let x = 20; `${let y = x + 1; y * 2 :+}`")?,
+        "+42"
+    );
+
+    assert_eq!(
+        engine.eval::<String>("let x = 42; `[${ x : >4 }]`")?,
+        "[  42]"
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(
+            engine.eval::<String>("let x = 3.14159; `pi=${x:.2}`")?,
+            "pi=3.14"
+        );
+        assert_eq!(engine.eval::<String>("`${ 3.14159 :.2 }`")?, "3.14");
+    }
+
+    assert!(matches!(
+        *engine
+            .compile("let x = 1; `${x:.}`")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::BadInput(LexError::MalformedFormatSpec(_))
+    ));
+
+    Ok(())
+}
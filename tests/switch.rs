@@ -125,15 +125,29 @@ fn test_switch_condition() -> Result<(), Box<EvalAltResult>> {
         9
     );
 
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "
+                switch x / 2 {
+                    21 if x < 40 => 1,
+                    21 if x == 42 => 10,
+                    0 if x < 100 => 2,
+                    1 => 3,
+                    _ => 9
+                }
+            "
+        )?,
+        10
+    );
+
     assert!(matches!(
         *engine
             .compile(
                 "
                     switch x {
-                        21 if x < 40 => 1,
+                        21 => 1,
                         21 if x == 10 => 10,
-                        0 if x < 100 => 2,
-                        1 => 3,
                         _ => 9
                     }
                 "
@@ -210,3 +224,112 @@ mod test_switch_enum {
         Ok(())
     }
 }
+
+#[test]
+fn test_switch_ranges() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+    scope.push("x", 42 as INT);
+
+    assert_eq!(
+        engine
+            .eval_with_scope::<INT>(&mut scope, "switch x { 10..20 => 1, 30..=50 => 2, _ => 9 }")?,
+        2
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "switch x { 42 if x < 0 => 1, 0..=42 => 2, _ => 9 }"
+        )?,
+        2
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "switch x { 0..42 => 1, 40..50 if x > 100 => 2, 40..50 => 3, _ => 9 }"
+        )?,
+        3
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "switch x { -10..0 => 1, _ => 9 }")?,
+        9
+    );
+    assert_eq!(
+        engine.eval::<INT>("switch 42 { 0..10 => 1, 40..=50 if true => 2, _ => 9 }")?,
+        2
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"switch "42" { 0..100 => 1, _ => 9 }"#)?,
+        9
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_switch_multi_cases() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+    scope.push("x", 42 as INT);
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "switch x { 1 | 2 | 3 => 1, 41 | 42 => 2, _ => 9 }"
+        )?,
+        2
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            r#"switch "b" { "a" | "b" => 1, "c" => 2, _ => 9 }"#
+        )?,
+        1
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "switch x { 0 | 10..20 => 1, 1 | 30..=50 if x > 100 => 2, 42 | 0..10 => 3, _ => 9 }"
+        )?,
+        3
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "switch x { 1 | 2 => 1, if x > 40 => 2, 42 => 3, _ => 9 }"
+        )?,
+        2
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "switch x { if x < 0 => 1, if x % 2 == 1 => 2, 42 if x < 0 => 3, _ => 9 }"
+        )?,
+        9
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "switch [x] { if x > 0 => 1, _ => 9 }")?,
+        1
+    );
+    assert_eq!(
+        engine.eval::<INT>("switch 42 { 1 | 2 => 1, if false => 2, 40..50 | 99 => 3, _ => 9 }")?,
+        3
+    );
+
+    assert!(matches!(
+        *engine
+            .compile("switch x { 1 | 2 => 123, 3 | 2 => 42 }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::DuplicatedSwitchCase
+    ));
+    assert!(matches!(
+        *engine
+            .compile("switch x { 1 | x => 123 }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::ExprExpected(_)
+    ));
+
+    Ok(())
+}
@@ -39,3 +39,150 @@ fn test_try_catch() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_try_catch_variables() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; try { throw 42; } catch (err) { x = err; } x")?,
+        42
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_try_catch_filtered() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let script = r#"
+        let x = 0;
+
+        try {
+            throw value;
+        } catch (err) if type_of(err) == "string" {
+            x = 1;
+        } catch (err) if err == 42 {
+            x = 2;
+        } catch {
+            x = 3;
+        }
+
+        x
+    "#;
+
+    let mut scope = rhai::Scope::new();
+    scope.push("value", "hello");
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, script)?, 1);
+    scope.set_value("value", 42 as INT);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, script)?, 2);
+    scope.set_value("value", true);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, script)?, 3);
+
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "unchecked"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let x = 0;
+                try {
+                    let y = 42 / 0;
+                } catch (err) if err.error == "ErrorArithmetic" {
+                    x = 1;
+                }
+                x
+            "#
+        )?,
+        1
+    );
+
+    // No catch clause matches
+    assert!(matches!(
+        *engine
+            .eval::<()>("try { throw 42; } catch (err) if err == 0 { }")
+            .expect_err("expects error"),
+        EvalAltResult::ErrorRuntime(x, _) if x.as_int().unwrap() == 42
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_try_finally() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; try { x = 1; } catch { x = 2; } finally { x *= 10; } x")?,
+        10
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; try { throw; } catch { x = 2; } finally { x *= 10; } x")?,
+        20
+    );
+
+    // Uncaught exceptions
+    let mut scope = rhai::Scope::new();
+    scope.push("x", 0 as INT);
+    assert!(matches!(
+        *engine
+            .eval_with_scope::<()>(&mut scope, "try { throw 42; } finally { x = 1; }")
+            .expect_err("expects error"),
+        EvalAltResult::ErrorRuntime(v, _) if v.as_int().unwrap() == 42
+    ));
+    assert_eq!(scope.get_value::<INT>("x").unwrap(), 1);
+
+    // Re-throw
+    assert!(engine
+        .eval_with_scope::<()>(
+            &mut scope,
+            "try { throw 42; } catch { throw; } finally { x = 2; }"
+        )
+        .is_err());
+    assert_eq!(scope.get_value::<INT>("x").unwrap(), 2);
+
+    // Break and continue
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                let x = 0;
+                for i in 0..10 {
+                    try {
+                        if i % 2 == 0 { continue; }
+                        if i > 6 { break; }
+                    } finally {
+                        x += 1;
+                    }
+                }
+                x
+            "
+        )?,
+        8
+    );
+
+    // Return
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            "
+                fn f() {
+                    try { return 1; } finally { this += 10; }
+                }
+                let x = 0;
+                let r = x.f();
+                r + x
+            "
+        )?,
+        11
+    );
+
+    // The finally block overrides the result
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval::<INT>("fn f() { try { return 1; } finally { return 2; } } f()")?,
+        2
+    );
+
+    Ok(())
+}
@@ -1,12 +1,12 @@
 #![cfg(not(feature = "no_std"))]
 #![cfg(not(target_arch = "wasm32"))]
 
-use rhai::{Engine, EvalAltResult};
+use rhai::{DateTime, Duration, Engine, EvalAltResult};
 
 #[cfg(not(feature = "no_float"))]
 use rhai::FLOAT;
 
-#[cfg(feature = "no_float")]
+#[cfg(any(feature = "no_float", not(feature = "no_object")))]
 use rhai::INT;
 
 #[test]
@@ -107,3 +107,146 @@ fn test_timestamp_op() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_datetime() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>("type_of(now())")?, "datetime");
+    assert_eq!(engine.eval::<String>("type_of(days(1))")?, "duration");
+    assert!(engine.eval::<INT>("now().year")? >= 2021);
+
+    assert_eq!(
+        engine.eval::<String>("datetime(2021, 8, 5, 14, 30, 0).to_string()")?,
+        "2021-08-05T14:30:00Z"
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let dt = parse_datetime("2021-08-05T14:30:15.25+02:00");
+                dt.year * 10000 + dt.month * 100 + dt.day + dt.nanosecond / 1000000
+            "#
+        )?,
+        20210805 + 250
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let dt = parse_datetime("2021-08-05T14:30:15+02:00");
+                dt.hour * 10000 + dt.minute * 100 + dt.second
+            "#
+        )?,
+        143015
+    );
+    assert_eq!(
+        engine.eval::<INT>("let dt = datetime(2021, 8, 5); dt.weekday * 1000 + dt.day_of_year")?,
+        4217
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"parse_datetime("1970-01-02").unix_timestamp"#)?,
+        86400
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"datetime(2021, 1, 31).add_months(1).format("%F")"#)?,
+        "2021-02-28"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"(datetime(2020, 2, 29) + days(366)).format("%a %e %b")"#)?,
+        "Mon  1 Mar"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"datetime(2021, 1, 1).with_offset(-5400).format("%D %R %z")"#)?,
+        "12/31/20 22:30 -0130"
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            r#"parse_datetime("5 Aug 21 2:30 PM", "%d %b %y %I:%M %p").to_string()"#
+        )?,
+        "2021-08-05T14:30:00Z"
+    );
+    assert!(engine.eval::<bool>(
+        r#"parse_datetime("2021-08-05T12:00:00Z") == parse_datetime("2021-08-05 14:00+02:00")"#
+    )?);
+    assert!(engine.eval::<bool>("datetime(2021, 8, 5) < datetime(2021, 8, 5, 0, 0, 1)")?);
+    assert_eq!(
+        engine.eval::<INT>("(datetime(2021, 3, 1) - datetime(2021, 2, 1)).total_days")?,
+        28
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<DateTime>("datetime(2021, 2, 29)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<DateTime>(r#"parse_datetime("2021-08-05T25:00")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"now().format("%Q")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_duration() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>("(days(1) + hours(2) + minutes(3) - seconds(4)).to_string()")?,
+        "P1DT2H2M56S"
+    );
+    assert_eq!(
+        engine.eval::<String>("(-milliseconds(1500)).to_string()")?,
+        "-PT1.5S"
+    );
+    assert_eq!(engine.eval::<INT>("(hours(3) * 4 / 6).total_minutes")?, 120);
+    assert_eq!(
+        engine.eval::<INT>("(-minutes(2)).abs().total_seconds")?,
+        120
+    );
+    assert!(engine.eval::<bool>("hours(24) == days(1) && hours(1) < minutes(61)")?);
+    assert!(engine.eval::<bool>("let d = days(1); d -= hours(25); d < seconds(0)")?);
+
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(engine.eval::<INT>("seconds(0.25).total_milliseconds")?, 250);
+
+    assert!(matches!(
+        *engine
+            .eval::<Duration>("hours(1) / 0")
+            .expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_datetime_rust() {
+    let dt = DateTime::from_ymd_hms(2021, 8, 5, 14, 30, 0).unwrap();
+
+    assert_eq!(dt.weekday(), 4);
+    assert_eq!(
+        dt.checked_add(Duration::from_mins(90)).unwrap().to_string(),
+        "2021-08-05T16:00:00Z"
+    );
+    assert_eq!(
+        dt.with_offset(3600).unwrap().format("%+").unwrap(),
+        "2021-08-05T15:30:00+01:00"
+    );
+    assert_eq!("2021-08-05T14:30:00Z".parse::<DateTime>(), Ok(dt));
+    assert_eq!(
+        DateTime::from_ymd(-1, 12, 31).unwrap().to_string(),
+        "-0001-12-31T00:00:00Z"
+    );
+    assert!(DateTime::from_ymd(2021, 13, 1).is_none());
+}
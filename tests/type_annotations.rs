@@ -0,0 +1,236 @@
+#![cfg(not(feature = "no_function"))]
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+
+#[test]
+fn test_type_annotations() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("fn area(w: int, h: int) -> int { w * h } area(6, 7)")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>("fn add(x: int, y: int = 2) -> int { x + y } add(40)")?,
+        42
+    );
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<INT>("fn f(x: int, ...rest) -> int { x + len(rest) } f(40, 1, 2)")?,
+        42
+    );
+    assert_eq!(engine.eval::<INT>("let x: int = 42; x")?, 42);
+    assert_eq!(
+        engine.eval::<String>(r#"const S: string = "hello"; S"#)?,
+        "hello"
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            r#"fn f(g: Fn, s: string, c: char) -> () { } let x: () = f(Fn("f"), "", 'x'); type_of(x)"#
+        )?,
+        "()"
+    );
+    assert_eq!(
+        engine.eval::<String>("fn f(x: int, y: bool) -> string { `${x}${y}` } f(1, true)")?,
+        "1true"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_type_annotations_runtime() {
+    let engine = Engine::new();
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"fn area(w: int, h: int) -> int { w * h } let x = "6"; area(x, 7)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(expected, actual, _)
+            if expected == "int for parameter 'w'" && actual == "string"
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"fn f(x) -> int { x } f("hello")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(expected, actual, _)
+            if expected == "int for the return value" && actual == "string"
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let y = true; let x: int = y; x")
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(expected, actual, _)
+            if expected == "int for variable 'x'" && actual == "bool"
+    ));
+}
+
+#[test]
+fn test_type_annotations_assignment() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let x: int = 1; x = 2; x += 40; x")?, 42);
+    assert_eq!(engine.eval::<INT>("fn f(x: int) { x *= 2; x } f(21)")?, 42);
+
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"let x: int = 1; x = "hello";"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(expected, actual, _)
+            if expected == "int for variable 'x'" && actual == "string"
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"fn f(x: int) { x = "hello"; } f(1)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorInFunctionCall(_, _, err, _)
+            if matches!(*err, EvalAltResult::ErrorMismatchDataType(ref expected, _, _)
+                if expected == "int for variable 'x'")
+    ));
+
+    #[cfg(not(feature = "no_closure"))]
+    {
+        assert_eq!(
+            engine.eval::<INT>("let x: int = 5; let f = || x += 37; call(f); x")?,
+            42
+        );
+        assert!(matches!(
+            *engine
+                .eval::<()>(r#"let x: int = 5; let f = || x = "s"; call(f);"#)
+                .expect_err("should error"),
+            EvalAltResult::ErrorInFunctionCall(_, _, err, _)
+                if matches!(*err, EvalAltResult::ErrorMismatchDataType(ref expected, _, _)
+                    if expected == "int for variable 'x'")
+        ));
+        assert_eq!(
+            engine.eval::<INT>(
+                r#"let x: int = 5; let f = || x = "s"; try { call(f); } catch { } x"#
+            )?,
+            5
+        );
+        assert!(engine
+            .eval::<()>(r#"fn f(x: int) { let g = || x = "s"; call(g); } f(1)"#)
+            .is_err());
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let x: int = 5; let x = 1; let f = || x = "s"; call(f); x"#)?,
+            "s"
+        );
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert!(matches!(
+            *engine
+                .eval::<()>("let x: int = 1; x += 0.5;")
+                .expect_err("should error"),
+            EvalAltResult::ErrorMismatchDataType(expected, _, _)
+                if expected == "int for variable 'x'"
+        ));
+        assert_eq!(
+            engine.eval::<INT>("let x: int = 1; try { x += 0.5; } catch { } x")?,
+            1
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_type_annotations_compile() {
+    let engine = Engine::new();
+    let int = std::any::type_name::<INT>();
+
+    for (script, expected, actual) in [
+        (
+            r#"fn area(w: int, h: int) { w * h } area("6", 7)"#,
+            "int for parameter 'w'",
+            "string",
+        ),
+        (
+            "fn f(x, y: bool) { } f(y: 1, x: 2)",
+            "bool for parameter 'y'",
+            int,
+        ),
+        ("fn f(x: int = true) { x }", "int for parameter 'x'", "bool"),
+        (
+            r#"fn f() -> int { "hello" }"#,
+            "int for the return value",
+            "string",
+        ),
+        (
+            "fn f(x) -> bool { if x { return 1; } true }",
+            "bool for the return value",
+            int,
+        ),
+        (
+            "fn f(x) -> bool { if x { return; } true }",
+            "bool for the return value",
+            "()",
+        ),
+        ("let x: string = 42;", "string for variable 'x'", int),
+        ("const X: bool = 'x';", "bool for variable 'X'", "char"),
+    ] {
+        assert!(
+            matches!(
+                *engine.compile(script).expect_err("should error").0,
+                ParseErrorType::MismatchedType(ref e, ref a) if e == expected && a == actual
+            ),
+            "{}",
+            script
+        );
+    }
+
+    assert!(matches!(
+        *engine.compile("fn f(x: foo) { }").expect_err("should error").0,
+        ParseErrorType::UnknownType(t) if t == "foo"
+    ));
+    assert!(matches!(
+        *engine.compile("let x: bar = 1;").expect_err("should error").0,
+        ParseErrorType::UnknownType(t) if t == "bar"
+    ));
+
+    // Calls that may resolve to other functions are not checked
+    assert!(engine
+        .compile(r#"fn f(x: int) { } fn f(x, y) { } f("a", 1)"#)
+        .is_ok());
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_type_annotations_custom_type() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Debug, Clone)]
+    struct Point {
+        x: INT,
+    }
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Point>("Point")
+        .register_fn("new_point", |x: INT| Point { x })
+        .register_fn("x", |p: &mut Point| p.x);
+
+    assert_eq!(
+        engine.eval::<INT>(
+            "fn get_x(p: Point) -> int { x(p) } let p: Point = new_point(42); get_x(p)"
+        )?,
+        42
+    );
+    assert!(matches!(
+        *engine
+            .eval::<INT>("fn get_x(p: Point) -> int { x(p) } let p = 42; get_x(p)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(expected, actual, _)
+            if expected == "Point for parameter 'p'" && actual == std::any::type_name::<INT>()
+    ));
+    assert!(matches!(
+        *engine
+            .compile("fn get_x(p: Point) -> int { x(p) } get_x(42)")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MismatchedType(expected, actual)
+            if expected == "Point for parameter 'p'" && actual == std::any::type_name::<INT>()
+    ));
+
+    Ok(())
+}